@@ -83,6 +83,10 @@ impl TableSource for DefaultTableSource {
     fn get_column_default(&self, column: &str) -> Option<&Expr> {
         self.table_provider.get_column_default(column)
     }
+
+    fn partition_columns(&self) -> Vec<&str> {
+        self.table_provider.partition_columns()
+    }
 }
 
 /// Wrap TableProvider in TableSource
@@ -84,6 +84,11 @@ pub trait TableProvider: Debug + Sync + Send {
         None
     }
 
+    /// Get the names of the columns the table is partitioned by, if any.
+    fn partition_columns(&self) -> Vec<&str> {
+        vec![]
+    }
+
     /// Create an [`ExecutionPlan`] for scanning the table with optionally
     /// specified `projection`, `filter` and `limit`, described below.
     ///
@@ -317,6 +322,23 @@ pub trait TableProviderFactory: Debug + Sync + Send {
 pub trait TableFunctionImpl: Debug + Sync + Send {
     /// Create a table provider
     fn call(&self, args: &[Expr]) -> Result<Arc<dyn TableProvider>>;
+
+    /// Return the names of the parameters of the function, in the order the
+    /// arguments are passed to [`Self::call`].
+    ///
+    /// Named arguments, e.g. `range(start => 1, stop => 10)`, are passed at
+    /// the position of the parameter of the same name. The default is no
+    /// names, and then only positional arguments are accepted.
+    fn parameter_names(&self) -> Vec<&str> {
+        vec![]
+    }
+
+    /// Return how many of the [`Self::parameter_names`], from the first, must
+    /// be given when the function is called with named arguments. The default
+    /// is all of them.
+    fn required_parameter_count(&self) -> usize {
+        self.parameter_names().len()
+    }
 }
 
 /// A table that uses a function to generate data
@@ -344,6 +366,18 @@ impl TableFunction {
         &self.fun
     }
 
+    /// Get the names of the parameters of the table function, see
+    /// [`TableFunctionImpl::parameter_names`]
+    pub fn parameter_names(&self) -> Vec<&str> {
+        self.fun.parameter_names()
+    }
+
+    /// Get how many parameters of the table function must be given with named
+    /// arguments, see [`TableFunctionImpl::required_parameter_count`]
+    pub fn required_parameter_count(&self) -> usize {
+        self.fun.required_parameter_count()
+    }
+
     /// Get the function implementation and generate a table
     pub fn create_table_provider(&self, args: &[Expr]) -> Result<Arc<dyn TableProvider>> {
         self.fun.call(args)
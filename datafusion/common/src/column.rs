@@ -263,6 +263,7 @@ impl Column {
                     // If not due to USING columns then due to ambiguous column name
                     return _schema_err!(SchemaError::AmbiguousReference {
                         field: Box::new(Column::new_unqualified(&self.name)),
+                        candidates: columns.clone(),
                     })
                     .map_err(|err| {
                         let mut diagnostic = Diagnostic::new_error(
@@ -278,6 +279,24 @@ impl Column {
                             &Column::new_unqualified(&self.name),
                             &columns,
                         );
+                        // Explain why the columns joined by `USING` do not
+                        // make the reference unambiguous
+                        for using_col in using_columns {
+                            let (joined, others): (Vec<_>, Vec<_>) =
+                                columns.iter().partition(|c| using_col.contains(*c));
+                            if joined.len() > 1 {
+                                diagnostic.add_help(
+                                    format!(
+                                        "{} are joined by USING or NATURAL, so the unqualified name would refer to {} if it did not also match {}",
+                                        list_columns(joined.iter().copied(), "and"),
+                                        joined[0].quoted_flat_name(),
+                                        list_columns(others.iter().copied(), "or")
+                                    ),
+                                    None,
+                                );
+                                break;
+                            }
+                        }
                         err.with_diagnostic(diagnostic)
                     });
                 }
@@ -370,6 +389,23 @@ impl fmt::Display for Column {
     }
 }
 
+/// Lists `columns` as `a.x, b.x <conjunction> c.x`
+pub(crate) fn list_columns<'a>(
+    columns: impl IntoIterator<Item = &'a Column>,
+    conjunction: &str,
+) -> String {
+    let names = columns
+        .into_iter()
+        .map(|column| column.quoted_flat_name())
+        .collect::<Vec<_>>();
+    match names.split_last() {
+        Some((last, rest)) if !rest.is_empty() => {
+            format!("{} {conjunction} {last}", rest.join(", "))
+        }
+        _ => names.join(", "),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -445,7 +481,8 @@ mod tests {
                 &[],
             )
             .expect_err("should've found ambiguous field");
-        let expected = "Schema error: Ambiguous reference to unqualified field a";
+        let expected = "Schema error: Ambiguous reference to unqualified field a, \
+            which could refer to t1.a or t3.a";
         assert_eq!(err.strip_backtrace(), expected);
 
         Ok(())
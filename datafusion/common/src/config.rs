@@ -288,6 +288,48 @@ config_namespace! {
         /// By default, `nulls_max` is used to follow Postgres's behavior.
         /// postgres rule: <https://www.postgresql.org/docs/current/queries-order.html>
         pub default_null_ordering: String, default = "nulls_max".to_string()
+
+        /// Specifies how duplicate column names are handled in the output of a query
+        /// that is used as a CTE, an aliased derived table or an input of a set operation
+        /// (e.g. `WITH c AS (SELECT a, a FROM t) SELECT * FROM c`). There are 2 options:
+        /// - `disambiguate`: Rename the second and later occurrences of a name by
+        ///   appending a suffix (`a:1`, `a:2`, ...).
+        /// - `error`: Return an error naming the duplicate column and its positions.
+        ///
+        /// The output of the top-level query is not affected.
+        pub duplicate_column_names: String, default = "disambiguate".to_string()
+
+        /// Maximum number of distinct placeholders (e.g. `$1`) in a statement.
+        /// Set to 0 to disable the limit.
+        pub max_placeholders: usize, default = 65535
+
+        /// Maximum length in characters of an identifier in a statement.
+        /// Set to 0 to disable the limit.
+        pub max_identifier_length: usize, default = 4096
+
+        /// Maximum number of common table expressions in a single `WITH` clause.
+        /// Set to 0 to disable the limit.
+        pub max_ctes: usize, default = 1024
+
+        /// Maximum number of inputs of a chain of set operations such as
+        /// `SELECT ... UNION ALL SELECT ... UNION ALL ...`. Set to 0 to disable the limit.
+        pub max_set_operation_inputs: usize, default = 10000
+
+        /// Maximum number of columns of a `SELECT` list, counted after wildcards
+        /// are expanded. Set to 0 to disable the limit.
+        pub max_projection_width: usize, default = 100000
+
+        /// When set to true, interval strings may also use a shorthand of
+        /// numbers directly followed by unit abbreviations, such as
+        /// `INTERVAL '1d2h30m'`. ISO 8601 durations such as `INTERVAL 'P1DT2H30M'`
+        /// are always accepted.
+        pub enable_interval_shorthand: bool, default = false
+
+        /// When set to true, an unqualified name in `GROUP BY` refers to an alias
+        /// of the `SELECT` list before a same-named column of the input, like in
+        /// `ORDER BY`. Aliases of aggregate and window functions are never used.
+        /// When false, the input column takes precedence.
+        pub prefer_group_by_aliases: bool, default = false
     }
 }
 
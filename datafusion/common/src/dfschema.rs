@@ -23,7 +23,7 @@ use std::fmt::{Display, Formatter};
 use std::hash::Hash;
 use std::sync::Arc;
 
-use crate::error::{DataFusionError, Result, _plan_err, _schema_err};
+use crate::error::{_plan_err, _schema_err, DataFusionError, Result};
 use crate::{
     field_not_found, unqualified_field_not_found, Column, FunctionalDependencies,
     SchemaError, TableReference,
@@ -248,7 +248,8 @@ impl DFSchema {
         for (qualifier, name) in qualified_names {
             if unqualified_names.contains(name) {
                 return _schema_err!(SchemaError::AmbiguousReference {
-                    field: Box::new(Column::new(Some(qualifier.clone()), name))
+                    field: Box::new(Column::new(Some(qualifier.clone()), name)),
+                    candidates: vec![],
                 });
             }
         }
@@ -508,7 +509,13 @@ impl DFSchema {
                     Ok((fields_without_qualifier[0].0, fields_without_qualifier[0].1))
                 } else {
                     _schema_err!(SchemaError::AmbiguousReference {
-                        field: Box::new(Column::new_unqualified(name.to_string()))
+                        field: Box::new(Column::new_unqualified(name.to_string())),
+                        candidates: matches
+                            .iter()
+                            .map(|(qualifier, field)| {
+                                Column::new(qualifier.cloned(), field.name())
+                            })
+                            .collect(),
                     })
                 }
             }
@@ -27,6 +27,7 @@ use std::io;
 use std::result;
 use std::sync::Arc;
 
+use crate::column::list_columns;
 use crate::utils::datafusion_strsim::normalized_levenshtein;
 use crate::utils::quote_identifier;
 use crate::{Column, DFSchema, Diagnostic, TableReference};
@@ -163,8 +164,12 @@ macro_rules! context {
 /// Schema-related errors
 #[derive(Debug)]
 pub enum SchemaError {
-    /// Schema contains a (possibly) qualified and unqualified field with same unqualified name
-    AmbiguousReference { field: Box<Column> },
+    /// Schema contains a (possibly) qualified and unqualified field with same unqualified name,
+    /// or an unqualified reference matches all of the qualified `candidates`
+    AmbiguousReference {
+        field: Box<Column>,
+        candidates: Vec<Column>,
+    },
     /// Schema contains duplicate qualified field name
     DuplicateQualifiedField {
         qualifier: Box<TableReference>,
@@ -240,7 +245,7 @@ impl Display for SchemaError {
                     quote_identifier(name)
                 )
             }
-            Self::AmbiguousReference { field } => {
+            Self::AmbiguousReference { field, candidates } => {
                 if field.relation.is_some() {
                     write!(
                         f,
@@ -253,7 +258,15 @@ impl Display for SchemaError {
                         f,
                         "Ambiguous reference to unqualified field {}",
                         field.quoted_flat_name()
-                    )
+                    )?;
+                    if !candidates.is_empty() {
+                        write!(
+                            f,
+                            ", which could refer to {}",
+                            list_columns(candidates, "or")
+                        )?;
+                    }
+                    Ok(())
                 }
             }
         }
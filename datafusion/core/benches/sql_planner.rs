@@ -146,6 +146,39 @@ fn benchmark_with_param_values_many_columns(
     });
 }
 
+/// Target of this benchmark: control the planning time of long `VALUES` lists
+/// of literals, like those of an INSERT of many rows.
+fn benchmark_values_many_rows(ctx: &SessionContext, rt: &Runtime, b: &mut Bencher) {
+    const ROWS_NUM: usize = 100_000;
+    const COLUMNS_NUM: usize = 10;
+    let rows = (0..ROWS_NUM)
+        .map(|i| {
+            let row = (0..COLUMNS_NUM)
+                .map(|j| match j % 3 {
+                    0 => format!("{i}"),
+                    1 => format!("'v{i}'"),
+                    _ if i % 10 == 0 => "NULL".to_string(),
+                    _ => format!("-{i}.5"),
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("({row})")
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    // SELECT * FROM (VALUES (0, 'v0', NULL, ...), (1, 'v1', -1.5, ...), ...)
+    let query = format!("SELECT * FROM (VALUES {rows})");
+    let statement = ctx.state().sql_to_statement(&query, "Generic").unwrap();
+    b.iter(|| {
+        let statement = statement.clone();
+        criterion::black_box(
+            rt.block_on(async {
+                ctx.state().statement_to_plan(statement).await.unwrap()
+            }),
+        );
+    });
+}
+
 /// Registers a table like this:
 /// c0,c1,c2...,c99
 /// 0,100...9900
@@ -508,6 +541,10 @@ fn criterion_benchmark(c: &mut Criterion) {
         })
     });
 
+    c.bench_function("logical_plan_values_100k_rows", |b| {
+        benchmark_values_many_rows(&ctx, &rt, b);
+    });
+
     c.bench_function("with_param_values_many_columns", |b| {
         benchmark_with_param_values_many_columns(&ctx, &rt, b);
     });
@@ -1159,6 +1159,14 @@ impl TableProvider for ListingTable {
         TableType::Base
     }
 
+    fn partition_columns(&self) -> Vec<&str> {
+        self.options
+            .table_partition_cols
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect()
+    }
+
     async fn scan(
         &self,
         state: &dyn Session,
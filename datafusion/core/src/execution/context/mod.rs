@@ -78,7 +78,7 @@ use datafusion_expr::{
     expr_rewriter::FunctionRewrite,
     logical_plan::{DdlStatement, Statement},
     planner::ExprPlanner,
-    Expr, UserDefinedLogicalNode, WindowUDF,
+    Cast, Expr, UserDefinedLogicalNode, WindowUDF,
 };
 use datafusion_optimizer::analyzer::type_coercion::TypeCoercion;
 use datafusion_optimizer::Analyzer;
@@ -1036,9 +1036,16 @@ impl SessionContext {
     }
 
     async fn set_variable(&self, stmt: SetVariable) -> Result<DataFrame> {
-        let SetVariable {
-            variable, value, ..
-        } = stmt;
+        let SetVariable { variable, values } = stmt;
+        let value = match values.as_slice() {
+            [] => self.default_variable_value(&variable)?,
+            [value] => value.clone(),
+            _ => {
+                return not_impl_err!(
+                    "SET '{variable}' to a list of values is not supported"
+                );
+            }
+        };
 
         // Check if this is a runtime configuration
         if variable.starts_with("datafusion.runtime.") {
@@ -1052,6 +1059,23 @@ impl SessionContext {
         self.return_empty_dataframe()
     }
 
+    /// Return the value of a configuration option when the session was
+    /// created, for `SET ... TO DEFAULT`
+    fn default_variable_value(&self, variable: &str) -> Result<String> {
+        self.state
+            .read()
+            .initial_config_options()
+            .entries()
+            .into_iter()
+            .find(|entry| entry.key == variable)
+            .and_then(|entry| entry.value)
+            .ok_or_else(|| {
+                plan_datafusion_err!(
+                    "'{variable}' does not have a default value that can be set with 'SET ... TO DEFAULT'"
+                )
+            })
+    }
+
     fn set_runtime_variable(&self, variable: &str, value: &str) -> Result<()> {
         let key = variable.strip_prefix("datafusion.runtime.").unwrap();
 
@@ -1220,12 +1244,34 @@ impl SessionContext {
             exec_datafusion_err!("Prepared statement '{}' does not exist", name)
         })?;
 
+        /// The value of a literal, possibly cast by a `CAST` of the parameter
+        fn literal_value(expr: &Expr) -> Option<Result<ScalarValue>> {
+            match expr {
+                Expr::Literal(scalar, _) => Some(Ok(scalar.clone())),
+                Expr::Cast(Cast { expr, data_type }) => Some(
+                    literal_value(expr)?.and_then(|scalar| scalar.cast_to(data_type)),
+                ),
+                _ => None,
+            }
+        }
+
         // Only allow literals as parameters for now.
         let mut params: Vec<ScalarValue> = parameters
-            .into_iter()
-            .map(|e| match e {
-                Expr::Literal(scalar, _) => Ok(scalar),
-                _ => not_impl_err!("Unsupported parameter type: {}", e),
+            .iter()
+            .enumerate()
+            .map(|(i, e)| {
+                let value = match e {
+                    // The cast to the declared type added by the planner is
+                    // applied below
+                    Expr::Cast(Cast { expr, data_type })
+                        if prepared.data_types.get(i) == Some(data_type) =>
+                    {
+                        literal_value(expr)
+                    }
+                    _ => literal_value(e),
+                };
+                value
+                    .unwrap_or_else(|| not_impl_err!("Unsupported parameter type: {}", e))
             })
             .collect::<Result<_>>()?;
 
@@ -1242,7 +1288,15 @@ impl SessionContext {
             params = params
                 .into_iter()
                 .zip(prepared.data_types.iter())
-                .map(|(e, dt)| e.cast_to(dt))
+                .enumerate()
+                .map(|(i, (e, dt))| {
+                    e.cast_to(dt).map_err(|err| {
+                        err.context(format!(
+                            "Failed to cast parameter ${} of prepared statement '{name}' to {dt}",
+                            i + 1
+                        ))
+                    })
+                })
                 .collect::<Result<_>>()?;
         }
 
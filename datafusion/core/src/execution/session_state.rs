@@ -51,13 +51,13 @@ use datafusion_execution::runtime_env::RuntimeEnv;
 use datafusion_execution::TaskContext;
 use datafusion_expr::execution_props::ExecutionProps;
 use datafusion_expr::expr_rewriter::FunctionRewrite;
-use datafusion_expr::planner::{ExprPlanner, TypePlanner};
+use datafusion_expr::planner::{ExprPlanner, RegisteredFunction, TypePlanner};
 use datafusion_expr::registry::{FunctionRegistry, SerializerRegistry};
 use datafusion_expr::simplify::SimplifyInfo;
 use datafusion_expr::var_provider::{is_system_variables, VarType};
 use datafusion_expr::{
-    AggregateUDF, Explain, Expr, ExprSchemable, LogicalPlan, ScalarUDF, TableSource,
-    WindowUDF,
+    AggregateUDF, DdlStatement, Explain, Expr, ExprSchemable, LogicalPlan, ScalarUDF,
+    TableSource, WindowUDF,
 };
 use datafusion_optimizer::simplify_expressions::ExprSimplifier;
 use datafusion_optimizer::{
@@ -70,7 +70,10 @@ use datafusion_physical_optimizer::PhysicalOptimizerRule;
 use datafusion_physical_plan::ExecutionPlan;
 use datafusion_session::Session;
 use datafusion_sql::parser::{DFParserBuilder, Statement};
-use datafusion_sql::planner::{ContextProvider, ParserOptions, PlannerContext, SqlToRel};
+use datafusion_sql::planner::{
+    ContextProvider, ParserOptions, PlanWithMetadata, PlannerContext, SqlToRel,
+    StatementSource,
+};
 
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
@@ -155,6 +158,8 @@ pub struct SessionState {
     file_formats: HashMap<String, Arc<dyn FileFormatFactory>>,
     /// Session configuration
     config: SessionConfig,
+    /// The configuration options the session was created with
+    initial_options: Arc<ConfigOptions>,
     /// Table options
     table_options: TableOptions,
     /// Execution properties
@@ -451,7 +456,31 @@ impl SessionState {
         &self,
         statement: Statement,
     ) -> datafusion_common::Result<LogicalPlan> {
-        let references = self.resolve_table_references(&statement)?;
+        let provider = self.statement_context_provider(&statement).await?;
+        let query = SqlToRel::new_with_options(&provider, self.get_parser_options());
+        query.statement_to_plan(statement)
+    }
+
+    /// Convert an AST Statement into a LogicalPlan along with its metadata,
+    /// keeping the SQL text `source` it was parsed from
+    ///
+    /// See [`SqlToRel::statement_to_plan_with_source`]
+    pub async fn statement_to_plan_with_source(
+        &self,
+        statement: Statement,
+        source: StatementSource,
+    ) -> datafusion_common::Result<PlanWithMetadata> {
+        let provider = self.statement_context_provider(&statement).await?;
+        let query = SqlToRel::new_with_options(&provider, self.get_parser_options());
+        query.statement_to_plan_with_source(statement, source)
+    }
+
+    /// Create a [`ContextProvider`] resolving the tables `statement` references
+    async fn statement_context_provider(
+        &self,
+        statement: &Statement,
+    ) -> datafusion_common::Result<SessionContextProvider<'_>> {
+        let references = self.resolve_table_references(statement)?;
 
         let mut provider = SessionContextProvider {
             state: self,
@@ -470,8 +499,7 @@ impl SessionState {
             }
         }
 
-        let query = SqlToRel::new_with_options(&provider, self.get_parser_options());
-        query.statement_to_plan(statement)
+        Ok(provider)
     }
 
     fn get_parser_options(&self) -> ParserOptions {
@@ -489,6 +517,17 @@ impl SessionState {
                 .default_null_ordering
                 .as_str()
                 .into(),
+            duplicate_column_names: sql_parser_options
+                .duplicate_column_names
+                .as_str()
+                .into(),
+            max_placeholders: sql_parser_options.max_placeholders,
+            max_identifier_length: sql_parser_options.max_identifier_length,
+            max_ctes: sql_parser_options.max_ctes,
+            max_set_operation_inputs: sql_parser_options.max_set_operation_inputs,
+            max_projection_width: sql_parser_options.max_projection_width,
+            enable_interval_shorthand: sql_parser_options.enable_interval_shorthand,
+            prefer_group_by_aliases: sql_parser_options.prefer_group_by_aliases,
         }
     }
 
@@ -510,7 +549,12 @@ impl SessionState {
     ) -> datafusion_common::Result<LogicalPlan> {
         let dialect = self.config.options().sql_parser.dialect.as_str();
         let statement = self.sql_to_statement(sql, dialect)?;
-        let plan = self.statement_to_plan(statement).await?;
+        let mut plan = self.statement_to_plan(statement).await?;
+        // A view is defined by the text as written, including its formatting
+        // and comments, rather than by the statement displayed again
+        if let LogicalPlan::Ddl(DdlStatement::CreateView(create_view)) = &mut plan {
+            create_view.definition = Some(StatementSource::new(sql, dialect).sql);
+        }
         Ok(plan)
     }
 
@@ -716,6 +760,12 @@ impl SessionState {
         &mut self.config
     }
 
+    /// Return the configuration options the session was created with, which
+    /// `SET ... TO DEFAULT` resets an option to
+    pub(crate) fn initial_config_options(&self) -> &ConfigOptions {
+        &self.initial_options
+    }
+
     /// Return the logical optimizers
     pub fn optimizers(&self) -> &[Arc<dyn OptimizerRule + Send + Sync>] {
         &self.optimizer.rules
@@ -1376,6 +1426,7 @@ impl SessionStateBuilder {
             table_options: table_options.unwrap_or_else(|| {
                 TableOptions::default_from_session_config(config.options())
             }),
+            initial_options: Arc::clone(config.options()),
             config,
             execution_props: execution_props.unwrap_or_default(),
             table_factories: table_factories.unwrap_or_default(),
@@ -1686,6 +1737,38 @@ impl ContextProvider for SessionContextProvider<'_> {
         Ok(provider_as_source(provider))
     }
 
+    fn get_table_function_parameter_names(
+        &self,
+        name: &str,
+    ) -> datafusion_common::Result<Vec<String>> {
+        let tbl_func =
+            self.state.table_functions.get(name).ok_or_else(|| {
+                plan_datafusion_err!("table function '{name}' not found")
+            })?;
+        Ok(tbl_func
+            .parameter_names()
+            .into_iter()
+            .map(String::from)
+            .collect())
+    }
+
+    fn get_table_function_required_parameter_count(
+        &self,
+        name: &str,
+    ) -> datafusion_common::Result<usize> {
+        let tbl_func =
+            self.state.table_functions.get(name).ok_or_else(|| {
+                plan_datafusion_err!("table function '{name}' not found")
+            })?;
+        Ok(tbl_func.required_parameter_count())
+    }
+
+    fn get_prepared_statement_data_types(&self, name: &str) -> Option<Vec<DataType>> {
+        self.state
+            .get_prepared(name)
+            .map(|prepared| prepared.data_types.clone())
+    }
+
     /// Create a new CTE work table for a recursive CTE logical plan
     /// This table will be used in conjunction with a Worktable physical plan
     /// to read and write each iteration of a recursive CTE
@@ -1744,6 +1827,25 @@ impl ContextProvider for SessionContextProvider<'_> {
         self.state.window_functions().keys().cloned().collect()
     }
 
+    fn registered_functions(&self) -> Vec<RegisteredFunction> {
+        let scalar = self
+            .state
+            .scalar_functions()
+            .values()
+            .map(|f| RegisteredFunction::Scalar(Arc::clone(f)));
+        let aggregate = self
+            .state
+            .aggregate_functions()
+            .values()
+            .map(|f| RegisteredFunction::Aggregate(Arc::clone(f)));
+        let window = self
+            .state
+            .window_functions()
+            .values()
+            .map(|f| RegisteredFunction::Window(Arc::clone(f)));
+        scalar.chain(aggregate).chain(window).collect()
+    }
+
     fn get_file_type(&self, ext: &str) -> datafusion_common::Result<Arc<dyn FileType>> {
         self.state
             .file_formats
@@ -1237,7 +1237,7 @@ async fn join_ambiguous_filter() -> Result<()> {
     let join = left
         .join_on(right, JoinType::Inner, [col("c1").eq(col("c1"))])
         .expect_err("join didn't fail check");
-    assert_snapshot!(join.strip_backtrace(), @"Schema error: Ambiguous reference to unqualified field c1");
+    assert_snapshot!(join.strip_backtrace(), @"Schema error: Ambiguous reference to unqualified field c1, which could refer to a.c1 or b.c1");
 
     Ok(())
 }
@@ -1734,7 +1734,7 @@ async fn with_column_renamed_ambiguous() -> Result<()> {
         // can be t1.c2 or t2.c2
         .with_column_renamed("c2", "AAA")
         .unwrap_err();
-    assert_snapshot!(actual_err.strip_backtrace(), @"Schema error: Ambiguous reference to unqualified field c2");
+    assert_snapshot!(actual_err.strip_backtrace(), @"Schema error: Ambiguous reference to unqualified field c2, which could refer to t1.c2 or t2.c2");
 
     Ok(())
 }
@@ -3205,7 +3205,7 @@ async fn sort_on_ambiguous_column() -> Result<()> {
         .sort(vec![col("b").sort(true, true)])
         .unwrap_err();
 
-    assert_snapshot!(err.strip_backtrace(), @"Schema error: Ambiguous reference to unqualified field b");
+    assert_snapshot!(err.strip_backtrace(), @"Schema error: Ambiguous reference to unqualified field b, which could refer to t1.b or t2.b");
     Ok(())
 }
 
@@ -3223,7 +3223,7 @@ async fn group_by_ambiguous_column() -> Result<()> {
         .aggregate(vec![col("b")], vec![max(col("a"))])
         .unwrap_err();
 
-    assert_snapshot!(err.strip_backtrace(), @"Schema error: Ambiguous reference to unqualified field b");
+    assert_snapshot!(err.strip_backtrace(), @"Schema error: Ambiguous reference to unqualified field b, which could refer to t1.b or t2.b");
     Ok(())
 }
 
@@ -3241,7 +3241,7 @@ async fn filter_on_ambiguous_column() -> Result<()> {
         .filter(col("b").eq(lit(1)))
         .unwrap_err();
 
-    assert_snapshot!(err.strip_backtrace(), @"Schema error: Ambiguous reference to unqualified field b");
+    assert_snapshot!(err.strip_backtrace(), @"Schema error: Ambiguous reference to unqualified field b, which could refer to t1.b or t2.b");
     Ok(())
 }
 
@@ -3259,7 +3259,7 @@ async fn select_ambiguous_column() -> Result<()> {
         .select(vec![col("b")])
         .unwrap_err();
 
-    assert_snapshot!(err.strip_backtrace(), @"Schema error: Ambiguous reference to unqualified field b");
+    assert_snapshot!(err.strip_backtrace(), @"Schema error: Ambiguous reference to unqualified field b, which could refer to t1.b or t2.b");
     Ok(())
 }
 
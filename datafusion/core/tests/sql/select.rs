@@ -16,7 +16,9 @@
 // under the License.
 
 use super::*;
-use datafusion_common::ScalarValue;
+use datafusion::logical_expr::{JoinType, LogicalPlanBuilder};
+use datafusion_common::{NullEquality, ScalarValue};
+use datafusion_sql::unparser::plan_to_sql;
 use insta::assert_snapshot;
 
 #[tokio::test]
@@ -344,3 +346,128 @@ async fn test_version_function() {
 
     assert_eq!(version.value(0), expected_version);
 }
+
+#[tokio::test]
+async fn test_unparsed_window_frame_round_trip() -> Result<()> {
+    let ctx = SessionContext::new();
+    ctx.sql(
+        "CREATE TABLE t (ts INT, v INT) AS VALUES (1, 1), (1, 2), (2, 3), (3, 4), (3, 5), (3, 6)",
+    )
+    .await?
+    .collect()
+    .await?;
+
+    // Rows with the same `ts` are peers, which are all in the implicit frame
+    // of each other. Grouping by `ts` makes the ordering strict, so the
+    // implicit frame is planned with ROWS units.
+    for sql in [
+        "SELECT ts, sum(v) OVER (ORDER BY ts) AS s FROM t",
+        "SELECT ts, sum(v) OVER (ORDER BY ts RANGE BETWEEN 1 PRECEDING AND CURRENT ROW) AS s FROM t",
+        "SELECT ts, sum(v) OVER (ORDER BY ts) AS s FROM (SELECT ts, sum(v) AS v FROM t GROUP BY ts)",
+    ] {
+        let df = ctx.sql(sql).await?;
+        let unparsed = plan_to_sql(df.logical_plan())?.to_string();
+        let expected = batches_to_sort_string(&df.collect().await?);
+        let actual = batches_to_sort_string(&ctx.sql(&unparsed).await?.collect().await?);
+        assert_eq!(actual, expected, "{sql} was unparsed as {unparsed}");
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_unparsed_sort_and_limit_round_trip() -> Result<()> {
+    let ctx = SessionContext::new();
+    ctx.sql(
+        "CREATE TABLE t (a INT, b INT) AS VALUES (1, 6), (2, 5), (3, 4), (4, 3), (5, 2), (6, 1)",
+    )
+    .await?
+    .collect()
+    .await?;
+
+    let by_b = || vec![col("b").sort(true, false)];
+    let t = ctx.table("t").await?;
+    let dataframes = [
+        ctx.sql("SELECT a FROM t ORDER BY b LIMIT 3").await?,
+        ctx.sql("SELECT a FROM (SELECT a, b FROM t LIMIT 3) ORDER BY b")
+            .await?,
+        // Sort over Limit, with and without a Projection on top
+        t.clone().limit(0, Some(3))?.sort(by_b())?,
+        t.clone()
+            .limit(0, Some(3))?
+            .sort(by_b())?
+            .select_columns(&["a"])?,
+        // Limit over Sort, with a Projection above and below
+        t.clone()
+            .sort(by_b())?
+            .limit(0, Some(3))?
+            .select_columns(&["a"])?,
+        t.select_columns(&["a", "b"])?
+            .sort(by_b())?
+            .limit(1, Some(3))?,
+    ];
+    for df in dataframes {
+        let unparsed = plan_to_sql(df.logical_plan())?.to_string();
+        let plan = df.logical_plan().display_indent().to_string();
+        let expected = batches_to_sort_string(&df.collect().await?);
+        let actual = batches_to_sort_string(&ctx.sql(&unparsed).await?.collect().await?);
+        assert_eq!(actual, expected, "{plan} was unparsed as {unparsed}");
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_unparsed_left_join_with_range_and_null_keys_round_trip() -> Result<()> {
+    let ctx = SessionContext::new();
+    ctx.sql(
+        "CREATE TABLE a (k INT, ts INT) AS VALUES (1, 5), (1, 50), (NULL, 7), (2, 20), (3, 1)",
+    )
+    .await?
+    .collect()
+    .await?;
+    ctx.sql(
+        "CREATE TABLE b (k INT, lo INT, hi INT) AS VALUES (1, 0, 10), (NULL, 5, 9), (2, 0, 10), (NULL, 0, 1)",
+    )
+    .await?
+    .collect()
+    .await?;
+
+    let range = col("a.ts").between(col("b.lo"), col("b.hi"));
+    let null_equal_join =
+        LogicalPlanBuilder::from(ctx.table("a").await?.into_unoptimized_plan())
+            .join_detailed(
+                ctx.table("b").await?.into_unoptimized_plan(),
+                JoinType::Left,
+                (vec!["a.k"], vec!["b.k"]),
+                Some(range),
+                NullEquality::NullEqualsNull,
+            )?
+            .build()?;
+
+    let mut plans = vec![null_equal_join];
+    for sql in [
+        "SELECT * FROM a LEFT JOIN b ON a.k = b.k AND a.ts BETWEEN b.lo AND b.hi",
+        "SELECT * FROM a LEFT JOIN b ON a.k IS NOT DISTINCT FROM b.k AND a.ts BETWEEN b.lo AND b.hi",
+        "SELECT * FROM a LEFT JOIN b ON a.k = b.k AND a.ts > b.lo AND b.hi < 10",
+        "SELECT * FROM a RIGHT JOIN b ON a.k IS NOT DISTINCT FROM b.k AND a.ts < b.hi",
+        "SELECT * FROM a FULL JOIN b ON a.k = b.k AND a.ts BETWEEN b.lo AND b.hi",
+    ] {
+        plans.push(ctx.sql(sql).await?.into_unoptimized_plan());
+    }
+
+    // The optimizer turns the ON conditions into join keys and a join filter,
+    // both must be unparsed back into ON without changing the results
+    for plan in plans {
+        let optimized = ctx.state().optimize(&plan)?;
+        for plan in [plan, optimized] {
+            let unparsed = plan_to_sql(&plan)?.to_string();
+            let display = plan.display_indent().to_string();
+            let expected = batches_to_sort_string(
+                &ctx.execute_logical_plan(plan).await?.collect().await?,
+            );
+            let actual =
+                batches_to_sort_string(&ctx.sql(&unparsed).await?.collect().await?);
+            assert_eq!(actual, expected, "{display} was unparsed as {unparsed}");
+        }
+    }
+    Ok(())
+}
@@ -206,3 +206,26 @@ async fn ddl_can_not_be_planned_by_session_state() {
         "This feature is not implemented: Unsupported logical plan: DropTable"
     );
 }
+
+#[tokio::test]
+async fn set_variable_to_default_uses_session_config() {
+    let config = SessionConfig::new().with_batch_size(100);
+    let ctx = SessionContext::new_with_config(config);
+
+    ctx.sql("SET datafusion.execution.batch_size = 1")
+        .await
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+    assert_eq!(ctx.state().config().batch_size(), 1);
+
+    // The default is the value the session was created with
+    ctx.sql("SET datafusion.execution.batch_size TO DEFAULT")
+        .await
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+    assert_eq!(ctx.state().config().batch_size(), 100);
+}
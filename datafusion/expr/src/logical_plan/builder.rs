@@ -52,6 +52,7 @@ use arrow::compute::can_cast_types;
 use arrow::datatypes::{DataType, Field, Fields, Schema, SchemaRef};
 use datafusion_common::display::ToStringifiedPlan;
 use datafusion_common::file_options::file_type::FileType;
+use datafusion_common::tree_node::{TreeNode, TreeNodeRecursion};
 use datafusion_common::{
     exec_err, get_target_functional_dependencies, not_impl_err, plan_datafusion_err,
     plan_err, Column, Constraints, DFSchema, DFSchemaRef, DataFusionError, NullEquality,
@@ -303,29 +304,45 @@ impl LogicalPlanBuilder {
         let schema = DFSchema::empty();
         let mut fields = ValuesFields::new();
 
-        for j in 0..n_cols {
-            let mut common_type: Option<DataType> = None;
-            for (i, row) in values.iter().enumerate() {
-                let value = &row[j];
+        // Collect the distinct types of each column, with the first row they
+        // appear in, in a single pass over the rows. NULLs are skipped as they
+        // can be cast to any type.
+        let mut column_types: Vec<Vec<(usize, DataType)>> = vec![vec![]; n_cols];
+        for (i, row) in values.iter().enumerate() {
+            for (value, row_types) in row.iter().zip(column_types.iter_mut()) {
                 let data_type = value.get_type(&schema)?;
-                if data_type == DataType::Null {
-                    continue;
-                }
-
-                if let Some(prev_type) = common_type {
-                    // get common type of each column values.
-                    let data_types = vec![prev_type.clone(), data_type.clone()];
-                    let Some(new_type) = type_union_resolution(&data_types) else {
-                        return plan_err!("Inconsistent data type across values list at row {i} column {j}. Was {prev_type} but found {data_type}");
-                    };
-                    common_type = Some(new_type);
-                } else {
-                    common_type = Some(data_type);
+                if data_type != DataType::Null
+                    && !row_types.iter().any(|(_, t)| *t == data_type)
+                {
+                    row_types.push((i, data_type));
                 }
             }
-            // assuming common_type was not set, and no error, therefore the type should be NULL
-            // since the code loop skips NULL
-            fields.push(common_type.unwrap_or(DataType::Null), true);
+        }
+
+        for (j, row_types) in column_types.into_iter().enumerate() {
+            // Resolve the common type of the column across all rows at once, so
+            // the result does not depend on the order of the rows. Rows of a type
+            // that already appeared do not change the common type.
+            let data_types = row_types
+                .iter()
+                .map(|(_, data_type)| data_type.clone())
+                .collect::<Vec<_>>();
+            let common_type = if data_types.is_empty() {
+                // all values are NULL
+                DataType::Null
+            } else if let Some(common_type) = type_union_resolution(&data_types) {
+                common_type
+            } else {
+                // report the first row that has no common type with the rows before it
+                let k = (1..data_types.len())
+                    .find(|&k| type_union_resolution(&data_types[..=k]).is_none())
+                    .unwrap_or(data_types.len() - 1);
+                let prev_type = type_union_resolution(&data_types[..k])
+                    .unwrap_or_else(|| data_types[0].clone());
+                let (i, data_type) = &row_types[k];
+                return plan_err!("Inconsistent data type across values list at row {i} column {j}. Was {prev_type} but found {data_type}");
+            };
+            fields.push(common_type, true);
         }
 
         Self::infer_inner(values, fields, &schema)
@@ -802,6 +819,23 @@ impl LogicalPlanBuilder {
                     .cloned(),
             );
 
+            // Outer columns referenced by subqueries in the sort expressions
+            // must be available to the sort as well
+            sort.expr.apply(|expr| {
+                if let Expr::ScalarSubquery(subquery) = expr {
+                    for outer_ref in &subquery.outer_ref_columns {
+                        if let Expr::OuterReferenceColumn(_, c) = outer_ref {
+                            if !schema.has_column(c)
+                                && self.plan.exists(|p| Ok(p.schema().has_column(c)))?
+                            {
+                                missing_cols.insert(c.clone());
+                            }
+                        }
+                    }
+                }
+                Ok(TreeNodeRecursion::Continue)
+            })?;
+
             Ok(())
         })?;
 
@@ -1817,12 +1851,26 @@ fn project_with_validation(
     plan: LogicalPlan,
     expr: impl IntoIterator<Item = (impl Into<SelectExpr>, bool)>,
 ) -> Result<LogicalPlan> {
+    let projected_expr = expand_select_exprs(&plan, expr)?;
+    validate_unique_names("Projections", projected_expr.iter())?;
+
+    Projection::try_new(projected_expr, Arc::new(plan)).map(LogicalPlan::Projection)
+}
+
+/// Expand the wildcards in `expr` against the schema of `plan`, returning the
+/// expressions a projection of `expr` over `plan` computes. Like
+/// [`project_with_validation`], expressions with the flag set to true are
+/// normalized and columnized.
+pub fn expand_select_exprs(
+    plan: &LogicalPlan,
+    expr: impl IntoIterator<Item = (impl Into<SelectExpr>, bool)>,
+) -> Result<Vec<Expr>> {
     let mut projected_expr = vec![];
     for (e, validate) in expr {
         let e = e.into();
         match e {
             SelectExpr::Wildcard(opt) => {
-                let expanded = expand_wildcard(plan.schema(), &plan, Some(&opt))?;
+                let expanded = expand_wildcard(plan.schema(), plan, Some(&opt))?;
 
                 // If there is a REPLACE statement, replace that column with the given
                 // replace expression. Column name remains the same.
@@ -1835,7 +1883,7 @@ fn project_with_validation(
                 for e in expanded {
                     if validate {
                         projected_expr
-                            .push(columnize_expr(normalize_col(e, &plan)?, &plan)?)
+                            .push(columnize_expr(normalize_col(e, plan)?, plan)?)
                     } else {
                         projected_expr.push(e)
                     }
@@ -1856,7 +1904,7 @@ fn project_with_validation(
                 for e in expanded {
                     if validate {
                         projected_expr
-                            .push(columnize_expr(normalize_col(e, &plan)?, &plan)?)
+                            .push(columnize_expr(normalize_col(e, plan)?, plan)?)
                     } else {
                         projected_expr.push(e)
                     }
@@ -1864,16 +1912,14 @@ fn project_with_validation(
             }
             SelectExpr::Expression(e) => {
                 if validate {
-                    projected_expr.push(columnize_expr(normalize_col(e, &plan)?, &plan)?)
+                    projected_expr.push(columnize_expr(normalize_col(e, plan)?, plan)?)
                 } else {
                     projected_expr.push(e)
                 }
             }
         }
     }
-    validate_unique_names("Projections", projected_expr.iter())?;
-
-    Projection::try_new(projected_expr, Arc::new(plan)).map(LogicalPlan::Projection)
+    Ok(projected_expr)
 }
 
 /// If there is a REPLACE statement in the projected expression in the form of
@@ -2145,6 +2191,7 @@ mod tests {
     use crate::{col, expr, expr_fn::exists, in_subquery, lit, scalar_subquery};
 
     use crate::test::function_stub::sum;
+    use datafusion_common::scalar::ScalarStructBuilder;
     use datafusion_common::{Constraint, RecursionUnnestOption, SchemaError};
     use insta::assert_snapshot;
 
@@ -2382,7 +2429,7 @@ mod tests {
 
         match plan {
             Err(DataFusionError::SchemaError(err, _)) => {
-                if let SchemaError::AmbiguousReference { field } = *err {
+                if let SchemaError::AmbiguousReference { field, .. } = *err {
                     let Column {
                         relation,
                         name,
@@ -2787,4 +2834,48 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn plan_builder_values_common_type() -> Result<()> {
+        let plan = LogicalPlanBuilder::values(vec![
+            vec![lit(1i64), lit("a")],
+            vec![lit(ScalarValue::Null), lit("b")],
+            vec![lit(2.5f64), lit("c")],
+        ])?
+        .build()?;
+
+        assert_snapshot!(plan, @r#"Values: (CAST(Int64(1) AS Float64), Utf8("a")), (Float64(NULL), Utf8("b")), (Float64(2.5), Utf8("c"))"#);
+        let data_types = plan
+            .schema()
+            .fields()
+            .iter()
+            .map(|f| f.data_type().clone())
+            .collect::<Vec<_>>();
+        assert_eq!(data_types, vec![DataType::Float64, DataType::Utf8]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn plan_builder_values_no_common_type() -> Result<()> {
+        let struct_value = ScalarStructBuilder::new()
+            .with_scalar(
+                Field::new("x", DataType::Int32, false),
+                ScalarValue::Int32(Some(1)),
+            )
+            .build()?;
+        let err = LogicalPlanBuilder::values(vec![
+            vec![lit("a")],
+            vec![lit("b")],
+            vec![lit(struct_value)],
+        ])
+        .unwrap_err();
+
+        assert_snapshot!(
+            err.strip_backtrace(),
+            @"Error during planning: Inconsistent data type across values list at row 2 column 0. Was Utf8 but found Struct(x Int32)"
+        );
+
+        Ok(())
+    }
 }
@@ -188,8 +188,17 @@ impl DdlStatement {
                     DdlStatement::CreateFunction(CreateFunction { name, .. }) => {
                         write!(f, "CreateFunction: name {name:?}")
                     }
-                    DdlStatement::DropFunction(DropFunction { name, .. }) => {
-                        write!(f, "DropFunction: name {name:?}")
+                    DdlStatement::DropFunction(DropFunction { name, args, .. }) => {
+                        write!(f, "DropFunction: name {name:?}")?;
+                        if let Some(args) = args {
+                            let args = args
+                                .iter()
+                                .map(ToString::to_string)
+                                .collect::<Vec<_>>()
+                                .join(", ");
+                            write!(f, " args ({args})")?;
+                        }
+                        Ok(())
                     }
                 }
             }
@@ -556,6 +565,9 @@ impl<'a> TreeNodeContainer<'a, Expr> for CreateFunctionBody {
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
 pub struct DropFunction {
     pub name: String,
+    /// The argument types of the function, if given to tell apart functions
+    /// of the same name
+    pub args: Option<Vec<DataType>>,
     pub if_exists: bool,
     pub schema: DFSchemaRef,
 }
@@ -563,7 +575,10 @@ pub struct DropFunction {
 impl PartialOrd for DropFunction {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         match self.name.partial_cmp(&other.name) {
-            Some(Ordering::Equal) => self.if_exists.partial_cmp(&other.if_exists),
+            Some(Ordering::Equal) => match self.args.partial_cmp(&other.args) {
+                Some(Ordering::Equal) => self.if_exists.partial_cmp(&other.if_exists),
+                cmp => cmp,
+            },
             cmp => cmp,
         }
     }
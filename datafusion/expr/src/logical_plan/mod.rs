@@ -42,7 +42,8 @@ pub use plan::{
     DistinctOn, EmptyRelation, Explain, ExplainFormat, ExplainOption, Extension,
     FetchType, Filter, Join, JoinConstraint, JoinType, Limit, LogicalPlan, Partitioning,
     PlanType, Projection, RecursiveQuery, Repartition, SkipType, Sort, StringifiedPlan,
-    Subquery, SubqueryAlias, TableScan, ToStringifiedPlan, Union, Unnest, Values, Window,
+    Subquery, SubqueryAlias, TableHints, TableScan, ToStringifiedPlan, Union, Unnest,
+    Values, Window,
 };
 pub use statement::{
     Deallocate, Execute, Prepare, SetVariable, Statement, TransactionAccessMode,
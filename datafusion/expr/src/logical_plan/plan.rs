@@ -31,7 +31,10 @@ use super::invariants::{
 };
 use super::DdlStatement;
 use crate::builder::{change_redundant_column, unnest_with_options};
-use crate::expr::{Placeholder, Sort as SortExpr, WindowFunction, WindowFunctionParams};
+use crate::expr::{
+    Exists, InSubquery, Placeholder, Sort as SortExpr, WindowFunction,
+    WindowFunctionParams,
+};
 use crate::expr_rewriter::{
     create_col_from_scalar_expr, normalize_cols, normalize_sorts, NamePreserver,
 };
@@ -61,6 +64,7 @@ use datafusion_common::{
     TableReference, UnnestOptions,
 };
 use indexmap::IndexSet;
+use sqlparser::ast::{Expr as SQLExpr, TableIndexHints};
 
 // backwards compatibility
 use crate::display::PgJsonVisitor;
@@ -418,15 +422,38 @@ impl LogicalPlan {
 
     /// Returns all the out reference(correlated) expressions (recursively) in the current
     /// logical plan nodes and all its descendant nodes.
+    ///
+    /// This includes the out references of the subqueries in the expressions of these
+    /// nodes that are not bound by the inputs of the node, as they refer past the
+    /// current plan to one of its enclosing queries.
     pub fn all_out_ref_exprs(self: &LogicalPlan) -> Vec<Expr> {
         let mut exprs = vec![];
+        let inputs = self.inputs();
         self.apply_expressions(|e| {
             find_out_reference_exprs(e).into_iter().for_each(|e| {
                 if !exprs.contains(&e) {
                     exprs.push(e)
                 }
             });
-            Ok(TreeNodeRecursion::Continue)
+            e.apply(|e| {
+                let (Expr::Exists(Exists { subquery, .. })
+                | Expr::InSubquery(InSubquery { subquery, .. })
+                | Expr::ScalarSubquery(subquery)) = e
+                else {
+                    return Ok(TreeNodeRecursion::Continue);
+                };
+                for out_ref in &subquery.outer_ref_columns {
+                    let Expr::OuterReferenceColumn(_, column) = out_ref else {
+                        continue;
+                    };
+                    let bound =
+                        inputs.iter().any(|input| input.schema().has_column(column));
+                    if !bound && !exprs.contains(out_ref) {
+                        exprs.push(out_ref.clone())
+                    }
+                }
+                Ok(TreeNodeRecursion::Continue)
+            })
         })
         // closure always returns OK
         .unwrap();
@@ -2522,6 +2549,29 @@ pub struct TableScan {
     pub filters: Vec<Expr>,
     /// Optional number of rows to read
     pub fetch: Option<usize>,
+    /// The hints given for the table in the SQL text
+    pub hints: TableHints,
+}
+
+/// The hints given for a table in the SQL text, such as the MySQL index hints
+/// of `FROM t USE INDEX (idx_a)` or the table hints of `FROM t WITH (NOLOCK)`.
+///
+/// The hints are kept as written and are not validated. Optimizer hints
+/// written in comments, such as `/*+ BROADCAST(dim) */`, are not captured, as
+/// the SQL tokenizer drops comments.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Hash)]
+pub struct TableHints {
+    /// The index hints, e.g. `USE INDEX (idx_a)`
+    pub index_hints: Vec<TableIndexHints>,
+    /// The hints of a `WITH (...)` clause, e.g. `NOLOCK`
+    pub with_hints: Vec<SQLExpr>,
+}
+
+impl TableHints {
+    /// Returns true if no hint is given
+    pub fn is_empty(&self) -> bool {
+        self.index_hints.is_empty() && self.with_hints.is_empty()
+    }
 }
 
 impl Debug for TableScan {
@@ -2533,6 +2583,7 @@ impl Debug for TableScan {
             .field("projected_schema", &self.projected_schema)
             .field("filters", &self.filters)
             .field("fetch", &self.fetch)
+            .field("hints", &self.hints)
             .finish_non_exhaustive()
     }
 }
@@ -2544,6 +2595,7 @@ impl PartialEq for TableScan {
             && self.projected_schema == other.projected_schema
             && self.filters == other.filters
             && self.fetch == other.fetch
+            && self.hints == other.hints
     }
 }
 
@@ -2563,18 +2615,22 @@ impl PartialOrd for TableScan {
             pub filters: &'a Vec<Expr>,
             /// Optional number of rows to read
             pub fetch: &'a Option<usize>,
+            /// The hints given for the table in the SQL text
+            pub hints: &'a TableHints,
         }
         let comparable_self = ComparableTableScan {
             table_name: &self.table_name,
             projection: &self.projection,
             filters: &self.filters,
             fetch: &self.fetch,
+            hints: &self.hints,
         };
         let comparable_other = ComparableTableScan {
             table_name: &other.table_name,
             projection: &other.projection,
             filters: &other.filters,
             fetch: &other.fetch,
+            hints: &other.hints,
         };
         comparable_self.partial_cmp(&comparable_other)
     }
@@ -2587,6 +2643,7 @@ impl Hash for TableScan {
         self.projected_schema.hash(state);
         self.filters.hash(state);
         self.fetch.hash(state);
+        self.hints.hash(state);
     }
 }
 
@@ -2640,8 +2697,15 @@ impl TableScan {
             projected_schema,
             filters,
             fetch,
+            hints: TableHints::default(),
         })
     }
+
+    /// Set the hints given for the table in the SQL text
+    pub fn with_hints(mut self, hints: TableHints) -> Self {
+        self.hints = hints;
+        self
+    }
 }
 
 // Repartition the plan based on a partitioning scheme.
@@ -3247,10 +3311,12 @@ impl PartialOrd for Extension {
 /// Produces the first `n` tuples from its input and discards the rest.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Hash)]
 pub struct Limit {
-    /// Number of rows to skip before fetch
+    /// Number of rows to skip before fetch. See [`Self::get_skip_type`] for
+    /// the number of rows of a constant skip.
     pub skip: Option<Box<Expr>>,
     /// Maximum number of rows to fetch,
-    /// None means fetching all rows
+    /// None means fetching all rows. See [`Self::get_fetch_type`] for the
+    /// number of rows of a constant fetch.
     pub fetch: Option<Box<Expr>>,
     /// The logical plan
     pub input: Arc<LogicalPlan>,
@@ -4823,6 +4889,7 @@ mod tests {
             projected_schema: Arc::clone(&schema),
             filters: vec![],
             fetch: None,
+            hints: TableHints::default(),
         }));
         let col = schema.field_names()[0].clone();
 
@@ -4853,6 +4920,7 @@ mod tests {
             projected_schema: Arc::clone(&unique_schema),
             filters: vec![],
             fetch: None,
+            hints: TableHints::default(),
         }));
         let col = schema.field_names()[0].clone();
 
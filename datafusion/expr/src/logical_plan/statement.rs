@@ -102,10 +102,16 @@ impl Statement {
                     }) => {
                         write!(f, "TransactionEnd: {conclusion:?} chain:={chain}")
                     }
-                    Statement::SetVariable(SetVariable {
-                        variable, value, ..
-                    }) => {
-                        write!(f, "SetVariable: set {variable:?} to {value:?}")
+                    Statement::SetVariable(SetVariable { variable, values }) => {
+                        match values.as_slice() {
+                            [] => write!(f, "SetVariable: set {variable:?} to default"),
+                            [value] => {
+                                write!(f, "SetVariable: set {variable:?} to {value:?}")
+                            }
+                            values => {
+                                write!(f, "SetVariable: set {variable:?} to {values:?}")
+                            }
+                        }
                     }
                     Statement::Prepare(Prepare {
                         name, data_types, ..
@@ -180,8 +186,10 @@ pub struct TransactionEnd {
 pub struct SetVariable {
     /// The variable name
     pub variable: String,
-    /// The value to set
-    pub value: String,
+    /// The values to set, more than one for a list such as
+    /// `SET search_path = a, b`. Empty for `SET ... TO DEFAULT`, which resets
+    /// the variable to the default of the session
+    pub values: Vec<String>,
 }
 
 /// Prepare a statement but do not execute it. Prepare statements can have 0 or more
@@ -23,13 +23,13 @@ use std::sync::Arc;
 use arrow::datatypes::{DataType, Field, SchemaRef};
 use datafusion_common::{
     config::ConfigOptions, file_options::file_type::FileType, not_impl_err, DFSchema,
-    Result, TableReference,
+    Result, ScalarValue, TableReference,
 };
 use sqlparser::ast::{self, NullTreatment};
 
 use crate::{
-    AggregateUDF, Expr, GetFieldAccess, ScalarUDF, SortExpr, TableSource, WindowFrame,
-    WindowFunctionDefinition, WindowUDF,
+    AggregateUDF, Expr, GetFieldAccess, LogicalPlan, ScalarUDF, SortExpr, TableSource,
+    WindowFrame, WindowFunctionDefinition, WindowUDF,
 };
 
 /// Provides the `SQL` query planner meta-data about tables and
@@ -41,6 +41,21 @@ pub trait ContextProvider {
     /// Returns a table by reference, if it exists
     fn get_table_source(&self, name: TableReference) -> Result<Arc<dyn TableSource>>;
 
+    /// Returns a table by reference as of a version, if it exists
+    ///
+    /// This is used to plan time travel queries such as
+    /// `SELECT * FROM t FOR SYSTEM_TIME AS OF TIMESTAMP '2024-01-01'`, where
+    /// `version` is the value of the `AS OF` expression, e.g. a timestamp or a
+    /// version number. The returned source should report the version it reads
+    /// with [`TableSource::version`].
+    fn get_table_source_with_version(
+        &self,
+        _name: TableReference,
+        _version: ScalarValue,
+    ) -> Result<Arc<dyn TableSource>> {
+        not_impl_err!("Time travel queries are not supported")
+    }
+
     /// Return the type of a file based on its extension (e.g. `.parquet`)
     ///
     /// This is used to plan `COPY` statements
@@ -57,6 +72,27 @@ pub trait ContextProvider {
         not_impl_err!("Table Functions are not supported")
     }
 
+    /// Return the names of the parameters of a table function, used to place
+    /// named arguments such as `range(start => 1, stop => 10)` at the position
+    /// of their parameter
+    fn get_table_function_parameter_names(&self, _name: &str) -> Result<Vec<String>> {
+        not_impl_err!("Named arguments of table functions are not supported")
+    }
+
+    /// Return how many parameters of a table function, from the first, must be
+    /// given when it is called with named arguments. The default is all of
+    /// [`Self::get_table_function_parameter_names`]
+    fn get_table_function_required_parameter_count(&self, name: &str) -> Result<usize> {
+        Ok(self.get_table_function_parameter_names(name)?.len())
+    }
+
+    /// Return the declared parameter types of the prepared statement `name`,
+    /// if it exists. Used to cast the arguments of an `EXECUTE` statement to
+    /// those types.
+    fn get_prepared_statement_data_types(&self, _name: &str) -> Option<Vec<DataType>> {
+        None
+    }
+
     /// Provides an intermediate table that is used to store the results of a CTE during execution
     ///
     /// CTE stands for "Common Table Expression"
@@ -114,6 +150,62 @@ pub trait ContextProvider {
 
     /// Return all window function names
     fn udwf_names(&self) -> Vec<String>;
+
+    /// Return all functions known to this provider, used to answer
+    /// `SHOW FUNCTIONS` when no `information_schema` is available
+    fn registered_functions(&self) -> Vec<RegisteredFunction> {
+        vec![]
+    }
+
+    /// Return the plan of a `SHOW TABLES`, `SHOW COLUMNS` or `DESCRIBE`
+    /// statement, if this provider answers it
+    ///
+    /// By default, `SHOW TABLES` and `SHOW COLUMNS` query the
+    /// `information_schema` tables, and `DESCRIBE` describes the schema of the
+    /// table source. Providers with catalogs that are not listed in the
+    /// `information_schema` can supply their own plan, e.g. a scan of a
+    /// table of their catalog. Returning `None` keeps the default planning.
+    fn plan_show_statement(
+        &self,
+        _kind: ShowStatementKind,
+        _args: &ShowStatementArgs,
+    ) -> Result<Option<LogicalPlan>> {
+        Ok(None)
+    }
+}
+
+/// The kind of a statement passed to [`ContextProvider::plan_show_statement`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ShowStatementKind {
+    /// `SHOW TABLES`
+    Tables,
+    /// `SHOW COLUMNS FROM <table>`
+    Columns,
+    /// `DESCRIBE <table>`
+    Describe,
+}
+
+/// The arguments of a statement passed to
+/// [`ContextProvider::plan_show_statement`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ShowStatementArgs {
+    /// The table of `SHOW COLUMNS` and `DESCRIBE`, as resolved by the planner
+    pub table: Option<TableReference>,
+    /// The pattern of a `LIKE` filter, e.g. `a%` for `SHOW TABLES LIKE 'a%'`
+    pub pattern: Option<String>,
+    /// Whether `SHOW FULL COLUMNS` or `SHOW EXTENDED COLUMNS` was given
+    pub full: bool,
+}
+
+/// A function enumerated by [`ContextProvider::registered_functions`]
+#[derive(Debug, Clone)]
+pub enum RegisteredFunction {
+    /// A scalar function
+    Scalar(Arc<ScalarUDF>),
+    /// An aggregate function
+    Aggregate(Arc<AggregateUDF>),
+    /// A window function
+    Window(Arc<WindowUDF>),
 }
 
 /// Customize planning of SQL AST expressions to [`Expr`]s
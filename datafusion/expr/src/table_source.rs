@@ -20,7 +20,7 @@
 use crate::{Expr, LogicalPlan};
 
 use arrow::datatypes::SchemaRef;
-use datafusion_common::{Constraints, Result};
+use datafusion_common::{Constraints, Result, ScalarValue};
 
 use std::{any::Any, borrow::Cow};
 
@@ -129,4 +129,22 @@ pub trait TableSource: Sync + Send {
     fn get_column_default(&self, _column: &str) -> Option<&Expr> {
         None
     }
+
+    /// Get the version of the table this source reads, if it reads the table
+    /// as of a version rather than its current state.
+    ///
+    /// See [`ContextProvider::get_table_source_with_version`].
+    ///
+    /// [`ContextProvider::get_table_source_with_version`]: crate::planner::ContextProvider::get_table_source_with_version
+    fn version(&self) -> Option<&ScalarValue> {
+        None
+    }
+
+    /// Get the names of the columns the table is partitioned by, if any.
+    ///
+    /// Inserts into a partitioned table may assign these columns in a
+    /// `PARTITION` clause, e.g. `INSERT INTO t PARTITION (dt = '2024-01-01')`.
+    fn partition_columns(&self) -> Vec<&str> {
+        vec![]
+    }
 }
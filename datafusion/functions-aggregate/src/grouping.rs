@@ -41,7 +41,7 @@ make_udaf_expr_and_func!(
 
 #[user_doc(
     doc_section(label = "General Functions"),
-    description = "Returns 1 if the data is aggregated across the specified column, or 0 if it is not aggregated in the result set.",
+    description = "Returns 1 if the data is aggregated across the specified column, or 0 if it is not aggregated in the result set. With multiple arguments, returns an integer bitmask of these values where the first argument is the most significant bit.",
     syntax_example = "grouping(expression)",
     sql_example = r#"```sql
 > SELECT column_name, GROUPING(column_name) AS group_column
@@ -63,6 +63,7 @@ make_udaf_expr_and_func!(
 #[derive(PartialEq, Eq, Hash)]
 pub struct Grouping {
     signature: Signature,
+    aliases: Vec<String>,
 }
 
 impl fmt::Debug for Grouping {
@@ -85,6 +86,7 @@ impl Grouping {
     pub fn new() -> Self {
         Self {
             signature: Signature::variadic_any(Volatility::Immutable),
+            aliases: vec![String::from("grouping_id")],
         }
     }
 }
@@ -102,6 +104,10 @@ impl AggregateUDFImpl for Grouping {
         &self.signature
     }
 
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+
     fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
         Ok(DataType::Int32)
     }
@@ -483,6 +483,14 @@ impl TableProvider for GenerateSeriesTable {
     }
 }
 
+/// Names of the parameters of `generate_series` and `range`, for calls with
+/// named arguments such as `range(start => 1, stop => 10)`
+const GENERATE_SERIES_PARAMETER_NAMES: [&str; 3] = ["start", "stop", "step"];
+
+/// With named arguments both `start` and `stop` must be given, as a single
+/// argument is otherwise read as the `stop` of the series
+const GENERATE_SERIES_REQUIRED_PARAMETER_COUNT: usize = 2;
+
 #[derive(Debug)]
 struct GenerateSeriesFuncImpl {
     name: &'static str,
@@ -763,6 +771,14 @@ impl TableFunctionImpl for GenerateSeriesFunc {
         };
         impl_func.call(exprs)
     }
+
+    fn parameter_names(&self) -> Vec<&str> {
+        GENERATE_SERIES_PARAMETER_NAMES.to_vec()
+    }
+
+    fn required_parameter_count(&self) -> usize {
+        GENERATE_SERIES_REQUIRED_PARAMETER_COUNT
+    }
 }
 
 #[derive(Debug)]
@@ -776,4 +792,12 @@ impl TableFunctionImpl for RangeFunc {
         };
         impl_func.call(exprs)
     }
+
+    fn parameter_names(&self) -> Vec<&str> {
+        GENERATE_SERIES_PARAMETER_NAMES.to_vec()
+    }
+
+    fn required_parameter_count(&self) -> usize {
+        GENERATE_SERIES_REQUIRED_PARAMETER_COUNT
+    }
 }
@@ -18,7 +18,7 @@
 //! Analyzed rule to replace TableScan references
 //! such as DataFrames and Views and inlines the LogicalPlan.
 
-use std::cmp::Ordering;
+use std::cmp::{Ordering, Reverse};
 use std::collections::HashMap;
 use std::sync::Arc;
 
@@ -171,7 +171,11 @@ fn validate_args(
         plan_err!(
             "Argument {} to grouping function is not in grouping columns {}",
             expr,
-            group_by_expr.keys().map(|e| e.to_string()).join(", ")
+            group_by_expr
+                .iter()
+                .sorted_by_key(|(_, idx)| Reverse(**idx))
+                .map(|(e, _)| e.to_string())
+                .join(", ")
         )
     } else {
         Ok(())
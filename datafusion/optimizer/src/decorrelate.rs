@@ -33,8 +33,8 @@ use datafusion_expr::utils::{
     collect_subquery_cols, conjunction, find_join_exprs, split_conjunction,
 };
 use datafusion_expr::{
-    expr, lit, BinaryExpr, Cast, EmptyRelation, Expr, FetchType, LogicalPlan,
-    LogicalPlanBuilder, Operator,
+    expr, lit, BinaryExpr, Cast, EmptyRelation, Expr, FetchType, Limit, LogicalPlan,
+    LogicalPlanBuilder, Operator, Projection, SkipType, Sort, SortExpr,
 };
 use datafusion_physical_expr::execution_props::ExecutionProps;
 
@@ -51,6 +51,8 @@ pub struct PullUpCorrelatedExpr {
     pub in_predicate_opt: Option<Expr>,
     /// Is this an Exists(Not Exists) SubQuery. Defaults to **FALSE**
     pub exists_sub_query: bool,
+    /// Is this a scalar SubQuery. Defaults to **FALSE**
+    pub scalar_sub_query: bool,
     /// Can the correlated expressions be pulled up. Defaults to **TRUE**
     pub can_pull_up: bool,
     /// Indicates if we encounter any correlated expression that can not be pulled up
@@ -89,6 +91,7 @@ impl PullUpCorrelatedExpr {
             correlated_subquery_cols_map: HashMap::new(),
             in_predicate_opt: None,
             exists_sub_query: false,
+            scalar_sub_query: false,
             can_pull_up: true,
             can_pull_over_aggregation: true,
             need_handle_count_bug: false,
@@ -117,6 +120,12 @@ impl PullUpCorrelatedExpr {
         self.exists_sub_query = exists_sub_query;
         self
     }
+
+    /// Set if this is a scalar SubQuery
+    pub fn with_scalar_sub_query(mut self, scalar_sub_query: bool) -> Self {
+        self.scalar_sub_query = scalar_sub_query;
+        self
+    }
 }
 
 /// Used to indicate the unmatched rows from the inner(subquery) table after the left out Join
@@ -136,6 +145,26 @@ impl TreeNodeRewriter for PullUpCorrelatedExpr {
     fn f_down(&mut self, plan: LogicalPlan) -> Result<Transformed<LogicalPlan>> {
         match plan {
             LogicalPlan::Filter(_) => Ok(Transformed::no(plan)),
+            // The first row of an ordered scalar subquery, see `pull_up_first_row`
+            LogicalPlan::Sort(Sort { fetch: Some(1), .. })
+                if self.scalar_sub_query && !plan.contains_outer_reference() =>
+            {
+                Ok(Transformed::no(plan))
+            }
+            LogicalPlan::Limit(limit)
+                if self.scalar_sub_query && is_first_row_limit(&limit)? =>
+            {
+                // Merge the limit into the sort below it, so they are pulled
+                // up together
+                match push_first_row_limit_into_sort(Arc::clone(&limit.input))? {
+                    Some(input) => {
+                        let mut input = self.f_down(input)?;
+                        input.transformed = true;
+                        Ok(input)
+                    }
+                    None => Ok(Transformed::no(LogicalPlan::Limit(limit))),
+                }
+            }
             LogicalPlan::Union(_) | LogicalPlan::Sort(_) | LogicalPlan::Extension(_) => {
                 let plan_hold_outer = !plan.all_out_ref_exprs().is_empty();
                 if plan_hold_outer {
@@ -353,6 +382,20 @@ impl TreeNodeRewriter for PullUpCorrelatedExpr {
                 }
                 Ok(Transformed::no(plan))
             }
+            LogicalPlan::Sort(Sort {
+                expr,
+                input,
+                fetch: Some(1),
+            }) if self.scalar_sub_query && !self.join_filters.is_empty() => {
+                self.pull_up_first_row(&plan, input, Some(expr))
+            }
+            LogicalPlan::Limit(limit)
+                if self.scalar_sub_query
+                    && !self.join_filters.is_empty()
+                    && is_first_row_limit(limit)? =>
+            {
+                self.pull_up_first_row(&plan, &limit.input, None)
+            }
             LogicalPlan::Limit(limit) => {
                 let input_expr_map = self
                     .collected_count_expr_map
@@ -385,6 +428,57 @@ impl TreeNodeRewriter for PullUpCorrelatedExpr {
 }
 
 impl PullUpCorrelatedExpr {
+    /// Rewrite the first row of a correlated scalar subquery, e.g.
+    /// `(SELECT x FROM t WHERE t.k = outer.k ORDER BY t.y LIMIT 1)`, into a
+    /// `DISTINCT ON` the correlated columns, ordered by the correlated columns
+    /// followed by the original ordering. This keeps the first row for each
+    /// value of the correlated columns, rather than a single row overall, so
+    /// the subquery can be joined to the outer query.
+    fn pull_up_first_row(
+        &mut self,
+        plan: &LogicalPlan,
+        input: &LogicalPlan,
+        sort_expr: Option<&Vec<SortExpr>>,
+    ) -> Result<Transformed<LogicalPlan>> {
+        let mut local_correlated_cols = BTreeSet::new();
+        collect_local_correlated_cols(
+            plan,
+            &self.correlated_subquery_cols_map,
+            &mut local_correlated_cols,
+        );
+        // The first row for each value of the correlated columns is only the
+        // first row for an outer row if the subquery is correlated by equality
+        if !self.can_pull_over_aggregation || local_correlated_cols.is_empty() {
+            self.can_pull_up = false;
+            return Ok(Transformed::no(plan.clone()));
+        }
+
+        let on_expr = local_correlated_cols
+            .into_iter()
+            .map(Expr::Column)
+            .collect::<Vec<_>>();
+        let sort_expr = on_expr
+            .iter()
+            .map(|expr| expr.clone().sort(true, false))
+            .chain(sort_expr.into_iter().flatten().cloned())
+            .collect();
+        let select_expr = input
+            .schema()
+            .columns()
+            .into_iter()
+            .map(Expr::Column)
+            .collect();
+        let new_plan = LogicalPlanBuilder::from(input.clone())
+            .distinct_on(on_expr, select_expr, Some(sort_expr))?
+            .build()?;
+
+        if let Some(input_map) = self.collected_count_expr_map.get(input).cloned() {
+            self.collected_count_expr_map
+                .insert(new_plan.clone(), input_map);
+        }
+        Ok(Transformed::yes(new_plan))
+    }
+
     fn collect_missing_exprs(
         &self,
         exprs: &[Expr],
@@ -418,6 +512,18 @@ impl PullUpCorrelatedExpr {
     }
 }
 
+/// Returns true if the subquery `plan` can not be decorrelated by joining it
+/// with `outer`, because it is correlated with a query enclosing `outer`
+pub(crate) fn references_enclosing_query(
+    plan: &LogicalPlan,
+    outer: &LogicalPlan,
+) -> bool {
+    plan.all_out_ref_exprs().iter().any(|expr| match expr {
+        Expr::OuterReferenceColumn(_, column) => !outer.schema().has_column(column),
+        _ => false,
+    })
+}
+
 fn can_pullup_over_aggregation(expr: &Expr) -> bool {
     if let Expr::BinaryExpr(BinaryExpr {
         left,
@@ -445,6 +551,38 @@ fn can_pullup_over_aggregation(expr: &Expr) -> bool {
     }
 }
 
+/// Returns true if `limit` keeps only the first row of its input
+fn is_first_row_limit(limit: &Limit) -> Result<bool> {
+    Ok(matches!(limit.get_skip_type()?, SkipType::Literal(0))
+        && matches!(limit.get_fetch_type()?, FetchType::Literal(Some(1))))
+}
+
+/// Sets the fetch of the [`Sort`] below `plan`, looking through projections,
+/// to keep only the first row. Returns `None` if there is no such sort.
+fn push_first_row_limit_into_sort(plan: Arc<LogicalPlan>) -> Result<Option<LogicalPlan>> {
+    match Arc::unwrap_or_clone(plan) {
+        LogicalPlan::Sort(sort) if sort.fetch != Some(0) => {
+            Ok(Some(LogicalPlan::Sort(Sort {
+                fetch: Some(1),
+                ..sort
+            })))
+        }
+        LogicalPlan::Projection(projection) => {
+            let Some(input) = push_first_row_limit_into_sort(projection.input)? else {
+                return Ok(None);
+            };
+            Ok(Some(LogicalPlan::Projection(
+                Projection::try_new_with_schema(
+                    projection.expr,
+                    Arc::new(input),
+                    projection.schema,
+                )?,
+            )))
+        }
+        _ => Ok(None),
+    }
+}
+
 fn collect_local_correlated_cols(
     plan: &LogicalPlan,
     all_cols_map: &HashMap<LogicalPlan, BTreeSet<Column>>,
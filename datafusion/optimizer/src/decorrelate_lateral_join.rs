@@ -21,8 +21,9 @@ use std::collections::BTreeSet;
 
 use crate::decorrelate::PullUpCorrelatedExpr;
 use crate::optimizer::ApplyOrder;
+use crate::utils::replace_qualified_name;
 use crate::{OptimizerConfig, OptimizerRule};
-use datafusion_expr::{lit, Join};
+use datafusion_expr::{lit, Expr, Join, SubqueryAlias};
 
 use datafusion_common::tree_node::{
     Transformed, TransformedResult, TreeNode, TreeNodeRecursion,
@@ -99,14 +100,28 @@ fn rewrite_internal(join: Join) -> Result<Transformed<LogicalPlan>> {
         }
     }
 
-    let LogicalPlan::Subquery(subquery) = join.right.as_ref() else {
-        return Ok(Transformed::no(LogicalPlan::Join(join)));
+    // An aliased lateral subquery, e.g. `LATERAL (SELECT ...) AS s`, is planned as
+    // the alias over the correlated subquery
+    let (subquery, alias) = match join.right.as_ref() {
+        LogicalPlan::Subquery(subquery) => (subquery, None),
+        LogicalPlan::SubqueryAlias(SubqueryAlias { input, alias, .. }) => {
+            match input.as_ref() {
+                LogicalPlan::Subquery(subquery) => (subquery, Some(alias.clone())),
+                _ => return Ok(Transformed::no(LogicalPlan::Join(join))),
+            }
+        }
+        _ => return Ok(Transformed::no(LogicalPlan::Join(join))),
     };
 
     if join.join_type != JoinType::Inner {
         return Ok(Transformed::no(LogicalPlan::Join(join)));
     }
     let subquery_plan = subquery.subquery.as_ref();
+    // Decorrelate the lateral joins nested in the subquery first, so that only
+    // the correlation with the left side of this join is pulled up
+    if subquery_plan.exists(|p| Ok(matches!(p, LogicalPlan::Subquery(_))))? {
+        return Ok(Transformed::no(LogicalPlan::Join(join)));
+    }
     let mut pull_up = PullUpCorrelatedExpr::new().with_need_handle_count_bug(true);
     let rewritten_subquery = subquery_plan.clone().rewrite(&mut pull_up).data()?;
     if !pull_up.can_pull_up {
@@ -119,25 +134,45 @@ fn rewrite_internal(join: Join) -> Result<Transformed<LogicalPlan>> {
         .values()
         .for_each(|cols| all_correlated_cols.extend(cols.clone()));
     let join_filter_opt = conjunction(pull_up.join_filters);
-    let join_filter = match join_filter_opt {
+    let mut join_filter = match join_filter_opt {
         Some(join_filter) => join_filter,
         None => lit(true),
     };
+    // The pulled up filters reference the columns of the subquery, which are
+    // requalified by the alias
+    let rewritten_subquery = match alias {
+        Some(alias) => {
+            join_filter = replace_qualified_name(
+                join_filter,
+                &all_correlated_cols,
+                &alias.to_string(),
+            )?;
+            LogicalPlanBuilder::from(rewritten_subquery)
+                .alias(alias)?
+                .build()?
+        }
+        None => rewritten_subquery,
+    };
     // -- inner join but the right side always has one row, we need to rewrite it to a left join
     // SELECT * FROM t0, LATERAL (SELECT sum(v1) FROM t1 WHERE t0.v0 = t1.v0);
     // -- inner join but the right side number of rows is related to the filter (join) condition, so keep inner join.
     // SELECT * FROM t0, LATERAL (SELECT * FROM t1 WHERE t0.v0 = t1.v0);
-    let new_plan = LogicalPlanBuilder::from(join.left)
-        .join_on(
-            rewritten_subquery,
-            if pull_up.pulled_up_scalar_agg {
-                JoinType::Left
-            } else {
-                JoinType::Inner
-            },
-            Some(join_filter),
-        )?
-        .build()?;
+    let mut new_plan = LogicalPlanBuilder::from(join.left).join_on(
+        rewritten_subquery,
+        if pull_up.pulled_up_scalar_agg {
+            JoinType::Left
+        } else {
+            JoinType::Inner
+        },
+        Some(join_filter),
+    )?;
+    // The columns pulled up into the subquery for the join filter are not part
+    // of the output of the lateral join
+    if new_plan.schema().fields().len() != join.schema.fields().len() {
+        new_plan =
+            new_plan.project(join.schema.columns().into_iter().map(Expr::Column))?;
+    }
+    let new_plan = new_plan.build()?;
     // TODO: handle count(*) bug
     Ok(Transformed::new(new_plan, true, TreeNodeRecursion::Jump))
 }
@@ -20,7 +20,7 @@ use std::collections::BTreeSet;
 use std::ops::Deref;
 use std::sync::Arc;
 
-use crate::decorrelate::PullUpCorrelatedExpr;
+use crate::decorrelate::{references_enclosing_query, PullUpCorrelatedExpr};
 use crate::optimizer::ApplyOrder;
 use crate::utils::replace_qualified_name;
 use crate::{OptimizerConfig, OptimizerRule};
@@ -318,12 +318,17 @@ fn build_join(
     join_type: JoinType,
     alias: String,
 ) -> Result<Option<LogicalPlan>> {
+    if references_enclosing_query(subquery, left) {
+        return Ok(None);
+    }
+
     let mut pull_up = PullUpCorrelatedExpr::new()
         .with_in_predicate_opt(in_predicate_opt.clone())
         .with_exists_sub_query(in_predicate_opt.is_none());
 
     let new_plan = subquery.clone().rewrite(&mut pull_up).data()?;
-    if !pull_up.can_pull_up {
+    // The out references left in nested subqueries can not be pulled up
+    if !pull_up.can_pull_up || !new_plan.all_out_ref_exprs().is_empty() {
         return Ok(None);
     }
 
@@ -1334,6 +1339,50 @@ mod tests {
         )
     }
 
+    /// Test a subquery correlated with the query enclosing its outer query,
+    /// which can not be converted to a join
+    #[test]
+    fn exists_subquery_references_grandparent_query() -> Result<()> {
+        let lineitem = Arc::new(
+            LogicalPlanBuilder::from(scan_tpch_table("lineitem"))
+                .filter(
+                    col("lineitem.l_orderkey")
+                        .eq(out_ref_col(DataType::Int64, "customer.c_custkey")),
+                )?
+                .project(vec![col("lineitem.l_orderkey")])?
+                .build()?,
+        );
+
+        let orders = Arc::new(
+            LogicalPlanBuilder::from(scan_tpch_table("orders"))
+                .filter(exists(lineitem))?
+                .project(vec![col("orders.o_custkey")])?
+                .build()?,
+        );
+
+        let plan = LogicalPlanBuilder::from(scan_tpch_table("customer"))
+            .filter(exists(orders))?
+            .project(vec![col("customer.c_custkey")])?
+            .build()?;
+
+        assert_optimized_plan_equal!(
+            plan,
+            @r"
+        Projection: customer.c_custkey [c_custkey:Int64]
+          Filter: EXISTS (<subquery>) [c_custkey:Int64, c_name:Utf8]
+            Subquery: [o_custkey:Int64]
+              Projection: orders.o_custkey [o_custkey:Int64]
+                Filter: EXISTS (<subquery>) [o_orderkey:Int64, o_custkey:Int64, o_orderstatus:Utf8, o_totalprice:Float64;N]
+                  Subquery: [l_orderkey:Int64]
+                    Projection: lineitem.l_orderkey [l_orderkey:Int64]
+                      Filter: lineitem.l_orderkey = outer_ref(customer.c_custkey) [l_orderkey:Int64, l_partkey:Int64, l_suppkey:Int64, l_linenumber:Int32, l_quantity:Float64, l_extendedprice:Float64]
+                        TableScan: lineitem [l_orderkey:Int64, l_partkey:Int64, l_suppkey:Int64, l_linenumber:Int32, l_quantity:Float64, l_extendedprice:Float64]
+                  TableScan: orders [o_orderkey:Int64, o_custkey:Int64, o_orderstatus:Utf8, o_totalprice:Float64;N]
+            TableScan: customer [c_custkey:Int64, c_name:Utf8]
+        "
+        )
+    }
+
     /// Test for correlated exists subquery filter with additional subquery filters
     #[test]
     fn exists_subquery_with_subquery_filters() -> Result<()> {
@@ -242,6 +242,7 @@ fn optimize_projections(
                 filters,
                 fetch,
                 projected_schema: _,
+                hints,
             } = table_scan;
 
             // Get indices referred to in the original (schema with all fields)
@@ -257,7 +258,7 @@ fn optimize_projections(
                 filters,
                 fetch,
             )
-            .map(LogicalPlan::TableScan)
+            .map(|table_scan| LogicalPlan::TableScan(table_scan.with_hints(hints)))
             .map(Transformed::yes);
         }
         // Other node types are handled below
@@ -1435,7 +1435,7 @@ mod tests {
     use datafusion_expr::{
         col, in_list, in_subquery, lit, ColumnarValue, ExprFunctionExt, Extension,
         LogicalPlanBuilder, ScalarFunctionArgs, ScalarUDF, ScalarUDFImpl, Signature,
-        TableSource, TableType, UserDefinedLogicalNodeCore, Volatility,
+        TableHints, TableSource, TableType, UserDefinedLogicalNodeCore, Volatility,
         WindowFunctionDefinition,
     };
 
@@ -3055,6 +3055,7 @@ mod tests {
             projection,
             source: Arc::new(test_provider),
             fetch: None,
+            hints: TableHints::default(),
         });
 
         Ok(LogicalPlanBuilder::from(table_scan))
@@ -20,7 +20,9 @@
 use std::collections::{BTreeSet, HashMap};
 use std::sync::Arc;
 
-use crate::decorrelate::{PullUpCorrelatedExpr, UN_MATCHED_ROW_INDICATOR};
+use crate::decorrelate::{
+    references_enclosing_query, PullUpCorrelatedExpr, UN_MATCHED_ROW_INDICATOR,
+};
 use crate::optimizer::ApplyOrder;
 use crate::utils::{evaluates_to_null, replace_qualified_name};
 use crate::{OptimizerConfig, OptimizerRule};
@@ -30,11 +32,13 @@ use datafusion_common::alias::AliasGenerator;
 use datafusion_common::tree_node::{
     Transformed, TransformedResult, TreeNode, TreeNodeRecursion, TreeNodeRewriter,
 };
-use datafusion_common::{internal_err, plan_err, Column, Result, ScalarValue};
+use datafusion_common::{plan_err, Column, Result, ScalarValue};
 use datafusion_expr::expr_rewriter::create_col_from_scalar_expr;
 use datafusion_expr::logical_plan::{JoinType, Subquery};
 use datafusion_expr::utils::conjunction;
-use datafusion_expr::{expr, EmptyRelation, Expr, LogicalPlan, LogicalPlanBuilder};
+use datafusion_expr::{
+    expr, Aggregate, EmptyRelation, Expr, LogicalPlan, LogicalPlanBuilder,
+};
 
 /// Optimizer rule for rewriting subquery filters to joins
 #[derive(Default, Debug)]
@@ -67,6 +71,55 @@ impl ScalarSubqueryToJoin {
             .data()
             .map(|new_expr| (extract.sub_query_info, new_expr))
     }
+
+    /// Turns every scalar subquery in `exprs` into a left join on top of
+    /// `input`
+    ///
+    /// Returns the joined plan along with `exprs` rewritten to reference the
+    /// joined subquery columns, or `None` if any of the subqueries can not be
+    /// decorrelated.
+    fn join_subqueries(
+        &self,
+        exprs: &[Expr],
+        input: &LogicalPlan,
+        alias_gen: &Arc<AliasGenerator>,
+    ) -> Result<Option<(LogicalPlan, Vec<Expr>)>> {
+        let extracted = exprs
+            .iter()
+            .map(|expr| self.extract_subquery_exprs(expr, alias_gen))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut cur_input = input.clone();
+        let mut rewrite_exprs = Vec::with_capacity(extracted.len());
+        for (subqueries, mut rewrite_expr) in extracted {
+            for (subquery, alias) in subqueries {
+                let Some((optimized_subquery, expr_check_map)) =
+                    build_join(&subquery, &cur_input, &alias)?
+                else {
+                    // if we can't handle all of the subqueries then bail for now
+                    return Ok(None);
+                };
+                if !expr_check_map.is_empty() {
+                    rewrite_expr = rewrite_expr
+                        .transform_up(|expr| {
+                            // replace column references with entry in map, if it exists
+                            if let Some(map_expr) = expr
+                                .try_as_col()
+                                .and_then(|col| expr_check_map.get(&col.name))
+                            {
+                                Ok(Transformed::yes(map_expr.clone()))
+                            } else {
+                                Ok(Transformed::no(expr))
+                            }
+                        })
+                        .data()?;
+                }
+                cur_input = optimized_subquery;
+            }
+            rewrite_exprs.push(rewrite_expr);
+        }
+        Ok(Some((cur_input, rewrite_exprs)))
+    }
 }
 
 impl OptimizerRule for ScalarSubqueryToJoin {
@@ -87,44 +140,16 @@ impl OptimizerRule for ScalarSubqueryToJoin {
                     return Ok(Transformed::no(LogicalPlan::Filter(filter)));
                 }
 
-                let (subqueries, mut rewrite_expr) = self.extract_subquery_exprs(
-                    &filter.predicate,
+                let Some((cur_input, mut rewrite_exprs)) = self.join_subqueries(
+                    std::slice::from_ref(&filter.predicate),
+                    &filter.input,
                     config.alias_generator(),
-                )?;
-
-                if subqueries.is_empty() {
-                    return internal_err!("Expected subqueries not found in filter");
-                }
-
-                // iterate through all subqueries in predicate, turning each into a left join
-                let mut cur_input = filter.input.as_ref().clone();
-                for (subquery, alias) in subqueries {
-                    if let Some((optimized_subquery, expr_check_map)) =
-                        build_join(&subquery, &cur_input, &alias)?
-                    {
-                        if !expr_check_map.is_empty() {
-                            rewrite_expr = rewrite_expr
-                                .transform_up(|expr| {
-                                    // replace column references with entry in map, if it exists
-                                    if let Some(map_expr) = expr
-                                        .try_as_col()
-                                        .and_then(|col| expr_check_map.get(&col.name))
-                                    {
-                                        Ok(Transformed::yes(map_expr.clone()))
-                                    } else {
-                                        Ok(Transformed::no(expr))
-                                    }
-                                })
-                                .data()?;
-                        }
-                        cur_input = optimized_subquery;
-                    } else {
-                        // if we can't handle all of the subqueries then bail for now
-                        return Ok(Transformed::no(LogicalPlan::Filter(filter)));
-                    }
-                }
+                )?
+                else {
+                    return Ok(Transformed::no(LogicalPlan::Filter(filter)));
+                };
                 let new_plan = LogicalPlanBuilder::from(cur_input)
-                    .filter(rewrite_expr)?
+                    .filter(rewrite_exprs.remove(0))?
                     .build()?;
                 Ok(Transformed::yes(new_plan))
             }
@@ -135,71 +160,117 @@ impl OptimizerRule for ScalarSubqueryToJoin {
                     return Ok(Transformed::no(LogicalPlan::Projection(projection)));
                 }
 
-                let mut all_subqueries = vec![];
-                let mut expr_to_rewrite_expr_map = HashMap::new();
-                let mut subquery_to_expr_map = HashMap::new();
-                for expr in projection.expr.iter() {
-                    let (subqueries, rewrite_exprs) =
-                        self.extract_subquery_exprs(expr, config.alias_generator())?;
-                    for (subquery, _) in &subqueries {
-                        subquery_to_expr_map.insert(subquery.clone(), expr.clone());
-                    }
-                    all_subqueries.extend(subqueries);
-                    expr_to_rewrite_expr_map.insert(expr, rewrite_exprs);
-                }
-                if all_subqueries.is_empty() {
-                    return internal_err!("Expected subqueries not found in projection");
+                let Some((cur_input, rewrite_exprs)) = self.join_subqueries(
+                    &projection.expr,
+                    &projection.input,
+                    config.alias_generator(),
+                )?
+                else {
+                    return Ok(Transformed::no(LogicalPlan::Projection(projection)));
+                };
+                let proj_exprs = rewrite_exprs
+                    .into_iter()
+                    .zip(projection.expr.iter())
+                    .map(|(new_expr, expr)| keep_expr_name(new_expr, expr))
+                    .collect::<Vec<_>>();
+                let new_plan = LogicalPlanBuilder::from(cur_input)
+                    .project(proj_exprs)?
+                    .build()?;
+                Ok(Transformed::yes(new_plan))
+            }
+            LogicalPlan::Sort(sort) => {
+                // Optimization: skip the rest of the rule and its copies if
+                // there are no scalar subqueries
+                if !sort.expr.iter().any(|s| contains_scalar_subquery(&s.expr)) {
+                    return Ok(Transformed::no(LogicalPlan::Sort(sort)));
                 }
-                // iterate through all subqueries in predicate, turning each into a left join
-                let mut cur_input = projection.input.as_ref().clone();
-                for (subquery, alias) in all_subqueries {
-                    if let Some((optimized_subquery, expr_check_map)) =
-                        build_join(&subquery, &cur_input, &alias)?
-                    {
-                        cur_input = optimized_subquery;
-                        if !expr_check_map.is_empty() {
-                            if let Some(expr) = subquery_to_expr_map.get(&subquery) {
-                                if let Some(rewrite_expr) =
-                                    expr_to_rewrite_expr_map.get(expr)
-                                {
-                                    let new_expr = rewrite_expr
-                                        .clone()
-                                        .transform_up(|expr| {
-                                            // replace column references with entry in map, if it exists
-                                            if let Some(map_expr) =
-                                                expr.try_as_col().and_then(|col| {
-                                                    expr_check_map.get(&col.name)
-                                                })
-                                            {
-                                                Ok(Transformed::yes(map_expr.clone()))
-                                            } else {
-                                                Ok(Transformed::no(expr))
-                                            }
-                                        })
-                                        .data()?;
-                                    expr_to_rewrite_expr_map.insert(expr, new_expr);
-                                }
-                            }
-                        }
-                    } else {
-                        // if we can't handle all of the subqueries then bail for now
-                        return Ok(Transformed::no(LogicalPlan::Projection(projection)));
-                    }
+
+                let exprs = sort.expr.iter().map(|s| s.expr.clone()).collect::<Vec<_>>();
+                let Some((cur_input, rewrite_exprs)) =
+                    self.join_subqueries(&exprs, &sort.input, config.alias_generator())?
+                else {
+                    return Ok(Transformed::no(LogicalPlan::Sort(sort)));
+                };
+                let sort_exprs = rewrite_exprs
+                    .into_iter()
+                    .zip(sort.expr.iter())
+                    .map(|(new_expr, s)| s.with_expr(new_expr))
+                    .collect::<Vec<_>>();
+                // the joined subquery columns must not leak past the sort
+                let new_plan = LogicalPlanBuilder::from(cur_input)
+                    .sort_with_limit(sort_exprs, sort.fetch)?
+                    .project(sort.input.schema().columns().into_iter().map(Expr::Column))?
+                    .build()?;
+                Ok(Transformed::yes(new_plan))
+            }
+            LogicalPlan::Aggregate(aggregate) => {
+                // Optimization: skip the rest of the rule and its copies if
+                // there are no scalar subqueries
+                if !aggregate
+                    .group_expr
+                    .iter()
+                    .chain(aggregate.aggr_expr.iter())
+                    .any(contains_scalar_subquery)
+                    // grouping sets are not supported yet
+                    || aggregate
+                        .group_expr
+                        .iter()
+                        .any(|e| matches!(e, Expr::GroupingSet(_)))
+                {
+                    return Ok(Transformed::no(LogicalPlan::Aggregate(aggregate)));
                 }
 
-                let mut proj_exprs = vec![];
-                for expr in projection.expr.iter() {
-                    let old_expr_name = expr.schema_name().to_string();
-                    let new_expr = expr_to_rewrite_expr_map.get(expr).unwrap();
-                    let new_expr_name = new_expr.schema_name().to_string();
-                    if new_expr_name != old_expr_name {
-                        proj_exprs.push(new_expr.clone().alias(old_expr_name))
-                    } else {
-                        proj_exprs.push(new_expr.clone());
-                    }
+                let exprs = aggregate
+                    .group_expr
+                    .iter()
+                    .chain(aggregate.aggr_expr.iter())
+                    .cloned()
+                    .collect::<Vec<_>>();
+                let Some((cur_input, rewrite_exprs)) = self.join_subqueries(
+                    &exprs,
+                    &aggregate.input,
+                    config.alias_generator(),
+                )?
+                else {
+                    return Ok(Transformed::no(LogicalPlan::Aggregate(aggregate)));
+                };
+                let mut new_exprs = rewrite_exprs
+                    .into_iter()
+                    .zip(exprs.iter())
+                    .map(|(new_expr, expr)| keep_expr_name(new_expr, expr))
+                    .collect::<Vec<_>>();
+                let aggr_expr = new_exprs.split_off(aggregate.group_expr.len());
+                let new_plan = LogicalPlan::Aggregate(Aggregate::try_new(
+                    Arc::new(cur_input),
+                    new_exprs,
+                    aggr_expr,
+                )?);
+                Ok(Transformed::yes(new_plan))
+            }
+            LogicalPlan::Window(window) => {
+                // Optimization: skip the rest of the rule and its copies if
+                // there are no scalar subqueries
+                if !window.window_expr.iter().any(contains_scalar_subquery) {
+                    return Ok(Transformed::no(LogicalPlan::Window(window)));
                 }
+
+                let Some((cur_input, rewrite_exprs)) = self.join_subqueries(
+                    &window.window_expr,
+                    &window.input,
+                    config.alias_generator(),
+                )?
+                else {
+                    return Ok(Transformed::no(LogicalPlan::Window(window)));
+                };
+                let window_exprs = rewrite_exprs
+                    .into_iter()
+                    .zip(window.window_expr.iter())
+                    .map(|(new_expr, expr)| keep_expr_name(new_expr, expr))
+                    .collect::<Vec<_>>();
+                // the joined subquery columns must not leak past the window
                 let new_plan = LogicalPlanBuilder::from(cur_input)
-                    .project(proj_exprs)?
+                    .window(window_exprs)?
+                    .project(window.schema.columns().into_iter().map(Expr::Column))?
                     .build()?;
                 Ok(Transformed::yes(new_plan))
             }
@@ -217,6 +288,16 @@ impl OptimizerRule for ScalarSubqueryToJoin {
     }
 }
 
+/// Aliases `new_expr` to the output name of `old_expr` if rewriting changed it
+fn keep_expr_name(new_expr: Expr, old_expr: &Expr) -> Expr {
+    let old_expr_name = old_expr.schema_name().to_string();
+    if new_expr.schema_name().to_string() != old_expr_name {
+        new_expr.alias(old_expr_name)
+    } else {
+        new_expr
+    }
+}
+
 /// Returns true if the expression has a scalar subquery somewhere in it
 /// false otherwise
 fn contains_scalar_subquery(expr: &Expr) -> bool {
@@ -298,9 +379,15 @@ fn build_join(
     subquery_alias: &str,
 ) -> Result<Option<(LogicalPlan, HashMap<String, Expr>)>> {
     let subquery_plan = subquery.subquery.as_ref();
-    let mut pull_up = PullUpCorrelatedExpr::new().with_need_handle_count_bug(true);
+    if references_enclosing_query(subquery_plan, filter_input) {
+        return Ok(None);
+    }
+    let mut pull_up = PullUpCorrelatedExpr::new()
+        .with_need_handle_count_bug(true)
+        .with_scalar_sub_query(true);
     let new_plan = subquery_plan.clone().rewrite(&mut pull_up).data()?;
-    if !pull_up.can_pull_up {
+    // The out references left in nested subqueries can not be pulled up
+    if !pull_up.can_pull_up || !new_plan.all_out_ref_exprs().is_empty() {
         return Ok(None);
     }
 
@@ -408,7 +495,11 @@ mod tests {
     use datafusion_expr::test::function_stub::sum;
 
     use crate::assert_optimized_plan_eq_display_indent_snapshot;
-    use datafusion_expr::{col, lit, out_ref_col, scalar_subquery, Between};
+    use datafusion_expr::expr::WindowFunction;
+    use datafusion_expr::{
+        col, lit, out_ref_col, scalar_subquery, Between, ExprFunctionExt,
+        WindowFunctionDefinition,
+    };
     use datafusion_functions_aggregate::min_max::{max, min};
 
     macro_rules! assert_optimized_plan_equal {
@@ -994,6 +1085,68 @@ mod tests {
         )
     }
 
+    /// Test for correlated scalar subquery returning the first row of an ordering
+    #[test]
+    fn scalar_subquery_with_order_by_limit() -> Result<()> {
+        let sq = Arc::new(
+            LogicalPlanBuilder::from(test_table_scan_with_name("sq")?)
+                .filter(out_ref_col(DataType::UInt32, "test.a").eq(col("sq.a")))?
+                .sort(vec![col("sq.b").sort(false, true)])?
+                .limit(0, Some(1))?
+                .project(vec![col("sq.c")])?
+                .build()?,
+        );
+
+        let plan = LogicalPlanBuilder::from(test_table_scan_with_name("test")?)
+            .project(vec![col("test.a"), scalar_subquery(sq).alias("last_c")])?
+            .build()?;
+
+        assert_optimized_plan_equal!(
+            plan,
+            @r"
+        Projection: test.a, __scalar_sq_1.c AS last_c [a:UInt32, last_c:UInt32;N]
+          Left Join:  Filter: test.a = __scalar_sq_1.a [a:UInt32, b:UInt32, c:UInt32, c:UInt32;N, a:UInt32;N]
+            TableScan: test [a:UInt32, b:UInt32, c:UInt32]
+            SubqueryAlias: __scalar_sq_1 [c:UInt32, a:UInt32]
+              Projection: sq.c, sq.a [c:UInt32, a:UInt32]
+                DistinctOn: on_expr=[[sq.a]], select_expr=[[sq.a, sq.b, sq.c]], sort_expr=[[sq.a ASC NULLS LAST, sq.b DESC NULLS FIRST]] [a:UInt32, b:UInt32, c:UInt32]
+                  TableScan: sq [a:UInt32, b:UInt32, c:UInt32]
+        "
+        )
+    }
+
+    /// Test for correlated scalar subquery returning the first row of an
+    /// ordering, which is not correlated by equality
+    #[test]
+    fn scalar_subquery_with_order_by_limit_not_eq() -> Result<()> {
+        let sq = Arc::new(
+            LogicalPlanBuilder::from(test_table_scan_with_name("sq")?)
+                .filter(out_ref_col(DataType::UInt32, "test.a").lt(col("sq.a")))?
+                .sort(vec![col("sq.b").sort(false, true)])?
+                .limit(0, Some(1))?
+                .project(vec![col("sq.c")])?
+                .build()?,
+        );
+
+        let plan = LogicalPlanBuilder::from(test_table_scan_with_name("test")?)
+            .project(vec![col("test.a"), scalar_subquery(sq).alias("last_c")])?
+            .build()?;
+
+        assert_optimized_plan_equal!(
+            plan,
+            @r"
+        Projection: test.a, (<subquery>) AS last_c [a:UInt32, last_c:UInt32]
+          Subquery: [c:UInt32]
+            Projection: sq.c [c:UInt32]
+              Limit: skip=0, fetch=1 [a:UInt32, b:UInt32, c:UInt32]
+                Sort: sq.b DESC NULLS FIRST [a:UInt32, b:UInt32, c:UInt32]
+                  Filter: outer_ref(test.a) < sq.a [a:UInt32, b:UInt32, c:UInt32]
+                    TableScan: sq [a:UInt32, b:UInt32, c:UInt32]
+          TableScan: test [a:UInt32, b:UInt32, c:UInt32]
+        "
+        )
+    }
+
     /// Test for non-correlated scalar subquery with no filters
     #[test]
     fn scalar_subquery_non_correlated_no_filters_with_non_equal_clause() -> Result<()> {
@@ -1154,4 +1307,113 @@ mod tests {
         "
         )
     }
+
+    /// Test a correlated scalar subquery in the sort expressions
+    #[test]
+    fn scalar_subquery_in_sort() -> Result<()> {
+        let sq = Arc::new(
+            LogicalPlanBuilder::from(scan_tpch_table("orders"))
+                .filter(
+                    col("orders.o_custkey")
+                        .eq(out_ref_col(DataType::Int64, "customer.c_custkey")),
+                )?
+                .aggregate(Vec::<Expr>::new(), vec![max(col("orders.o_custkey"))])?
+                .project(vec![max(col("orders.o_custkey"))])?
+                .build()?,
+        );
+
+        let plan = LogicalPlanBuilder::from(scan_tpch_table("customer"))
+            .sort(vec![scalar_subquery(sq).sort(true, false)])?
+            .project(vec![col("customer.c_custkey")])?
+            .build()?;
+
+        assert_optimized_plan_equal!(
+            plan,
+            @r"
+        Projection: customer.c_custkey [c_custkey:Int64]
+          Projection: customer.c_custkey, customer.c_name [c_custkey:Int64, c_name:Utf8]
+            Sort: __scalar_sq_1.max(orders.o_custkey) ASC NULLS LAST [c_custkey:Int64, c_name:Utf8, max(orders.o_custkey):Int64;N, o_custkey:Int64;N, __always_true:Boolean;N]
+              Left Join:  Filter: __scalar_sq_1.o_custkey = customer.c_custkey [c_custkey:Int64, c_name:Utf8, max(orders.o_custkey):Int64;N, o_custkey:Int64;N, __always_true:Boolean;N]
+                TableScan: customer [c_custkey:Int64, c_name:Utf8]
+                SubqueryAlias: __scalar_sq_1 [max(orders.o_custkey):Int64;N, o_custkey:Int64, __always_true:Boolean]
+                  Projection: max(orders.o_custkey), orders.o_custkey, __always_true [max(orders.o_custkey):Int64;N, o_custkey:Int64, __always_true:Boolean]
+                    Aggregate: groupBy=[[orders.o_custkey, Boolean(true) AS __always_true]], aggr=[[max(orders.o_custkey)]] [o_custkey:Int64, __always_true:Boolean, max(orders.o_custkey):Int64;N]
+                      TableScan: orders [o_orderkey:Int64, o_custkey:Int64, o_orderstatus:Utf8, o_totalprice:Float64;N]
+        "
+        )
+    }
+
+    /// Test an uncorrelated scalar subquery in the group by expressions
+    #[test]
+    fn scalar_subquery_in_group_by() -> Result<()> {
+        let sq = Arc::new(
+            LogicalPlanBuilder::from(scan_tpch_table("orders"))
+                .aggregate(Vec::<Expr>::new(), vec![max(col("orders.o_custkey"))])?
+                .project(vec![max(col("orders.o_custkey"))])?
+                .build()?,
+        );
+
+        let plan = LogicalPlanBuilder::from(scan_tpch_table("customer"))
+            .aggregate(
+                vec![scalar_subquery(sq)],
+                vec![sum(col("customer.c_custkey"))],
+            )?
+            .build()?;
+
+        assert_optimized_plan_equal!(
+            plan,
+            @r"
+        Aggregate: groupBy=[[__scalar_sq_1.max(orders.o_custkey) AS max(orders.o_custkey)]], aggr=[[sum(customer.c_custkey)]] [max(orders.o_custkey):Int64;N, sum(customer.c_custkey):Int64;N]
+          Left Join:  Filter: Boolean(true) [c_custkey:Int64, c_name:Utf8, max(orders.o_custkey):Int64;N]
+            TableScan: customer [c_custkey:Int64, c_name:Utf8]
+            SubqueryAlias: __scalar_sq_1 [max(orders.o_custkey):Int64;N]
+              Projection: max(orders.o_custkey) [max(orders.o_custkey):Int64;N]
+                Aggregate: groupBy=[[]], aggr=[[max(orders.o_custkey)]] [max(orders.o_custkey):Int64;N]
+                  TableScan: orders [o_orderkey:Int64, o_custkey:Int64, o_orderstatus:Utf8, o_totalprice:Float64;N]
+        "
+        )
+    }
+
+    /// Test a correlated scalar subquery in the window partition expressions
+    #[test]
+    fn scalar_subquery_in_window_partition_by() -> Result<()> {
+        let sq = Arc::new(
+            LogicalPlanBuilder::from(scan_tpch_table("orders"))
+                .filter(
+                    col("orders.o_custkey")
+                        .eq(out_ref_col(DataType::Int64, "customer.c_custkey")),
+                )?
+                .aggregate(Vec::<Expr>::new(), vec![max(col("orders.o_custkey"))])?
+                .project(vec![max(col("orders.o_custkey"))])?
+                .build()?,
+        );
+
+        let window = Expr::from(WindowFunction::new(
+            WindowFunctionDefinition::WindowUDF(
+                datafusion_functions_window::rank::rank_udwf(),
+            ),
+            vec![],
+        ))
+        .partition_by(vec![scalar_subquery(sq)])
+        .order_by(vec![col("customer.c_custkey").sort(true, true)])
+        .build()?;
+
+        let plan = LogicalPlanBuilder::from(scan_tpch_table("customer"))
+            .window(vec![window])?
+            .build()?;
+
+        assert_optimized_plan_equal!(
+            plan,
+            @r"
+        Projection: customer.c_custkey, customer.c_name, rank() PARTITION BY [max(orders.o_custkey)] ORDER BY [customer.c_custkey ASC NULLS FIRST] ROWS BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW [c_custkey:Int64, c_name:Utf8, rank() PARTITION BY [max(orders.o_custkey)] ORDER BY [customer.c_custkey ASC NULLS FIRST] ROWS BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW:UInt64]
+          WindowAggr: windowExpr=[[rank() PARTITION BY [__scalar_sq_1.max(orders.o_custkey)] ORDER BY [customer.c_custkey ASC NULLS FIRST] ROWS BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW AS rank() PARTITION BY [max(orders.o_custkey)] ORDER BY [customer.c_custkey ASC NULLS FIRST] ROWS BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW]] [c_custkey:Int64, c_name:Utf8, max(orders.o_custkey):Int64;N, o_custkey:Int64;N, __always_true:Boolean;N, rank() PARTITION BY [max(orders.o_custkey)] ORDER BY [customer.c_custkey ASC NULLS FIRST] ROWS BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW:UInt64]
+            Left Join:  Filter: __scalar_sq_1.o_custkey = customer.c_custkey [c_custkey:Int64, c_name:Utf8, max(orders.o_custkey):Int64;N, o_custkey:Int64;N, __always_true:Boolean;N]
+              TableScan: customer [c_custkey:Int64, c_name:Utf8]
+              SubqueryAlias: __scalar_sq_1 [max(orders.o_custkey):Int64;N, o_custkey:Int64, __always_true:Boolean]
+                Projection: max(orders.o_custkey), orders.o_custkey, __always_true [max(orders.o_custkey):Int64;N, o_custkey:Int64, __always_true:Boolean]
+                  Aggregate: groupBy=[[orders.o_custkey, Boolean(true) AS __always_true]], aggr=[[max(orders.o_custkey)]] [o_custkey:Int64, __always_true:Boolean, max(orders.o_custkey):Int64;N]
+                    TableScan: orders [o_orderkey:Int64, o_custkey:Int64, o_orderstatus:Utf8, o_totalprice:Float64;N]
+        "
+        )
+    }
 }
@@ -15,9 +15,11 @@
 // specific language governing permissions and limitations
 // under the License.
 
+use std::collections::HashSet;
 use std::sync::Arc;
 
 use crate::planner::{ContextProvider, PlannerContext, SqlToRel};
+use crate::utils::check_limit;
 
 use arrow::datatypes::Schema;
 use datafusion_common::{
@@ -34,23 +36,35 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
         with: With,
         planner_context: &mut PlannerContext,
     ) -> Result<()> {
+        check_limit(
+            "max_ctes",
+            self.options.max_ctes,
+            "Number of CTEs",
+            with.cte_tables.len(),
+        )?;
         let is_recursive = with.recursive;
+        let mut cte_names = HashSet::new();
         // Process CTEs from top to bottom
         for cte in with.cte_tables {
-            // A `WITH` block can't use the same name more than once
+            // A `WITH` block can't use the same name more than once. A CTE of an
+            // enclosing query with the same name is shadowed, like a table is.
             let cte_name = self.ident_normalizer.normalize(cte.alias.name.clone());
-            if planner_context.contains_cte(&cte_name) {
+            if !cte_names.insert(cte_name.clone()) {
                 return plan_err!(
                     "WITH query name {cte_name:?} specified more than once"
                 );
             }
+            planner_context.record_cte(&cte_name);
 
             // Create a logical plan for the CTE
+            let unique_output_names = planner_context.set_unique_output_names(true);
             let cte_plan = if is_recursive {
-                self.recursive_cte(cte_name.clone(), *cte.query, planner_context)?
+                self.recursive_cte(cte_name.clone(), *cte.query, planner_context)
             } else {
-                self.non_recursive_cte(*cte.query, planner_context)?
+                self.non_recursive_cte(*cte.query, planner_context)
             };
+            planner_context.set_unique_output_names(unique_output_names);
+            let cte_plan = cte_plan?;
 
             // Each `WITH` block can change the column names in the last
             // projection (e.g. "WITH table(t1, t2) AS SELECT 1, 2").
@@ -83,6 +97,7 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
         {
             return not_impl_err!("Recursive CTEs are not enabled");
         }
+        self.check_set_operation_inputs(&cte_query.body)?;
 
         let (left_expr, right_expr, set_quantifier) = match *cte_query.body {
             SetExpr::SetOperation {
@@ -162,6 +177,7 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
         // this uses the named_relation we inserted above to resolve the
         // relation. This ensures that the recursive term uses the named relation logical plan
         // and thus the 'continuance' physical plan as its input and source
+        planner_context.set_unique_output_names(true);
         let recursive_plan = self.set_expr_to_plan(*right_expr, planner_context)?;
 
         // Check if the recursive term references the CTE itself,
@@ -30,7 +30,7 @@ use datafusion_expr::{
 use sqlparser::ast::{
     DuplicateTreatment, Expr as SQLExpr, Function as SQLFunction, FunctionArg,
     FunctionArgExpr, FunctionArgumentClause, FunctionArgumentList, FunctionArguments,
-    NullTreatment, ObjectName, OrderByExpr, Spanned, WindowType,
+    NullTreatment, ObjectName, OrderByExpr, Spanned, Value, WindowType,
 };
 
 /// Suggest a valid function based on an invalid input function name
@@ -74,6 +74,26 @@ fn find_closest_match(candidates: Vec<String>, target: &str) -> Option<String> {
     })
 }
 
+/// Returns the name and arguments of the `string_agg` call a MySQL
+/// `GROUP_CONCAT` of a single argument is planned as. The delimiter defaults
+/// to `,`, as in MySQL
+fn group_concat_to_string_agg_args(
+    mut args: Vec<FunctionArg>,
+    separator: Option<Value>,
+) -> Result<(String, Vec<FunctionArg>)> {
+    if args.len() != 1 {
+        return not_impl_err!(
+            "GROUP_CONCAT of {} arguments is not supported, only a single argument is",
+            args.len()
+        );
+    }
+    let separator = separator.unwrap_or(Value::SingleQuotedString(",".to_string()));
+    args.push(FunctionArg::Unnamed(FunctionArgExpr::Expr(SQLExpr::Value(
+        separator.with_empty_span(),
+    ))));
+    Ok(("string_agg".to_string(), args))
+}
+
 /// Arguments for a function call extracted from the SQL AST
 #[derive(Debug)]
 struct FunctionArgs {
@@ -93,6 +113,8 @@ struct FunctionArgs {
     distinct: bool,
     /// WITHIN GROUP clause, if any
     within_group: Vec<OrderByExpr>,
+    /// SEPARATOR clause of the MySQL `GROUP_CONCAT`, if any
+    separator: Option<Value>,
     /// Was the function called without parenthesis, i.e. could this also be a column reference?
     function_without_paranthesis: bool,
 }
@@ -120,6 +142,7 @@ impl FunctionArgs {
                 null_treatment,
                 distinct: false,
                 within_group,
+                separator: None,
                 function_without_paranthesis: matches!(args, FunctionArguments::None),
             });
         };
@@ -138,6 +161,7 @@ impl FunctionArgs {
 
         // Pull out argument handling
         let mut order_by = None;
+        let mut separator = None;
         for clause in clauses {
             match clause {
                 FunctionArgumentClause::IgnoreOrRespectNulls(nt) => {
@@ -173,9 +197,12 @@ impl FunctionArgs {
                     )
                 }
                 FunctionArgumentClause::Separator(sep) => {
-                    return not_impl_err!(
-                        "Calling {name}: SEPARATOR not supported in function arguments: {sep}"
-                    )
+                    if separator.is_some() {
+                        return not_impl_err!(
+                            "Calling {name}: Duplicated SEPARATOR clause in function arguments"
+                        );
+                    }
+                    separator = Some(sep);
                 }
                 FunctionArgumentClause::JsonNullClause(jn) => {
                     return not_impl_err!(
@@ -202,6 +229,7 @@ impl FunctionArgs {
             null_treatment,
             distinct,
             within_group,
+            separator,
             function_without_paranthesis: false,
         })
     }
@@ -224,6 +252,7 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
             null_treatment,
             distinct,
             within_group,
+            separator,
             function_without_paranthesis,
         } = function_args;
 
@@ -247,7 +276,10 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
             object_name.to_string()
         } else {
             match object_name.0[0].as_ident() {
-                Some(ident) => crate::utils::normalize_ident(ident.clone()),
+                Some(ident) => {
+                    self.check_identifier_length(ident)?;
+                    crate::utils::normalize_ident(ident.clone())
+                }
                 None => {
                     return plan_err!(
                         "Expected an identifier in function name, but found {:?}",
@@ -257,6 +289,20 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
             }
         };
 
+        // MySQL `GROUP_CONCAT(x [ORDER BY ...] [SEPARATOR sep])` is planned as
+        // `string_agg(x, sep)`, unless a function of that name is registered
+        let (name, args) = if name.eq("group_concat")
+            && self.context_provider.get_aggregate_meta(&name).is_none()
+        {
+            group_concat_to_string_agg_args(args, separator)?
+        } else if let Some(sep) = separator {
+            return not_impl_err!(
+                "Calling {name}: SEPARATOR not supported in function arguments: {sep}"
+            );
+        } else {
+            (name, args)
+        };
+
         if name.eq("make_map") {
             let mut fn_args =
                 self.function_args_to_expr(args.clone(), schema, planner_context)?;
@@ -309,12 +355,16 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
             )?;
 
             let func_deps = schema.functional_dependencies();
-            // Find whether ties are possible in the given ordering
+            // Find whether ties are possible in the given ordering. A unique
+            // column that may contain NULLs still has ties between the NULLs.
             let is_ordering_strict = order_by.iter().find_map(|orderby_expr| {
                 if let Expr::Column(col) = &orderby_expr.expr {
                     let idx = schema.index_of_column(col).ok()?;
+                    let nullable = schema.field(idx).is_nullable();
                     return if func_deps.iter().any(|dep| {
-                        dep.source_indices == vec![idx] && dep.mode == Dependency::Single
+                        dep.source_indices == vec![idx]
+                            && dep.mode == Dependency::Single
+                            && !(dep.nullable && nullable)
                     }) {
                         Some(true)
                     } else {
@@ -373,6 +423,13 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                 } = window_expr;
 
                 if distinct {
+                    // Only aggregate functions can drop duplicate input rows
+                    if let WindowFunctionDefinition::WindowUDF(udwf) = &func_def {
+                        return plan_err!(
+                            "DISTINCT is not supported for the window function {}",
+                            udwf.name()
+                        );
+                    }
                     return Expr::from(expr::WindowFunction::new(func_def, args))
                         .partition_by(partition_by)
                         .order_by(order_by)
@@ -18,10 +18,11 @@
 use arrow::datatypes::Field;
 use datafusion_common::{
     internal_err, not_impl_err, plan_datafusion_err, plan_err, Column, DFSchema,
-    DataFusionError, Result, Span, TableReference,
+    DataFusionError, Diagnostic, Result, Span, TableReference,
 };
 use datafusion_expr::planner::PlannerResult;
 use datafusion_expr::{Case, Expr};
+use log::warn;
 use sqlparser::ast::{CaseWhen, Expr as SQLExpr, Ident};
 
 use crate::planner::{ContextProvider, PlannerContext, SqlToRel};
@@ -34,6 +35,18 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
         schema: &DFSchema,
         planner_context: &mut PlannerContext,
     ) -> Result<Expr> {
+        let expr = self.identifier_to_expr(id, schema, planner_context)?;
+        planner_context.record_column_references(&expr);
+        Ok(expr)
+    }
+
+    fn identifier_to_expr(
+        &self,
+        id: Ident,
+        schema: &DFSchema,
+        planner_context: &mut PlannerContext,
+    ) -> Result<Expr> {
+        self.check_identifier_length(&id)?;
         let id_span = id.span;
         if id.value.starts_with('@') {
             // TODO: figure out if ScalarVariables should be insensitive.
@@ -52,6 +65,13 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
             // identifier. (e.g. it is "foo.bar" not foo.bar)
             let normalize_ident = self.ident_normalizer.normalize(id);
 
+            // Check for a column joined by a right or full `USING` join
+            if let Some(expr) =
+                planner_context.using_join_column(normalize_ident.as_str(), schema)
+            {
+                return Ok(expr);
+            }
+
             // Check for qualified field with unqualified name
             if let Ok((qualifier, _)) =
                 schema.qualified_field_with_unqualified_name(normalize_ident.as_str())
@@ -68,17 +88,22 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                 return Ok(Expr::Column(column));
             }
 
-            // Check the outer query schema
-            if let Some(outer) = planner_context.outer_query_schema() {
-                if let Ok((qualifier, field)) =
-                    outer.qualified_field_with_unqualified_name(normalize_ident.as_str())
-                {
-                    // Found an exact match on a qualified name in the outer plan schema, so this is an outer reference column
-                    return Ok(Expr::OuterReferenceColumn(
-                        field.data_type().clone(),
-                        Column::from((qualifier, field)),
-                    ));
-                }
+            // Check the outer query schemas
+            if let Some((qualifier, field)) = search_outer_queries_schemas(
+                planner_context,
+                &normalize_ident,
+                Span::try_from_sqlparser_span(id_span),
+                |outer| {
+                    outer
+                        .qualified_field_with_unqualified_name(normalize_ident.as_str())
+                        .ok()
+                },
+            ) {
+                // Found an exact match on a qualified name in the outer plan schema, so this is an outer reference column
+                return Ok(Expr::OuterReferenceColumn(
+                    field.data_type().clone(),
+                    Column::from((qualifier, field)),
+                ));
             }
 
             // Default case
@@ -97,10 +122,24 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
         ids: Vec<Ident>,
         schema: &DFSchema,
         planner_context: &mut PlannerContext,
+    ) -> Result<Expr> {
+        let expr = self.compound_identifier_to_expr(ids, schema, planner_context)?;
+        planner_context.record_column_references(&expr);
+        Ok(expr)
+    }
+
+    fn compound_identifier_to_expr(
+        &self,
+        ids: Vec<Ident>,
+        schema: &DFSchema,
+        planner_context: &mut PlannerContext,
     ) -> Result<Expr> {
         if ids.len() < 2 {
             return internal_err!("Not a compound identifier: {ids:?}");
         }
+        for id in &ids {
+            self.check_identifier_length(id)?;
+        }
 
         let ids_span = Span::union_iter(
             ids.iter()
@@ -164,9 +203,14 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                     if ids.len() == 5 {
                         not_impl_err!("compound identifier: {ids:?}")
                     } else {
-                        // Check the outer_query_schema and try to find a match
-                        if let Some(outer) = planner_context.outer_query_schema() {
-                            let search_result = search_dfschema(&ids, outer);
+                        // Check the outer query schemas and try to find a match
+                        if planner_context.outer_query_schema().is_some() {
+                            let search_result = search_outer_queries_schemas(
+                                planner_context,
+                                &ids.join("."),
+                                ids_span,
+                                |outer| search_dfschema(&ids, outer),
+                            );
                             match search_result {
                                 // Found matching field with spare identifier(s) for nested field(s) in structure
                                 Some((field, qualifier, nested_names))
@@ -290,6 +334,31 @@ fn form_identifier(idents: &[String]) -> Result<(Option<TableReference>, &String
     }
 }
 
+/// Searches the schemas of the enclosing queries with `search`, from the
+/// nearest enclosing query to the outermost one, and returns the first match.
+///
+/// A column matching several enclosing queries is resolved to the nearest one,
+/// which shadows the columns of the queries around it, with a warning recorded
+/// for the column at `span`.
+fn search_outer_queries_schemas<'a, T>(
+    planner_context: &'a PlannerContext,
+    name: &str,
+    span: Option<Span>,
+    search: impl Fn(&'a DFSchema) -> Option<T>,
+) -> Option<T> {
+    let mut outer_schemas = planner_context.outer_queries_schemas().iter().rev();
+    let found = outer_schemas.find_map(|outer| search(outer))?;
+    if outer_schemas.any(|outer| search(outer).is_some()) {
+        let message = format!(
+            "Column {name} matches several enclosing queries of a subquery, \
+            resolving it to the nearest one"
+        );
+        warn!("{message}");
+        planner_context.record_warning(Diagnostic::new_warning(message, span));
+    }
+    Some(found)
+}
+
 fn search_dfschema<'ids, 'schema>(
     ids: &'ids [String],
     schema: &'schema DFSchema,
@@ -21,22 +21,27 @@ use datafusion_expr::planner::{
 };
 use sqlparser::ast::{
     AccessExpr, BinaryOperator, CastFormat, CastKind, DataType as SQLDataType,
-    DictionaryField, Expr as SQLExpr, ExprWithAlias as SQLExprWithAlias, MapEntry,
-    StructField, Subscript, TrimWhereField, Value, ValueWithSpan,
+    DictionaryField, Expr as SQLExpr, ExprWithAlias as SQLExprWithAlias, Function,
+    FunctionArguments, MapEntry, StructField, Subscript, TrimWhereField, Value,
+    ValueWithSpan,
 };
+use sqlparser::dialect::dialect_from_str;
 
 use datafusion_common::{
-    internal_datafusion_err, internal_err, not_impl_err, plan_err, DFSchema, Result,
-    ScalarValue,
+    internal_datafusion_err, internal_err, not_impl_err, plan_datafusion_err, plan_err,
+    DFSchema, Result, ScalarValue,
 };
 
 use datafusion_expr::expr::ScalarFunction;
 use datafusion_expr::expr::{InList, WildcardOptions};
+use datafusion_expr::expr_rewriter::normalize_col_with_schemas_and_ambiguity_check;
+use datafusion_expr::utils::{find_aggregate_exprs, find_window_exprs};
 use datafusion_expr::{
     lit, Between, BinaryExpr, Cast, Expr, ExprSchemable, GetFieldAccess, Like, Literal,
     Operator, TryCast,
 };
 
+use crate::parser::DFParserBuilder;
 use crate::planner::{ContextProvider, PlannerContext, SqlToRel};
 
 mod binary_op;
@@ -155,6 +160,7 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
         expr = self.rewrite_partial_qualifier(expr, schema);
         self.validate_schema_satisfies_exprs(schema, &[expr.clone()])?;
         let (expr, _) = expr.infer_placeholder_types(schema)?;
+        planner_context.record_placeholders(&expr);
         Ok(expr)
     }
 
@@ -170,6 +176,45 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
         expr = self.rewrite_partial_qualifier(expr, schema);
         self.validate_schema_satisfies_exprs(schema, std::slice::from_ref(&expr))?;
         let (expr, _) = expr.infer_placeholder_types(schema)?;
+        planner_context.record_placeholders(&expr);
+        Ok(expr)
+    }
+
+    /// Generate a relational expression from the standalone SQL expression
+    /// string `sql`, such as a filter like `a > 1 AND b LIKE 'x%'`, whose
+    /// columns refer to `schema`.
+    ///
+    /// The expression is parsed with the dialect of
+    /// [`ContextProvider::options`], so error spans point into `sql`. It is
+    /// planned like the expressions of a `SELECT`: it may call the functions
+    /// of the [`ContextProvider`], the types of its placeholders are inferred,
+    /// and its columns are qualified with the relations of `schema`. As there
+    /// is no query to compute them in, aggregate and window functions are not
+    /// allowed.
+    pub fn parse_sql_expr(&self, sql: &str, schema: &DFSchema) -> Result<Expr> {
+        let options = &self.context_provider.options().sql_parser;
+        let dialect = dialect_from_str(&options.dialect).ok_or_else(|| {
+            plan_datafusion_err!("Unsupported SQL dialect: {}", options.dialect)
+        })?;
+        let sql_expr = DFParserBuilder::new(sql)
+            .with_dialect(dialect.as_ref())
+            .with_recursion_limit(options.recursion_limit)
+            .build()?
+            .parse_into_expr()?;
+        let expr =
+            self.sql_to_expr_with_alias(sql_expr, schema, &mut PlannerContext::new())?;
+        let expr =
+            normalize_col_with_schemas_and_ambiguity_check(expr, &[&[schema]], &[])?;
+        if let Some(aggregate) = find_aggregate_exprs([&expr]).first() {
+            return plan_err!(
+                "Aggregate function {aggregate} is not allowed in a standalone expression"
+            );
+        }
+        if let Some(window) = find_window_exprs([&expr]).first() {
+            return plan_err!(
+                "Window function {window} is not allowed in a standalone expression"
+            );
+        }
         Ok(expr)
     }
 
@@ -211,7 +256,7 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
         //       more context.
         match sql {
             SQLExpr::Value(value) => {
-                self.parse_value(value.into(), planner_context.prepare_param_data_types())
+                self.sql_value_to_expr(value.into(), planner_context)
             }
             SQLExpr::Extract { field, expr, .. } => {
                 let mut extract_args = vec![
@@ -231,7 +276,9 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                 not_impl_err!("Extract not supported by ExprPlanner: {extract_args:?}")
             }
 
-            SQLExpr::Array(arr) => self.sql_array_literal(arr.elem, schema),
+            SQLExpr::Array(arr) => {
+                self.sql_array_literal(arr.elem, schema, planner_context)
+            }
             SQLExpr::Interval(interval) => self.sql_interval_to_expr(false, interval),
             SQLExpr::Identifier(id) => {
                 self.sql_identifier_to_expr(id, schema, planner_context)
@@ -478,6 +525,13 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                 planner_context,
             ),
 
+            SQLExpr::Function(Function {
+                name,
+                args: FunctionArguments::Subquery(subquery),
+                ..
+            }) if name.to_string().eq_ignore_ascii_case("array") => {
+                self.parse_array_subquery(*subquery, schema, planner_context)
+            }
             SQLExpr::Function(function) => {
                 self.sql_function_to_expr(function, schema, planner_context)
             }
@@ -825,6 +879,8 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
         if any {
             return not_impl_err!("ANY in LIKE expression");
         }
+        let expr = strip_like_collation(expr)?;
+        let pattern = strip_like_collation(pattern)?;
         let pattern = self.sql_expr_to_logical_expr(pattern, schema, planner_context)?;
         let escape_char = match escape_char {
             Some(Value::SingleQuotedString(char)) if char.len() == 1 => {
@@ -1167,6 +1223,30 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
     }
 }
 
+/// Removes a `COLLATE` clause from an operand of `LIKE`.
+///
+/// DataFusion compares strings byte-wise, so collations with binary semantics
+/// (such as `"C"`) can be dropped without changing the result. Any other
+/// collation is reported as not implemented rather than silently ignored.
+fn strip_like_collation(expr: SQLExpr) -> Result<SQLExpr> {
+    match expr {
+        SQLExpr::Collate { expr, collation } => {
+            let is_binary = collation.0.last().is_some_and(|part| {
+                part.as_ident().is_some_and(|ident| {
+                    ["c", "posix", "binary", "ucs_basic"]
+                        .contains(&ident.value.to_lowercase().as_str())
+                })
+            });
+            if is_binary {
+                Ok(*expr)
+            } else {
+                not_impl_err!("COLLATE {collation} in LIKE expression")
+            }
+        }
+        expr => Ok(expr),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
@@ -16,9 +16,14 @@
 // under the License.
 
 use crate::planner::{ContextProvider, PlannerContext, SqlToRel};
-use datafusion_common::{plan_err, DFSchema, Diagnostic, Result, Span, Spans};
-use datafusion_expr::expr::{Exists, InSubquery};
-use datafusion_expr::{Expr, LogicalPlan, Subquery};
+use datafusion_common::{
+    plan_err, Column, DFSchema, Diagnostic, Result, ScalarValue, Span, Spans,
+};
+use datafusion_expr::expr::{AggregateFunction, Exists, InSubquery};
+use datafusion_expr::{
+    when, AggregateUDF, Expr, Limit, LogicalPlan, LogicalPlanBuilder, Projection, Sort,
+    Subquery,
+};
 use sqlparser::ast::Expr as SQLExpr;
 use sqlparser::ast::{Query, SelectItem, SetExpr};
 use std::sync::Arc;
@@ -31,11 +36,10 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
         input_schema: &DFSchema,
         planner_context: &mut PlannerContext,
     ) -> Result<Expr> {
-        let old_outer_query_schema =
-            planner_context.set_outer_query_schema(Some(input_schema.clone().into()));
+        planner_context.append_outer_query_schema(input_schema.clone().into());
         let sub_plan = self.query_to_plan(subquery, planner_context)?;
         let outer_ref_columns = sub_plan.all_out_ref_exprs();
-        planner_context.set_outer_query_schema(old_outer_query_schema);
+        planner_context.pop_outer_query_schema();
         Ok(Expr::Exists(Exists {
             subquery: Subquery {
                 subquery: Arc::new(sub_plan),
@@ -54,8 +58,7 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
         input_schema: &DFSchema,
         planner_context: &mut PlannerContext,
     ) -> Result<Expr> {
-        let old_outer_query_schema =
-            planner_context.set_outer_query_schema(Some(input_schema.clone().into()));
+        planner_context.append_outer_query_schema(input_schema.clone().into());
 
         let mut spans = Spans::new();
         if let SetExpr::Select(select) = &subquery.body.as_ref() {
@@ -70,7 +73,7 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
 
         let sub_plan = self.query_to_plan(subquery, planner_context)?;
         let outer_ref_columns = sub_plan.all_out_ref_exprs();
-        planner_context.set_outer_query_schema(old_outer_query_schema);
+        planner_context.pop_outer_query_schema();
 
         self.validate_single_column(
             &sub_plan,
@@ -98,8 +101,7 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
         input_schema: &DFSchema,
         planner_context: &mut PlannerContext,
     ) -> Result<Expr> {
-        let old_outer_query_schema =
-            planner_context.set_outer_query_schema(Some(input_schema.clone().into()));
+        planner_context.append_outer_query_schema(input_schema.clone().into());
         let mut spans = Spans::new();
         if let SetExpr::Select(select) = subquery.body.as_ref() {
             for item in &select.projection {
@@ -112,7 +114,7 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
         }
         let sub_plan = self.query_to_plan(subquery, planner_context)?;
         let outer_ref_columns = sub_plan.all_out_ref_exprs();
-        planner_context.set_outer_query_schema(old_outer_query_schema);
+        planner_context.pop_outer_query_schema();
 
         self.validate_single_column(
             &sub_plan,
@@ -128,6 +130,43 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
         }))
     }
 
+    /// Plan an `ARRAY(subquery)` expression as a scalar subquery that collects
+    /// the rows of `subquery` into a list with `array_agg`.
+    ///
+    /// The `ORDER BY` of the subquery determines the order of the elements, and
+    /// a subquery without rows produces an empty list rather than `NULL`.
+    pub(super) fn parse_array_subquery(
+        &self,
+        subquery: Query,
+        input_schema: &DFSchema,
+        planner_context: &mut PlannerContext,
+    ) -> Result<Expr> {
+        planner_context.append_outer_query_schema(input_schema.clone().into());
+        let sub_plan = self.query_to_plan(subquery, planner_context)?;
+        planner_context.pop_outer_query_schema();
+
+        self.validate_single_column(
+            &sub_plan,
+            Spans::new(),
+            "Too many columns! The ARRAY subquery should only return one column",
+            "Select only one column in the subquery",
+        )?;
+        let Some(array_agg) = self.context_provider.get_aggregate_meta("array_agg")
+        else {
+            return plan_err!(
+                "ARRAY subqueries require the array_agg aggregate function"
+            );
+        };
+
+        let sub_plan = array_agg_subquery_plan(sub_plan, array_agg)?;
+        let outer_ref_columns = sub_plan.all_out_ref_exprs();
+        Ok(Expr::ScalarSubquery(Subquery {
+            subquery: Arc::new(sub_plan),
+            outer_ref_columns,
+            spans: Spans::new(),
+        }))
+    }
+
     fn validate_single_column(
         &self,
         sub_plan: &LogicalPlan,
@@ -163,3 +202,77 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
         diagnostic
     }
 }
+
+/// Aggregate the single column of `plan` into a list, keeping the name of the
+/// column:
+///
+/// ```text
+/// Projection: CASE WHEN array_agg(t.a) IS NULL THEN [] ELSE array_agg(t.a) END AS a
+///   Aggregate: groupBy=[[]], aggr=[[array_agg(t.a)]]
+///     <plan>
+/// ```
+///
+/// A `Sort` at the top of `plan` is moved into the aggregate as its ordering,
+/// since the order of the rows is otherwise not preserved by the aggregation.
+fn array_agg_subquery_plan(
+    plan: LogicalPlan,
+    array_agg: Arc<AggregateUDF>,
+) -> Result<LogicalPlan> {
+    let field = plan.schema().field(0).clone();
+    let first_column =
+        |plan: &LogicalPlan| Expr::Column(Column::from(plan.schema().qualified_field(0)));
+    // The sort of a `LIMIT` selects its rows, so it stays below the aggregate
+    let limit_order_by = match &plan {
+        LogicalPlan::Sort(sort) if sort.fetch.is_some() => sort.expr.clone(),
+        LogicalPlan::Limit(Limit { input, .. }) => match input.as_ref() {
+            LogicalPlan::Sort(sort) => sort.expr.clone(),
+            _ => vec![],
+        },
+        _ => vec![],
+    };
+    let (input, arg, order_by) = match plan {
+        LogicalPlan::Sort(Sort {
+            expr,
+            input,
+            fetch: None,
+        }) => {
+            let arg = first_column(&input);
+            (Arc::unwrap_or_clone(input), arg, expr)
+        }
+        // `ORDER BY` on columns that are not selected
+        LogicalPlan::Projection(Projection {
+            mut expr, input, ..
+        }) if matches!(input.as_ref(), LogicalPlan::Sort(Sort { fetch: None, .. })) => {
+            let LogicalPlan::Sort(sort) = Arc::unwrap_or_clone(input) else {
+                unreachable!()
+            };
+            let arg = expr.swap_remove(0).unalias();
+            (Arc::unwrap_or_clone(sort.input), arg, sort.expr)
+        }
+        plan => {
+            let arg = first_column(&plan);
+            (plan, arg, limit_order_by)
+        }
+    };
+
+    let aggregate = Expr::AggregateFunction(AggregateFunction::new_udf(
+        array_agg,
+        vec![arg],
+        false,
+        None,
+        order_by,
+        None,
+    ));
+    let aggregated = LogicalPlanBuilder::from(input)
+        .aggregate(Vec::<Expr>::new(), vec![aggregate])?
+        .build()?;
+    let list = first_column(&aggregated);
+    let empty_list =
+        ScalarValue::List(ScalarValue::new_list_nullable(&[], field.data_type()));
+    let array = when(list.clone().is_null(), Expr::Literal(empty_list, None))
+        .otherwise(list)?
+        .alias(field.name());
+    LogicalPlanBuilder::from(aggregated)
+        .project(vec![array])?
+        .build()
+}
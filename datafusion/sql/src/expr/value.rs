@@ -20,13 +20,14 @@ use arrow::compute::kernels::cast_utils::{
     parse_interval_month_day_nano_config, IntervalParseConfig, IntervalUnit,
 };
 use arrow::datatypes::{
-    i256, DataType, DECIMAL128_MAX_PRECISION, DECIMAL256_MAX_PRECISION,
+    i256, DataType, IntervalMonthDayNano, DECIMAL128_MAX_PRECISION,
+    DECIMAL256_MAX_PRECISION,
 };
 use bigdecimal::num_bigint::BigInt;
 use bigdecimal::{BigDecimal, Signed, ToPrimitive};
 use datafusion_common::{
-    internal_datafusion_err, not_impl_err, plan_err, DFSchema, DataFusionError, Result,
-    ScalarValue,
+    internal_datafusion_err, not_impl_err, plan_datafusion_err, plan_err, DFSchema,
+    DataFusionError, Result, ScalarValue,
 };
 use datafusion_expr::expr::{BinaryExpr, Placeholder};
 use datafusion_expr::planner::PlannerResult;
@@ -68,8 +69,20 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
         }
     }
 
+    /// Generate an expression from a SQL value, recording it in the metadata
+    /// of the statement if it is a placeholder
+    pub(super) fn sql_value_to_expr(
+        &self,
+        value: Value,
+        planner_context: &PlannerContext,
+    ) -> Result<Expr> {
+        let expr = self.parse_value(value, planner_context.prepare_param_data_types())?;
+        planner_context.record_placeholders(&expr);
+        Ok(expr)
+    }
+
     /// Parse number in sql string, convert to Expr::Literal
-    pub(super) fn parse_sql_number(
+    pub(crate) fn parse_sql_number(
         &self,
         unsigned_number: &str,
         negative: bool,
@@ -143,11 +156,12 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
         &self,
         elements: Vec<SQLExpr>,
         schema: &DFSchema,
+        planner_context: &mut PlannerContext,
     ) -> Result<Expr> {
         let values = elements
             .into_iter()
             .map(|element| {
-                self.sql_expr_to_logical_expr(element, schema, &mut PlannerContext::new())
+                self.sql_expr_to_logical_expr(element, schema, planner_context)
             })
             .collect::<Result<Vec<_>>>()?;
 
@@ -238,6 +252,14 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
 
         let value = interval_literal(*interval.value, negative)?;
 
+        if interval.leading_field.is_none() {
+            if let Some(val) =
+                parse_interval_duration(&value, self.options.enable_interval_shorthand)?
+            {
+                return Ok(lit(ScalarValue::IntervalMonthDayNano(Some(val))));
+            }
+        }
+
         // leading_field really means the unit if specified
         // For example, "month" in  `INTERVAL '5' month`
         let value = match interval.leading_field.as_ref() {
@@ -292,6 +314,313 @@ fn interval_literal(interval_value: SQLExpr, negative: bool) -> Result<String> {
     }
 }
 
+/// Parse an interval string written as an ISO 8601 duration such as
+/// `P1Y2M3DT4H5M6.5S`, or in the shorthand form such as `1d2h30m` if
+/// `shorthand` is set. Returns `None` for any other interval string, which is
+/// parsed in the verbose form such as `1 day 2 hours`.
+///
+/// Years and months add to the months of the interval, weeks and days to its
+/// days and the time components to its nanoseconds, so `P1M` and `P30D` stay
+/// distinct. Only time components can be fractional, and they are rounded to
+/// the nearest nanosecond, with ties rounded away from zero. A leading sign
+/// applies to the whole interval, and the components may not have different
+/// signs.
+fn parse_interval_duration(
+    value: &str,
+    shorthand: bool,
+) -> Result<Option<IntervalMonthDayNano>> {
+    let trimmed = value.trim();
+    let unsigned = trimmed.trim_start_matches(['-', '+']);
+    // A negated negative interval literal has two leading signs
+    let negative = trimmed[..trimmed.len() - unsigned.len()]
+        .matches('-')
+        .count()
+        % 2
+        == 1;
+
+    if let Some(duration) = unsigned.strip_prefix(['P', 'p']) {
+        parse_iso8601_duration(value, duration, negative).map(Some)
+    } else if shorthand {
+        parse_interval_shorthand(value, unsigned, negative)
+    } else {
+        Ok(None)
+    }
+}
+
+/// Parse the components of the ISO 8601 `duration` following its `P`
+fn parse_iso8601_duration(
+    value: &str,
+    duration: &str,
+    negative: bool,
+) -> Result<IntervalMonthDayNano> {
+    let (date, time) = match duration.split_once(['T', 't']) {
+        Some((_, "")) => {
+            return plan_err!(
+                "Invalid ISO 8601 duration '{value}': no time components follow 'T'"
+            )
+        }
+        Some((date, time)) => (date, time),
+        None if duration.is_empty() => {
+            return plan_err!("Invalid ISO 8601 duration '{value}': no components")
+        }
+        None => (duration, ""),
+    };
+
+    let mut components = IntervalComponents::new(value);
+    for (part, units) in [
+        (date, &ISO8601_DATE_UNITS[..]),
+        (time, &ISO8601_TIME_UNITS[..]),
+    ] {
+        let mut remaining_units = units;
+        let mut rest = part;
+        while !rest.is_empty() {
+            let number_len = rest
+                .find(|c: char| {
+                    !(c.is_ascii_digit() || matches!(c, '+' | '-' | '.' | ','))
+                })
+                .unwrap_or(rest.len());
+            let designator = rest[number_len..].chars().next();
+            let token_len = number_len + designator.map_or(0, char::len_utf8);
+            let (token, number) = (&rest[..token_len], &rest[..number_len]);
+            rest = &rest[token_len..];
+
+            let Some(designator) = designator else {
+                return plan_err!(
+                    "Invalid interval component '{token}' in '{value}': missing unit designator"
+                );
+            };
+            let Some(position) = remaining_units
+                .iter()
+                .position(|(d, _)| d.eq_ignore_ascii_case(&designator))
+            else {
+                let reason = if units
+                    .iter()
+                    .any(|(d, _)| d.eq_ignore_ascii_case(&designator))
+                {
+                    "repeated or out of order unit"
+                } else {
+                    "unexpected unit designator"
+                };
+                return plan_err!(
+                    "Invalid interval component '{token}' in '{value}': {reason}"
+                );
+            };
+            components.add(token, number, remaining_units[position].1)?;
+            remaining_units = &remaining_units[position + 1..];
+        }
+    }
+    components.finish(negative)
+}
+
+/// Parse the components of the shorthand interval string `shorthand`, or
+/// return `None` if it is not one, e.g. for `1 day`
+fn parse_interval_shorthand(
+    value: &str,
+    shorthand: &str,
+    negative: bool,
+) -> Result<Option<IntervalMonthDayNano>> {
+    if shorthand.is_empty() {
+        return Ok(None);
+    }
+
+    let mut components = IntervalComponents::new(value);
+    let mut remaining_units = &SHORTHAND_UNITS[..];
+    let mut rest = shorthand;
+    while !rest.is_empty() {
+        let number_len = rest
+            .find(|c: char| !(c.is_ascii_digit() || matches!(c, '+' | '-' | '.')))
+            .unwrap_or(rest.len());
+        let unit_len = rest[number_len..]
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(rest.len() - number_len);
+        let token = &rest[..number_len + unit_len];
+        let (number, unit) = token.split_at(number_len);
+        rest = &rest[token.len()..];
+
+        if number.is_empty() || unit.is_empty() {
+            return Ok(None);
+        }
+        let Some(position) = remaining_units
+            .iter()
+            .position(|(u, _)| u.eq_ignore_ascii_case(unit))
+        else {
+            if SHORTHAND_UNITS
+                .iter()
+                .any(|(u, _)| u.eq_ignore_ascii_case(unit))
+            {
+                return plan_err!(
+                    "Invalid interval component '{token}' in '{value}': repeated or out of order unit"
+                );
+            }
+            return Ok(None);
+        };
+        components.add(token, number, remaining_units[position].1)?;
+        remaining_units = &remaining_units[position + 1..];
+    }
+    components.finish(negative).map(Some)
+}
+
+/// The field of an `IntervalMonthDayNano` a component of an interval string
+/// adds to, with the amount of that field per unit of the component
+#[derive(Debug, Clone, Copy)]
+enum IntervalComponentUnit {
+    Months(i128),
+    Days(i128),
+    Nanoseconds(i128),
+}
+
+const NANOS_PER_SECOND: i128 = 1_000_000_000;
+
+/// The designators of the date components of an ISO 8601 duration, in the
+/// order they appear in
+const ISO8601_DATE_UNITS: [(char, IntervalComponentUnit); 4] = [
+    ('Y', IntervalComponentUnit::Months(12)),
+    ('M', IntervalComponentUnit::Months(1)),
+    ('W', IntervalComponentUnit::Days(7)),
+    ('D', IntervalComponentUnit::Days(1)),
+];
+
+/// The designators of the time components of an ISO 8601 duration, which
+/// follow its `T`, in the order they appear in
+const ISO8601_TIME_UNITS: [(char, IntervalComponentUnit); 3] = [
+    (
+        'H',
+        IntervalComponentUnit::Nanoseconds(3600 * NANOS_PER_SECOND),
+    ),
+    (
+        'M',
+        IntervalComponentUnit::Nanoseconds(60 * NANOS_PER_SECOND),
+    ),
+    ('S', IntervalComponentUnit::Nanoseconds(NANOS_PER_SECOND)),
+];
+
+/// The units of the components of a shorthand interval string, in the order
+/// they appear in. `m` is minutes and `mo` months.
+const SHORTHAND_UNITS: [(&str, IntervalComponentUnit); 10] = [
+    ("y", IntervalComponentUnit::Months(12)),
+    ("mo", IntervalComponentUnit::Months(1)),
+    ("w", IntervalComponentUnit::Days(7)),
+    ("d", IntervalComponentUnit::Days(1)),
+    (
+        "h",
+        IntervalComponentUnit::Nanoseconds(3600 * NANOS_PER_SECOND),
+    ),
+    (
+        "m",
+        IntervalComponentUnit::Nanoseconds(60 * NANOS_PER_SECOND),
+    ),
+    ("s", IntervalComponentUnit::Nanoseconds(NANOS_PER_SECOND)),
+    ("ms", IntervalComponentUnit::Nanoseconds(1_000_000)),
+    ("us", IntervalComponentUnit::Nanoseconds(1_000)),
+    ("ns", IntervalComponentUnit::Nanoseconds(1)),
+];
+
+/// The fields of an `IntervalMonthDayNano` summed from the components of the
+/// interval string `value`
+struct IntervalComponents<'a> {
+    value: &'a str,
+    months: i128,
+    days: i128,
+    nanoseconds: i128,
+    /// Whether the components are negative, once one is added
+    negative: Option<bool>,
+}
+
+impl<'a> IntervalComponents<'a> {
+    fn new(value: &'a str) -> Self {
+        Self {
+            value,
+            months: 0,
+            days: 0,
+            nanoseconds: 0,
+            negative: None,
+        }
+    }
+
+    /// Add the component `token`, which is the optionally signed decimal
+    /// `number` of `unit`
+    fn add(
+        &mut self,
+        token: &str,
+        number: &str,
+        unit: IntervalComponentUnit,
+    ) -> Result<()> {
+        let value = self.value;
+        let (negative, unsigned) = match number.strip_prefix('-') {
+            Some(unsigned) => (true, unsigned),
+            None => (false, number.strip_prefix('+').unwrap_or(number)),
+        };
+        if *self.negative.get_or_insert(negative) != negative {
+            return plan_err!(
+                "Invalid interval component '{token}' in '{value}': components have different signs"
+            );
+        }
+
+        let (integer, fraction) =
+            unsigned.split_once(['.', ',']).unwrap_or((unsigned, ""));
+        let is_digits = |digits: &str| digits.bytes().all(|b| b.is_ascii_digit());
+        if (integer.is_empty() && fraction.is_empty())
+            || !is_digits(integer)
+            || !is_digits(fraction)
+        {
+            return plan_err!(
+                "Invalid interval component '{token}' in '{value}': invalid number"
+            );
+        }
+        let out_of_range = || {
+            plan_datafusion_err!(
+                "Invalid interval component '{token}' in '{value}': out of range"
+            )
+        };
+
+        let (field, per_unit) = match unit {
+            IntervalComponentUnit::Months(months) => (&mut self.months, months),
+            IntervalComponentUnit::Days(days) => (&mut self.days, days),
+            IntervalComponentUnit::Nanoseconds(nanos) => (&mut self.nanoseconds, nanos),
+        };
+        let integer = match integer {
+            "" => 0,
+            integer => integer.parse::<i128>().map_err(|_| out_of_range())?,
+        };
+        let mut amount = integer.checked_mul(per_unit).ok_or_else(out_of_range)?;
+        if fraction.bytes().any(|b| b != b'0') {
+            if !matches!(unit, IntervalComponentUnit::Nanoseconds(_)) {
+                return plan_err!(
+                    "Invalid interval component '{token}' in '{value}': only hours, minutes and seconds can be fractional"
+                );
+            }
+            // Digits past the 18th are far below a nanosecond for components
+            // of at most an hour
+            let fraction = &fraction[..fraction.len().min(18)];
+            let denominator = 10_i128.pow(fraction.len() as u32);
+            let numerator = fraction.parse::<i128>().map_err(|_| out_of_range())?;
+            let rounded = (2 * numerator * per_unit + denominator) / (2 * denominator);
+            amount = amount.checked_add(rounded).ok_or_else(out_of_range)?;
+        }
+        *field = field.checked_add(amount).ok_or_else(out_of_range)?;
+        Ok(())
+    }
+
+    /// Return the interval of the components, negated if `negative`
+    fn finish(self, negative: bool) -> Result<IntervalMonthDayNano> {
+        let value = self.value;
+        let sign = if negative != self.negative.unwrap_or(false) {
+            -1
+        } else {
+            1
+        };
+        let out_of_range = |field: &str| {
+            plan_datafusion_err!("Interval '{value}' is out of range: too many {field}")
+        };
+        Ok(IntervalMonthDayNano::new(
+            i32::try_from(sign * self.months).map_err(|_| out_of_range("months"))?,
+            i32::try_from(sign * self.days).map_err(|_| out_of_range("days"))?,
+            i64::try_from(sign * self.nanoseconds)
+                .map_err(|_| out_of_range("nanoseconds"))?,
+        ))
+    }
+}
+
 /// Try to decode bytes from hex literal string.
 ///
 /// None will be returned if the input literal is hex-invalid.
@@ -506,4 +835,144 @@ mod tests {
             "This feature is not implemented: Decimal precision 77 exceeds the maximum supported precision: 76"
         );
     }
+
+    #[test]
+    fn test_parse_interval_duration() {
+        const SEC: i64 = 1_000_000_000;
+        let cases = [
+            // ISO 8601 durations
+            ("P1DT2H30M", false, (0, 1, 9000 * SEC)),
+            ("P1Y2M", false, (14, 0, 0)),
+            ("P2W3D", false, (0, 17, 0)),
+            ("P1M", false, (1, 0, 0)),
+            ("P30D", false, (0, 30, 0)),
+            ("PT36H", false, (0, 0, 36 * 3600 * SEC)),
+            ("PT0.5S", false, (0, 0, SEC / 2)),
+            ("PT0,25M", false, (0, 0, 15 * SEC)),
+            ("PT1.5H", false, (0, 0, 5400 * SEC)),
+            ("P1.0D", false, (0, 1, 0)),
+            ("p1dt2h", false, (0, 1, 7200 * SEC)),
+            (" P1D ", false, (0, 1, 0)),
+            // Fractional seconds are rounded to the nearest nanosecond, with
+            // ties rounded away from zero
+            ("PT1.0000000004S", false, (0, 0, SEC)),
+            ("PT1.0000000005S", false, (0, 0, SEC + 1)),
+            ("-PT1.0000000005S", false, (0, 0, -SEC - 1)),
+            ("PT0.333333333333333333333S", false, (0, 0, 333_333_333)),
+            // Negative durations
+            ("-P1DT2H", false, (0, -1, -7200 * SEC)),
+            ("P-1DT-2H", false, (0, -1, -7200 * SEC)),
+            ("-P-1D", false, (0, 1, 0)),
+            ("--P1D", false, (0, 1, 0)),
+            ("+P1D", false, (0, 1, 0)),
+            // Shorthand
+            ("1d2h", true, (0, 1, 7200 * SEC)),
+            ("1D2H", true, (0, 1, 7200 * SEC)),
+            ("1y2mo3w", true, (14, 21, 0)),
+            ("1h30m", true, (0, 0, 5400 * SEC)),
+            ("1.5s", true, (0, 0, 3 * SEC / 2)),
+            ("1s250ms5us7ns", true, (0, 0, SEC + 250_005_007)),
+            ("-1d12h", true, (0, -1, -43200 * SEC)),
+            // ISO 8601 durations are accepted in shorthand mode too
+            ("PT5M", true, (0, 0, 300 * SEC)),
+        ];
+        for (input, shorthand, (months, days, nanoseconds)) in cases {
+            let output = parse_interval_duration(input, shorthand).unwrap();
+            assert_eq!(
+                output,
+                Some(IntervalMonthDayNano::new(months, days, nanoseconds)),
+                "{input}"
+            );
+        }
+
+        // Verbose interval strings are left to the arrow parser
+        for (input, shorthand) in [
+            ("1 day", false),
+            ("1 day", true),
+            ("1d", false),
+            ("5", true),
+            ("1day", true),
+            ("", true),
+        ] {
+            let output = parse_interval_duration(input, shorthand).unwrap();
+            assert_eq!(output, None, "{input}");
+        }
+
+        let cases = [
+            ("P", false, "Invalid ISO 8601 duration 'P': no components"),
+            (
+                "P1DT",
+                false,
+                "Invalid ISO 8601 duration 'P1DT': no time components follow 'T'",
+            ),
+            (
+                "P1D2D",
+                false,
+                "Invalid interval component '2D' in 'P1D2D': repeated or out of order unit",
+            ),
+            (
+                "P1D1Y",
+                false,
+                "Invalid interval component '1Y' in 'P1D1Y': repeated or out of order unit",
+            ),
+            (
+                "P1H",
+                false,
+                "Invalid interval component '1H' in 'P1H': unexpected unit designator",
+            ),
+            (
+                "P12",
+                false,
+                "Invalid interval component '12' in 'P12': missing unit designator",
+            ),
+            (
+                "PD",
+                false,
+                "Invalid interval component 'D' in 'PD': invalid number",
+            ),
+            (
+                "PT1.2.3S",
+                false,
+                "Invalid interval component '1.2.3S' in 'PT1.2.3S': invalid number",
+            ),
+            (
+                "P1DT-2H",
+                false,
+                "Invalid interval component '-2H' in 'P1DT-2H': components have different signs",
+            ),
+            (
+                "P1.5D",
+                false,
+                "Invalid interval component '1.5D' in 'P1.5D': only hours, minutes and seconds can be fractional",
+            ),
+            (
+                "P99999999999D",
+                false,
+                "Interval 'P99999999999D' is out of range: too many days",
+            ),
+            (
+                "1d-2h",
+                true,
+                "Invalid interval component '-2h' in '1d-2h': components have different signs",
+            ),
+            (
+                "2h1d",
+                true,
+                "Invalid interval component '1d' in '2h1d': repeated or out of order unit",
+            ),
+            (
+                "1.5mo",
+                true,
+                "Invalid interval component '1.5mo' in '1.5mo': only hours, minutes and seconds can be fractional",
+            ),
+        ];
+        for (input, shorthand, expected) in cases {
+            let err = parse_interval_duration(input, shorthand).unwrap_err();
+            assert_eq!(
+                err.strip_backtrace(),
+                format!("Error during planning: {expected}"),
+                "{input}"
+            );
+        }
+    }
 }
@@ -22,9 +22,14 @@
 
 use datafusion_common::config::SqlParserOptions;
 use datafusion_common::DataFusionError;
-use datafusion_common::{sql_err, Diagnostic, Span};
-use sqlparser::ast::{ExprWithAlias, OrderByOptions};
-use sqlparser::tokenizer::TokenWithSpan;
+use datafusion_common::{
+    not_impl_datafusion_err, plan_datafusion_err, sql_err, Diagnostic, Span,
+};
+use sqlparser::ast::{
+    Expr as SQLExpr, ExprWithAlias, GroupByExpr, OrderByOptions, Select, SetExpr,
+    Spanned, VisitMut, VisitorMut,
+};
+use sqlparser::tokenizer::{Location, TokenWithSpan, Whitespace};
 use sqlparser::{
     ast::{
         ColumnDef, ColumnOptionDef, ObjectName, OrderByExpr, Query,
@@ -36,6 +41,7 @@ use sqlparser::{
 };
 use std::collections::VecDeque;
 use std::fmt;
+use std::ops::{ControlFlow, Range};
 
 // Use `Parser::expected` instead, if possible
 macro_rules! parser_err {
@@ -231,15 +237,76 @@ pub struct CreateExternalTable {
     pub constraints: Vec<TableConstraint>,
 }
 
+/// Parts of the keys of options whose values are credentials
+const SECRET_OPTION_KEY_PARTS: [&str; 5] =
+    ["secret", "password", "token", "key", "credential"];
+
+/// Returns true if the value of the option `key` must not be displayed
+fn is_secret_option(key: &str) -> bool {
+    let key = key.to_lowercase();
+    SECRET_OPTION_KEY_PARTS
+        .iter()
+        .any(|part| key.contains(part))
+}
+
+/// Displays the statement as SQL. The values of options holding credentials,
+/// such as `aws.secret_access_key`, are replaced by `'*****'`.
 impl fmt::Display for CreateExternalTable {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "CREATE EXTERNAL TABLE ")?;
+        write!(f, "CREATE ")?;
+        if self.unbounded {
+            write!(f, "UNBOUNDED ")?;
+        }
+        write!(f, "EXTERNAL ")?;
+        if self.temporary {
+            write!(f, "TEMPORARY ")?;
+        }
+        write!(f, "TABLE ")?;
         if self.if_not_exists {
             write!(f, "IF NOT EXISTS ")?;
         }
-        write!(f, "{} ", self.name)?;
-        write!(f, "STORED AS {} ", self.file_type)?;
-        write!(f, "LOCATION {} ", self.location)
+        write!(f, "{}", self.name)?;
+        if !self.columns.is_empty() || !self.constraints.is_empty() {
+            let elements: Vec<_> = self
+                .columns
+                .iter()
+                .map(ToString::to_string)
+                .chain(self.constraints.iter().map(ToString::to_string))
+                .collect();
+            write!(f, " ({})", elements.join(", "))?;
+        }
+        write!(f, " STORED AS {}", self.file_type)?;
+        if !self.table_partition_cols.is_empty() {
+            write!(
+                f,
+                " PARTITIONED BY ({})",
+                self.table_partition_cols.join(", ")
+            )?;
+        }
+        for order in &self.order_exprs {
+            let exprs: Vec<_> = order.iter().map(ToString::to_string).collect();
+            write!(f, " WITH ORDER ({})", exprs.join(", "))?;
+        }
+        if !self.options.is_empty() {
+            let opts: Vec<_> = self
+                .options
+                .iter()
+                .map(|(k, v)| {
+                    let key = Value::SingleQuotedString(k.clone());
+                    if is_secret_option(k) {
+                        format!("{key} '*****'")
+                    } else {
+                        format!("{key} {v}")
+                    }
+                })
+                .collect();
+            write!(f, " OPTIONS ({})", opts.join(", "))?;
+        }
+        write!(
+            f,
+            " LOCATION {}",
+            Value::SingleQuotedString(self.location.clone())
+        )
     }
 }
 
@@ -291,6 +358,9 @@ fn ensure_not_set<T>(field: &Option<T>, name: &str) -> Result<(), DataFusionErro
 pub struct DFParser<'a> {
     pub parser: Parser<'a>,
     options: SqlParserOptions,
+    /// Locations of the `DISTINCT` of the `GROUP BY DISTINCT` clauses not
+    /// parsed yet, see [`remove_group_by_distinct`]
+    group_by_distinct: Vec<Location>,
 }
 
 /// Same as `sqlparser`
@@ -365,9 +435,10 @@ impl<'a> DFParserBuilder<'a> {
     pub fn build(self) -> Result<DFParser<'a>, DataFusionError> {
         let mut tokenizer = Tokenizer::new(self.dialect, self.sql);
         // Convert TokenizerError -> ParserError
-        let tokens = tokenizer
+        let mut tokens = tokenizer
             .tokenize_with_location()
             .map_err(ParserError::from)?;
+        let group_by_distinct = remove_group_by_distinct(&mut tokens);
 
         Ok(DFParser {
             parser: Parser::new(self.dialect)
@@ -377,10 +448,209 @@ impl<'a> DFParserBuilder<'a> {
                 recursion_limit: self.recursion_limit,
                 ..Default::default()
             },
+            group_by_distinct,
+        })
+    }
+
+    /// Build a [`ScriptParser`] that parses the statements of the SQL script
+    /// one at a time
+    pub fn build_script_parser(self) -> Result<ScriptParser<'a>, DataFusionError> {
+        let sql = self.sql;
+        let line_offsets = std::iter::once(0)
+            .chain(sql.match_indices('\n').map(|(offset, _)| offset + 1))
+            .collect();
+        Ok(ScriptParser {
+            sql,
+            parser: self.build()?,
+            line_offsets,
+            expecting_statement_delimiter: false,
+            finished: false,
         })
     }
 }
 
+/// Removes the `DISTINCT` of the `GROUP BY DISTINCT` clauses in `tokens`,
+/// which the SQL parser can not parse, and returns their locations.
+///
+/// The grouping sets of the parsed clauses are then made distinct by
+/// [`DFParser::rewrite_group_by_distinct`].
+fn remove_group_by_distinct(tokens: &mut [TokenWithSpan]) -> Vec<Location> {
+    fn is_keyword(token: &TokenWithSpan, keyword: Keyword) -> bool {
+        matches!(&token.token, Token::Word(w) if w.keyword == keyword)
+    }
+
+    let words = tokens
+        .iter()
+        .enumerate()
+        .filter(|(_, token)| !matches!(token.token, Token::Whitespace(_)))
+        .map(|(index, _)| index)
+        .collect::<Vec<_>>();
+    let mut locations = vec![];
+    for window in words.windows(3) {
+        let &[group, by, distinct] = window else {
+            continue;
+        };
+        if is_keyword(&tokens[group], Keyword::GROUP)
+            && is_keyword(&tokens[by], Keyword::BY)
+            && is_keyword(&tokens[distinct], Keyword::DISTINCT)
+        {
+            locations.push(tokens[distinct].span.start);
+            tokens[distinct].token = Token::Whitespace(Whitespace::Space);
+        }
+    }
+    locations
+}
+
+/// Calls `f` with every `SELECT` in `node`, including those of subqueries
+fn visit_selects<V: VisitMut>(
+    node: &mut V,
+    f: impl FnMut(&mut Select) -> Result<(), DataFusionError>,
+) -> Result<(), DataFusionError> {
+    struct SelectVisitor<F> {
+        f: F,
+    }
+
+    impl<F> SelectVisitor<F>
+    where
+        F: FnMut(&mut Select) -> Result<(), DataFusionError>,
+    {
+        fn visit_set_expr(
+            &mut self,
+            set_expr: &mut SetExpr,
+        ) -> Result<(), DataFusionError> {
+            match set_expr {
+                SetExpr::Select(select) => (self.f)(select),
+                SetExpr::SetOperation { left, right, .. } => {
+                    self.visit_set_expr(left)?;
+                    self.visit_set_expr(right)
+                }
+                // Nested queries are visited on their own
+                _ => Ok(()),
+            }
+        }
+    }
+
+    impl<F> VisitorMut for SelectVisitor<F>
+    where
+        F: FnMut(&mut Select) -> Result<(), DataFusionError>,
+    {
+        type Break = DataFusionError;
+
+        fn pre_visit_query(&mut self, query: &mut Query) -> ControlFlow<DataFusionError> {
+            match self.visit_set_expr(&mut query.body) {
+                Ok(()) => ControlFlow::Continue(()),
+                Err(e) => ControlFlow::Break(e),
+            }
+        }
+    }
+
+    match node.visit(&mut SelectVisitor { f }) {
+        ControlFlow::Continue(()) => Ok(()),
+        ControlFlow::Break(e) => Err(e),
+    }
+}
+
+/// Returns the location of the first expression of the `GROUP BY` of `select`
+fn group_by_start(select: &Select) -> Option<Location> {
+    match &select.group_by {
+        GroupByExpr::Expressions(exprs, _) => exprs.first().map(|e| e.span().start),
+        GroupByExpr::All(_) => None,
+    }
+}
+
+/// Replaces the `GROUP BY` of `select`, a `GROUP BY DISTINCT`, with the
+/// `GROUPING SETS` it groups by, without duplicate grouping sets.
+///
+/// The grouping sets of a `GROUP BY` are the cross product of the grouping
+/// sets of its `ROLLUP`, `CUBE`, `GROUPING SETS` and plain expressions.
+/// Grouping sets with the same expressions, in any order, are duplicates.
+fn distinct_grouping_sets(select: &mut Select) -> Result<(), DataFusionError> {
+    // Same as the maximum number of grouping sets of the planner
+    const MAX_GROUPING_SETS: usize = 4096;
+
+    let GroupByExpr::Expressions(exprs, modifiers) = &mut select.group_by else {
+        return Ok(());
+    };
+    if !modifiers.is_empty() {
+        return Err(not_impl_datafusion_err!(
+            "GROUP BY DISTINCT with modifiers is not supported"
+        ));
+    }
+    let has_grouping_sets = exprs.iter().any(|expr| {
+        matches!(
+            expr,
+            SQLExpr::Rollup(_) | SQLExpr::Cube(_) | SQLExpr::GroupingSets(_)
+        )
+    });
+    // Without grouping sets there is a single grouping set
+    if !has_grouping_sets {
+        return Ok(());
+    }
+
+    let mut grouping_sets: Vec<Vec<SQLExpr>> = vec![vec![]];
+    for expr in exprs.drain(..) {
+        let element_sets = match expr {
+            SQLExpr::Rollup(lists) => (0..=lists.len())
+                .rev()
+                .map(|len| lists[..len].concat())
+                .collect(),
+            SQLExpr::Cube(lists) => {
+                if (1usize << lists.len().min(63)) > MAX_GROUPING_SETS {
+                    return Err(plan_datafusion_err!(
+                        "The number of grouping sets of CUBE exceeds the maximum limit {MAX_GROUPING_SETS}"
+                    ));
+                }
+                (0..1usize << lists.len())
+                    .rev()
+                    .map(|mask| {
+                        lists
+                            .iter()
+                            .enumerate()
+                            .filter(|(index, _)| mask & (1 << index) != 0)
+                            .flat_map(|(_, list)| list.iter().cloned())
+                            .collect()
+                    })
+                    .collect()
+            }
+            SQLExpr::GroupingSets(sets) => sets,
+            expr => vec![vec![expr]],
+        };
+        let len = grouping_sets.len() * element_sets.len();
+        if len > MAX_GROUPING_SETS {
+            return Err(plan_datafusion_err!(
+                "The number of grouping sets exceeds the maximum limit {MAX_GROUPING_SETS}, found {len}"
+            ));
+        }
+        grouping_sets = grouping_sets
+            .iter()
+            .flat_map(|set| {
+                element_sets
+                    .iter()
+                    .map(move |element| [set.as_slice(), element].concat())
+            })
+            .collect();
+    }
+
+    let mut distinct: Vec<Vec<SQLExpr>> = vec![];
+    for set in grouping_sets {
+        let mut set_exprs: Vec<SQLExpr> = vec![];
+        for expr in set {
+            if !set_exprs.contains(&expr) {
+                set_exprs.push(expr);
+            }
+        }
+        let is_duplicate = distinct.iter().any(|other| {
+            other.len() == set_exprs.len()
+                && set_exprs.iter().all(|expr| other.contains(expr))
+        });
+        if !is_duplicate {
+            distinct.push(set_exprs);
+        }
+    }
+    *exprs = vec![SQLExpr::GroupingSets(distinct)];
+    Ok(())
+}
+
 impl<'a> DFParser<'a> {
     #[deprecated(since = "46.0.0", note = "DFParserBuilder")]
     pub fn new(sql: &'a str) -> Result<Self, DataFusionError> {
@@ -441,7 +711,7 @@ impl<'a> DFParser<'a> {
                 break;
             }
             if expecting_statement_delimiter {
-                return self.expected("end of statement", self.parser.peek_token());
+                return self.expected_end_of_statement(self.parser.peek_token());
             }
 
             let statement = self.parse_statement()?;
@@ -470,6 +740,17 @@ impl<'a> DFParser<'a> {
         )
     }
 
+    /// Report an unexpected token at the end of a statement
+    fn expected_end_of_statement<T>(
+        &self,
+        found: TokenWithSpan,
+    ) -> Result<T, DataFusionError> {
+        if let Some(err) = self.order_by_all_keys_error() {
+            return Err(err);
+        }
+        self.expected("end of statement", found)
+    }
+
     fn expect_token(
         &mut self,
         expected: &str,
@@ -529,7 +810,10 @@ impl<'a> DFParser<'a> {
             }
         }
 
-        Ok(self.parser.parse_expr_with_alias()?)
+        let start = self.parser.peek_token().span.start;
+        let mut expr = self.parser.parse_expr_with_alias()?;
+        self.rewrite_group_by_distinct(start, &mut expr.expr)?;
+        Ok(expr)
     }
 
     /// Parses the entire SQL string into an expression.
@@ -544,26 +828,118 @@ impl<'a> DFParser<'a> {
 
     /// Helper method to parse a statement and handle errors consistently, especially for recursion limits
     fn parse_and_handle_statement(&mut self) -> Result<Statement, DataFusionError> {
-        self.parser
-            .parse_statement()
-            .map(|stmt| Statement::Statement(Box::from(stmt)))
-            .map_err(|e| match e {
-                ParserError::RecursionLimitExceeded => DataFusionError::SQL(
-                    Box::new(ParserError::RecursionLimitExceeded),
-                    Some(format!(
-                        " (current limit: {})",
-                        self.options.recursion_limit
-                    )),
-                ),
-                other => DataFusionError::SQL(Box::new(other), None),
+        let start = self.parser.peek_token().span.start;
+        let mut statement = self.parser.parse_statement().map_err(|e| match e {
+            ParserError::RecursionLimitExceeded => DataFusionError::SQL(
+                Box::new(ParserError::RecursionLimitExceeded),
+                Some(format!(
+                    " (current limit: {})",
+                    self.options.recursion_limit
+                )),
+            ),
+            other => self
+                .order_by_all_keys_error()
+                .unwrap_or_else(|| DataFusionError::SQL(Box::new(other), None)),
+        })?;
+        self.rewrite_group_by_distinct(start, &mut statement)?;
+        Ok(Statement::Statement(Box::from(statement)))
+    }
+
+    /// Rewrites the `GROUP BY DISTINCT` clauses of `node`, just parsed from
+    /// `start`, to group by their distinct grouping sets.
+    ///
+    /// The `DISTINCT` of a clause is located before the first expression of
+    /// its `GROUP BY`, so each clause is the `SELECT` whose first `GROUP BY`
+    /// expression is the nearest one after its `DISTINCT`.
+    fn rewrite_group_by_distinct<V: VisitMut>(
+        &mut self,
+        start: Location,
+        node: &mut V,
+    ) -> Result<(), DataFusionError> {
+        // Drop the clauses of statements that failed to parse
+        self.group_by_distinct.retain(|location| *location >= start);
+        let next = self.parser.peek_token();
+        let (parsed, rest): (Vec<_>, Vec<_>) =
+            std::mem::take(&mut self.group_by_distinct)
+                .into_iter()
+                .partition(|location| {
+                    next.token == Token::EOF || *location < next.span.start
+                });
+        self.group_by_distinct = rest;
+        if parsed.is_empty() {
+            return Ok(());
+        }
+
+        let mut group_by_starts = vec![];
+        visit_selects(node, |select| {
+            group_by_starts.extend(group_by_start(select));
+            Ok(())
+        })?;
+        let targets = parsed
+            .iter()
+            .map(|location| {
+                group_by_starts
+                    .iter()
+                    .filter(|start| *start >= location)
+                    .min()
+                    .copied()
+                    .ok_or_else(|| {
+                        not_impl_datafusion_err!(
+                            "GROUP BY DISTINCT is not supported here"
+                        )
+                    })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        visit_selects(node, |select| match group_by_start(select) {
+            Some(start) if targets.contains(&start) => distinct_grouping_sets(select),
+            _ => Ok(()),
+        })
+    }
+
+    /// Returns an error for sort keys following an `ORDER BY ALL`, e.g.
+    /// `ORDER BY ALL, a`, as `ALL` can not be mixed with explicit sort keys.
+    ///
+    /// The SQL parser stops at the comma following the `ORDER BY ALL`, so this
+    /// is checked once parsing failed before a comma.
+    fn order_by_all_keys_error(&self) -> Option<DataFusionError> {
+        if self.parser.peek_token().token != Token::Comma {
+            return None;
+        }
+        // The keywords before the comma, from the last one
+        let mut keywords = (0..self.parser.get_current_index())
+            .rev()
+            .map(|index| &self.parser.token_at(index).token)
+            .filter(|token| !matches!(token, Token::Whitespace(_)))
+            .map(|token| match token {
+                Token::Word(w) => w.keyword,
+                _ => Keyword::NoKeyword,
             })
+            .peekable();
+        if keywords
+            .next_if(|k| matches!(k, Keyword::FIRST | Keyword::LAST))
+            .is_some()
+            && keywords.next() != Some(Keyword::NULLS)
+        {
+            return None;
+        }
+        keywords.next_if(|k| matches!(k, Keyword::ASC | Keyword::DESC));
+        let found = keywords
+            .take(3)
+            .eq([Keyword::ALL, Keyword::BY, Keyword::ORDER]);
+        found.then(|| {
+            not_impl_datafusion_err!(
+                "ORDER BY ALL followed by other sort keys is not supported"
+            )
+        })
     }
 
     /// Parse a SQL `COPY TO` statement
     pub fn parse_copy(&mut self) -> Result<Statement, DataFusionError> {
         // parse as a query
         let source = if self.parser.consume_token(&Token::LParen) {
-            let query = self.parser.parse_query()?;
+            let start = self.parser.peek_token().span.start;
+            let mut query = self.parser.parse_query()?;
+            self.rewrite_group_by_distinct(start, &mut query)?;
             self.parser.expect_token(&Token::RParen)?;
             CopyToSource::Query(query)
         } else {
@@ -1044,13 +1420,137 @@ impl<'a> DFParser<'a> {
     }
 }
 
+/// A [`Statement`] parsed by a [`ScriptParser`], along with its location in
+/// the script
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptStatement {
+    /// The parsed statement
+    pub statement: Statement,
+    /// The line and column span of the statement, not including the
+    /// terminating semicolon
+    pub span: Span,
+    /// The byte offsets of the statement in the script, so `&sql[range]` is
+    /// the text of the statement
+    pub range: Range<usize>,
+}
+
+/// Parses a SQL script of semicolon separated statements, one statement at a
+/// time.
+///
+/// The whole script is tokenized up front, so semicolons in string literals,
+/// quoted identifiers, comments and dollar-quoted strings do not end a
+/// statement. Each statement is only parsed when the iterator is advanced, and
+/// iteration stops after the first error.
+///
+/// # Example
+/// ```
+/// # use datafusion_sql::parser::ScriptParser;
+/// # use datafusion_common::Result;
+/// # fn test() -> Result<()> {
+/// let sql = "SELECT ';' AS a; -- comment; with a semicolon\nSELECT 2";
+/// let statements = ScriptParser::new(sql)?.collect::<Result<Vec<_>>>()?;
+/// assert_eq!(statements.len(), 2);
+/// assert_eq!(&sql[statements[0].range.clone()], "SELECT ';' AS a");
+/// assert_eq!(&sql[statements[1].range.clone()], "SELECT 2");
+/// # Ok(())
+/// # }
+/// ```
+pub struct ScriptParser<'a> {
+    sql: &'a str,
+    parser: DFParser<'a>,
+    /// The byte offset of the start of each line of `sql`
+    line_offsets: Vec<usize>,
+    expecting_statement_delimiter: bool,
+    finished: bool,
+}
+
+impl<'a> ScriptParser<'a> {
+    /// Create a parser for the statements of `sql` using the [`GenericDialect`]
+    pub fn new(sql: &'a str) -> Result<Self, DataFusionError> {
+        DFParserBuilder::new(sql).build_script_parser()
+    }
+
+    /// The SQL script being parsed
+    pub fn sql(&self) -> &'a str {
+        self.sql
+    }
+
+    fn parse_next(&mut self) -> Result<Option<ScriptStatement>, DataFusionError> {
+        // ignore empty statements (between successive statement delimiters)
+        while self.parser.parser.consume_token(&Token::SemiColon) {
+            self.expecting_statement_delimiter = false;
+        }
+
+        let first = self.parser.parser.peek_token();
+        if first.token == Token::EOF {
+            return Ok(None);
+        }
+        if self.expecting_statement_delimiter {
+            return self.parser.expected_end_of_statement(first);
+        }
+
+        let statement = self.parser.parse_statement()?;
+        self.expecting_statement_delimiter = true;
+
+        // the parser may have stepped back onto whitespace after the statement
+        let mut index = self.parser.parser.get_current_index();
+        while index > 0
+            && matches!(
+                self.parser.parser.token_at(index).token,
+                Token::Whitespace(_)
+            )
+        {
+            index -= 1;
+        }
+        let last = self.parser.parser.token_at(index);
+        let span = Span::new(first.span.start.into(), last.span.end.into());
+        let range = self.byte_offset(first.span.start)..self.byte_offset(last.span.end);
+        Ok(Some(ScriptStatement {
+            statement,
+            span,
+            range,
+        }))
+    }
+
+    /// Convert a tokenizer location, whose column counts characters, into a
+    /// byte offset in the script
+    fn byte_offset(&self, location: sqlparser::tokenizer::Location) -> usize {
+        let line = (location.line as usize).saturating_sub(1);
+        let column = (location.column as usize).saturating_sub(1);
+        let line_start = self
+            .line_offsets
+            .get(line)
+            .copied()
+            .unwrap_or(self.sql.len());
+        self.sql[line_start..]
+            .char_indices()
+            .nth(column)
+            .map_or(self.sql.len(), |(offset, _)| line_start + offset)
+    }
+}
+
+impl Iterator for ScriptParser<'_> {
+    type Item = Result<ScriptStatement, DataFusionError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let next = self.parse_next().transpose();
+        if !matches!(next, Some(Ok(_))) {
+            self.finished = true;
+        }
+        next
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use datafusion_common::assert_contains;
     use sqlparser::ast::Expr::Identifier;
     use sqlparser::ast::{BinaryOperator, DataType, Expr, Ident, ValueWithSpan};
-    use sqlparser::dialect::SnowflakeDialect;
+    use sqlparser::dialect::{DuckDbDialect, SnowflakeDialect};
     use sqlparser::tokenizer::Span;
 
     fn expect_parse_ok(sql: &str, expected: Statement) -> Result<(), DataFusionError> {
@@ -1567,6 +2067,38 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn create_external_table_round_trip() {
+        verified_stmt("CREATE EXTERNAL TABLE t STORED AS CSV LOCATION 'foo.csv'");
+        verified_stmt(
+            "CREATE UNBOUNDED EXTERNAL TEMPORARY TABLE IF NOT EXISTS t (c1 INT NOT NULL, \
+            c2 DATE, PRIMARY KEY (c1)) STORED AS PARQUET PARTITIONED BY (c2) \
+            WITH ORDER (c1 ASC NULLS FIRST) WITH ORDER (c2 DESC) \
+            OPTIONS ('format.compression' 'zstd(5)', 'k' 10) LOCATION 'it''s.parquet'",
+        );
+        // partition columns declared with a type are part of the column list
+        one_statement_parses_to(
+            "CREATE EXTERNAL TABLE t (c1 INT) STORED AS CSV PARTITIONED BY (dt DATE) LOCATION 'foo'",
+            "CREATE EXTERNAL TABLE t (c1 INT, dt DATE) STORED AS CSV PARTITIONED BY (dt) LOCATION 'foo'",
+        );
+    }
+
+    #[test]
+    fn create_external_table_display_redacts_secrets() {
+        let sql = "CREATE EXTERNAL TABLE t STORED AS PARQUET OPTIONS (\
+            'aws.access_key_id' 'id', 'AWS.SECRET_ACCESS_KEY' 'secret', \
+            'aws.session_token' 'token', 'format.compression' 'zstd(5)') \
+            LOCATION 's3://bucket/t/'";
+        let statement = DFParser::parse_sql(sql).unwrap().pop_front().unwrap();
+        assert_eq!(
+            statement.to_string(),
+            "CREATE EXTERNAL TABLE t STORED AS PARQUET OPTIONS (\
+            'aws.access_key_id' '*****', 'AWS.SECRET_ACCESS_KEY' '*****', \
+            'aws.session_token' '*****', 'format.compression' 'zstd(5)') \
+            LOCATION 's3://bucket/t/'"
+        );
+    }
+
     #[test]
     fn copy_to_table_to_table() -> Result<(), DataFusionError> {
         // positive case
@@ -1890,4 +2422,134 @@ mod tests {
             "Expected: end of expression, found: bar",
         )
     }
+
+    fn parse_script(sql: &str) -> Result<Vec<&str>, DataFusionError> {
+        ScriptParser::new(sql)?
+            .map(|statement| statement.map(|statement| &sql[statement.range]))
+            .collect()
+    }
+
+    #[test]
+    fn script_semicolons_in_strings_and_comments() -> Result<(), DataFusionError> {
+        let sql = "SELECT 'a;b', \"c;d\" FROM t;\n\
+                   -- a comment; with a semicolon\n\
+                   SELECT /* another; comment */ 1;;\n\
+                   CREATE FUNCTION f() RETURNS TEXT AS $$ SELECT 'x'; $$ LANGUAGE SQL;\n\
+                   SELECT 'déjà;vu' AS ünïcode;  ";
+        assert_eq!(
+            parse_script(sql)?,
+            vec![
+                "SELECT 'a;b', \"c;d\" FROM t",
+                "SELECT /* another; comment */ 1",
+                "CREATE FUNCTION f() RETURNS TEXT AS $$ SELECT 'x'; $$ LANGUAGE SQL",
+                "SELECT 'déjà;vu' AS ünïcode",
+            ]
+        );
+
+        let statement = ScriptParser::new(sql)?.nth(1).unwrap()?;
+        let (start, end) = (statement.span.start, statement.span.end);
+        assert_eq!(
+            (start.line, start.column, end.line, end.column),
+            (3, 1, 3, 32)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn script_is_parsed_lazily() -> Result<(), DataFusionError> {
+        let mut parser = ScriptParser::new("SELECT 1; SELEC 2; SELECT 3")?;
+        assert!(parser.next().unwrap().is_ok());
+        let err = parser.next().unwrap().unwrap_err();
+        assert_contains!(err.to_string(), "Expected: an SQL statement, found: SELEC");
+        assert!(parser.next().is_none());
+
+        let mut parser = ScriptParser::new("SELECT 1 SELECT 2")?;
+        assert!(parser.next().unwrap().is_ok());
+        let err = parser.next().unwrap().unwrap_err();
+        assert_contains!(err.to_string(), "Expected: end of statement, found: SELECT");
+
+        assert!(ScriptParser::new("  ;; -- only a comment")?
+            .next()
+            .is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn order_by_all_with_other_keys_not_supported() {
+        let dialect = DuckDbDialect {};
+        for sql in [
+            "SELECT a, b FROM t ORDER BY ALL, a",
+            "SELECT a, b FROM t ORDER BY ALL DESC, a",
+            "SELECT a, b FROM t ORDER BY ALL ASC NULLS FIRST, a DESC",
+            "SELECT * FROM (SELECT a, b FROM t ORDER BY ALL, b LIMIT 1)",
+            "WITH c AS (SELECT a FROM t ORDER BY ALL NULLS LAST, a) SELECT * FROM c",
+        ] {
+            let err = DFParser::parse_sql_with_dialect(sql, &dialect).unwrap_err();
+            assert_eq!(
+                err.strip_backtrace(),
+                "This feature is not implemented: ORDER BY ALL followed by other sort keys is not supported",
+                "{sql}"
+            );
+        }
+
+        let sql = "SELECT a, b FROM t ORDER BY ALL DESC NULLS LAST";
+        assert!(DFParser::parse_sql_with_dialect(sql, &dialect).is_ok());
+        // Other parse errors after a comma are not affected
+        let sql = "SELECT a, b FROM t ORDER BY a, b, FROM";
+        let err = DFParser::parse_sql_with_dialect(sql, &dialect).unwrap_err();
+        assert_contains!(err.to_string(), "Expected: end of statement, found: FROM");
+    }
+
+    #[test]
+    fn group_by_distinct() {
+        for (sql, expected) in [
+            (
+                "SELECT a, b FROM t GROUP BY DISTINCT a, b",
+                "SELECT a, b FROM t GROUP BY a, b",
+            ),
+            (
+                "SELECT a, b FROM t GROUP BY DISTINCT GROUPING SETS ((a), (a, b), (b, a), (a))",
+                "SELECT a, b FROM t GROUP BY GROUPING SETS ((a), (a, b))",
+            ),
+            (
+                "SELECT a FROM t GROUP BY DISTINCT ROLLUP (a, b), ROLLUP (a, c)",
+                "SELECT a FROM t GROUP BY GROUPING SETS ((a, b, c), (a, b), (a, c), (a), ())",
+            ),
+            (
+                "SELECT * FROM (SELECT a FROM t GROUP BY DISTINCT CUBE (a, a)) GROUP BY a",
+                "SELECT * FROM (SELECT a FROM t GROUP BY GROUPING SETS ((a), ())) GROUP BY a",
+            ),
+            (
+                "SELECT a FROM t GROUP BY GROUPING SETS ((a), (a))",
+                "SELECT a FROM t GROUP BY GROUPING SETS ((a), (a))",
+            ),
+            (
+                "SELECT DISTINCT a FROM t GROUP BY a",
+                "SELECT DISTINCT a FROM t GROUP BY a",
+            ),
+        ] {
+            let statements = DFParser::parse_sql(sql).unwrap();
+            assert_eq!(statements[0].to_string(), expected);
+        }
+
+        // Only the grouping sets of the statement with GROUP BY DISTINCT are distinct
+        let sql = "SELECT a FROM t GROUP BY GROUPING SETS ((a), (a)); \
+                   SELECT a FROM t GROUP BY DISTINCT GROUPING SETS ((a), (a))";
+        let statements = DFParser::parse_sql(sql).unwrap();
+        assert_eq!(
+            statements[0].to_string(),
+            "SELECT a FROM t GROUP BY GROUPING SETS ((a), (a))"
+        );
+        assert_eq!(
+            statements[1].to_string(),
+            "SELECT a FROM t GROUP BY GROUPING SETS ((a))"
+        );
+
+        let sql = "SELECT a FROM t GROUP BY DISTINCT ROLLUP (a) WITH TOTALS";
+        let err = DFParser::parse_sql(sql).unwrap_err();
+        assert_eq!(
+            err.strip_backtrace(),
+            "This feature is not implemented: GROUP BY DISTINCT with modifiers is not supported"
+        );
+    }
 }
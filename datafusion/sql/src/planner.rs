@@ -17,27 +17,30 @@
 
 //! [`SqlToRel`]: SQL Query Planner (produces [`LogicalPlan`] from SQL AST)
 use std::collections::HashMap;
+use std::ops::Range;
 use std::str::FromStr;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, PoisonError};
 use std::vec;
 
 use arrow::datatypes::*;
 use datafusion_common::config::SqlParserOptions;
 use datafusion_common::error::add_possible_columns_to_diag;
+use datafusion_common::tree_node::{TreeNode, TreeNodeRecursion};
 use datafusion_common::TableReference;
 use datafusion_common::{
-    field_not_found, internal_err, plan_datafusion_err, DFSchemaRef, Diagnostic,
-    SchemaError,
+    field_not_found, internal_err, plan_datafusion_err, Column, DFSchemaRef, Diagnostic,
+    SchemaError, Span,
 };
 use datafusion_common::{not_impl_err, plan_err, DFSchema, DataFusionError, Result};
 use datafusion_expr::logical_plan::{LogicalPlan, LogicalPlanBuilder};
 use datafusion_expr::utils::find_column_exprs;
 use datafusion_expr::{col, Expr};
+use indexmap::{IndexMap, IndexSet};
 use sqlparser::ast::{ArrayElemTypeDef, ExactNumberInfo, TimezoneInfo};
 use sqlparser::ast::{ColumnDef as SQLColumnDef, ColumnOption};
 use sqlparser::ast::{DataType as SQLDataType, Ident, ObjectName, TableAlias};
 
-use crate::utils::make_decimal_type;
+use crate::utils::{check_limit, make_decimal_type, UsingJoinColumn};
 pub use datafusion_expr::planner::ContextProvider;
 
 /// SQL parser options
@@ -57,6 +60,25 @@ pub struct ParserOptions {
     pub map_string_types_to_utf8view: bool,
     /// Default null ordering for sorting expressions.
     pub default_null_ordering: NullOrdering,
+    /// How duplicate column names in the output of a CTE, derived table or
+    /// set operation input are handled.
+    pub duplicate_column_names: DuplicateColumnNames,
+    /// Maximum number of distinct placeholders in a statement, 0 for no limit.
+    pub max_placeholders: usize,
+    /// Maximum length of an identifier in characters, 0 for no limit.
+    pub max_identifier_length: usize,
+    /// Maximum number of CTEs in a `WITH` clause, 0 for no limit.
+    pub max_ctes: usize,
+    /// Maximum number of inputs of a chain of set operations, 0 for no limit.
+    pub max_set_operation_inputs: usize,
+    /// Maximum number of columns of a projection after wildcard expansion, 0
+    /// for no limit.
+    pub max_projection_width: usize,
+    /// Whether interval strings may use the shorthand form such as `1d2h30m`.
+    pub enable_interval_shorthand: bool,
+    /// Whether unqualified names in `GROUP BY` resolve to `SELECT` aliases
+    /// before same-named input columns.
+    pub prefer_group_by_aliases: bool,
 }
 
 impl ParserOptions {
@@ -81,6 +103,14 @@ impl ParserOptions {
             // By default, `nulls_max` is used to follow Postgres's behavior.
             // postgres rule: https://www.postgresql.org/docs/current/queries-order.html
             default_null_ordering: NullOrdering::NullsMax,
+            duplicate_column_names: DuplicateColumnNames::Disambiguate,
+            max_placeholders: 65535,
+            max_identifier_length: 4096,
+            max_ctes: 1024,
+            max_set_operation_inputs: 10000,
+            max_projection_width: 100000,
+            enable_interval_shorthand: false,
+            prefer_group_by_aliases: false,
         }
     }
 
@@ -141,6 +171,54 @@ impl ParserOptions {
         self.default_null_ordering = value;
         self
     }
+
+    /// Sets the `duplicate_column_names` option.
+    pub fn with_duplicate_column_names(mut self, value: DuplicateColumnNames) -> Self {
+        self.duplicate_column_names = value;
+        self
+    }
+
+    /// Sets the `max_placeholders` option.
+    pub fn with_max_placeholders(mut self, value: usize) -> Self {
+        self.max_placeholders = value;
+        self
+    }
+
+    /// Sets the `max_identifier_length` option.
+    pub fn with_max_identifier_length(mut self, value: usize) -> Self {
+        self.max_identifier_length = value;
+        self
+    }
+
+    /// Sets the `max_ctes` option.
+    pub fn with_max_ctes(mut self, value: usize) -> Self {
+        self.max_ctes = value;
+        self
+    }
+
+    /// Sets the `max_set_operation_inputs` option.
+    pub fn with_max_set_operation_inputs(mut self, value: usize) -> Self {
+        self.max_set_operation_inputs = value;
+        self
+    }
+
+    /// Sets the `max_projection_width` option.
+    pub fn with_max_projection_width(mut self, value: usize) -> Self {
+        self.max_projection_width = value;
+        self
+    }
+
+    /// Sets the `enable_interval_shorthand` option.
+    pub fn with_enable_interval_shorthand(mut self, value: bool) -> Self {
+        self.enable_interval_shorthand = value;
+        self
+    }
+
+    /// Sets the `prefer_group_by_aliases` option.
+    pub fn with_prefer_group_by_aliases(mut self, value: bool) -> Self {
+        self.prefer_group_by_aliases = value;
+        self
+    }
 }
 
 impl Default for ParserOptions {
@@ -160,6 +238,14 @@ impl From<&SqlParserOptions> for ParserOptions {
                 .enable_options_value_normalization,
             collect_spans: options.collect_spans,
             default_null_ordering: options.default_null_ordering.as_str().into(),
+            duplicate_column_names: options.duplicate_column_names.as_str().into(),
+            max_placeholders: options.max_placeholders,
+            max_identifier_length: options.max_identifier_length,
+            max_ctes: options.max_ctes,
+            max_set_operation_inputs: options.max_set_operation_inputs,
+            max_projection_width: options.max_projection_width,
+            enable_interval_shorthand: options.enable_interval_shorthand,
+            prefer_group_by_aliases: options.prefer_group_by_aliases,
         }
     }
 }
@@ -213,6 +299,36 @@ impl From<&str> for NullOrdering {
     }
 }
 
+/// How duplicate column names are handled in the output of a query that is
+/// used as a CTE, a derived table or an input of a set operation, where the
+/// columns are referenced by their unqualified names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DuplicateColumnNames {
+    /// Return an error naming the duplicate column and its positions.
+    Error,
+    /// Rename the second and later occurrences of a name by appending a
+    /// suffix, e.g. `SELECT a, a` produces the columns `a` and `a:1`.
+    Disambiguate,
+}
+
+impl FromStr for DuplicateColumnNames {
+    type Err = DataFusionError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "error" => Ok(Self::Error),
+            "disambiguate" => Ok(Self::Disambiguate),
+            _ => plan_err!("Unknown duplicate column names handling: Expected one of 'error' or 'disambiguate'. Got {s}"),
+        }
+    }
+}
+
+impl From<&str> for DuplicateColumnNames {
+    fn from(s: &str) -> Self {
+        Self::from_str(s).unwrap_or(Self::Disambiguate)
+    }
+}
+
 /// Ident Normalizer
 #[derive(Debug)]
 pub struct IdentNormalizer {
@@ -251,6 +367,8 @@ impl IdentNormalizer {
 /// This helps resolve scoping issues of CTEs.
 /// By using cloning, a subquery can inherit CTEs from the outer query
 /// and can also define its own private CTEs without affecting the outer query.
+/// The metadata collected for [`SqlToRel::statement_to_plan_with_metadata`]
+/// is shared by all clones.
 ///
 #[derive(Debug, Clone)]
 pub struct PlannerContext {
@@ -260,13 +378,26 @@ pub struct PlannerContext {
     /// Map of CTE name to logical plan of the WITH clause.
     /// Use `Arc<LogicalPlan>` to allow cheap cloning
     ctes: HashMap<String, Arc<LogicalPlan>>,
-    /// The query schema of the outer query plan, used to resolve the columns in subquery
-    outer_query_schema: Option<DFSchemaRef>,
+    /// The query schemas of the enclosing query plans, used to resolve the columns
+    /// in subqueries. The last schema belongs to the nearest enclosing query
+    outer_queries_schemas_stack: Vec<DFSchemaRef>,
     /// The joined schemas of all FROM clauses planned so far. When planning LATERAL
     /// FROM clauses, this should become a suffix of the `outer_query_schema`.
     outer_from_schema: Option<DFSchemaRef>,
     /// The query schema defined by the table
     create_table_schema: Option<DFSchemaRef>,
+    /// Whether the `ORDER BY` of the derived tables in the FROM clause being
+    /// planned must be kept, because a `LIMIT` reads their rows in order
+    preserve_derived_order: bool,
+    /// Whether the output of the query being planned is used as a CTE, a
+    /// derived table or an input of a set operation, and therefore needs
+    /// unique column names
+    unique_output_names: bool,
+    /// The columns joined by the `USING` joins of the FROM clause of the
+    /// query being planned
+    using_join_columns: Vec<UsingJoinColumn>,
+    /// The metadata of the statement being planned, if it is collected
+    metadata: Option<Arc<Mutex<MetadataCollector>>>,
 }
 
 impl Default for PlannerContext {
@@ -281,9 +412,13 @@ impl PlannerContext {
         Self {
             prepare_param_data_types: Arc::new(vec![]),
             ctes: HashMap::new(),
-            outer_query_schema: None,
+            outer_queries_schemas_stack: vec![],
             outer_from_schema: None,
             create_table_schema: None,
+            preserve_derived_order: false,
+            unique_output_names: false,
+            using_join_columns: vec![],
+            metadata: None,
         }
     }
 
@@ -296,19 +431,38 @@ impl PlannerContext {
         self
     }
 
-    // Return a reference to the outer query's schema
+    // Return a reference to the nearest outer query's schema
     pub fn outer_query_schema(&self) -> Option<&DFSchema> {
-        self.outer_query_schema.as_ref().map(|s| s.as_ref())
+        self.outer_queries_schemas_stack.last().map(|s| s.as_ref())
     }
 
-    /// Sets the outer query schema, returning the existing one, if
+    /// Return the schemas of all enclosing queries, from the outermost to the
+    /// nearest one
+    pub fn outer_queries_schemas(&self) -> &[DFSchemaRef] {
+        &self.outer_queries_schemas_stack
+    }
+
+    /// Sets the nearest outer query schema, returning the existing one, if
     /// any
     pub fn set_outer_query_schema(
         &mut self,
-        mut schema: Option<DFSchemaRef>,
+        schema: Option<DFSchemaRef>,
     ) -> Option<DFSchemaRef> {
-        std::mem::swap(&mut self.outer_query_schema, &mut schema);
-        schema
+        let old_schema = self.outer_queries_schemas_stack.pop();
+        self.outer_queries_schemas_stack.extend(schema);
+        old_schema
+    }
+
+    /// Enters the scope of a subquery, making `schema` the nearest outer
+    /// query schema
+    pub fn append_outer_query_schema(&mut self, schema: DFSchemaRef) {
+        self.outer_queries_schemas_stack.push(schema);
+    }
+
+    /// Leaves the scope of a subquery, returning the nearest outer query
+    /// schema, if any
+    pub fn pop_outer_query_schema(&mut self) -> Option<DFSchemaRef> {
+        self.outer_queries_schemas_stack.pop()
     }
 
     pub fn set_table_schema(
@@ -374,6 +528,328 @@ impl PlannerContext {
     pub(super) fn remove_cte(&mut self, cte_name: &str) {
         self.ctes.remove(cte_name);
     }
+
+    /// Return whether the `ORDER BY` of derived tables in the FROM clause
+    /// being planned must be kept
+    pub(crate) fn preserve_derived_order(&self) -> bool {
+        self.preserve_derived_order
+    }
+
+    /// Set whether the `ORDER BY` of derived tables in the FROM clause being
+    /// planned must be kept
+    pub(crate) fn set_preserve_derived_order(&mut self, preserve: bool) {
+        self.preserve_derived_order = preserve;
+    }
+
+    /// Set whether the output of the next query planned with this context
+    /// needs unique column names, returning the previous setting
+    pub(crate) fn set_unique_output_names(&mut self, unique: bool) -> bool {
+        std::mem::replace(&mut self.unique_output_names, unique)
+    }
+
+    /// Return whether the output of the query being planned needs unique
+    /// column names, and reset the setting so it does not apply to the
+    /// queries nested in it
+    pub(crate) fn take_unique_output_names(&mut self) -> bool {
+        std::mem::take(&mut self.unique_output_names)
+    }
+
+    /// Set the columns joined by the `USING` joins of the FROM clause of the
+    /// query being planned, returning the previous ones
+    pub(crate) fn set_using_join_columns(
+        &mut self,
+        columns: Vec<UsingJoinColumn>,
+    ) -> Vec<UsingJoinColumn> {
+        std::mem::replace(&mut self.using_join_columns, columns)
+    }
+
+    /// Return the columns joined by the `USING` joins of the FROM clause of
+    /// the query being planned, outermost join first
+    pub(crate) fn using_join_columns(&self) -> &[UsingJoinColumn] {
+        &self.using_join_columns
+    }
+
+    /// Return the value of the unqualified column `name` if it is joined by
+    /// a `USING` join of the FROM clause being planned, both of its joined
+    /// columns are in `schema`, and its value is not the left column. The
+    /// left column resolves like any other column.
+    pub(crate) fn using_join_column(
+        &self,
+        name: &str,
+        schema: &DFSchema,
+    ) -> Option<Expr> {
+        let UsingJoinColumn { left, value, .. } =
+            self.using_join_columns.iter().find(|column| {
+                column.left.name == name
+                    && schema.has_column(&column.left)
+                    && schema.has_column(&column.right)
+            })?;
+        (!matches!(value, Expr::Column(column) if column == left)).then(|| value.clone())
+    }
+
+    /// Collect the metadata of the statement planned with this context
+    pub(crate) fn with_metadata_collection(mut self) -> Self {
+        self.metadata = Some(Arc::default());
+        self
+    }
+
+    /// Create an empty PlannerContext for a statement nested in the one being
+    /// planned, such as the query of a `CREATE VIEW`, which adds to the
+    /// metadata collected for it
+    pub(crate) fn new_nested(&self) -> Self {
+        Self {
+            metadata: self.metadata.clone(),
+            ..Self::new()
+        }
+    }
+
+    fn update_metadata(&self, f: impl FnOnce(&mut MetadataCollector)) {
+        if let Some(metadata) = &self.metadata {
+            f(&mut metadata.lock().unwrap_or_else(PoisonError::into_inner))
+        }
+    }
+
+    /// Record the placeholders of `expr`, with their data type if known
+    pub(crate) fn record_placeholders(&self, expr: &Expr) {
+        self.update_metadata(|metadata| {
+            expr.apply(|expr| {
+                if let Expr::Placeholder(placeholder) = expr {
+                    let data_type = metadata
+                        .placeholders
+                        .entry(placeholder.id.clone())
+                        .or_default();
+                    if data_type.is_none() {
+                        data_type.clone_from(&placeholder.data_type);
+                    }
+                }
+                Ok(TreeNodeRecursion::Continue)
+            })
+            .expect("traversal is infallible");
+        })
+    }
+
+    /// Record that the statement reads or writes `table`, whose columns are
+    /// referenced with `qualifier`, the name of the table or of its alias
+    pub(crate) fn record_table(
+        &self,
+        table: &TableReference,
+        qualifier: Option<TableReference>,
+    ) {
+        self.update_metadata(|metadata| {
+            metadata.tables.entry(table.clone()).or_default();
+            if let Some(qualifier) = qualifier {
+                if metadata.qualifiers.is_empty() {
+                    metadata.qualifiers.push(HashMap::new());
+                }
+                let scope = metadata.qualifiers.last_mut().unwrap();
+                scope.insert(qualifier, table.clone());
+            }
+        })
+    }
+
+    /// Start recording the qualifiers of the tables of a nested query, such
+    /// as a subquery or a derived table, which may reuse the qualifiers of
+    /// the enclosing query for other tables
+    pub(crate) fn push_qualifier_scope(&self) {
+        self.update_metadata(|metadata| metadata.qualifiers.push(HashMap::new()))
+    }
+
+    /// Forget the qualifiers recorded since the matching
+    /// [`Self::push_qualifier_scope`]
+    pub(crate) fn pop_qualifier_scope(&self) {
+        self.update_metadata(|metadata| {
+            metadata.qualifiers.pop();
+        })
+    }
+
+    /// Record the columns of tables that `expr` uses
+    pub(crate) fn record_column_references(&self, expr: &Expr) {
+        self.update_metadata(|metadata| {
+            expr.apply(|expr| {
+                match expr {
+                    Expr::Column(column) => metadata.record_column(column, false),
+                    Expr::OuterReferenceColumn(_, column) => {
+                        metadata.record_column(column, true)
+                    }
+                    _ => {}
+                }
+                Ok(TreeNodeRecursion::Continue)
+            })
+            .expect("traversal is infallible");
+        })
+    }
+
+    /// Record that the statement uses the column `name` of `table`
+    pub(crate) fn record_table_column(&self, table: &TableReference, name: &str) {
+        self.update_metadata(|metadata| {
+            metadata
+                .tables
+                .entry(table.clone())
+                .or_default()
+                .insert(name.to_string());
+        })
+    }
+
+    /// Record that the statement defines the CTE `name`
+    pub(crate) fn record_cte(&self, name: &str) {
+        self.update_metadata(|metadata| metadata.ctes.push(name.to_string()))
+    }
+
+    /// Record a warning about the statement, which was planned nonetheless
+    pub(crate) fn record_warning(&self, warning: Diagnostic) {
+        self.update_metadata(|metadata| metadata.warnings.push(warning))
+    }
+
+    /// Return the metadata collected by this context and its clones
+    pub(crate) fn take_metadata(&self) -> Option<MetadataCollector> {
+        let metadata = self.metadata.as_ref()?;
+        Some(std::mem::take(
+            &mut metadata.lock().unwrap_or_else(PoisonError::into_inner),
+        ))
+    }
+}
+
+/// The metadata of a statement accumulated while planning it
+#[derive(Debug, Default)]
+pub(crate) struct MetadataCollector {
+    placeholders: IndexMap<String, Option<DataType>>,
+    tables: IndexMap<TableReference, IndexSet<String>>,
+    /// The tables whose columns are referenced with each qualifier, for the
+    /// query being planned and the queries enclosing it, innermost last
+    qualifiers: Vec<HashMap<TableReference, TableReference>>,
+    ctes: Vec<String>,
+    warnings: Vec<Diagnostic>,
+}
+
+impl MetadataCollector {
+    /// Record the use of `column`, which refers to a table of an enclosing
+    /// query if `outer`
+    fn record_column(&mut self, column: &Column, outer: bool) {
+        let Some(qualifier) = &column.relation else {
+            return;
+        };
+        let skip = usize::from(outer);
+        let Some(table) = self
+            .qualifiers
+            .iter()
+            .rev()
+            .skip(skip)
+            .find_map(|scope| scope.get(qualifier))
+        else {
+            return;
+        };
+        if let Some(columns) = self.tables.get_mut(table) {
+            columns.insert(column.name.clone());
+        }
+    }
+
+    /// Build the [`PlanWithMetadata`] of `plan`
+    pub(crate) fn finish(self, plan: LogicalPlan, read_only: bool) -> PlanWithMetadata {
+        let mut placeholders = self.placeholders.into_iter().collect::<Vec<_>>();
+        // Numbered placeholders are listed in the order of their numbers
+        placeholders.sort_by_key(|(id, _)| {
+            id.strip_prefix('$')
+                .and_then(|index| index.parse::<usize>().ok())
+                .unwrap_or(usize::MAX)
+        });
+        let tables = self
+            .tables
+            .into_iter()
+            .map(|(table, columns)| TableUsage {
+                table,
+                columns: columns.into_iter().collect(),
+            })
+            .collect();
+        PlanWithMetadata {
+            plan,
+            placeholders,
+            tables,
+            ctes: self.ctes,
+            read_only,
+            source: None,
+            warnings: self.warnings,
+        }
+    }
+}
+
+/// The [`LogicalPlan`] of a statement, along with metadata about the
+/// statement collected while planning it.
+///
+/// See [`SqlToRel::statement_to_plan_with_metadata`]
+#[derive(Debug, Clone)]
+pub struct PlanWithMetadata {
+    /// The plan of the statement
+    pub plan: LogicalPlan,
+    /// The placeholders of the statement, with their data type if it is
+    /// declared or inferred. Numbered placeholders (`$1`, `$2`, etc) come
+    /// first in the order of their numbers, followed by named placeholders in
+    /// the order they appear in.
+    pub placeholders: Vec<(String, Option<DataType>)>,
+    /// The tables the statement reads or writes, in the order they are
+    /// referenced in. CTEs are not included.
+    pub tables: Vec<TableUsage>,
+    /// The names of the CTEs the statement defines
+    pub ctes: Vec<String>,
+    /// Whether the statement only reads data
+    pub read_only: bool,
+    /// The SQL text the statement was parsed from, if it was given to the
+    /// planner with [`SqlToRel::statement_to_plan_with_source`]
+    pub source: Option<StatementSource>,
+    /// The warnings about the statement, such as a column of a subquery that
+    /// is resolved to the nearest of several enclosing queries
+    pub warnings: Vec<Diagnostic>,
+}
+
+/// A table referenced by a statement
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableUsage {
+    /// The table, as resolved by the planner
+    pub table: TableReference,
+    /// The names of the columns of the table that the statement uses, in the
+    /// order they are first used in
+    pub columns: Vec<String>,
+}
+
+/// The SQL text a statement was parsed from, along with the dialect it was
+/// parsed with.
+///
+/// The planner only sees the parsed statement, which does not keep the
+/// formatting and comments of the text.
+///
+/// See [`SqlToRel::statement_to_plan_with_source`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatementSource {
+    /// The text of the statement
+    pub sql: String,
+    /// The name of the dialect, e.g. `generic` or `postgresql`
+    pub dialect: String,
+}
+
+impl StatementSource {
+    /// Create the source of the statement written as `sql`, without the
+    /// surrounding whitespace and the semicolon terminating the statement
+    pub fn new(sql: impl AsRef<str>, dialect: impl Into<String>) -> Self {
+        let sql = sql.as_ref().trim();
+        let sql = sql.strip_suffix(';').unwrap_or(sql).trim_end();
+        Self {
+            sql: sql.to_string(),
+            dialect: dialect.into(),
+        }
+    }
+}
+
+/// The [`LogicalPlan`] of one statement of a SQL script, along with the
+/// location of the statement in the script.
+///
+/// See [`SqlToRel::script_statement_to_plan`]
+#[derive(Debug, Clone)]
+pub struct ScriptPlan {
+    /// The plan of the statement
+    pub plan: LogicalPlan,
+    /// The line and column span of the statement in the script
+    pub span: Span,
+    /// The byte offsets of the statement in the script
+    pub range: Range<usize>,
 }
 
 /// SQL query planner and binder
@@ -428,6 +904,7 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
         let mut fields = Vec::with_capacity(columns.len());
 
         for column in columns {
+            self.check_identifier_length(&column.name)?;
             let data_type = self.convert_data_type(&column.data_type)?;
             let not_nullable = column
                 .options
@@ -489,6 +966,7 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
         plan: LogicalPlan,
         alias: TableAlias,
     ) -> Result<LogicalPlan> {
+        self.check_identifier_length(&alias.name)?;
         let idents = alias.columns.into_iter().map(|c| c.name).collect();
         let plan = self.apply_expr_alias(plan, idents)?;
 
@@ -514,6 +992,9 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                 idents.len()
             )
         } else {
+            for ident in &idents {
+                self.check_identifier_length(ident)?;
+            }
             let fields = plan.schema().fields().clone();
             LogicalPlanBuilder::from(plan)
                 .project(fields.iter().zip(idents.into_iter()).map(|(field, ident)| {
@@ -828,11 +1309,25 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
         &self,
         object_name: ObjectName,
     ) -> Result<TableReference> {
+        for ident in object_name.0.iter().filter_map(|part| part.as_ident()) {
+            self.check_identifier_length(ident)?;
+        }
         object_name_to_table_reference(
             object_name,
             self.options.enable_ident_normalization,
         )
     }
+
+    /// Return an error if `ident` is longer than
+    /// [`ParserOptions::max_identifier_length`] characters
+    pub(crate) fn check_identifier_length(&self, ident: &Ident) -> Result<()> {
+        check_limit(
+            "max_identifier_length",
+            self.options.max_identifier_length,
+            "Identifier length",
+            ident.value.chars().count(),
+        )
+    }
 }
 
 /// Create a [`TableReference`] after normalizing the specified ObjectName
@@ -15,22 +15,26 @@
 // specific language governing permissions and limitations
 // under the License.
 
+use std::collections::HashSet;
 use std::sync::Arc;
 
 use crate::planner::{ContextProvider, PlannerContext, SqlToRel};
+use crate::relation::remove_unordered_subquery_sorts;
 
 use crate::stack::StackGuard;
-use datafusion_common::{not_impl_err, Constraints, DFSchema, Result};
+use datafusion_common::{
+    not_impl_err, plan_err, Constraints, DFSchema, DFSchemaRef, Result,
+};
 use datafusion_expr::expr::Sort;
 
 use datafusion_expr::{
-    CreateMemoryTable, DdlStatement, Distinct, Expr, LogicalPlan, LogicalPlanBuilder,
+    lit, CreateMemoryTable, DdlStatement, Distinct, Expr, ExprSchemable, LogicalPlan,
+    LogicalPlanBuilder,
 };
 use sqlparser::ast::{
-    Expr as SQLExpr, Ident, LimitClause, OrderBy, OrderByExpr, OrderByKind, Query,
-    SelectInto, SetExpr,
+    Expr as SQLExpr, Fetch, LimitClause, OrderBy, OrderByExpr, OrderByKind, Query,
+    SelectInto, SetExpr, Top, TopQuantity, Value,
 };
-use sqlparser::tokenizer::Span;
 
 impl<S: ContextProvider> SqlToRel<'_, S> {
     /// Generate a logical plan from an SQL query/subquery
@@ -38,11 +42,26 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
         &self,
         query: Query,
         outer_planner_context: &mut PlannerContext,
+    ) -> Result<LogicalPlan> {
+        self.query_to_plan_with_order(query, outer_planner_context, false)
+    }
+
+    /// Generate a logical plan from an SQL query/subquery. `read_in_order` is
+    /// true if the rows of the query are read in order by a `LIMIT` of an
+    /// enclosing query, which then depends on the `ORDER BY` of the derived
+    /// tables of this query.
+    pub(crate) fn query_to_plan_with_order(
+        &self,
+        query: Query,
+        outer_planner_context: &mut PlannerContext,
+        read_in_order: bool,
     ) -> Result<LogicalPlan> {
         // Each query has its own planner context, including CTEs that are visible within that query.
         // It also inherits the CTEs from the outer query by cloning the outer planner context.
         let mut query_plan_context = outer_planner_context.clone();
         let planner_context = &mut query_plan_context;
+        planner_context.set_preserve_derived_order(false);
+        let unique_output_names = planner_context.take_unique_output_names();
 
         if let Some(with) = query.with {
             self.plan_with_clause(with, planner_context)?;
@@ -52,9 +71,31 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
         match set_expr {
             SetExpr::Select(mut select) => {
                 let select_into = select.into.take();
+                let top = select.top.take();
+                // A `LIMIT` without an `ORDER BY` returns the first rows of the
+                // FROM clause, so the order of its derived tables matters
+                let has_order_by = query.order_by.is_some();
+                let preserve_derived_order = !has_order_by
+                    && (read_in_order || query.limit_clause.is_some() || top.is_some());
+                planner_context.set_preserve_derived_order(preserve_derived_order);
+                planner_context.set_unique_output_names(unique_output_names);
                 let plan =
                     self.select_to_plan(*select, query.order_by, planner_context)?;
-                let plan = self.limit(plan, query.limit_clause, planner_context)?;
+                let plan = if preserve_derived_order {
+                    remove_unordered_subquery_sorts(plan)?
+                } else {
+                    plan
+                };
+                // The limit applies to the rows of the `DISTINCT ON` and the
+                // `ORDER BY` planned above, so it counts deduplicated rows
+                let limit = self.limit_spec(
+                    top,
+                    query.limit_clause,
+                    query.fetch,
+                    has_order_by,
+                    planner_context,
+                )?;
+                let plan = self.limit(plan, limit)?;
                 // Process the `SELECT INTO` after `LIMIT`.
                 self.select_into(plan, select_into)
             }
@@ -62,70 +103,223 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                 // The functions called from `set_expr_to_plan()` need more than 128KB
                 // stack in debug builds as investigated in:
                 // https://github.com/apache/datafusion/pull/13310#discussion_r1836813902
+                self.check_set_operation_inputs(&other)?;
+                let is_set_operation = matches!(other, SetExpr::SetOperation { .. });
+                planner_context.set_unique_output_names(unique_output_names);
                 let plan = {
                     // scope for dropping _guard
                     let _guard = StackGuard::new(256 * 1024);
                     self.set_expr_to_plan(other, planner_context)
                 }?;
+                planner_context.set_unique_output_names(false);
                 let oby_exprs = to_order_by_exprs(query.order_by)?;
+                let schema = if is_set_operation {
+                    let output_schema = set_expr_output_schema(&plan)?;
+                    self.check_set_expr_order_by(
+                        &oby_exprs,
+                        &output_schema,
+                        planner_context,
+                    )?;
+                    output_schema
+                } else {
+                    Arc::clone(plan.schema())
+                };
                 let order_by_rex = self.order_by_to_sort_expr(
                     oby_exprs,
-                    plan.schema(),
+                    &schema,
                     planner_context,
                     true,
                     None,
                 )?;
+                let has_order_by = !order_by_rex.is_empty();
                 let plan = self.order_by(plan, order_by_rex)?;
-                self.limit(plan, query.limit_clause, planner_context)
+                let limit = self.limit_spec(
+                    None,
+                    query.limit_clause,
+                    query.fetch,
+                    has_order_by,
+                    planner_context,
+                )?;
+                self.limit(plan, limit)
             }
         }
     }
 
-    /// Wrap a plan in a limit
-    fn limit(
+    /// Return an error if the `ORDER BY` of a set operation refers to a column
+    /// that is not one of its output columns, such as a column of an input
+    /// other than the first.
+    fn check_set_expr_order_by(
         &self,
-        input: LogicalPlan,
+        order_by: &[OrderByExpr],
+        output_schema: &DFSchema,
+        planner_context: &PlannerContext,
+    ) -> Result<()> {
+        for order_by_expr in order_by {
+            let SQLExpr::Identifier(ident) = &order_by_expr.expr else {
+                continue;
+            };
+            let name = self.ident_normalizer.normalize(ident.clone());
+            let is_outer_column = planner_context
+                .outer_queries_schemas()
+                .iter()
+                .any(|schema| schema.has_column_with_unqualified_name(&name));
+            if !output_schema.has_column_with_unqualified_name(&name) && !is_outer_column
+            {
+                return plan_err!(
+                    "ORDER BY column {name} is not an output column of the set operation, whose columns are named after its first input: {}",
+                    output_schema.field_names().join(", ")
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Normalize the `TOP`, `LIMIT`, `OFFSET` and `FETCH` clauses of a query
+    /// into a single [`LimitSpec`], so that every spelling of a limit is
+    /// planned by [`Self::limit`]. `has_order_by` is true if the rows are
+    /// sorted by the `ORDER BY` of the query, which `WITH TIES` requires.
+    pub(super) fn limit_spec(
+        &self,
+        top: Option<Top>,
         limit_clause: Option<LimitClause>,
+        fetch_clause: Option<Fetch>,
+        has_order_by: bool,
         planner_context: &mut PlannerContext,
-    ) -> Result<LogicalPlan> {
-        let Some(limit_clause) = limit_clause else {
-            return Ok(input);
+    ) -> Result<LimitSpec> {
+        let with_ties = match (&top, &fetch_clause) {
+            (Some(top), _) if top.with_ties => Some("TOP"),
+            (_, Some(fetch)) if fetch.with_ties => Some("FETCH"),
+            _ => None,
         };
+        if let Some(clause) = with_ties {
+            if !has_order_by {
+                return plan_err!("{clause} WITH TIES requires an ORDER BY");
+            }
+            return not_impl_err!("{clause} WITH TIES");
+        }
 
         let empty_schema = DFSchema::empty();
+        let mut to_expr =
+            |sql: SQLExpr| self.sql_to_expr(sql, &empty_schema, planner_context);
 
-        let (skip, fetch, limit_by_exprs) = match limit_clause {
-            LimitClause::LimitOffset {
-                limit,
-                offset,
-                limit_by,
-            } => {
-                let skip = offset
-                    .map(|o| self.sql_to_expr(o.value, &empty_schema, planner_context))
-                    .transpose()?;
-
-                let fetch = limit
-                    .map(|e| self.sql_to_expr(e, &empty_schema, planner_context))
-                    .transpose()?;
-
-                let limit_by_exprs = limit_by
-                    .into_iter()
-                    .map(|e| self.sql_to_expr(e, &empty_schema, planner_context))
-                    .collect::<Result<Vec<_>>>()?;
-
-                (skip, fetch, limit_by_exprs)
+        match (top, limit_clause, fetch_clause) {
+            (None, None, None) => Ok(LimitSpec::default()),
+            (Some(_), Some(_), _) => {
+                plan_err!("TOP cannot be used together with LIMIT")
             }
-            LimitClause::OffsetCommaLimit { offset, limit } => {
-                let skip =
-                    Some(self.sql_to_expr(offset, &empty_schema, planner_context)?);
-                let fetch =
-                    Some(self.sql_to_expr(limit, &empty_schema, planner_context)?);
-                (skip, fetch, vec![])
+            (Some(_), _, Some(_)) => {
+                plan_err!("TOP cannot be used together with FETCH")
             }
-        };
+            (
+                None,
+                Some(
+                    LimitClause::LimitOffset { limit: Some(_), .. }
+                    | LimitClause::OffsetCommaLimit { .. },
+                ),
+                Some(_),
+            ) => plan_err!("FETCH cannot be used together with LIMIT"),
+            (Some(top), None, None) => {
+                let fetch = match top.quantity {
+                    Some(TopQuantity::Constant(quantity)) => {
+                        match i64::try_from(quantity) {
+                            Ok(quantity) => lit(quantity),
+                            Err(_) => {
+                                return plan_err!("TOP quantity {quantity} is too large")
+                            }
+                        }
+                    }
+                    Some(TopQuantity::Expr(expr)) => to_expr(expr)?,
+                    None => return plan_err!("TOP requires a quantity"),
+                };
+                Ok(LimitSpec {
+                    clause: "TOP",
+                    skip: None,
+                    fetch: Some(fetch),
+                    percent: top.percent,
+                    by_exprs: vec![],
+                })
+            }
+            (None, limit_clause, fetch_clause) => {
+                let (skip, fetch, by_exprs) = match limit_clause {
+                    None => (None, None, vec![]),
+                    Some(LimitClause::LimitOffset {
+                        limit,
+                        offset,
+                        limit_by,
+                    }) => (
+                        offset.map(|o| to_expr(o.value)).transpose()?,
+                        limit.map(&mut to_expr).transpose()?,
+                        limit_by
+                            .into_iter()
+                            .map(&mut to_expr)
+                            .collect::<Result<Vec<_>>>()?,
+                    ),
+                    Some(LimitClause::OffsetCommaLimit { offset, limit }) => {
+                        (Some(to_expr(offset)?), Some(to_expr(limit)?), vec![])
+                    }
+                };
+                match fetch_clause {
+                    None => Ok(LimitSpec {
+                        clause: "LIMIT",
+                        skip,
+                        fetch,
+                        percent: false,
+                        by_exprs,
+                    }),
+                    Some(Fetch {
+                        quantity, percent, ..
+                    }) => Ok(LimitSpec {
+                        clause: "FETCH",
+                        skip,
+                        // `FETCH FIRST ROW ONLY` fetches a single row
+                        fetch: Some(match quantity {
+                            Some(quantity) => to_expr(quantity)?,
+                            None => lit(1_i64),
+                        }),
+                        percent,
+                        by_exprs,
+                    }),
+                }
+            }
+        }
+    }
+
+    /// Wrap a plan in a limit for a [`LimitSpec`]
+    pub(super) fn limit(
+        &self,
+        input: LogicalPlan,
+        limit: LimitSpec,
+    ) -> Result<LogicalPlan> {
+        let LimitSpec {
+            clause,
+            skip,
+            fetch,
+            percent,
+            by_exprs,
+        } = limit;
+
+        if !by_exprs.is_empty() {
+            return not_impl_err!("{clause} BY clause is not supported yet");
+        }
+        if percent {
+            return not_impl_err!("{clause} PERCENT is not supported");
+        }
 
-        if !limit_by_exprs.is_empty() {
-            return not_impl_err!("LIMIT BY clause is not supported yet");
+        // Any expression of an integer type is accepted, as it is folded into a
+        // constant during optimization, which also rejects negative values. The
+        // type of a placeholder is only known once its value is provided.
+        let empty_schema = DFSchema::empty();
+        for (clause, expr) in [("OFFSET", &skip), (clause, &fetch)] {
+            let Some(expr) = expr else {
+                continue;
+            };
+            if let Ok(data_type) = expr.get_type(&empty_schema) {
+                if !data_type.is_integer() && !data_type.is_null() {
+                    return plan_err!(
+                        "{clause} requires an integer, but got {data_type}"
+                    );
+                }
+            }
         }
 
         if skip.is_none() && fetch.is_none() {
@@ -171,6 +365,8 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                     input: Arc::new(plan),
                     if_not_exists: false,
                     or_replace: false,
+                    // `SELECT INTO TEMPORARY` creates a regular table, as
+                    // temporary tables are not supported
                     temporary: false,
                     column_defaults: vec![],
                 },
@@ -180,12 +376,54 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
     }
 }
 
+/// The limit of a query, normalized from its `TOP`, `LIMIT`, `OFFSET` and
+/// `FETCH` clauses by [`SqlToRel::limit_spec`]
+#[derive(Debug, Default)]
+pub(crate) struct LimitSpec {
+    /// The spelling of the limit, used in error messages
+    clause: &'static str,
+    /// The number of rows to skip
+    skip: Option<Expr>,
+    /// The number of rows to return, or their percentage if `percent` is set
+    fetch: Option<Expr>,
+    /// Whether `fetch` is a percentage of the rows, as in `TOP 10 PERCENT`.
+    /// Such a limit is not supported, and is rejected by [`SqlToRel::limit`].
+    percent: bool,
+    /// The expressions of a `LIMIT .. BY` clause
+    by_exprs: Vec<Expr>,
+}
+
+/// The schema that the `ORDER BY` of a set operation is resolved against: its
+/// output columns, which are named after the columns of its first input and
+/// are not qualified by any of the input relations. The qualifiers are kept
+/// when they are needed to tell apart output columns with the same name.
+fn set_expr_output_schema(plan: &LogicalPlan) -> Result<DFSchemaRef> {
+    let schema = plan.schema();
+    let mut names = HashSet::new();
+    if !schema
+        .fields()
+        .iter()
+        .all(|field| names.insert(field.name()))
+    {
+        return Ok(Arc::clone(schema));
+    }
+    Ok(Arc::new(DFSchema::from_unqualified_fields(
+        schema.fields().clone(),
+        schema.metadata().clone(),
+    )?))
+}
+
 /// Returns the order by expressions from the query.
 fn to_order_by_exprs(order_by: Option<OrderBy>) -> Result<Vec<OrderByExpr>> {
     to_order_by_exprs_with_select(order_by, None)
 }
 
 /// Returns the order by expressions from the query with the select expressions.
+///
+/// `ORDER BY ALL` sorts by every output column in projection order, so it is
+/// expanded to the positions of the select expressions, which refer to the
+/// output columns whether they are plain columns, aggregates or window
+/// functions.
 pub(crate) fn to_order_by_exprs_with_select(
     order_by: Option<OrderBy>,
     select_exprs: Option<&Vec<Expr>>,
@@ -202,24 +440,15 @@ pub(crate) fn to_order_by_exprs_with_select(
             let Some(exprs) = select_exprs else {
                 return Ok(vec![]);
             };
-            let order_by_exprs = exprs
-                .iter()
-                .map(|select_expr| match select_expr {
-                    Expr::Column(column) => Ok(OrderByExpr {
-                        expr: SQLExpr::Identifier(Ident {
-                            value: column.name.clone(),
-                            quote_style: None,
-                            span: Span::empty(),
-                        }),
-                        options: order_by_options.clone(),
-                        with_fill: None,
-                    }),
-                    // TODO: Support other types of expressions
-                    _ => not_impl_err!(
-                        "ORDER BY ALL is not supported for non-column expressions"
+            let order_by_exprs = (1..=exprs.len())
+                .map(|position| OrderByExpr {
+                    expr: SQLExpr::value(
+                        Value::Number(position.to_string(), false).with_empty_span(),
                     ),
+                    options: order_by_options.clone(),
+                    with_fill: None,
                 })
-                .collect::<Result<Vec<_>>>()?;
+                .collect();
             Ok(order_by_exprs)
         }
         OrderByKind::Expressions(order_by_exprs) => Ok(order_by_exprs),
@@ -96,6 +96,14 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                 self.parse_join(left, right, constraint, JoinType::Full, planner_context)
             }
             JoinOperator::CrossJoin => self.parse_cross_join(left, right),
+            JoinOperator::CrossApply => self.parse_cross_join(left, right),
+            JoinOperator::OuterApply => self.parse_join(
+                left,
+                right,
+                JoinConstraint::None,
+                JoinType::Left,
+                planner_context,
+            ),
             other => not_impl_err!("Unsupported JOIN operator {other:?}"),
         }
     }
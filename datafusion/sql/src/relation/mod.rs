@@ -19,18 +19,110 @@ use std::sync::Arc;
 
 use crate::planner::{ContextProvider, PlannerContext, SqlToRel};
 
-use datafusion_common::tree_node::{Transformed, TreeNode};
+use datafusion_common::tree_node::{Transformed, TreeNode, TreeNodeRecursion};
 use datafusion_common::{
-    not_impl_err, plan_err, DFSchema, Diagnostic, Result, Span, Spans, TableReference,
+    not_impl_err, plan_datafusion_err, plan_err, Column, DFSchema, Diagnostic, Result,
+    ScalarValue, Span, Spans, TableReference,
 };
 use datafusion_expr::builder::subquery_alias;
-use datafusion_expr::{expr::Unnest, Expr, LogicalPlan, LogicalPlanBuilder};
+use datafusion_expr::expr::{Cast, ScalarFunction, TryCast, Unnest, WindowFunction};
+use datafusion_expr::utils::find_out_reference_exprs;
+use datafusion_expr::{Expr, ExprSchemable, LogicalPlan, LogicalPlanBuilder, TableHints};
 use datafusion_expr::{Subquery, SubqueryAlias};
-use sqlparser::ast::{FunctionArg, FunctionArgExpr, Spanned, TableFactor};
+use sqlparser::ast::{
+    FunctionArg, FunctionArgExpr, Spanned, TableFactor, TableSampleKind,
+    TableSampleMethod, TableSampleQuantity, TableSampleUnit, TableVersion,
+};
 
 mod join;
 
 impl<S: ContextProvider> SqlToRel<'_, S> {
+    /// Plan the arguments of the table function `name`. A named argument,
+    /// e.g. `stop => 10`, is placed at the position of the parameter of that
+    /// name, and must follow the positional arguments.
+    fn table_function_args(
+        &self,
+        name: &str,
+        args: Vec<FunctionArg>,
+        schema: &DFSchema,
+        planner_context: &mut PlannerContext,
+    ) -> Result<Vec<Expr>> {
+        let mut positional = vec![];
+        let mut named = vec![];
+        for arg in args {
+            match arg {
+                FunctionArg::Unnamed(FunctionArgExpr::Expr(expr)) => {
+                    if !named.is_empty() {
+                        return plan_err!(
+                            "Positional arguments of table function {name} must precede named arguments"
+                        );
+                    }
+                    positional.push(self.sql_expr_to_logical_expr(
+                        expr,
+                        schema,
+                        planner_context,
+                    )?);
+                }
+                FunctionArg::Named {
+                    name: arg_name,
+                    arg: FunctionArgExpr::Expr(expr),
+                    ..
+                } => named.push((self.ident_normalizer.normalize(arg_name), expr)),
+                _ => return plan_err!("Unsupported function argument: {arg:?}"),
+            }
+        }
+        if named.is_empty() {
+            return Ok(positional);
+        }
+
+        let parameters = self
+            .context_provider
+            .get_table_function_parameter_names(name)?;
+        if parameters.is_empty() {
+            return plan_err!("Table function {name} does not accept named arguments");
+        }
+        let mut resolved = positional.into_iter().map(Some).collect::<Vec<_>>();
+        for (arg_name, expr) in named {
+            let Some(index) = parameters.iter().position(|p| *p == arg_name) else {
+                return plan_err!(
+                    "Table function {name} has no parameter named {arg_name}, parameters are: {}",
+                    parameters.join(", ")
+                );
+            };
+            if index >= resolved.len() {
+                resolved.resize(index + 1, None);
+            }
+            if resolved[index].is_some() {
+                return plan_err!(
+                    "Table function {name} got more than one value for parameter {arg_name}"
+                );
+            }
+            resolved[index] =
+                Some(self.sql_expr_to_logical_expr(expr, schema, planner_context)?);
+        }
+        // Omitting a required parameter would shift the meaning of the others,
+        // e.g. a lone `start` of `generate_series` would be read as its `stop`
+        let required = self
+            .context_provider
+            .get_table_function_required_parameter_count(name)?
+            .min(parameters.len());
+        if resolved.len() < required {
+            resolved.resize(required, None);
+        }
+        resolved
+            .into_iter()
+            .enumerate()
+            .map(|(index, arg)| {
+                arg.ok_or_else(|| {
+                    plan_datafusion_err!(
+                        "Table function {name} is missing an argument for parameter {}",
+                        parameters[index]
+                    )
+                })
+            })
+            .collect()
+    }
+
     /// Create a `LogicalPlan` that scans the named relation
     fn create_relation(
         &self,
@@ -40,73 +132,143 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
         let relation_span = relation.span();
         let (plan, alias) = match relation {
             TableFactor::Table {
-                name, alias, args, ..
+                name,
+                alias,
+                args,
+                sample,
+                version,
+                with_ordinality,
+                with_hints,
+                index_hints,
+                ..
             } => {
+                if with_ordinality && args.is_none() {
+                    return plan_err!(
+                        "WITH ORDINALITY is only supported on table functions"
+                    );
+                }
                 if let Some(func_args) = args {
+                    if sample.is_some() {
+                        return not_impl_err!(
+                            "TABLESAMPLE on a table function is not supported yet"
+                        );
+                    }
+                    if version.is_some() {
+                        return not_impl_err!(
+                            "Time travel on a table function is not supported yet"
+                        );
+                    }
                     let tbl_func_name =
                         name.0.first().unwrap().as_ident().unwrap().to_string();
-                    let args = func_args
-                        .args
-                        .into_iter()
-                        .flat_map(|arg| {
-                            if let FunctionArg::Unnamed(FunctionArgExpr::Expr(expr)) = arg
-                            {
-                                self.sql_expr_to_logical_expr(
-                                    expr,
-                                    &DFSchema::empty(),
-                                    planner_context,
-                                )
-                            } else {
-                                plan_err!("Unsupported function argument type: {:?}", arg)
-                            }
-                        })
-                        .collect::<Vec<_>>();
+                    let args = self.table_function_args(
+                        &tbl_func_name,
+                        func_args.args,
+                        &DFSchema::empty(),
+                        planner_context,
+                    )?;
                     let provider = self
                         .context_provider
                         .get_table_function_source(&tbl_func_name, args)?;
-                    let plan = LogicalPlanBuilder::scan(
-                        TableReference::Bare {
-                            table: format!("{tbl_func_name}()").into(),
-                        },
-                        provider,
-                        None,
-                    )?
-                    .build()?;
-                    (plan, alias)
+                    let table_ref = TableReference::Bare {
+                        table: format!("{tbl_func_name}()").into(),
+                    };
+                    let plan =
+                        LogicalPlanBuilder::scan(table_ref.clone(), provider, None)?
+                            .build()?;
+                    if with_ordinality {
+                        (self.append_ordinality(plan, table_ref)?, alias)
+                    } else {
+                        (plan, alias)
+                    }
                 } else {
                     // Normalize name and alias
                     let table_ref = self.object_name_to_table_reference(name)?;
-                    let table_name = table_ref.to_string();
-                    let cte = planner_context.get_cte(&table_name);
-                    (
-                        match (
-                            cte,
-                            self.context_provider.get_table_source(table_ref.clone()),
-                        ) {
-                            (Some(cte_plan), _) => Ok(cte_plan.clone()),
-                            (_, Ok(provider)) => LogicalPlanBuilder::scan(
+                    let version = version
+                        .map(|version| {
+                            self.table_version_to_value(version, planner_context)
+                        })
+                        .transpose()?;
+                    // Only an unqualified name refers to a CTE, which then
+                    // shadows any table of the same name
+                    let cte = match &table_ref {
+                        TableReference::Bare { table } => planner_context.get_cte(table),
+                        _ => None,
+                    };
+                    let source = match version {
+                        Some(version) => {
+                            if cte.is_some() {
+                                return plan_err!(
+                                    "Time travel is not supported on the CTE {table_ref}"
+                                );
+                            }
+                            self.context_provider
+                                .get_table_source_with_version(table_ref.clone(), version)
+                        }
+                        None => self.context_provider.get_table_source(table_ref.clone()),
+                    };
+                    let plan = match (cte, source) {
+                        (Some(cte_plan), _) => Ok(cte_plan.clone()),
+                        (_, Ok(provider)) => {
+                            // Columns renamed by an alias are not attributed to the table
+                            let qualifier = match &alias {
+                                None => Some(table_ref.clone()),
+                                Some(alias) if alias.columns.is_empty() => {
+                                    Some(TableReference::bare(
+                                        self.ident_normalizer
+                                            .normalize(alias.name.clone()),
+                                    ))
+                                }
+                                Some(_) => None,
+                            };
+                            planner_context.record_table(&table_ref, qualifier);
+                            // The hints of a view are dropped, as its plan is inlined
+                            let hints = TableHints {
+                                index_hints,
+                                with_hints,
+                            };
+                            let plan = LogicalPlanBuilder::scan(
                                 table_ref.clone(),
                                 provider,
                                 None,
                             )?
-                            .build(),
-                            (None, Err(e)) => {
-                                let e = e.with_diagnostic(Diagnostic::new_error(
-                                    format!("table '{table_ref}' not found"),
-                                    Span::try_from_sqlparser_span(relation_span),
-                                ));
-                                Err(e)
+                            .build()?;
+                            match plan {
+                                LogicalPlan::TableScan(scan) => {
+                                    Ok(LogicalPlan::TableScan(scan.with_hints(hints)))
+                                }
+                                plan => Ok(plan),
                             }
-                        }?,
-                        alias,
-                    )
+                        }
+                        (None, Err(e)) => {
+                            let e = e.with_diagnostic(Diagnostic::new_error(
+                                format!("table '{table_ref}' not found"),
+                                Span::try_from_sqlparser_span(relation_span),
+                            ));
+                            Err(e)
+                        }
+                    }?;
+                    match sample {
+                        Some(sample) => {
+                            (self.table_sample(plan, sample, planner_context)?, alias)
+                        }
+                        None => (plan, alias),
+                    }
                 }
             }
             TableFactor::Derived {
                 subquery, alias, ..
             } => {
-                let logical_plan = self.query_to_plan(*subquery, planner_context)?;
-                (logical_plan, alias)
+                let read_in_order = planner_context.preserve_derived_order();
+                // The columns of an aliased derived table lose their qualifiers
+                let unique_output_names =
+                    planner_context.set_unique_output_names(alias.is_some());
+                let logical_plan = self.query_to_plan_with_order(
+                    *subquery,
+                    planner_context,
+                    read_in_order,
+                );
+                planner_context.set_unique_output_names(unique_output_names);
+                (logical_plan?, alias)
             }
             TableFactor::NestedJoin {
                 table_with_joins,
@@ -162,19 +324,12 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                     .outer_query_schema()
                     .cloned()
                     .unwrap_or_else(DFSchema::empty);
-                let func_args = args
-                    .into_iter()
-                    .map(|arg| match arg {
-                        FunctionArg::Unnamed(FunctionArgExpr::Expr(expr))
-                        | FunctionArg::Named {
-                            arg: FunctionArgExpr::Expr(expr),
-                            ..
-                        } => {
-                            self.sql_expr_to_logical_expr(expr, &schema, planner_context)
-                        }
-                        _ => plan_err!("Unsupported function argument: {arg:?}"),
-                    })
-                    .collect::<Result<Vec<Expr>>>()?;
+                let func_args = self.table_function_args(
+                    tbl_func_ref.table(),
+                    args,
+                    &schema,
+                    planner_context,
+                )?;
                 let provider = self
                     .context_provider
                     .get_table_function_source(tbl_func_ref.table(), func_args)?;
@@ -191,7 +346,11 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
             }
         };
 
-        let optimized_plan = optimize_subquery_sort(plan)?.data;
+        let optimized_plan = if planner_context.preserve_derived_order() {
+            plan
+        } else {
+            optimize_subquery_sort(plan)?.data
+        };
         if let Some(alias) = alias {
             self.apply_table_alias(optimized_plan, alias)
         } else {
@@ -199,6 +358,117 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
         }
     }
 
+    /// Evaluate the `AS OF` expression of a time travel query to the version
+    /// of the table to read
+    fn table_version_to_value(
+        &self,
+        version: TableVersion,
+        planner_context: &mut PlannerContext,
+    ) -> Result<ScalarValue> {
+        let expr = match version {
+            TableVersion::ForSystemTimeAsOf(expr) => expr,
+            TableVersion::Function(func) => {
+                return not_impl_err!("Time travel with {func} is not supported yet");
+            }
+        };
+        let expr = self.sql_to_expr(expr, &DFSchema::empty(), planner_context)?;
+        match constant_value(&expr) {
+            Some(value) => value,
+            None => plan_err!("AS OF requires a constant expression, but got {expr}"),
+        }
+    }
+
+    /// Append the ordinality column of a table function called `WITH
+    /// ORDINALITY`: the 1-based position of each row in the output of the
+    /// function, qualified by `table_ref` like the columns of the function.
+    ///
+    /// The column is named `ordinality`, with a numeric suffix if the function
+    /// already returns a column of that name.
+    fn append_ordinality(
+        &self,
+        plan: LogicalPlan,
+        table_ref: TableReference,
+    ) -> Result<LogicalPlan> {
+        let Some(row_number) = self.context_provider.get_window_meta("row_number") else {
+            return plan_err!("WITH ORDINALITY requires the row_number function");
+        };
+        let schema = Arc::clone(plan.schema());
+        let name = std::iter::once(ORDINALITY_COLUMN.to_string())
+            .chain((1..).map(|i| format!("{ORDINALITY_COLUMN}_{i}")))
+            .find(|name| schema.fields().iter().all(|f| f.name() != name))
+            .unwrap();
+        let plan = LogicalPlanBuilder::from(plan)
+            .window(vec![Expr::from(WindowFunction::new(row_number, vec![]))])?
+            .build()?;
+        let ordinality = Expr::Column(Column::from(
+            plan.schema().qualified_field(schema.fields().len()),
+        ));
+        LogicalPlanBuilder::from(plan)
+            .project(schema.columns().into_iter().map(Expr::Column).chain(
+                std::iter::once(ordinality.alias_qualified(Some(table_ref), name)),
+            ))?
+            .build()
+    }
+
+    /// Sample the rows of a table for its `TABLESAMPLE` clause
+    ///
+    /// Only a sample of a number of rows is supported, which is planned as the
+    /// first rows of the table in a random order.
+    fn table_sample(
+        &self,
+        plan: LogicalPlan,
+        sample: TableSampleKind,
+        planner_context: &mut PlannerContext,
+    ) -> Result<LogicalPlan> {
+        let (TableSampleKind::BeforeTableAlias(sample)
+        | TableSampleKind::AfterTableAlias(sample)) = sample;
+        if sample.bucket.is_some() {
+            return not_impl_err!("TABLESAMPLE BUCKET is not supported yet");
+        }
+        if sample.seed.is_some() {
+            return not_impl_err!("TABLESAMPLE with a seed is not supported yet");
+        }
+        if sample.offset.is_some() {
+            return not_impl_err!("TABLESAMPLE with an offset is not supported yet");
+        }
+        if let Some(method @ (TableSampleMethod::System | TableSampleMethod::Block)) =
+            sample.name
+        {
+            return not_impl_err!("TABLESAMPLE {method} is not supported yet");
+        }
+        let quantity = match sample.quantity {
+            Some(TableSampleQuantity {
+                value,
+                unit: Some(TableSampleUnit::Rows),
+                ..
+            }) => value,
+            Some(_) => {
+                return not_impl_err!(
+                    "TABLESAMPLE of a percentage of rows is not supported yet"
+                );
+            }
+            None => return plan_err!("TABLESAMPLE requires a quantity"),
+        };
+
+        let empty_schema = DFSchema::empty();
+        let fetch = self.sql_to_expr(quantity, &empty_schema, planner_context)?;
+        if let Ok(data_type) = fetch.get_type(&empty_schema) {
+            if !data_type.is_integer() && !data_type.is_null() {
+                return plan_err!(
+                    "TABLESAMPLE requires an integer number of rows, but got {data_type}"
+                );
+            }
+        }
+        let Some(random) = self.context_provider.get_function_meta("random") else {
+            return plan_err!("TABLESAMPLE requires the random function");
+        };
+        let random = Expr::ScalarFunction(ScalarFunction::new_udf(random, vec![]));
+        LogicalPlanBuilder::from(plan)
+            .sort(vec![random.sort(true, false)])?
+            .limit_by_expr(None, Some(fetch))?
+            .build()
+    }
+
     pub(crate) fn create_relation_subquery(
         &self,
         subquery: TableFactor,
@@ -217,16 +487,16 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
             Some(old_query_schema) => {
                 let mut new_query_schema = old_from_schema.as_ref().clone();
                 new_query_schema.merge(old_query_schema);
-                Some(Arc::new(new_query_schema))
+                Arc::new(new_query_schema)
             }
-            None => Some(Arc::clone(&old_from_schema)),
+            None => Arc::clone(&old_from_schema),
         };
-        let old_query_schema = planner_context.set_outer_query_schema(new_query_schema);
+        planner_context.append_outer_query_schema(new_query_schema);
 
         let plan = self.create_relation(subquery, planner_context)?;
-        let outer_ref_columns = plan.all_out_ref_exprs();
+        let outer_ref_columns = lateral_outer_ref_exprs(&plan);
 
-        planner_context.set_outer_query_schema(old_query_schema);
+        planner_context.pop_outer_query_schema();
         planner_context.set_outer_from_schema(Some(old_from_schema));
 
         // We can omit the subquery wrapper if there are no columns
@@ -255,6 +525,24 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
     }
 }
 
+/// The name of the column appended by `WITH ORDINALITY`
+const ORDINALITY_COLUMN: &str = "ordinality";
+
+/// The value of a constant expression: a literal, possibly cast or negated
+fn constant_value(expr: &Expr) -> Option<Result<ScalarValue>> {
+    Some(match expr {
+        Expr::Literal(value, _) => Ok(value.clone()),
+        Expr::Cast(Cast { expr, data_type })
+        | Expr::TryCast(TryCast { expr, data_type }) => {
+            constant_value(expr)?.and_then(|value| value.cast_to(data_type))
+        }
+        Expr::Negative(expr) => {
+            constant_value(expr)?.and_then(|value| value.arithmetic_negate())
+        }
+        _ => return None,
+    })
+}
+
 fn optimize_subquery_sort(plan: LogicalPlan) -> Result<Transformed<LogicalPlan>> {
     // When initializing subqueries, we examine sort options since they might be unnecessary.
     // They are only important if the subquery result is affected by the ORDER BY statement,
@@ -281,3 +569,71 @@ fn optimize_subquery_sort(plan: LogicalPlan) -> Result<Transformed<LogicalPlan>>
     });
     new_plan
 }
+
+/// Remove the sorts of the derived tables of a SELECT planned with
+/// [`PlannerContext::preserve_derived_order`] whose order does not reach the
+/// `LIMIT` above, because a join, aggregate, window or other operator that
+/// does not keep the order of its input is in between.
+pub(crate) fn remove_unordered_subquery_sorts(plan: LogicalPlan) -> Result<LogicalPlan> {
+    match plan {
+        LogicalPlan::Projection(_)
+        | LogicalPlan::Filter(_)
+        | LogicalPlan::SubqueryAlias(_) => Ok(plan
+            .map_children(|child| {
+                remove_unordered_subquery_sorts(child).map(Transformed::no)
+            })?
+            .data),
+        // The order of this derived table is read by the `LIMIT`
+        LogicalPlan::Sort(_) => Ok(plan),
+        _ => Ok(optimize_subquery_sort(plan)?.data),
+    }
+}
+
+/// Collect the outer references of the plan of a lateral subquery, i.e. the
+/// columns of the preceding FROM items it is correlated with.
+///
+/// Unlike [`LogicalPlan::all_out_ref_exprs`], the outer references of a lateral
+/// join nested in `plan` that are bound by the left input of that join are not
+/// included, as they are not correlated with the FROM items outside of `plan`.
+fn lateral_outer_ref_exprs(plan: &LogicalPlan) -> Vec<Expr> {
+    let mut exprs = vec![];
+    let mut push = |expr: Expr| {
+        if !exprs.contains(&expr) {
+            exprs.push(expr)
+        }
+    };
+    plan.apply_expressions(|expr| {
+        find_out_reference_exprs(expr)
+            .into_iter()
+            .for_each(&mut push);
+        Ok(TreeNodeRecursion::Continue)
+    })
+    // closure always returns OK
+    .unwrap();
+    match plan {
+        LogicalPlan::Join(join) => {
+            let lateral = match join.right.as_ref() {
+                LogicalPlan::SubqueryAlias(SubqueryAlias { input, .. }) => input.as_ref(),
+                right => right,
+            };
+            lateral_outer_ref_exprs(&join.left)
+                .into_iter()
+                .for_each(&mut push);
+            lateral_outer_ref_exprs(&join.right)
+                .into_iter()
+                .filter(|expr| match (lateral, expr) {
+                    (LogicalPlan::Subquery(_), Expr::OuterReferenceColumn(_, column)) => {
+                        !join.left.schema().has_column(column)
+                    }
+                    _ => true,
+                })
+                .for_each(&mut push);
+        }
+        _ => plan
+            .inputs()
+            .into_iter()
+            .flat_map(lateral_outer_ref_exprs)
+            .for_each(&mut push),
+    }
+    exprs
+}
@@ -15,39 +15,45 @@
 // specific language governing permissions and limitations
 // under the License.
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::ops::ControlFlow;
 use std::sync::Arc;
 
-use crate::planner::{ContextProvider, PlannerContext, SqlToRel};
+use crate::planner::{ContextProvider, DuplicateColumnNames, PlannerContext, SqlToRel};
 use crate::query::to_order_by_exprs_with_select;
 use crate::utils::{
-    check_columns_satisfy_exprs, extract_aliases, rebase_expr, resolve_aliases_to_exprs,
-    resolve_columns, resolve_positions_to_exprs, rewrite_recursive_unnests_bottom_up,
-    CheckColumnsSatisfyExprsPurpose,
+    check_columns_satisfy_exprs, check_limit, extract_aliases, rebase_expr,
+    resolve_aliases_to_exprs, resolve_columns, resolve_positions_to_exprs,
+    rewrite_recursive_unnests_bottom_up, using_join_columns,
+    CheckColumnsSatisfyExprsPurpose, UsingJoinColumn,
 };
 
 use datafusion_common::error::DataFusionErrorBuilder;
 use datafusion_common::tree_node::{TreeNode, TreeNodeRecursion};
-use datafusion_common::{not_impl_err, plan_err, Result};
+use datafusion_common::{not_impl_err, plan_err, Column, DFSchema, Result};
 use datafusion_common::{RecursionUnnestOption, UnnestOptions};
 use datafusion_expr::expr::{Alias, PlannedReplaceSelectItem, WildcardOptions};
 use datafusion_expr::expr_rewriter::{
     normalize_col, normalize_col_with_schemas_and_ambiguity_check, normalize_sorts,
 };
+use datafusion_expr::logical_plan::builder::{
+    change_redundant_column, expand_select_exprs,
+};
 use datafusion_expr::select_expr::SelectExpr;
 use datafusion_expr::utils::{
     expr_as_column_expr, expr_to_columns, find_aggregate_exprs, find_window_exprs,
 };
 use datafusion_expr::{
-    Aggregate, Expr, Filter, GroupingSet, LogicalPlan, LogicalPlanBuilder,
+    Aggregate, Expr, ExprSchemable, Filter, GroupingSet, LogicalPlan, LogicalPlanBuilder,
     LogicalPlanBuilderOptions, Partitioning,
 };
 
+use arrow::datatypes::Fields;
 use indexmap::IndexMap;
 use sqlparser::ast::{
-    visit_expressions_mut, Distinct, Expr as SQLExpr, GroupByExpr, NamedWindowExpr,
-    OrderBy, SelectItemQualifiedWildcardKind, WildcardAdditionalOptions, WindowType,
+    visit_expressions_mut, Distinct, Expr as SQLExpr, GroupByExpr, Ident,
+    NamedWindowExpr, OrderBy, OrderByKind, SelectItemQualifiedWildcardKind,
+    WildcardAdditionalOptions, WindowSpec, WindowType,
 };
 use sqlparser::ast::{NamedWindowDefinition, Select, SelectItem, TableWithJoins};
 
@@ -67,23 +73,73 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
             return not_impl_err!("LATERAL VIEWS");
         }
 
-        if select.top.is_some() {
-            return not_impl_err!("TOP");
-        }
         if !select.sort_by.is_empty() {
             return not_impl_err!("SORT BY");
         }
 
+        let unique_output_names = planner_context.take_unique_output_names();
+
+        // The tables of the `from` clause may reuse the qualifiers of the tables
+        // of an enclosing query
+        planner_context.push_qualifier_scope();
+
         // Process `from` clause
-        let plan = self.plan_from_tables(select.from, planner_context)?;
+        let plan =
+            self.plan_from_tables(std::mem::take(&mut select.from), planner_context)?;
+
+        // The other clauses resolve their column references against the
+        // `from` clause, which lists the columns joined by `USING`
+        let outer_using_join_columns =
+            planner_context.set_using_join_columns(using_join_columns(&plan)?);
+        let plan = self.select_clauses_to_plan(
+            plan,
+            select,
+            query_order_by,
+            unique_output_names,
+            planner_context,
+        );
+        planner_context.set_using_join_columns(outer_using_join_columns);
+        planner_context.pop_qualifier_scope();
+        plan
+    }
+
+    /// Plan the clauses of `select` other than `from`, over `plan` of the
+    /// `from` clause
+    fn select_clauses_to_plan(
+        &self,
+        plan: LogicalPlan,
+        mut select: Select,
+        mut query_order_by: Option<OrderBy>,
+        unique_output_names: bool,
+        planner_context: &mut PlannerContext,
+    ) -> Result<LogicalPlan> {
         let empty_from = matches!(plan, LogicalPlan::EmptyRelation(_));
 
         // Process `where` clause
         let base_plan = self.plan_selection(select.selection, plan, planner_context)?;
 
-        // Handle named windows before processing the projection expression
+        // Handle named windows before processing the projection and ORDER BY
+        // expressions
         check_conflicting_windows(&select.named_window)?;
-        self.match_window_definitions(&mut select.projection, &select.named_window)?;
+        let projection_exprs =
+            select.projection.iter_mut().filter_map(|item| match item {
+                SelectItem::ExprWithAlias { expr, alias: _ }
+                | SelectItem::UnnamedExpr(expr) => Some(expr),
+                _ => None,
+            });
+        let order_by_exprs = query_order_by
+            .as_mut()
+            .and_then(|order_by| match &mut order_by.kind {
+                OrderByKind::Expressions(exprs) => Some(exprs),
+                OrderByKind::All(_) => None,
+            })
+            .into_iter()
+            .flatten()
+            .map(|order_by_expr| &mut order_by_expr.expr);
+        self.match_window_definitions(
+            projection_exprs.chain(order_by_exprs),
+            &select.named_window,
+        )?;
         // Process the SELECT expressions
         let select_exprs = self.prepare_select_exprs(
             &base_plan,
@@ -91,10 +147,20 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
             empty_from,
             planner_context,
         )?;
+        let select_exprs = expand_using_join_wildcards(
+            &base_plan,
+            select_exprs,
+            planner_context.using_join_columns(),
+        )?;
 
         // Having and group by clause may reference aliases defined in select projection
-        let projected_plan = self.project(base_plan.clone(), select_exprs)?;
+        let projected_plan =
+            self.project(base_plan.clone(), select_exprs, unique_output_names)?;
         let select_exprs = projected_plan.expressions();
+        // Includes the columns of expanded wildcards
+        select_exprs
+            .iter()
+            .for_each(|expr| planner_context.record_column_references(expr));
 
         let order_by =
             to_order_by_exprs_with_select(query_order_by, Some(&select_exprs))?;
@@ -114,7 +180,7 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
             true,
             Some(base_plan.schema().as_ref()),
         )?;
-        let order_by_rex = normalize_sorts(order_by_rex, &projected_plan)?;
+        let mut order_by_rex = normalize_sorts(order_by_rex, &projected_plan)?;
 
         // This alias map is resolved and looked up in both having exprs and group by exprs
         let alias_map = extract_aliases(&select_exprs);
@@ -179,24 +245,63 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
         // All of the aggregate expressions (deduplicated).
         let aggr_exprs = find_aggregate_exprs(aggr_expr_haystack);
 
+        // An empty grouping `()` groups all rows into a single group
+        let mut has_empty_grouping = false;
+
+        let alias_schema;
+        let (group_by_alias_map, group_by_schema) =
+            if self.options.prefer_group_by_aliases {
+                // Like in ORDER BY, names in GROUP BY refer to aliases from the projection
+                // before same-named columns of the input. An alias of an aggregate or
+                // window function can not be grouped by, so the input column is used.
+                let group_by_alias_map = alias_map
+                    .iter()
+                    .filter(|(name, expr)| {
+                        !base_plan.schema().has_column_with_unqualified_name(name)
+                            || !expr
+                                .exists(|e| {
+                                    Ok(matches!(
+                                        e,
+                                        Expr::AggregateFunction(_)
+                                            | Expr::WindowFunction(_)
+                                    ))
+                                })
+                                .unwrap_or(true)
+                    })
+                    .map(|(name, expr)| (name.clone(), expr.clone()))
+                    .collect();
+                let mut schema = projected_plan.schema().as_ref().clone();
+                schema.merge(base_plan.schema());
+                alias_schema = schema;
+                (group_by_alias_map, &alias_schema)
+            } else {
+                // Aliases from the projection can conflict with same-named expressions in the input
+                let mut group_by_alias_map = alias_map.clone();
+                for f in base_plan.schema().fields() {
+                    group_by_alias_map.remove(f.name());
+                }
+                (group_by_alias_map, &combined_schema)
+            };
+
         // All of the group by expressions
         let group_by_exprs = if let GroupByExpr::Expressions(exprs, _) = select.group_by {
             exprs
                 .into_iter()
+                .filter(|e| {
+                    let is_empty_grouping =
+                        matches!(e, SQLExpr::Tuple(values) if values.is_empty());
+                    has_empty_grouping |= is_empty_grouping;
+                    !is_empty_grouping
+                })
                 .map(|e| {
                     let group_by_expr = self.sql_expr_to_logical_expr(
                         e,
-                        &combined_schema,
+                        group_by_schema,
                         planner_context,
                     )?;
 
-                    // Aliases from the projection can conflict with same-named expressions in the input
-                    let mut alias_map = alias_map.clone();
-                    for f in base_plan.schema().fields() {
-                        alias_map.remove(f.name());
-                    }
                     let group_by_expr =
-                        resolve_aliases_to_exprs(group_by_expr, &alias_map)?;
+                        resolve_aliases_to_exprs(group_by_expr, &group_by_alias_map)?;
                     let group_by_expr =
                         resolve_positions_to_exprs(group_by_expr, &select_exprs)?;
                     let group_by_expr = normalize_col(group_by_expr, &projected_plan)?;
@@ -223,6 +328,15 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                 .collect()
         };
 
+        // Without any aggregate, the single group of `GROUP BY ()` has to be
+        // planned as a grouping set, which an aggregation allows to be empty
+        let group_by_exprs =
+            if has_empty_grouping && group_by_exprs.is_empty() && aggr_exprs.is_empty() {
+                vec![Expr::GroupingSet(GroupingSet::GroupingSets(vec![vec![]]))]
+            } else {
+                group_by_exprs
+            };
+
         // Process group by, aggregation or having
         let (plan, mut select_exprs_post_aggr, having_expr_post_aggr) = if !group_by_exprs
             .is_empty()
@@ -256,11 +370,32 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
             select_exprs_post_aggr.iter().chain(qualify_expr_opt.iter());
         // All of the window expressions (deduplicated).
         let window_func_exprs = find_window_exprs(windows_expr_haystack);
+        // The window functions that are only sorted by are computed along with
+        // the others, so that the sort can refer to their results
+        let order_by_window_exprs: Vec<Expr> =
+            find_window_exprs(order_by_rex.iter().map(|sort| &sort.expr))
+                .into_iter()
+                .filter(|expr| !window_func_exprs.contains(expr))
+                .collect();
+        if !order_by_window_exprs.is_empty()
+            && (!group_by_exprs.is_empty() || !aggr_exprs.is_empty())
+        {
+            return not_impl_err!(
+                "ORDER BY window functions that are not in the SELECT list of an aggregation"
+            );
+        }
+        let has_window_exprs =
+            !window_func_exprs.is_empty() || !order_by_window_exprs.is_empty();
 
-        let plan = if window_func_exprs.is_empty() {
+        let plan = if !has_window_exprs {
             plan
         } else {
-            let plan = LogicalPlanBuilder::window_plan(plan, window_func_exprs.clone())?;
+            let all_window_exprs = window_func_exprs
+                .iter()
+                .chain(&order_by_window_exprs)
+                .cloned()
+                .collect::<Vec<_>>();
+            let plan = LogicalPlanBuilder::window_plan(plan, all_window_exprs)?;
 
             // Re-write the projection
             select_exprs_post_aggr = select_exprs_post_aggr
@@ -268,6 +403,19 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                 .map(|expr| rebase_expr(expr, &window_func_exprs, &plan))
                 .collect::<Result<Vec<Expr>>>()?;
 
+            // Re-write the sort keys, whose window columns are added to the
+            // projection by the sort
+            order_by_rex = order_by_rex
+                .iter()
+                .map(|sort| {
+                    Ok(sort.with_expr(rebase_expr(
+                        &sort.expr,
+                        &order_by_window_exprs,
+                        &plan,
+                    )?))
+                })
+                .collect::<Result<Vec<_>>>()?;
+
             plan
         };
 
@@ -316,11 +464,19 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
             Some(Distinct::On(on_expr)) => {
                 if !aggr_exprs.is_empty()
                     || !group_by_exprs.is_empty()
-                    || !window_func_exprs.is_empty()
+                    || has_window_exprs
                 {
                     return not_impl_err!("DISTINCT ON expressions with GROUP BY, aggregation or window functions are not supported ");
                 }
 
+                // Without expressions all rows would be in a single group, which
+                // is easily mistaken for the whole-row distinct of `DISTINCT`
+                if on_expr.is_empty() {
+                    return plan_err!(
+                        "DISTINCT ON requires at least one expression, use DISTINCT to remove duplicate rows"
+                    );
+                }
+
                 let on_expr = on_expr
                     .into_iter()
                     .map(|e| {
@@ -583,12 +739,6 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
         match selection {
             Some(predicate_expr) => {
                 let fallback_schemas = plan.fallback_normalize_schemas();
-                let outer_query_schema = planner_context.outer_query_schema().cloned();
-                let outer_query_schema_vec = outer_query_schema
-                    .as_ref()
-                    .map(|schema| vec![schema])
-                    .unwrap_or_else(Vec::new);
-
                 let filter_expr =
                     self.sql_to_expr(predicate_expr, plan.schema(), planner_context)?;
 
@@ -603,9 +753,21 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
 
                 let mut using_columns = HashSet::new();
                 expr_to_columns(&filter_expr, &mut using_columns)?;
+                // Columns of the enclosing queries resolve to the nearest one
+                let outer_query_schemas = planner_context
+                    .outer_queries_schemas()
+                    .iter()
+                    .rev()
+                    .map(|schema| [schema.as_ref()])
+                    .collect::<Vec<_>>();
+                let plan_schema = [plan.schema().as_ref()];
+                let schemas = [plan_schema.as_slice(), &fallback_schemas]
+                    .into_iter()
+                    .chain(outer_query_schemas.iter().map(|schema| schema.as_slice()))
+                    .collect::<Vec<_>>();
                 let filter_expr = normalize_col_with_schemas_and_ambiguity_check(
                     filter_expr,
-                    &[&[plan.schema()], &fallback_schemas, &outer_query_schema_vec],
+                    &schemas,
                     &[using_columns],
                 )?;
 
@@ -683,12 +845,27 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
     ) -> Result<SelectExpr> {
         match sql {
             SelectItem::UnnamedExpr(expr) => {
+                // A column joined by a right or full `USING` join keeps its
+                // name when it resolves to the value of both joined columns
+                let using_join_column = match &expr {
+                    SQLExpr::Identifier(ident) => {
+                        let name = self.ident_normalizer.normalize(ident.clone());
+                        planner_context
+                            .using_join_column(&name, plan.schema())
+                            .map(|_| name)
+                    }
+                    _ => None,
+                };
                 let expr = self.sql_to_expr(expr, plan.schema(), planner_context)?;
                 let col = normalize_col_with_schemas_and_ambiguity_check(
                     expr,
                     &[&[plan.schema()]],
                     &plan.using_columns()?,
                 )?;
+                let col = match (using_join_column, col) {
+                    (Some(name), col @ Expr::Case(_)) => col.alias(name),
+                    (_, col) => col,
+                };
 
                 Ok(SelectExpr::Expression(col))
             }
@@ -700,6 +877,7 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                     &[&[plan.schema()]],
                     &plan.using_columns()?,
                 )?;
+                self.check_identifier_length(&alias)?;
                 let name = self.ident_normalizer.normalize(alias);
                 // avoiding adding an alias if the column name is the same.
                 let expr = match &col {
@@ -814,7 +992,12 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
     }
 
     /// Wrap a plan in a projection
-    fn project(&self, input: LogicalPlan, expr: Vec<SelectExpr>) -> Result<LogicalPlan> {
+    fn project(
+        &self,
+        input: LogicalPlan,
+        expr: Vec<SelectExpr>,
+        unique_output_names: bool,
+    ) -> Result<LogicalPlan> {
         // convert to Expr for validate_schema_satisfies_exprs
         let exprs = expr
             .iter()
@@ -825,7 +1008,72 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
             .collect::<Vec<_>>();
         self.validate_schema_satisfies_exprs(input.schema(), &exprs)?;
 
-        LogicalPlanBuilder::from(input).project(expr)?.build()
+        let plan = if unique_output_names {
+            let exprs = expand_select_exprs(&input, expr.into_iter().map(|e| (e, true)))?;
+            let exprs = self.make_output_names_unique(exprs, input.schema())?;
+            LogicalPlanBuilder::from(input).project(exprs)?.build()?
+        } else {
+            LogicalPlanBuilder::from(input).project(expr)?.build()?
+        };
+        // Wildcards are expanded by now, so this counts the columns they add
+        check_limit(
+            "max_projection_width",
+            self.options.max_projection_width,
+            "Projection width",
+            plan.schema().fields().len(),
+        )?;
+        Ok(plan)
+    }
+
+    /// Handle duplicate output names of a projection whose output is used as
+    /// a CTE, a derived table or an input of a set operation, where columns
+    /// are referenced by their unqualified names, according to
+    /// [`ParserOptions::duplicate_column_names`]
+    ///
+    /// [`ParserOptions::duplicate_column_names`]: crate::planner::ParserOptions::duplicate_column_names
+    fn make_output_names_unique(
+        &self,
+        exprs: Vec<Expr>,
+        schema: &DFSchema,
+    ) -> Result<Vec<Expr>> {
+        let fields = exprs
+            .iter()
+            .map(|expr| Ok(expr.to_field(schema)?.1))
+            .collect::<Result<Fields>>()?;
+
+        match self.options.duplicate_column_names {
+            DuplicateColumnNames::Error => {
+                let mut positions = HashMap::new();
+                for (position, field) in fields.iter().enumerate() {
+                    if let Some(first) = positions.insert(field.name(), position) {
+                        return plan_err!(
+                            "Column names of a CTE, derived table or set operation input must be unique, \
+                            but the expression \"{}\" at position {first} and \"{}\" at position {position} \
+                            are both named \"{}\". Consider aliasing (\"AS\") one of them.",
+                            exprs[first],
+                            exprs[position],
+                            field.name()
+                        );
+                    }
+                }
+                Ok(exprs)
+            }
+            DuplicateColumnNames::Disambiguate => {
+                // Use the same names a `SubqueryAlias` gives to duplicate fields
+                let unique_fields = change_redundant_column(&fields);
+                Ok(exprs
+                    .into_iter()
+                    .zip(fields.iter().zip(unique_fields))
+                    .map(|(expr, (field, unique_field))| {
+                        if field.name() == unique_field.name() {
+                            expr
+                        } else {
+                            expr.unalias().alias(unique_field.name())
+                        }
+                    })
+                    .collect())
+            }
+        }
     }
 
     /// Create an aggregate plan.
@@ -946,60 +1194,119 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
         Ok((plan, select_exprs_post_aggr, having_expr_post_aggr))
     }
 
-    // If the projection is done over a named window, that window
-    // name must be defined. Otherwise, it gives an error.
-    fn match_window_definitions(
+    // If a projection or ORDER BY expression is computed over a named window,
+    // that window name must be defined. Otherwise, it gives an error.
+    fn match_window_definitions<'a>(
         &self,
-        projection: &mut [SelectItem],
+        exprs: impl IntoIterator<Item = &'a mut SQLExpr>,
         named_windows: &[NamedWindowDefinition],
     ) -> Result<()> {
         let named_windows: Vec<(&NamedWindowDefinition, String)> = named_windows
             .iter()
             .map(|w| (w, self.ident_normalizer.normalize(w.0.clone())))
             .collect();
-        for proj in projection.iter_mut() {
-            if let SelectItem::ExprWithAlias { expr, alias: _ }
-            | SelectItem::UnnamedExpr(expr) = proj
-            {
-                let mut err = None;
-                let _ = visit_expressions_mut(expr, |expr| {
-                    if let SQLExpr::Function(f) = expr {
-                        if let Some(WindowType::NamedWindow(ident)) = &f.over {
-                            let normalized_ident =
-                                self.ident_normalizer.normalize(ident.clone());
-                            for (
-                                NamedWindowDefinition(_, window_expr),
-                                normalized_window_ident,
-                            ) in named_windows.iter()
-                            {
-                                if normalized_ident.eq(normalized_window_ident) {
-                                    f.over = Some(match window_expr {
-                                        NamedWindowExpr::NamedWindow(ident) => {
-                                            WindowType::NamedWindow(ident.clone())
-                                        }
-                                        NamedWindowExpr::WindowSpec(spec) => {
-                                            WindowType::WindowSpec(spec.clone())
-                                        }
-                                    })
-                                }
-                            }
-                            // All named windows must be defined with a WindowSpec.
-                            if let Some(WindowType::NamedWindow(ident)) = &f.over {
-                                err =
-                                    Some(plan_err!("The window {ident} is not defined!"));
-                                return ControlFlow::Break(());
-                            }
+        for expr in exprs {
+            let mut err = None;
+            let _ = visit_expressions_mut(expr, |expr| {
+                if let SQLExpr::Function(f) = expr {
+                    let resolved = match &f.over {
+                        Some(WindowType::NamedWindow(ident)) => {
+                            self.resolve_named_window(ident, &named_windows, 0)
+                        }
+                        Some(WindowType::WindowSpec(spec))
+                            if spec.window_name.is_some() =>
+                        {
+                            self.resolve_window_spec(spec, &named_windows, 0)
+                        }
+                        _ => return ControlFlow::Continue(()),
+                    };
+                    match resolved {
+                        Ok(spec) => f.over = Some(WindowType::WindowSpec(spec)),
+                        Err(e) => {
+                            err = Some(Err(e));
+                            return ControlFlow::Break(());
                         }
                     }
-                    ControlFlow::Continue(())
-                });
-                if let Some(err) = err {
-                    return err;
                 }
+                ControlFlow::Continue(())
+            });
+            if let Some(err) = err {
+                return err;
             }
         }
         Ok(())
     }
+
+    /// Returns the window specification of the named window `ident`, with any
+    /// reference to another named window resolved.
+    fn resolve_named_window(
+        &self,
+        ident: &Ident,
+        named_windows: &[(&NamedWindowDefinition, String)],
+        depth: usize,
+    ) -> Result<WindowSpec> {
+        // All named windows must be defined with a WindowSpec.
+        let normalized_ident = self.ident_normalizer.normalize(ident.clone());
+        let Some((NamedWindowDefinition(_, window_expr), _)) =
+            named_windows.iter().find(|(_, normalized_window_ident)| {
+                normalized_ident.eq(normalized_window_ident)
+            })
+        else {
+            return plan_err!("The window {ident} is not defined!");
+        };
+        if depth >= named_windows.len() {
+            return plan_err!("The window {ident} is defined recursively!");
+        }
+        match window_expr {
+            NamedWindowExpr::NamedWindow(ident) => {
+                self.resolve_named_window(ident, named_windows, depth + 1)
+            }
+            NamedWindowExpr::WindowSpec(spec) => {
+                self.resolve_window_spec(spec, named_windows, depth + 1)
+            }
+        }
+    }
+
+    /// Merges a window specification such as `(w ORDER BY a)` with the
+    /// definition of the named window `w` it extends.
+    ///
+    /// Following the SQL standard, the extending window takes the `PARTITION BY`
+    /// of `w`, may only add an `ORDER BY` if `w` has none, and `w` must not
+    /// have a frame clause.
+    fn resolve_window_spec(
+        &self,
+        spec: &WindowSpec,
+        named_windows: &[(&NamedWindowDefinition, String)],
+        depth: usize,
+    ) -> Result<WindowSpec> {
+        let Some(base_ident) = &spec.window_name else {
+            return Ok(spec.clone());
+        };
+        let base = self.resolve_named_window(base_ident, named_windows, depth)?;
+        if !spec.partition_by.is_empty() {
+            return plan_err!(
+                "Cannot override PARTITION BY clause of window {base_ident}"
+            );
+        }
+        if !spec.order_by.is_empty() && !base.order_by.is_empty() {
+            return plan_err!("Cannot override ORDER BY clause of window {base_ident}");
+        }
+        if base.window_frame.is_some() {
+            return plan_err!(
+                "Cannot copy window {base_ident} because it has a frame clause"
+            );
+        }
+        Ok(WindowSpec {
+            window_name: None,
+            partition_by: base.partition_by,
+            order_by: if spec.order_by.is_empty() {
+                base.order_by
+            } else {
+                spec.order_by.clone()
+            },
+            window_frame: spec.window_frame.clone(),
+        })
+    }
 }
 
 // If there are any multiple-defined windows, we raise an error.
@@ -1017,6 +1324,73 @@ fn check_conflicting_windows(window_defs: &[NamedWindowDefinition]) -> Result<()
     Ok(())
 }
 
+/// Expand the unqualified wildcards of `exprs` over `plan`, whose FROM clause
+/// has the `USING` joins of `using_join_columns`, as in the SQL standard: a
+/// joined column is output once, before the other columns, with its value in
+/// the output of the join, e.g. the first non-null of both joined columns for
+/// a full join.
+fn expand_using_join_wildcards(
+    plan: &LogicalPlan,
+    exprs: Vec<SelectExpr>,
+    using_join_columns: &[UsingJoinColumn],
+) -> Result<Vec<SelectExpr>> {
+    // The columns joined with each other, along with their value in the
+    // output of the outermost of their joins. A column joined by an outer join
+    // may be joined by an inner join too, e.g. `a.x` in
+    // `(a JOIN b USING (x)) JOIN c USING (x)`, whose output includes it once.
+    let schema = plan.schema();
+    let mut groups: Vec<(HashSet<&Column>, &Expr, &str)> = vec![];
+    for UsingJoinColumn { left, right, value } in using_join_columns {
+        if !schema.has_column(left) || !schema.has_column(right) {
+            continue;
+        }
+        match groups
+            .iter_mut()
+            .find(|(joined, ..)| joined.contains(left) || joined.contains(right))
+        {
+            Some((joined, ..)) => {
+                joined.insert(left);
+                joined.insert(right);
+            }
+            None => groups.push((HashSet::from([left, right]), value, &left.name)),
+        }
+    }
+    if groups.is_empty() {
+        return Ok(exprs);
+    }
+
+    let mut expanded = vec![];
+    for expr in exprs {
+        if !matches!(expr, SelectExpr::Wildcard(_)) {
+            expanded.push(expr);
+            continue;
+        }
+        // The wildcard outputs one of the columns joined with each other,
+        // unless they are excluded or replaced
+        let mut columns = expand_select_exprs(plan, [(expr, true)])?;
+        let mut join_columns = vec![];
+        for (joined, value, name) in &groups {
+            let len = columns.len();
+            columns.retain(
+                |expr| !matches!(expr, Expr::Column(column) if joined.contains(column)),
+            );
+            if columns.len() < len {
+                join_columns.push(match value {
+                    Expr::Column(_) => (*value).clone(),
+                    _ => (*value).clone().alias(*name),
+                });
+            }
+        }
+        expanded.extend(
+            join_columns
+                .into_iter()
+                .chain(columns)
+                .map(SelectExpr::Expression),
+        );
+    }
+    Ok(expanded)
+}
+
 /// Returns true if the expression recursively contains an `Expr::Unnest` expression
 fn has_unnest_expr_recursively(expr: &Expr) -> bool {
     let mut has_unnest = false;
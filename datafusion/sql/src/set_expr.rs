@@ -16,6 +16,7 @@
 // under the License.
 
 use crate::planner::{ContextProvider, PlannerContext, SqlToRel};
+use crate::utils::check_limit;
 use datafusion_common::{
     not_impl_err, plan_err, DataFusionError, Diagnostic, Result, Span,
 };
@@ -31,7 +32,12 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
     ) -> Result<LogicalPlan> {
         let set_expr_span = Span::try_from_sqlparser_span(set_expr.span());
         match set_expr {
-            SetExpr::Select(s) => self.select_to_plan(*s, None, planner_context),
+            SetExpr::Select(mut s) => {
+                let top = s.top.take();
+                let plan = self.select_to_plan(*s, None, planner_context)?;
+                let limit = self.limit_spec(top, None, None, false, planner_context)?;
+                self.limit(plan, limit)
+            }
             SetExpr::Values(v) => self.sql_values_to_plan(v, planner_context),
             SetExpr::SetOperation {
                 op,
@@ -41,8 +47,12 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
             } => {
                 let left_span = Span::try_from_sqlparser_span(left.span());
                 let right_span = Span::try_from_sqlparser_span(right.span());
+                // The output of a set operation is named after its inputs
+                let unique_output_names = planner_context.set_unique_output_names(true);
                 let left_plan = self.set_expr_to_plan(*left, planner_context);
+                planner_context.set_unique_output_names(true);
                 let right_plan = self.set_expr_to_plan(*right, planner_context);
+                planner_context.set_unique_output_names(unique_output_names);
                 let (left_plan, right_plan) = match (left_plan, right_plan) {
                     (Ok(left_plan), Ok(right_plan)) => (left_plan, right_plan),
                     (Err(left_err), Err(right_err)) => {
@@ -73,6 +83,31 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
         }
     }
 
+    /// Return an error if the set operations at the top of `set_expr` have
+    /// more inputs than [`ParserOptions::max_set_operation_inputs`]. The inputs
+    /// of a parenthesized query are checked when the query is planned.
+    ///
+    /// [`ParserOptions::max_set_operation_inputs`]: crate::planner::ParserOptions::max_set_operation_inputs
+    pub(super) fn check_set_operation_inputs(&self, set_expr: &SetExpr) -> Result<()> {
+        let mut inputs = 0;
+        let mut stack = vec![set_expr];
+        while let Some(set_expr) = stack.pop() {
+            match set_expr {
+                SetExpr::SetOperation { left, right, .. } => {
+                    stack.push(left);
+                    stack.push(right);
+                }
+                _ => inputs += 1,
+            }
+        }
+        check_limit(
+            "max_set_operation_inputs",
+            self.options.max_set_operation_inputs,
+            "Number of set operation inputs",
+            inputs,
+        )
+    }
+
     pub(super) fn is_union_all(set_quantifier: SetQuantifier) -> Result<bool> {
         match set_quantifier {
             SetQuantifier::All | SetQuantifier::AllByName => Ok(true),
@@ -92,25 +127,21 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
         right_plan: &LogicalPlan,
         set_expr_span: Option<Span>,
     ) -> Result<()> {
-        if left_plan.schema().fields().len() == right_plan.schema().fields().len() {
+        let left_columns = left_plan.schema().fields().len();
+        let right_columns = right_plan.schema().fields().len();
+        if left_columns == right_columns {
             return Ok(());
         }
         let diagnostic = Diagnostic::new_error(
             format!("{op} queries have different number of columns"),
             set_expr_span,
         )
-        .with_note(
-            format!("this side has {} fields", left_plan.schema().fields().len()),
-            left_span,
+        .with_note(format!("this side has {left_columns} fields"), left_span)
+        .with_note(format!("this side has {right_columns} fields"), right_span);
+        plan_err!(
+            "{op} queries have different number of columns: left has {left_columns} columns whereas right has {right_columns} columns";
+            diagnostic = diagnostic
         )
-        .with_note(
-            format!(
-                "this side has {} fields",
-                right_plan.schema().fields().len()
-            ),
-            right_span,
-        );
-        plan_err!("{} queries have different number of columns", op; diagnostic =diagnostic)
     }
 
     pub(super) fn set_operation_to_plan(
@@ -15,36 +15,42 @@
 // specific language governing permissions and limitations
 // under the License.
 
-use std::collections::{BTreeMap, HashMap, HashSet};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::path::Path;
 use std::str::FromStr;
 use std::sync::Arc;
 
 use crate::parser::{
     CopyToSource, CopyToStatement, CreateExternalTable, DFParser, ExplainStatement,
-    LexOrdering, Statement as DFStatement,
+    LexOrdering, ScriptParser, ScriptStatement, Statement as DFStatement,
 };
 use crate::planner::{
-    object_name_to_qualifier, ContextProvider, PlannerContext, SqlToRel,
+    object_name_to_qualifier, ContextProvider, PlanWithMetadata, PlannerContext,
+    ScriptPlan, SqlToRel, StatementSource,
 };
-use crate::utils::normalize_ident;
+use crate::utils::{check_limit, normalize_ident};
 
-use arrow::datatypes::{DataType, Fields};
+use arrow::compute::can_cast_types;
+use arrow::datatypes::{DataType, Field, Fields, Schema};
 use datafusion_common::error::_plan_err;
 use datafusion_common::parsers::CompressionTypeVariant;
+use datafusion_common::types::NativeType;
 use datafusion_common::{
-    exec_err, internal_err, not_impl_err, plan_datafusion_err, plan_err, schema_err,
+    exec_err, internal_err, not_impl_err, plan_datafusion_err, plan_err,
     unqualified_field_not_found, Column, Constraint, Constraints, DFSchema, DFSchemaRef,
-    DataFusionError, Result, ScalarValue, SchemaError, SchemaReference, TableReference,
-    ToDFSchema,
+    DataFusionError, Result, ScalarValue, SchemaReference, TableReference, ToDFSchema,
 };
 use datafusion_expr::dml::{CopyTo, InsertOp};
 use datafusion_expr::expr_rewriter::normalize_col_with_schemas_and_ambiguity_check;
 use datafusion_expr::logical_plan::builder::project;
 use datafusion_expr::logical_plan::DdlStatement;
+use datafusion_expr::planner::{
+    RegisteredFunction, ShowStatementArgs, ShowStatementKind,
+};
 use datafusion_expr::utils::expr_to_columns;
 use datafusion_expr::{
-    cast, col, Analyze, CreateCatalog, CreateCatalogSchema,
+    cast, col, lit, Analyze, CreateCatalog, CreateCatalogSchema,
     CreateExternalTable as PlanCreateExternalTable, CreateFunction, CreateFunctionBody,
     CreateIndex as PlanCreateIndex, CreateMemoryTable, CreateView, Deallocate,
     DescribeTable, DmlStatement, DropCatalogSchema, DropFunction, DropTable, DropView,
@@ -55,9 +61,10 @@ use datafusion_expr::{
     Volatility, WriteOp,
 };
 use sqlparser::ast::{
-    self, BeginTransactionKind, IndexColumn, IndexType, NullsDistinctOption, OrderByExpr,
-    OrderByOptions, Set, ShowStatementIn, ShowStatementOptions, SqliteOnConflict,
-    TableObject, UpdateTableFromKind, ValueWithSpan,
+    self, BeginTransactionKind, BinaryOperator, ContextModifier, IndexColumn, IndexType,
+    NullsDistinctOption, OrderByExpr, OrderByOptions, Set, ShowStatementFilterPosition,
+    ShowStatementIn, ShowStatementOptions, SqliteOnConflict, TableObject,
+    UpdateTableFromKind, ValueWithSpan,
 };
 use sqlparser::ast::{
     Assignment, AssignmentTarget, ColumnDef, CreateIndex, CreateTable,
@@ -99,6 +106,45 @@ fn get_schema_name(schema_name: &SchemaName) -> String {
     }
 }
 
+/// Return an error if a column of a `CREATE EXTERNAL TABLE` statement has an
+/// option that the external table would silently ignore
+fn check_external_table_column_options(columns: &[ColumnDef]) -> Result<()> {
+    for column in columns {
+        for ast::ColumnOptionDef { option, .. } in &column.options {
+            match option {
+                ast::ColumnOption::Null
+                | ast::ColumnOption::NotNull
+                | ast::ColumnOption::Default(_)
+                // constraints are validated with the table constraints
+                | ast::ColumnOption::Unique { .. } => {}
+                _ => {
+                    return plan_err!(
+                        "Unsupported option {option} for column {} in CREATE EXTERNAL TABLE, \
+                        supported options are NULL, NOT NULL, DEFAULT, PRIMARY KEY and UNIQUE",
+                        column.name
+                    );
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Convert a partition column name, as written in `PARTITIONED BY`, back
+/// into an [`Ident`] so it is normalized like the other column names
+fn partition_col_ident(name: String) -> Ident {
+    for quote in ['"', '`'] {
+        if let Some(value) = name
+            .strip_prefix(quote)
+            .and_then(|name| name.strip_suffix(quote))
+        {
+            let escaped = format!("{quote}{quote}");
+            return Ident::with_quote(quote, value.replace(&escaped, &quote.to_string()));
+        }
+    }
+    Ident::new(name)
+}
+
 /// Construct `TableConstraint`(s) for the given columns by iterating over
 /// `columns` and extracting individual inline constraint definitions.
 fn calc_inline_constraints_from_columns(columns: &[ColumnDef]) -> Vec<TableConstraint> {
@@ -198,28 +244,301 @@ fn calc_inline_constraints_from_columns(columns: &[ColumnDef]) -> Vec<TableConst
     constraints
 }
 
+/// Project the output of the query of a `CREATE TABLE .. AS` to the declared
+/// `columns` of the table: the query columns are renamed after the declared
+/// columns and cast to their types. The declared nullability is not checked
+/// against the query, which may produce nulls for `NOT NULL` columns.
+fn cast_query_to_columns(plan: LogicalPlan, columns: &DFSchema) -> Result<LogicalPlan> {
+    let input_schema = plan.schema();
+    if columns.fields().len() != input_schema.fields().len() {
+        return plan_err!(
+            "Mismatch: {} columns specified, but result has {} columns",
+            columns.fields().len(),
+            input_schema.fields().len()
+        );
+    }
+    let exprs = columns
+        .fields()
+        .iter()
+        .zip(input_schema.iter())
+        .map(|(field, (qualifier, input_field))| {
+            let expr = Expr::Column(Column::from((qualifier, input_field)));
+            let input_type = input_field.data_type();
+            let expr = if input_type == field.data_type() {
+                expr
+            } else if can_cast_types(input_type, field.data_type()) {
+                cast(expr, field.data_type().clone())
+            } else {
+                return plan_err!(
+                    "Cannot cast column {} of type {input_type} to the type {} of column {}",
+                    input_field.name(),
+                    field.data_type(),
+                    field.name()
+                );
+            };
+            Ok(expr.alias(field.name()))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    project(plan, exprs)
+}
+
+/// A row of `SHOW FUNCTIONS`, with the columns of the plan over the
+/// `information_schema`
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct FunctionRow {
+    function_name: String,
+    return_type: Option<String>,
+    parameters: Vec<Option<String>>,
+    parameter_types: Vec<String>,
+    function_type: &'static str,
+    description: Option<String>,
+    syntax_example: Option<String>,
+}
+
+/// Describe `function` for `SHOW FUNCTIONS`, with a row for each distinct
+/// combination of the example argument types of its signature and the return
+/// type for them, as the `information_schema` does
+fn describe_function(function: &RegisteredFunction) -> Vec<FunctionRow> {
+    let (name, function_type, signature, documentation) = match function {
+        RegisteredFunction::Scalar(udf) => {
+            (udf.name(), "SCALAR", udf.signature(), udf.documentation())
+        }
+        RegisteredFunction::Aggregate(udaf) => (
+            udaf.name(),
+            "AGGREGATE",
+            udaf.signature(),
+            udaf.documentation(),
+        ),
+        RegisteredFunction::Window(udwf) => (
+            udwf.name(),
+            "WINDOW",
+            udwf.signature(),
+            udwf.documentation(),
+        ),
+    };
+    let return_type = |arg_types: &[DataType]| match function {
+        RegisteredFunction::Scalar(udf) => udf.return_type(arg_types).ok(),
+        RegisteredFunction::Aggregate(udaf) => udaf.return_type(arg_types).ok(),
+        RegisteredFunction::Window(_) => None,
+    };
+    let type_name = |data_type: DataType| format!("{:?}", NativeType::from(data_type));
+    let argument_names = documentation
+        .and_then(|documentation| documentation.arguments.as_ref())
+        .map(|arguments| arguments.iter().map(|(name, _)| name.clone()).collect())
+        .unwrap_or_else(Vec::new);
+
+    let mut combinations = signature
+        .type_signature
+        .get_example_types()
+        .into_iter()
+        .map(|arg_types| {
+            let return_type = return_type(&arg_types).map(type_name);
+            let arg_types = arg_types.into_iter().map(type_name).collect::<Vec<_>>();
+            (arg_types, return_type)
+        })
+        .collect::<BTreeSet<_>>();
+    if combinations.is_empty() {
+        combinations.insert((vec![], None));
+    }
+    combinations
+        .into_iter()
+        .map(|(parameter_types, return_type)| FunctionRow {
+            function_name: name.to_string(),
+            return_type,
+            parameters: (0..parameter_types.len())
+                .map(|position| argument_names.get(position).cloned())
+                .collect(),
+            parameter_types,
+            function_type,
+            description: documentation
+                .map(|documentation| documentation.description.clone()),
+            syntax_example: documentation
+                .map(|documentation| documentation.syntax_example.clone()),
+        })
+        .collect()
+}
+
+/// Returns true if planning and running `statement` can not modify any
+/// state: plain queries, `EXPLAIN` without `ANALYZE`, `DESCRIBE` and `SHOW`
+fn is_read_only(statement: &DFStatement) -> bool {
+    match statement {
+        DFStatement::Statement(statement) => is_sql_statement_read_only(statement),
+        DFStatement::Explain(ExplainStatement {
+            analyze, statement, ..
+        }) => !analyze || is_read_only(statement),
+        DFStatement::CreateExternalTable(_) | DFStatement::CopyTo(_) => false,
+    }
+}
+
+fn is_sql_statement_read_only(statement: &Statement) -> bool {
+    match statement {
+        Statement::Query(query) => is_query_read_only(query),
+        Statement::Explain {
+            analyze, statement, ..
+        } => !analyze || is_sql_statement_read_only(statement),
+        Statement::ExplainTable { .. }
+        | Statement::ShowVariable { .. }
+        | Statement::ShowColumns { .. }
+        | Statement::ShowCreate { .. }
+        | Statement::ShowFunctions { .. }
+        | Statement::ShowTables { .. } => true,
+        _ => false,
+    }
+}
+
+/// Returns true if `query` and its CTEs neither create a table with
+/// `SELECT INTO` nor modify data
+fn is_query_read_only(query: &Query) -> bool {
+    query
+        .with
+        .iter()
+        .flat_map(|with| &with.cte_tables)
+        .all(|cte| is_query_read_only(&cte.query))
+        && is_set_expr_read_only(&query.body)
+}
+
+fn is_set_expr_read_only(set_expr: &SetExpr) -> bool {
+    match set_expr {
+        SetExpr::Select(select) => select.into.is_none(),
+        SetExpr::Query(query) => is_query_read_only(query),
+        SetExpr::SetOperation { left, right, .. } => {
+            is_set_expr_read_only(left) && is_set_expr_read_only(right)
+        }
+        SetExpr::Values(_) | SetExpr::Table(_) => true,
+        _ => false,
+    }
+}
+
+/// The pattern of the `LIKE` filter of a `SHOW` statement, returning a not
+/// implemented error with the message `unsupported` for other filters
+fn show_like_pattern(
+    filter_position: Option<ShowStatementFilterPosition>,
+    unsupported: &str,
+) -> Result<Option<String>> {
+    match filter_position {
+        None => Ok(None),
+        Some(
+            ShowStatementFilterPosition::Infix(ShowStatementFilter::Like(pattern))
+            | ShowStatementFilterPosition::Suffix(ShowStatementFilter::Like(pattern)),
+        ) => Ok(Some(pattern)),
+        Some(_) => not_impl_err!("{unsupported}"),
+    }
+}
+
 impl<S: ContextProvider> SqlToRel<'_, S> {
     /// Generate a logical plan from an DataFusion SQL statement
     pub fn statement_to_plan(&self, statement: DFStatement) -> Result<LogicalPlan> {
-        match statement {
+        self.statement_to_plan_with_context(statement, &mut PlannerContext::new())
+    }
+
+    /// Generate a logical plan from a DataFusion SQL statement, along with
+    /// metadata about the statement collected while planning it: its
+    /// placeholders, the tables it references and which of their columns it
+    /// uses, the CTEs it defines and whether it only reads data.
+    ///
+    /// This avoids walking the plan again to reconstruct this information.
+    pub fn statement_to_plan_with_metadata(
+        &self,
+        statement: DFStatement,
+    ) -> Result<PlanWithMetadata> {
+        let read_only = is_read_only(&statement);
+        let mut planner_context = PlannerContext::new().with_metadata_collection();
+        let plan =
+            self.statement_to_plan_with_context(statement, &mut planner_context)?;
+        let metadata = planner_context.take_metadata().unwrap_or_default();
+        Ok(metadata.finish(plan, read_only))
+    }
+
+    /// Generate a logical plan from a DataFusion SQL statement along with its
+    /// metadata, like [`Self::statement_to_plan_with_metadata`], and keep the
+    /// SQL text the statement was parsed from in [`PlanWithMetadata::source`].
+    ///
+    /// A view created by the statement is defined by the text as written,
+    /// including its formatting and comments, rather than by the statement
+    /// displayed again.
+    pub fn statement_to_plan_with_source(
+        &self,
+        statement: DFStatement,
+        source: StatementSource,
+    ) -> Result<PlanWithMetadata> {
+        let mut metadata = self.statement_to_plan_with_metadata(statement)?;
+        if let LogicalPlan::Ddl(DdlStatement::CreateView(create_view)) =
+            &mut metadata.plan
+        {
+            create_view.definition = Some(source.sql.clone());
+        }
+        metadata.source = Some(source);
+        Ok(metadata)
+    }
+
+    fn statement_to_plan_with_context(
+        &self,
+        statement: DFStatement,
+        planner_context: &mut PlannerContext,
+    ) -> Result<LogicalPlan> {
+        let plan = match statement {
             DFStatement::CreateExternalTable(s) => self.external_table_to_plan(s),
-            DFStatement::Statement(s) => self.sql_statement_to_plan(*s),
-            DFStatement::CopyTo(s) => self.copy_to_plan(s),
+            DFStatement::Statement(s) => {
+                self.sql_statement_to_plan_with_context_impl(*s, planner_context)
+            }
+            DFStatement::CopyTo(s) => self.copy_to_plan(s, planner_context),
             DFStatement::Explain(ExplainStatement {
                 verbose,
                 analyze,
                 format,
                 statement,
-            }) => self.explain_to_plan(verbose, analyze, format, *statement),
-        }
+            }) => self.explain_to_plan(
+                verbose,
+                analyze,
+                format,
+                *statement,
+                planner_context,
+            ),
+        }?;
+        self.check_placeholders(&plan)?;
+        Ok(plan)
+    }
+
+    /// Generate a logical plan from a statement of a SQL script parsed by a
+    /// [`ScriptParser`]. Planning errors report where the statement is in the
+    /// script.
+    ///
+    /// Planning the statements of a script one at a time, as they are parsed,
+    /// allows a statement to refer to objects created by running the earlier
+    /// statements, as long as the [`ContextProvider`] observes them.
+    ///
+    /// [`ScriptParser`]: crate::parser::ScriptParser
+    pub fn script_statement_to_plan(
+        &self,
+        statement: ScriptStatement,
+    ) -> Result<ScriptPlan> {
+        let ScriptStatement {
+            statement,
+            span,
+            range,
+        } = statement;
+        let plan = self.statement_to_plan(statement).map_err(|e| {
+            e.context(format!(
+                "Error planning statement at line {}, column {}",
+                span.start.line, span.start.column
+            ))
+        })?;
+        Ok(ScriptPlan { plan, span, range })
+    }
+
+    /// Parse and plan every statement of the SQL `script`
+    ///
+    /// Use [`Self::script_statement_to_plan`] to plan statements lazily
+    /// when later statements depend on the effects of earlier ones.
+    pub fn script_to_plans(&self, script: ScriptParser) -> Result<Vec<ScriptPlan>> {
+        script
+            .map(|statement| self.script_statement_to_plan(statement?))
+            .collect()
     }
 
     /// Generate a logical plan from an SQL statement
     pub fn sql_statement_to_plan(&self, statement: Statement) -> Result<LogicalPlan> {
-        self.sql_statement_to_plan_with_context_impl(
-            statement,
-            &mut PlannerContext::new(),
-        )
+        self.sql_statement_to_plan_with_context(statement, &mut PlannerContext::new())
     }
 
     /// Generate a logical plan from an SQL statement
@@ -228,7 +547,26 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
         statement: Statement,
         planner_context: &mut PlannerContext,
     ) -> Result<LogicalPlan> {
-        self.sql_statement_to_plan_with_context_impl(statement, planner_context)
+        let plan =
+            self.sql_statement_to_plan_with_context_impl(statement, planner_context)?;
+        self.check_placeholders(&plan)?;
+        Ok(plan)
+    }
+
+    /// Return an error if `plan` has more distinct placeholders than
+    /// [`ParserOptions::max_placeholders`]
+    ///
+    /// [`ParserOptions::max_placeholders`]: crate::planner::ParserOptions::max_placeholders
+    fn check_placeholders(&self, plan: &LogicalPlan) -> Result<()> {
+        if self.options.max_placeholders == 0 {
+            return Ok(());
+        }
+        check_limit(
+            "max_placeholders",
+            self.options.max_placeholders,
+            "Number of placeholders",
+            plan.get_parameter_names()?.len(),
+        )
     }
 
     fn sql_statement_to_plan_with_context_impl(
@@ -241,7 +579,19 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                 describe_alias: DescribeAlias::Describe | DescribeAlias::Desc, // only parse 'DESCRIBE table_name' or 'DESC table_name' and not 'EXPLAIN table_name'
                 table_name,
                 ..
-            } => self.describe_table_to_plan(table_name),
+            } => {
+                let args = ShowStatementArgs {
+                    table: Some(self.object_name_to_table_reference(table_name.clone())?),
+                    ..Default::default()
+                };
+                match self
+                    .context_provider
+                    .plan_show_statement(ShowStatementKind::Describe, &args)?
+                {
+                    Some(plan) => Ok(plan),
+                    None => self.describe_table_to_plan(table_name),
+                }
+            }
             Statement::Explain {
                 verbose,
                 statement,
@@ -252,7 +602,7 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
             } => {
                 let format = format.map(|format| format.to_string());
                 let statement = DFStatement::Statement(statement);
-                self.explain_to_plan(verbose, analyze, format, statement)
+                self.explain_to_plan(verbose, analyze, format, statement, planner_context)
             }
             Statement::Query(query) => self.query_to_plan(*query, planner_context),
             Statement::ShowVariable { variable } => self.show_variable_to_plan(&variable),
@@ -354,8 +704,13 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                 if comment.is_some() {
                     return not_impl_err!("Comment not supported")?;
                 }
-                if on_commit.is_some() {
-                    return not_impl_err!("On commit not supported")?;
+                if let Some(on_commit) = on_commit {
+                    let on_commit = match on_commit {
+                        ast::OnCommit::PreserveRows => "PRESERVE ROWS",
+                        ast::OnCommit::DeleteRows => "DELETE ROWS",
+                        ast::OnCommit::Drop => "DROP",
+                    };
+                    return not_impl_err!("ON COMMIT {on_commit} not supported")?;
                 }
                 if on_cluster.is_some() {
                     return not_impl_err!("On cluster not supported")?;
@@ -439,40 +794,20 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
 
                 let has_columns = !columns.is_empty();
                 let schema = self.build_schema(columns)?.to_dfschema_ref()?;
-                if has_columns {
+                // The declared columns type the rows of a `VALUES` query. Other
+                // queries are cast to the declared columns once planned.
+                let is_values_query = query
+                    .as_ref()
+                    .is_some_and(|query| matches!(*query.body, SetExpr::Values(_)));
+                if has_columns && is_values_query {
                     planner_context.set_table_schema(Some(Arc::clone(&schema)));
                 }
 
                 match query {
                     Some(query) => {
                         let plan = self.query_to_plan(*query, planner_context)?;
-                        let input_schema = plan.schema();
-
                         let plan = if has_columns {
-                            if schema.fields().len() != input_schema.fields().len() {
-                                return plan_err!(
-                                    "Mismatch: {} columns specified, but result has {} columns",
-                                    schema.fields().len(),
-                                    input_schema.fields().len()
-                                );
-                            }
-                            let input_fields = input_schema.fields();
-                            let project_exprs = schema
-                                .fields()
-                                .iter()
-                                .zip(input_fields)
-                                .map(|(field, input_field)| {
-                                    cast(
-                                        col(input_field.name()),
-                                        field.data_type().clone(),
-                                    )
-                                    .alias(field.name())
-                                })
-                                .collect::<Vec<_>>();
-
-                            LogicalPlanBuilder::from(plan.clone())
-                                .project(project_exprs)?
-                                .build()?
+                            cast_query_to_columns(plan, &schema)?
                         } else {
                             plan
                         };
@@ -599,7 +934,8 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                     })
                     .collect::<Result<Vec<_>>>()?;
 
-                let mut plan = self.query_to_plan(*query, &mut PlannerContext::new())?;
+                let mut plan =
+                    self.query_to_plan(*query, &mut planner_context.new_nested())?;
                 plan = self.apply_expr_alias(plan, columns)?;
 
                 Ok(LogicalPlan::Ddl(DdlStatement::CreateView(CreateView {
@@ -706,7 +1042,8 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                     .collect::<Result<_>>()?;
 
                 // Create planner context with parameters
-                let mut planner_context = PlannerContext::new()
+                let mut planner_context = planner_context
+                    .new_nested()
                     .with_prepare_param_data_types(data_types.clone());
 
                 // Build logical plan for inner statement of the prepare statement
@@ -769,14 +1106,28 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                         "Execute statement with DEFAULT is not supported"
                     );
                 }
+                let name = object_name_to_string(&name.unwrap());
                 let empty_schema = DFSchema::empty();
                 let parameters = parameters
                     .into_iter()
                     .map(|expr| self.sql_to_expr(expr, &empty_schema, planner_context))
                     .collect::<Result<Vec<Expr>>>()?;
+                let parameters = match self
+                    .context_provider
+                    .get_prepared_statement_data_types(&name)
+                {
+                    Some(data_types) if !data_types.is_empty() => self
+                        .cast_execute_parameters(
+                            &name,
+                            parameters,
+                            &data_types,
+                            &empty_schema,
+                        )?,
+                    _ => parameters,
+                };
 
                 Ok(LogicalPlan::Statement(PlanStatement::Execute(Execute {
-                    name: object_name_to_string(&name.unwrap()),
+                    name,
                     parameters,
                 })))
             }
@@ -834,8 +1185,19 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                 if limit_from.is_some() {
                     return not_impl_err!("SHOW TABLES LIMIT FROM not supported")?;
                 }
-                if filter_position.is_some() {
-                    return not_impl_err!("SHOW TABLES FILTER not supported")?;
+                let unsupported_filter = "SHOW TABLES FILTER not supported";
+                let args = ShowStatementArgs {
+                    pattern: show_like_pattern(filter_position, unsupported_filter)?,
+                    ..Default::default()
+                };
+                if let Some(plan) = self
+                    .context_provider
+                    .plan_show_statement(ShowStatementKind::Tables, &args)?
+                {
+                    return Ok(plan);
+                }
+                if args.pattern.is_some() {
+                    return not_impl_err!("{unsupported_filter}");
                 }
                 self.show_tables_to_plan()
             }
@@ -861,11 +1223,9 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                 if limit_from.is_some() {
                     return not_impl_err!("SHOW COLUMNS LIMIT FROM not supported")?;
                 }
-                if filter_position.is_some() {
-                    return not_impl_err!(
-                        "SHOW COLUMNS with WHERE or LIKE is not supported"
-                    )?;
-                }
+                let unsupported_filter =
+                    "SHOW COLUMNS with WHERE or LIKE is not supported";
+                let pattern = show_like_pattern(filter_position, unsupported_filter)?;
                 let Some(ShowStatementIn {
                     // specifies if the syntax was `SHOW COLUMNS IN` or `SHOW
                     // COLUMNS FROM` which is not different in DataFusion
@@ -884,6 +1244,20 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                     return plan_err!("SHOW COLUMNS requires a table name");
                 };
 
+                let args = ShowStatementArgs {
+                    table: Some(self.object_name_to_table_reference(table_name.clone())?),
+                    pattern,
+                    full: full || extended,
+                };
+                if let Some(plan) = self
+                    .context_provider
+                    .plan_show_statement(ShowStatementKind::Columns, &args)?
+                {
+                    return Ok(plan);
+                }
+                if args.pattern.is_some() {
+                    return not_impl_err!("{unsupported_filter}");
+                }
                 self.show_columns_to_plan(extended, full, table_name)
             }
 
@@ -924,9 +1298,6 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                         _ => plan_err!("Inserts with {or} clause is not supported")?,
                     }
                 }
-                if partitioned.is_some() {
-                    plan_err!("Partitioned inserts not yet supported")?;
-                }
                 if !after_columns.is_empty() {
                     plan_err!("After-columns clause not supported")?;
                 }
@@ -967,7 +1338,15 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                 // optional keywords don't change behavior
                 let _ = into;
                 let _ = has_table_keyword;
-                self.insert_to_plan(table_name, columns, source, overwrite, replace_into)
+                self.insert_to_plan(
+                    table_name,
+                    columns,
+                    partitioned,
+                    source,
+                    overwrite,
+                    replace_into,
+                    planner_context,
+                )
             }
             Statement::Update {
                 table,
@@ -993,7 +1372,13 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                 if or.is_some() {
                     plan_err!("ON conflict not supported")?;
                 }
-                self.update_to_plan(table, assignments, update_from, selection)
+                self.update_to_plan(
+                    table,
+                    assignments,
+                    update_from,
+                    selection,
+                    planner_context,
+                )
             }
 
             Statement::Delete(Delete {
@@ -1026,7 +1411,7 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                 }
 
                 let table_name = self.get_delete_target(from)?;
-                self.delete_to_plan(table_name, selection)
+                self.delete_to_plan(table_name, selection, planner_context)
             }
 
             Statement::StartTransaction {
@@ -1186,11 +1571,23 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                 //
                 // Convert resulting expression to data fusion expression
                 //
+                // The body refers to the named arguments as columns, and to all
+                // arguments by position as placeholders `$1`, `$2`, ...
                 let arg_types = args.as_ref().map(|arg| {
                     arg.iter().map(|t| t.data_type.clone()).collect::<Vec<_>>()
                 });
                 let mut planner_context = PlannerContext::new()
                     .with_prepare_param_data_types(arg_types.unwrap_or_default());
+                let arg_fields = args
+                    .iter()
+                    .flatten()
+                    .filter_map(|arg| {
+                        let name = self.ident_normalizer.normalize(arg.name.clone()?);
+                        Some(Field::new(name, arg.data_type.clone(), true))
+                    })
+                    .collect::<Fields>();
+                let body_schema =
+                    DFSchema::from_unqualified_fields(arg_fields, HashMap::new())?;
 
                 let function_body = match function_body {
                     Some(r) => Some(self.sql_to_expr(
@@ -1210,7 +1607,7 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                                 )?
                             }
                         },
-                        &DFSchema::empty(),
+                        &body_schema,
                         &mut planner_context,
                     )?),
                     None => None,
@@ -1243,24 +1640,38 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                 func_desc,
                 ..
             } => {
-                // According to postgresql documentation it can be only one function
-                // specified in drop statement
-                if let Some(desc) = func_desc.first() {
-                    // At the moment functions can't be qualified `schema.name`
-                    let name = match &desc.name.0[..] {
-                        [] => exec_err!("Function should have name")?,
-                        [n] => n.as_ident().unwrap().value.clone(),
-                        [..] => not_impl_err!("Qualified functions are not supported")?,
-                    };
-                    let statement = DdlStatement::DropFunction(DropFunction {
-                        if_exists,
-                        name,
-                        schema: DFSchemaRef::new(DFSchema::empty()),
-                    });
-                    Ok(LogicalPlan::Ddl(statement))
-                } else {
-                    exec_err!("Function name not provided")
-                }
+                let desc = match &func_desc[..] {
+                    [] => return exec_err!("Function name not provided"),
+                    [desc] => desc,
+                    [..] => {
+                        return not_impl_err!(
+                            "Dropping more than one function in a statement is not supported"
+                        );
+                    }
+                };
+                // At the moment functions can't be qualified `schema.name`
+                let name = match &desc.name.0[..] {
+                    [] => exec_err!("Function should have name")?,
+                    [n] => n.as_ident().unwrap().value.clone(),
+                    [..] => not_impl_err!("Qualified functions are not supported")?,
+                };
+                // The argument types tell apart functions of the same name
+                let args = desc
+                    .args
+                    .as_ref()
+                    .map(|args| {
+                        args.iter()
+                            .map(|arg| self.convert_data_type(&arg.data_type))
+                            .collect::<Result<Vec<_>>>()
+                    })
+                    .transpose()?;
+                let statement = DdlStatement::DropFunction(DropFunction {
+                    if_exists,
+                    name,
+                    args,
+                    schema: DFSchemaRef::new(DFSchema::empty()),
+                });
+                Ok(LogicalPlan::Ddl(statement))
             }
             Statement::CreateIndex(CreateIndex {
                 name,
@@ -1335,6 +1746,42 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
         Ok(name)
     }
 
+    /// Cast the `parameters` of an `EXECUTE` of the prepared statement `name`
+    /// to the `data_types` declared by its `PREPARE`
+    fn cast_execute_parameters(
+        &self,
+        name: &str,
+        parameters: Vec<Expr>,
+        data_types: &[DataType],
+        schema: &DFSchema,
+    ) -> Result<Vec<Expr>> {
+        if parameters.len() != data_types.len() {
+            return plan_err!(
+                "Prepared statement '{name}' expects {} parameters, but {} provided",
+                data_types.len(),
+                parameters.len()
+            );
+        }
+        parameters
+            .into_iter()
+            .zip(data_types)
+            .enumerate()
+            .map(|(i, (parameter, data_type))| {
+                let parameter_type = parameter.get_type(schema)?;
+                if parameter_type == *data_type {
+                    return Ok(parameter);
+                }
+                if !can_cast_types(&parameter_type, data_type) {
+                    return plan_err!(
+                        "Parameter ${} of prepared statement '{name}' has type {parameter_type} which can not be cast to the declared type {data_type}",
+                        i + 1
+                    );
+                }
+                parameter.cast_to(data_type, schema)
+            })
+            .collect()
+    }
+
     /// Generate a logical plan from a "SHOW TABLES" query
     fn show_tables_to_plan(&self) -> Result<LogicalPlan> {
         if self.has_table("information_schema", "tables") {
@@ -1362,7 +1809,11 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
         }))
     }
 
-    fn copy_to_plan(&self, statement: CopyToStatement) -> Result<LogicalPlan> {
+    fn copy_to_plan(
+        &self,
+        statement: CopyToStatement,
+        planner_context: &PlannerContext,
+    ) -> Result<LogicalPlan> {
         // Determine if source is table or query and handle accordingly
         let copy_source = statement.source;
         let (input, input_schema, table_ref) = match copy_source {
@@ -1371,13 +1822,15 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                 let table_ref = self.object_name_to_table_reference(object_name)?;
                 let table_source =
                     self.context_provider.get_table_source(table_ref.clone())?;
+                planner_context.record_table(&table_ref, None);
                 let plan =
                     LogicalPlanBuilder::scan(table_name, table_source, None)?.build()?;
                 let input_schema = Arc::clone(plan.schema());
                 (plan, input_schema, Some(table_ref))
             }
             CopyToSource::Query(query) => {
-                let plan = self.query_to_plan(*query, &mut PlannerContext::new())?;
+                let plan =
+                    self.query_to_plan(*query, &mut planner_context.new_nested())?;
                 let input_schema = Arc::clone(plan.schema());
                 (plan, input_schema, None)
             }
@@ -1445,25 +1898,23 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                         .map(|order_by_expr| {
                             let ordered_expr = &order_by_expr.expr;
                             let ordered_expr = ordered_expr.to_owned();
-                            let ordered_expr = self
-                                .sql_expr_to_logical_expr(
-                                    ordered_expr,
-                                    schema,
-                                    planner_context,
-                                )
-                                .unwrap();
+                            let ordered_expr = self.sql_expr_to_logical_expr(
+                                ordered_expr,
+                                schema,
+                                planner_context,
+                            )?;
                             let asc = order_by_expr.options.asc.unwrap_or(true);
                             let nulls_first =
                                 order_by_expr.options.nulls_first.unwrap_or_else(|| {
                                     self.options.default_null_ordering.nulls_first(asc)
                                 });
 
-                            SortExpr::new(ordered_expr, asc, nulls_first)
+                            Ok(SortExpr::new(ordered_expr, asc, nulls_first))
                         })
-                        .collect::<Vec<SortExpr>>();
+                        .collect::<Result<Vec<SortExpr>>>();
                     result
                 })
-                .collect::<Vec<Vec<SortExpr>>>();
+                .collect::<Result<Vec<Vec<SortExpr>>>>()?;
 
             return Ok(results);
         }
@@ -1531,15 +1982,31 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
 
         let mut planner_context = PlannerContext::new();
 
+        check_external_table_column_options(&columns)?;
         let column_defaults = self
             .build_column_defaults(&columns, &mut planner_context)?
             .into_iter()
             .collect();
 
+        // Partition columns declared with a type are part of `columns`, so the
+        // schema contains both the file columns and the partition columns
         let schema = self.build_schema(columns)?;
         let df_schema = schema.to_dfschema_ref()?;
         df_schema.check_names()?;
 
+        let table_partition_cols = table_partition_cols
+            .into_iter()
+            .map(|name| {
+                let name = self.ident_normalizer.normalize(partition_col_ident(name));
+                if !df_schema.fields().is_empty()
+                    && df_schema.field_with_unqualified_name(&name).is_err()
+                {
+                    return plan_err!("Partition column {name} is not in schema");
+                }
+                Ok(name)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
         let ordered_exprs =
             self.build_order_by(order_exprs, &df_schema, &mut planner_context)?;
 
@@ -1685,8 +2152,12 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
         analyze: bool,
         format: Option<String>,
         statement: DFStatement,
+        planner_context: &PlannerContext,
     ) -> Result<LogicalPlan> {
-        let plan = self.statement_to_plan(statement)?;
+        let plan = self.statement_to_plan_with_context(
+            statement,
+            &mut planner_context.new_nested(),
+        )?;
         if matches!(plan, LogicalPlan::Explain(_)) {
             return plan_err!("Nested EXPLAINs are not supported");
         }
@@ -1790,8 +2261,15 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                 variable,
                 values,
             } => {
-                if scope.is_some() {
-                    return not_impl_err!("SET with scope modifiers is not supported");
+                match scope {
+                    None | Some(ContextModifier::Session) => {}
+                    // There are no transactions to scope the value to
+                    Some(ContextModifier::Local) => {
+                        return not_impl_err!("SET LOCAL is not supported");
+                    }
+                    Some(ContextModifier::Global) => {
+                        return not_impl_err!("SET GLOBAL is not supported");
+                    }
                 }
 
                 if hivevar {
@@ -1805,34 +2283,68 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                     variable_lower = "datafusion.execution.time_zone".to_string();
                 }
 
-                if values.len() != 1 {
-                    return plan_err!("SET only supports single value assignment");
+                self.set_variable_to_plan(variable_lower, &values)
+            }
+            Set::SetTimeZone { local, mut value } => {
+                // `SET TIME ZONE LOCAL` sets the default time zone, like `DEFAULT`
+                if matches!(&value, SQLExpr::Identifier(ident)
+                    if ident.quote_style.is_none() && ident.value.eq_ignore_ascii_case("local"))
+                {
+                    value = SQLExpr::Identifier(Ident::new("DEFAULT"));
+                }
+                if local {
+                    return not_impl_err!("SET LOCAL is not supported");
+                }
+                self.set_variable_to_plan(
+                    "datafusion.execution.time_zone".to_string(),
+                    &[value],
+                )
+            }
+            other => not_impl_err!("SET variant not implemented yet: {other:?}"),
+        }
+    }
+
+    fn set_variable_to_plan(
+        &self,
+        variable: String,
+        values: &[SQLExpr],
+    ) -> Result<LogicalPlan> {
+        let values = match values {
+            [] => return plan_err!("SET requires a value for '{variable}'"),
+            // The `ON` and `OFF` keywords are normalized to the value they
+            // stand for, and `DEFAULT` to no value
+            [SQLExpr::Identifier(ident)] if ident.quote_style.is_none() => {
+                match ident.value.to_lowercase().as_str() {
+                    "on" => vec!["true".to_string()],
+                    "off" => vec!["false".to_string()],
+                    "default" => vec![],
+                    _ => vec![ident_to_string(ident)],
                 }
+            }
+            values => values
+                .iter()
+                .map(Self::set_value_to_string)
+                .collect::<Result<Vec<_>>>()?,
+        };
 
-                let value_string = match &values[0] {
-                    SQLExpr::Identifier(i) => ident_to_string(i),
-                    SQLExpr::Value(v) => match crate::utils::value_to_string(&v.value) {
-                        None => {
-                            return plan_err!("Unsupported value {:?}", v.value);
-                        }
-                        Some(s) => s,
-                    },
-                    SQLExpr::UnaryOp { op, expr } => match op {
-                        UnaryOperator::Plus => format!("+{expr}"),
-                        UnaryOperator::Minus => format!("-{expr}"),
-                        _ => return plan_err!("Unsupported unary op {:?}", op),
-                    },
-                    _ => return plan_err!("Unsupported expr {:?}", values[0]),
-                };
+        Ok(LogicalPlan::Statement(PlanStatement::SetVariable(
+            SetVariable { variable, values },
+        )))
+    }
 
-                Ok(LogicalPlan::Statement(PlanStatement::SetVariable(
-                    SetVariable {
-                        variable: variable_lower,
-                        value: value_string,
-                    },
-                )))
-            }
-            other => not_impl_err!("SET variant not implemented yet: {other:?}"),
+    fn set_value_to_string(value: &SQLExpr) -> Result<String> {
+        match value {
+            SQLExpr::Identifier(i) => Ok(ident_to_string(i)),
+            SQLExpr::Value(v) => match crate::utils::value_to_string(&v.value) {
+                None => plan_err!("Unsupported value {:?}", v.value),
+                Some(s) => Ok(s),
+            },
+            SQLExpr::UnaryOp { op, expr } => match op {
+                UnaryOperator::Plus => Ok(format!("+{expr}")),
+                UnaryOperator::Minus => Ok(format!("-{expr}")),
+                _ => plan_err!("Unsupported unary op {:?}", op),
+            },
+            _ => plan_err!("Unsupported expr {:?}", value),
         }
     }
 
@@ -1840,6 +2352,7 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
         &self,
         table_name: ObjectName,
         predicate_expr: Option<SQLExpr>,
+        planner_context: &PlannerContext,
     ) -> Result<LogicalPlan> {
         // Do a table lookup to verify the table exists
         let table_ref = self.object_name_to_table_reference(table_name.clone())?;
@@ -1851,7 +2364,8 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
         let scan =
             LogicalPlanBuilder::scan(table_ref.clone(), Arc::clone(&table_source), None)?
                 .build()?;
-        let mut planner_context = PlannerContext::new();
+        let mut planner_context = planner_context.new_nested();
+        planner_context.record_table(&table_ref, Some(table_ref.clone()));
 
         let source = match predicate_expr {
             None => scan,
@@ -1885,6 +2399,7 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
         assignments: Vec<Assignment>,
         from: Option<TableWithJoins>,
         predicate_expr: Option<SQLExpr>,
+        planner_context: &PlannerContext,
     ) -> Result<LogicalPlan> {
         let (table_name, table_alias) = match &table.relation {
             TableFactor::Table { name, alias, .. } => (name.clone(), alias.clone()),
@@ -1900,7 +2415,7 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
         )?);
 
         // Overwrite with assignment expressions
-        let mut planner_context = PlannerContext::new();
+        let mut planner_context = planner_context.new_nested();
         let mut assign_map = assignments
             .iter()
             .map(|assign| {
@@ -1917,6 +2432,7 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                     .unwrap();
                 // Validate that the assignment target column exists
                 table_schema.field_with_unqualified_name(&col_name.value)?;
+                planner_context.record_table_column(&table_name, &col_name.value);
                 Ok((col_name.value.clone(), assign.value.clone()))
             })
             .collect::<Result<HashMap<String, SQLExpr>>>()?;
@@ -1964,6 +2480,7 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                                 .take()
                                 .or_else(|| Some(field.data_type().clone()));
                         }
+                        planner_context.record_placeholders(&expr);
                         // Cast to target column type, if necessary
                         expr.cast_to(field.data_type(), source.schema())?
                     }
@@ -1998,15 +2515,64 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
         &self,
         table_name: ObjectName,
         columns: Vec<Ident>,
+        partitioned: Option<Vec<SQLExpr>>,
         source: Box<Query>,
         overwrite: bool,
         replace_into: bool,
+        planner_context: &PlannerContext,
     ) -> Result<LogicalPlan> {
         // Do a table lookup to verify the table exists
         let table_name = self.object_name_to_table_reference(table_name)?;
         let table_source = self.context_provider.get_table_source(table_name.clone())?;
         let table_schema = DFSchema::try_from(table_source.schema())?;
 
+        let mut columns = columns
+            .into_iter()
+            .map(|c| self.ident_normalizer.normalize(c))
+            .collect::<Vec<_>>();
+
+        // With a `PARTITION` clause, the source provides the listed (or all
+        // non-partition) columns followed by the dynamic partition columns.
+        // The values of the static partition columns are appended to it.
+        let mut dynamic_partition_columns = vec![];
+        let mut static_partition_values = vec![];
+        if let Some(partitioned) = partitioned {
+            let partition_spec = self.insert_partition_spec(
+                partitioned,
+                &table_name,
+                &table_source.partition_columns(),
+                &table_schema,
+            )?;
+            let is_partition_column =
+                |c: &str| partition_spec.iter().any(|(name, _)| name == c);
+            if let Some(c) = columns.iter().find(|c| is_partition_column(c)) {
+                return plan_err!(
+                    "Partition column {c} can not be listed in the INSERT columns as it is assigned in the PARTITION clause"
+                );
+            }
+            if columns.is_empty() {
+                columns = table_schema
+                    .fields()
+                    .iter()
+                    .map(|f| f.name().clone())
+                    .filter(|c| !is_partition_column(c))
+                    .collect();
+            }
+            let (dynamic_partition_spec, static_partition_spec): (Vec<_>, Vec<_>) =
+                partition_spec
+                    .into_iter()
+                    .partition(|(_, value)| value.is_none());
+            dynamic_partition_columns = dynamic_partition_spec
+                .into_iter()
+                .map(|(name, _)| name)
+                .collect();
+            columns.extend(dynamic_partition_columns.iter().cloned());
+            for (name, value) in static_partition_spec {
+                columns.push(name.clone());
+                static_partition_values.extend(value.map(|value| value.alias(name)));
+            }
+        }
+
         // Get insert fields and target table's value indices
         //
         // If value_indices[i] = Some(j), it means that the value of the i-th target table's column is
@@ -2014,7 +2580,7 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
         //
         // If value_indices[i] = None, it means that the value of the i-th target table's column is
         // not provided, and should be filled with a default value later.
-        let (fields, value_indices) = if columns.is_empty() {
+        let (fields, mut value_indices) = if columns.is_empty() {
             // Empty means we're inserting into all columns of the table
             (
                 table_schema.fields().clone(),
@@ -2026,7 +2592,6 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
             let mut value_indices = vec![None; table_schema.fields().len()];
             let fields = columns
                 .into_iter()
-                .map(|c| self.ident_normalizer.normalize(c))
                 .enumerate()
                 .map(|(i, c)| {
                     let column_index = table_schema
@@ -2034,9 +2599,9 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                         .ok_or_else(|| unqualified_field_not_found(&c, &table_schema))?;
 
                     if value_indices[column_index].is_some() {
-                        return schema_err!(SchemaError::DuplicateUnqualifiedField {
-                            name: c,
-                        });
+                        return plan_err!(
+                            "Column {c} is specified more than once in INSERT"
+                        );
                     } else {
                         value_indices[column_index] = Some(i);
                     }
@@ -2045,6 +2610,9 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                 .collect::<Result<Vec<_>>>()?;
             (Fields::from(fields), value_indices)
         };
+        // The fields provided by the source, without the static partition values
+        let fields =
+            Fields::from(fields[..fields.len() - static_partition_values.len()].to_vec());
 
         // infer types for Values clause... other types should be resolvable the regular way
         let mut prepare_param_data_types = BTreeMap::new();
@@ -2075,16 +2643,68 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
         let prepare_param_data_types = prepare_param_data_types.into_values().collect();
 
         // Projection
-        let mut planner_context =
-            PlannerContext::new().with_prepare_param_data_types(prepare_param_data_types);
+        planner_context.record_table(&table_name, None);
+        for field in fields.iter() {
+            planner_context.record_table_column(&table_name, field.name());
+        }
+        let mut planner_context = planner_context
+            .new_nested()
+            .with_prepare_param_data_types(prepare_param_data_types);
         planner_context.set_table_schema(Some(DFSchemaRef::new(
             DFSchema::from_unqualified_fields(fields.clone(), Default::default())?,
         )));
         let source = self.query_to_plan(*source, &mut planner_context)?;
-        if fields.len() != source.schema().fields().len() {
-            plan_err!("Column count doesn't match insert query!")?;
+        let source_len = source.schema().fields().len();
+        match fields.len().cmp(&source_len) {
+            Ordering::Greater => plan_err!(
+                "INSERT has more target columns ({}) than expressions ({source_len})",
+                fields.len()
+            )?,
+            Ordering::Less => plan_err!(
+                "INSERT has more expressions ({source_len}) than target columns ({})",
+                fields.len()
+            )?,
+            Ordering::Equal => {}
         }
 
+        // Dynamic partition columns are matched by name to the tail of the source
+        let dynamic_partition_start = source_len - dynamic_partition_columns.len();
+        let dynamic_partition_fields =
+            &source.schema().fields()[dynamic_partition_start..];
+        for name in &dynamic_partition_columns {
+            let Some(position) = dynamic_partition_fields
+                .iter()
+                .position(|field| field.name() == name)
+            else {
+                return plan_err!(
+                    "Dynamic partition column {name} not found in the last {} columns of the INSERT source: {}",
+                    dynamic_partition_fields.len(),
+                    dynamic_partition_fields
+                        .iter()
+                        .map(|field| field.name().as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                );
+            };
+            let column_index = table_schema
+                .index_of_column_by_name(None, name)
+                .ok_or_else(|| unqualified_field_not_found(name, &table_schema))?;
+            value_indices[column_index] = Some(dynamic_partition_start + position);
+        }
+        // Static partition values follow the columns of the source
+        let source = if static_partition_values.is_empty() {
+            source
+        } else {
+            let exprs = source
+                .schema()
+                .columns()
+                .into_iter()
+                .map(Expr::Column)
+                .chain(static_partition_values)
+                .collect::<Vec<_>>();
+            project(source, exprs)?
+        };
+
         let exprs = value_indices
             .into_iter()
             .enumerate()
@@ -2126,6 +2746,87 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
         Ok(plan)
     }
 
+    /// Plans the `PARTITION` clause of an insert into the table `table_name`
+    /// partitioned by `partition_columns`, e.g. `PARTITION (dt = '2024-01-01', region)`
+    ///
+    /// Returns the columns in the order of the clause, with the values of
+    /// static partition columns. Dynamic partition columns have no value.
+    fn insert_partition_spec(
+        &self,
+        partitioned: Vec<SQLExpr>,
+        table_name: &TableReference,
+        partition_columns: &[&str],
+        table_schema: &DFSchema,
+    ) -> Result<Vec<(String, Option<Expr>)>> {
+        if partition_columns.is_empty() {
+            return plan_err!("Table {table_name} is not partitioned");
+        }
+        let mut partition_spec: Vec<(String, Option<Expr>)> = vec![];
+        for expr in partitioned {
+            let (ident, value) = match expr {
+                SQLExpr::Identifier(ident) => (ident, None),
+                SQLExpr::BinaryOp {
+                    left,
+                    op: BinaryOperator::Eq,
+                    right,
+                } => match *left {
+                    SQLExpr::Identifier(ident) => (ident, Some(*right)),
+                    left => {
+                        return plan_err!(
+                            "Unsupported partition specification: {left} = {right}"
+                        )
+                    }
+                },
+                _ => return plan_err!("Unsupported partition specification: {expr}"),
+            };
+            let name = self.ident_normalizer.normalize(ident);
+            if !partition_columns.contains(&name.as_str()) {
+                return plan_err!(
+                    "Column {name} is not a partition column of table {table_name}, partition columns are: {}",
+                    partition_columns.join(", ")
+                );
+            }
+            if partition_spec.iter().any(|(n, _)| *n == name) {
+                return plan_err!(
+                    "Partition column {name} is specified more than once in PARTITION"
+                );
+            }
+            let value = value
+                .map(|value| {
+                    let empty_schema = DFSchema::empty();
+                    let expr = self.sql_to_expr(
+                        value,
+                        &empty_schema,
+                        &mut PlannerContext::new(),
+                    )?;
+                    let value_type = expr.get_type(&empty_schema)?;
+                    let target_type =
+                        table_schema.field_with_unqualified_name(&name)?.data_type();
+                    if !can_cast_types(&value_type, target_type) {
+                        return plan_err!(
+                            "Partition value {expr} of type {value_type} is incompatible with partition column {name} of type {target_type}"
+                        );
+                    }
+                    Ok(expr)
+                })
+                .transpose()?;
+            partition_spec.push((name, value));
+        }
+
+        let missing = partition_columns
+            .iter()
+            .filter(|c| !partition_spec.iter().any(|(name, _)| name == *c))
+            .copied()
+            .collect::<Vec<_>>();
+        if !missing.is_empty() {
+            return plan_err!(
+                "PARTITION of table {table_name} is missing partition columns: {}",
+                missing.join(", ")
+            );
+        }
+        Ok(partition_spec)
+    }
+
     fn show_columns_to_plan(
         &self,
         extended: bool,
@@ -2178,6 +2879,10 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
         &self,
         filter: Option<ShowStatementFilter>,
     ) -> Result<LogicalPlan> {
+        if !self.has_table("information_schema", "routines") {
+            return self.show_registered_functions_to_plan(filter);
+        }
+
         let where_clause = if let Some(filter) = filter {
             match filter {
                 ShowStatementFilter::Like(like) => {
@@ -2248,6 +2953,86 @@ ON p.function_name = r.routine_name
         self.statement_to_plan(rewrite.pop_front().unwrap()) // length of rewrite is 1
     }
 
+    /// Plan `SHOW FUNCTIONS` over the functions enumerated by
+    /// [`ContextProvider::registered_functions`], for providers without an
+    /// `information_schema`
+    fn show_registered_functions_to_plan(
+        &self,
+        filter: Option<ShowStatementFilter>,
+    ) -> Result<LogicalPlan> {
+        let predicate = match filter {
+            None => None,
+            Some(ShowStatementFilter::Like(pattern)) => {
+                Some(col("function_name").like(lit(pattern)))
+            }
+            Some(_) => return plan_err!("Unsupported SHOW FUNCTIONS filter"),
+        };
+
+        let list_type = DataType::new_list(DataType::Utf8, true);
+        let schema = Arc::new(DFSchema::try_from(Schema::new(vec![
+            Field::new("function_name", DataType::Utf8, false),
+            Field::new("return_type", DataType::Utf8, true),
+            Field::new("parameters", list_type.clone(), true),
+            Field::new("parameter_types", list_type, true),
+            Field::new("function_type", DataType::Utf8, false),
+            Field::new("description", DataType::Utf8, true),
+            Field::new("syntax_example", DataType::Utf8, true),
+        ]))?);
+
+        // Functions registered under several aliases are listed once
+        let mut functions = self
+            .context_provider
+            .registered_functions()
+            .iter()
+            .flat_map(describe_function)
+            .collect::<Vec<_>>();
+        functions.sort();
+        functions.dedup();
+
+        let plan = if functions.is_empty() {
+            LogicalPlan::EmptyRelation(EmptyRelation {
+                produce_one_row: false,
+                schema,
+            })
+        } else {
+            let values = functions
+                .into_iter()
+                .map(|function| {
+                    let list = |values: Vec<Option<String>>| {
+                        let values = values
+                            .into_iter()
+                            .map(ScalarValue::Utf8)
+                            .collect::<Vec<_>>();
+                        lit(ScalarValue::List(ScalarValue::new_list_nullable(
+                            &values,
+                            &DataType::Utf8,
+                        )))
+                    };
+                    let parameter_types =
+                        function.parameter_types.into_iter().map(Some).collect();
+                    vec![
+                        lit(function.function_name),
+                        lit(ScalarValue::Utf8(function.return_type)),
+                        list(function.parameters),
+                        list(parameter_types),
+                        lit(function.function_type),
+                        lit(ScalarValue::Utf8(function.description)),
+                        lit(ScalarValue::Utf8(function.syntax_example)),
+                    ]
+                })
+                .collect();
+            LogicalPlanBuilder::values_with_schema(values, &schema)?.build()?
+        };
+
+        match predicate {
+            Some(predicate) => Ok(LogicalPlan::Filter(Filter::try_new(
+                predicate,
+                Arc::new(plan),
+            )?)),
+            None => Ok(plan),
+        }
+    }
+
     fn show_create_table_to_plan(
         &self,
         sql_table_name: ObjectName,
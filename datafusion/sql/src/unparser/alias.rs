@@ -0,0 +1,157 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Generation of the table aliases the [`Unparser`] introduces for derived tables
+//!
+//! [`Unparser`]: super::Unparser
+
+use std::collections::HashSet;
+use std::fmt::Debug;
+
+use datafusion_common::tree_node::TreeNodeRecursion;
+use datafusion_common::Result;
+use datafusion_expr::LogicalPlan;
+
+/// Generates the aliases of derived tables created while unparsing a plan
+/// (e.g. `SELECT ... FROM (SELECT ...) AS derived_projection`).
+///
+/// [`Self::begin_statement`] is called once before each top-level statement is
+/// unparsed, with the table and CTE names referenced by the plan, and returns
+/// the [`StatementAliasGenerator`] of that statement. As each statement has its
+/// own generator, an [`Unparser`] can unparse several statements concurrently.
+///
+/// [`Unparser`]: super::Unparser
+pub trait AliasGenerator: Debug + Send + Sync {
+    /// Start a new statement. `reserved` contains the (lowercased) names of the
+    /// tables and CTEs referenced by the plan being unparsed.
+    fn begin_statement(
+        &self,
+        reserved: HashSet<String>,
+    ) -> Box<dyn StatementAliasGenerator>;
+}
+
+/// Generates the aliases of the derived tables of a single statement, see
+/// [`AliasGenerator`].
+///
+/// Implementations should avoid returning the reserved names given to
+/// [`AliasGenerator::begin_statement`] and must return the same sequence of
+/// aliases for the same sequence of calls, so identical plans produce identical
+/// SQL text.
+pub trait StatementAliasGenerator: Debug + Send {
+    /// Return the alias for a derived table of the given `kind`, such as
+    /// `projection`, `limit`, `sort`, `distinct`, `union` or `unnest`.
+    fn next_alias(&mut self, kind: &str) -> String;
+}
+
+/// The default [`AliasGenerator`].
+///
+/// Generates `{prefix}{kind}` (`derived_projection` with the default prefix).
+/// If that name is used by a table or CTE in the plan, a counter that increases
+/// monotonically within a statement is appended (`derived_projection_1`,
+/// `derived_projection_2`, ...) until the name is unique.
+#[derive(Debug)]
+pub struct DefaultAliasGenerator {
+    prefix: String,
+}
+
+impl DefaultAliasGenerator {
+    pub fn new(prefix: impl Into<String>) -> Self {
+        Self {
+            prefix: prefix.into(),
+        }
+    }
+}
+
+impl Default for DefaultAliasGenerator {
+    fn default() -> Self {
+        Self::new("derived_")
+    }
+}
+
+impl AliasGenerator for DefaultAliasGenerator {
+    fn begin_statement(
+        &self,
+        reserved: HashSet<String>,
+    ) -> Box<dyn StatementAliasGenerator> {
+        Box::new(DefaultStatementAliasGenerator {
+            prefix: self.prefix.clone(),
+            reserved,
+            counter: 0,
+        })
+    }
+}
+
+/// The [`StatementAliasGenerator`] of [`DefaultAliasGenerator`]
+#[derive(Debug)]
+struct DefaultStatementAliasGenerator {
+    prefix: String,
+    reserved: HashSet<String>,
+    counter: usize,
+}
+
+impl StatementAliasGenerator for DefaultStatementAliasGenerator {
+    fn next_alias(&mut self, kind: &str) -> String {
+        let mut alias = format!("{}{kind}", self.prefix);
+        while self.reserved.contains(&alias.to_lowercase()) {
+            self.counter += 1;
+            alias = format!("{}{kind}_{}", self.prefix, self.counter);
+        }
+        alias
+    }
+}
+
+/// Collect the lowercased names of all tables and table aliases (including
+/// CTEs) referenced by `plan` and its subqueries
+pub(super) fn collect_relation_names(plan: &LogicalPlan) -> Result<HashSet<String>> {
+    let mut names = HashSet::new();
+    plan.apply_with_subqueries(|node| {
+        match node {
+            LogicalPlan::TableScan(scan) => {
+                names.insert(scan.table_name.table().to_lowercase());
+            }
+            LogicalPlan::SubqueryAlias(alias) => {
+                names.insert(alias.alias.table().to_lowercase());
+            }
+            _ => {}
+        }
+        Ok(TreeNodeRecursion::Continue)
+    })?;
+    Ok(names)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_alias_generator_avoids_reserved_names() {
+        let generator = DefaultAliasGenerator::default();
+        let mut aliases = generator.begin_statement(HashSet::from([
+            "derived_projection".to_string(),
+            "derived_projection_1".to_string(),
+        ]));
+        assert_eq!(aliases.next_alias("projection"), "derived_projection_2");
+        assert_eq!(aliases.next_alias("limit"), "derived_limit");
+        assert_eq!(aliases.next_alias("projection"), "derived_projection_3");
+
+        // each statement has its own counter
+        let mut other =
+            generator.begin_statement(HashSet::from(["derived_sort".to_string()]));
+        assert_eq!(other.next_alias("sort"), "derived_sort_1");
+        assert_eq!(aliases.next_alias("sort"), "derived_sort");
+    }
+}
@@ -18,6 +18,7 @@
 use core::fmt;
 use std::ops::ControlFlow;
 
+use super::dialect::{Dialect, LimitStyle};
 use sqlparser::ast::helpers::attached_token::AttachedToken;
 use sqlparser::ast::{
     self, visit_expressions_mut, LimitClause, OrderByKind, SelectFlavor,
@@ -31,10 +32,12 @@ pub struct QueryBuilder {
     limit: Option<ast::Expr>,
     limit_by: Vec<ast::Expr>,
     offset: Option<ast::Offset>,
+    limit_style: LimitStyle,
     fetch: Option<ast::Fetch>,
     locks: Vec<ast::LockClause>,
     for_clause: Option<ast::ForClause>,
-    // If true, we need to unparse LogicalPlan::Union as a SQL `UNION` rather than a `UNION ALL`.
+    hint: Option<String>,
+    // Only kept for the deprecated `distinct_union` and `is_distinct_union`
     distinct_union: bool,
 }
 
@@ -51,10 +54,18 @@ impl QueryBuilder {
     pub fn take_body(&mut self) -> Option<Box<ast::SetExpr>> {
         self.body.take()
     }
+    /// Returns the body, if set, without removing it like [`Self::take_body`]
+    pub fn body_ref(&self) -> Option<&ast::SetExpr> {
+        self.body.as_deref()
+    }
     pub fn order_by(&mut self, value: OrderByKind) -> &mut Self {
         self.order_by_kind = Some(value);
         self
     }
+    /// Returns true if an `ORDER BY` clause is set
+    pub fn has_order_by(&self) -> bool {
+        self.order_by_kind.is_some()
+    }
     pub fn limit(&mut self, value: Option<ast::Expr>) -> &mut Self {
         self.limit = value;
         self
@@ -67,10 +78,42 @@ impl QueryBuilder {
         self.offset = value;
         self
     }
+    /// Set the `OFFSET` clause to skip `value` rows, e.g. `OFFSET 10`
+    pub fn offset_value(&mut self, value: i64) -> &mut Self {
+        self.offset(Some(ast::Offset {
+            value: ast::Expr::value(ast::Value::Number(value.to_string(), false)),
+            rows: ast::OffsetRows::None,
+        }))
+    }
+    /// Set the style of the `LIMIT` and `OFFSET` clauses, see [`LimitStyle`]
+    pub fn limit_style(&mut self, value: LimitStyle) -> &mut Self {
+        self.limit_style = value;
+        self
+    }
     pub fn fetch(&mut self, value: Option<ast::Fetch>) -> &mut Self {
         self.fetch = value;
         self
     }
+    /// Set the `OFFSET` and `FETCH` clauses to skip `skip` rows and return the
+    /// next `fetch` rows, e.g. `OFFSET 10 ROWS FETCH FIRST 5 ROWS ONLY`.
+    ///
+    /// Both clauses use the `ROWS` keyword, and any `LIMIT` is cleared as it
+    /// can not be combined with `FETCH`.
+    pub fn offset_fetch(&mut self, skip: u64, fetch: u64) -> &mut Self {
+        self.limit(None)
+            .offset(Some(ast::Offset {
+                value: ast::Expr::value(ast::Value::Number(skip.to_string(), false)),
+                rows: ast::OffsetRows::Rows,
+            }))
+            .fetch(Some(ast::Fetch {
+                with_ties: false,
+                percent: false,
+                quantity: Some(ast::Expr::value(ast::Value::Number(
+                    fetch.to_string(),
+                    false,
+                ))),
+            }))
+    }
     pub fn locks(&mut self, value: Vec<ast::LockClause>) -> &mut Self {
         self.locks = value;
         self
@@ -79,13 +122,42 @@ impl QueryBuilder {
         self.for_clause = value;
         self
     }
+    #[deprecated(
+        since = "50.0.0",
+        note = "The quantifier of a set operation is set on the operation itself. This method has no effect on the built query"
+    )]
     pub fn distinct_union(&mut self) -> &mut Self {
         self.distinct_union = true;
         self
     }
+    #[deprecated(
+        since = "50.0.0",
+        note = "The quantifier of a set operation is set on the operation itself"
+    )]
     pub fn is_distinct_union(&self) -> bool {
         self.distinct_union
     }
+    /// Set an optimizer hint for the target engine, which [`Self::build_sql`]
+    /// emits as a leading `/*+ ... */` block comment
+    pub fn hint(&mut self, value: Option<String>) -> &mut Self {
+        self.hint = value;
+        self
+    }
+    /// Build the query and render it as SQL text, preceded by the hint comment
+    /// if one is set.
+    ///
+    /// The hint is not part of the [`ast::Query`] returned by [`Self::build`],
+    /// as the AST can not represent comments.
+    pub fn build_sql(&self) -> Result<String, BuilderError> {
+        let query = self.build()?;
+        match &self.hint {
+            Some(hint) if hint.contains("*/") => Err(Into::into(format!(
+                "Query hint can not contain the end of a comment: {hint}"
+            ))),
+            Some(hint) => Ok(format!("/*+ {hint} */ {query}")),
+            None => Ok(query.to_string()),
+        }
+    }
     pub fn build(&self) -> Result<ast::Query, BuilderError> {
         let order_by = self
             .order_by_kind
@@ -102,11 +174,7 @@ impl QueryBuilder {
                 None => return Err(Into::into(UninitializedFieldError::from("body"))),
             },
             order_by,
-            limit_clause: Some(LimitClause::LimitOffset {
-                limit: self.limit.clone(),
-                offset: self.offset.clone(),
-                limit_by: self.limit_by.clone(),
-            }),
+            limit_clause: Some(self.limit_clause()),
             fetch: self.fetch.clone(),
             locks: self.locks.clone(),
             for_clause: self.for_clause.clone(),
@@ -115,6 +183,29 @@ impl QueryBuilder {
             pipe_operators: vec![],
         })
     }
+    fn limit_clause(&self) -> LimitClause {
+        match (self.limit_style, &self.limit, &self.offset) {
+            (
+                LimitStyle::OffsetCommaLimit,
+                limit,
+                Some(ast::Offset {
+                    value: offset,
+                    rows: ast::OffsetRows::None,
+                }),
+            ) if self.limit_by.is_empty() => LimitClause::OffsetCommaLimit {
+                offset: offset.clone(),
+                // The comma form requires a count, use the largest one MySQL accepts
+                limit: limit.clone().unwrap_or_else(|| {
+                    ast::Expr::value(ast::Value::Number(u64::MAX.to_string(), false))
+                }),
+            },
+            _ => LimitClause::LimitOffset {
+                limit: self.limit.clone(),
+                offset: self.offset.clone(),
+                limit_by: self.limit_by.clone(),
+            },
+        }
+    }
     fn create_empty() -> Self {
         Self {
             with: Default::default(),
@@ -123,9 +214,11 @@ impl QueryBuilder {
             limit: Default::default(),
             limit_by: Default::default(),
             offset: Default::default(),
+            limit_style: LimitStyle::LimitOffset,
             fetch: Default::default(),
             locks: Default::default(),
             for_clause: Default::default(),
+            hint: Default::default(),
             distinct_union: false,
         }
     }
@@ -175,6 +268,9 @@ impl SelectBuilder {
         self.projection.clear();
         ret
     }
+    pub fn projections(&self) -> &[ast::SelectItem] {
+        &self.projection
+    }
     pub fn already_projected(&self) -> bool {
         !self.projection.is_empty()
     }
@@ -253,10 +349,34 @@ impl SelectBuilder {
 
         self
     }
+    /// Sets the selection to `value`, replacing any existing selection.
+    ///
+    /// Unlike the `selection` method, which combines existing and new
+    /// selections with AND, this overwrites the selection, e.g. after its
+    /// predicates were moved elsewhere. Passing `None` clears the selection.
+    pub fn set_selection(&mut self, value: Option<ast::Expr>) -> &mut Self {
+        self.selection = value;
+        self
+    }
     pub fn group_by(&mut self, value: ast::GroupByExpr) -> &mut Self {
         self.group_by = Some(value);
         self
     }
+    /// Set the `GROUP BY` clause to `ROLLUP (expr1, expr2, ...)`
+    pub fn group_by_rollup(&mut self, exprs: Vec<ast::Expr>) -> &mut Self {
+        let rollup = ast::Expr::Rollup(exprs.into_iter().map(|e| vec![e]).collect());
+        self.group_by(ast::GroupByExpr::Expressions(vec![rollup], vec![]))
+    }
+    /// Set the `GROUP BY` clause to `CUBE (expr1, expr2, ...)`
+    pub fn group_by_cube(&mut self, exprs: Vec<ast::Expr>) -> &mut Self {
+        let cube = ast::Expr::Cube(exprs.into_iter().map(|e| vec![e]).collect());
+        self.group_by(ast::GroupByExpr::Expressions(vec![cube], vec![]))
+    }
+    /// Set the `GROUP BY` clause to `GROUPING SETS ((set1...), (set2...), ...)`
+    pub fn group_by_grouping_sets(&mut self, sets: Vec<Vec<ast::Expr>>) -> &mut Self {
+        let grouping_sets = ast::Expr::GroupingSets(sets);
+        self.group_by(ast::GroupByExpr::Expressions(vec![grouping_sets], vec![]))
+    }
     pub fn cluster_by(&mut self, value: Vec<ast::Expr>) -> &mut Self {
         self.cluster_by = value;
         self
@@ -372,6 +492,42 @@ impl TableWithJoinsBuilder {
         self
     }
 
+    /// Pushes an as-of join, matching each row to the closest row of
+    /// `relation` that satisfies `match_condition` (and `on`, if given).
+    ///
+    /// For dialects without [`Dialect::supports_asof_join`] this falls back to
+    /// a `LEFT JOIN` on both conditions, which returns every row satisfying
+    /// `match_condition` rather than only the closest one.
+    pub fn push_asof_join(
+        &mut self,
+        relation: ast::TableFactor,
+        match_condition: ast::Expr,
+        on: Option<ast::Expr>,
+        dialect: &dyn Dialect,
+    ) -> &mut Self {
+        let join_operator = if dialect.supports_asof_join() {
+            ast::JoinOperator::AsOf {
+                match_condition,
+                constraint: on.map_or(ast::JoinConstraint::None, ast::JoinConstraint::On),
+            }
+        } else {
+            let condition = match on {
+                Some(on) => ast::Expr::BinaryOp {
+                    left: Box::new(nest_or(on)),
+                    op: ast::BinaryOperator::And,
+                    right: Box::new(nest_or(match_condition)),
+                },
+                None => match_condition,
+            };
+            ast::JoinOperator::LeftOuter(ast::JoinConstraint::On(condition))
+        };
+        self.push_join(ast::Join {
+            relation,
+            global: false,
+            join_operator,
+        })
+    }
+
     pub fn build(&self) -> Result<Option<ast::TableWithJoins>, BuilderError> {
         match self.relation {
             Some(ref value) => match value.build()? {
@@ -397,6 +553,17 @@ impl Default for TableWithJoinsBuilder {
     }
 }
 
+/// Parenthesizes an `OR` expression so it can be an operand of `AND`
+fn nest_or(expr: ast::Expr) -> ast::Expr {
+    match expr {
+        ast::Expr::BinaryOp {
+            op: ast::BinaryOperator::Or,
+            ..
+        } => ast::Expr::Nested(Box::new(expr)),
+        _ => expr,
+    }
+}
+
 #[derive(Clone)]
 pub struct RelationBuilder {
     relation: Option<TableFactorBuilder>,
@@ -409,6 +576,7 @@ enum TableFactorBuilder {
     Table(TableRelationBuilder),
     Derived(DerivedRelationBuilder),
     Unnest(UnnestRelationBuilder),
+    Values(ValuesRelationBuilder),
     Empty,
 }
 
@@ -431,6 +599,11 @@ impl RelationBuilder {
         self
     }
 
+    pub fn values(&mut self, value: ValuesRelationBuilder) -> &mut Self {
+        self.relation = Some(TableFactorBuilder::Values(value));
+        self
+    }
+
     pub fn empty(&mut self) -> &mut Self {
         self.relation = Some(TableFactorBuilder::Empty);
         self
@@ -447,16 +620,52 @@ impl RelationBuilder {
             Some(TableFactorBuilder::Unnest(ref mut rel_builder)) => {
                 rel_builder.alias = value;
             }
+            Some(TableFactorBuilder::Values(ref mut rel_builder)) => {
+                rel_builder.alias = value;
+            }
             Some(TableFactorBuilder::Empty) => (),
             None => (),
         }
         new
     }
+    /// Whether the relation is a `LATERAL` derived table
+    pub fn is_lateral(&self) -> bool {
+        matches!(
+            self.relation,
+            Some(TableFactorBuilder::Derived(ref rel_builder))
+                if rel_builder.lateral == Some(true)
+        )
+    }
+    pub fn lateral(&mut self, value: bool) -> &mut Self {
+        if let Some(TableFactorBuilder::Derived(ref mut rel_builder)) = self.relation {
+            rel_builder.lateral = Some(value);
+        }
+        self
+    }
+    pub fn sample(&mut self, value: Option<ast::TableSampleKind>) -> &mut Self {
+        if let Some(TableFactorBuilder::Table(ref mut rel_builder)) = self.relation {
+            rel_builder.sample = value;
+        }
+        self
+    }
+    pub fn with_ordinality(&mut self, value: bool) -> &mut Self {
+        match self.relation {
+            Some(TableFactorBuilder::Table(ref mut rel_builder)) => {
+                rel_builder.with_ordinality = value;
+            }
+            Some(TableFactorBuilder::Unnest(ref mut rel_builder)) => {
+                rel_builder.with_ordinality = value;
+            }
+            _ => (),
+        }
+        self
+    }
     pub fn build(&self) -> Result<Option<ast::TableFactor>, BuilderError> {
         Ok(match self.relation {
             Some(TableFactorBuilder::Table(ref value)) => Some(value.build()?),
             Some(TableFactorBuilder::Derived(ref value)) => Some(value.build()?),
             Some(TableFactorBuilder::Unnest(ref value)) => Some(value.build()?),
+            Some(TableFactorBuilder::Values(ref value)) => Some(value.build()?),
             Some(TableFactorBuilder::Empty) => None,
             None => return Err(Into::into(UninitializedFieldError::from("relation"))),
         })
@@ -480,8 +689,10 @@ pub struct TableRelationBuilder {
     args: Option<Vec<ast::FunctionArg>>,
     with_hints: Vec<ast::Expr>,
     version: Option<ast::TableVersion>,
+    with_ordinality: bool,
     partitions: Vec<ast::Ident>,
     index_hints: Vec<ast::TableIndexHints>,
+    sample: Option<ast::TableSampleKind>,
 }
 
 #[allow(dead_code)]
@@ -506,6 +717,10 @@ impl TableRelationBuilder {
         self.version = value;
         self
     }
+    pub fn with_ordinality(&mut self, value: bool) -> &mut Self {
+        self.with_ordinality = value;
+        self
+    }
     pub fn partitions(&mut self, value: Vec<ast::Ident>) -> &mut Self {
         self.partitions = value;
         self
@@ -514,6 +729,10 @@ impl TableRelationBuilder {
         self.index_hints = value;
         self
     }
+    pub fn sample(&mut self, value: Option<ast::TableSampleKind>) -> &mut Self {
+        self.sample = value;
+        self
+    }
     pub fn build(&self) -> Result<ast::TableFactor, BuilderError> {
         Ok(ast::TableFactor::Table {
             name: match self.name {
@@ -528,9 +747,9 @@ impl TableRelationBuilder {
             with_hints: self.with_hints.clone(),
             version: self.version.clone(),
             partitions: self.partitions.clone(),
-            with_ordinality: false,
+            with_ordinality: self.with_ordinality,
             json_path: None,
-            sample: None,
+            sample: self.sample.clone(),
             index_hints: self.index_hints.clone(),
         })
     }
@@ -541,8 +760,10 @@ impl TableRelationBuilder {
             args: Default::default(),
             with_hints: Default::default(),
             version: Default::default(),
+            with_ordinality: Default::default(),
             partitions: Default::default(),
             index_hints: Default::default(),
+            sample: Default::default(),
         }
     }
 }
@@ -663,6 +884,51 @@ impl Default for UnnestRelationBuilder {
     }
 }
 
+/// Builds a `VALUES` list used as a table, e.g. `(VALUES (1), (2)) AS t (x)`
+#[derive(Clone)]
+pub struct ValuesRelationBuilder {
+    pub alias: Option<ast::TableAlias>,
+    rows: Vec<Vec<ast::Expr>>,
+}
+
+#[allow(dead_code)]
+impl ValuesRelationBuilder {
+    pub fn alias(&mut self, value: Option<ast::TableAlias>) -> &mut Self {
+        self.alias = value;
+        self
+    }
+    pub fn rows(&mut self, value: Vec<Vec<ast::Expr>>) -> &mut Self {
+        self.rows = value;
+        self
+    }
+    pub fn build(&self) -> Result<ast::TableFactor, BuilderError> {
+        let values = ast::Values {
+            explicit_row: false,
+            rows: self.rows.clone(),
+        };
+        let subquery = QueryBuilder::default()
+            .body(Box::new(ast::SetExpr::Values(values)))
+            .build()?;
+        Ok(ast::TableFactor::Derived {
+            lateral: false,
+            subquery: Box::new(subquery),
+            alias: self.alias.clone(),
+        })
+    }
+    fn create_empty() -> Self {
+        Self {
+            alias: Default::default(),
+            rows: Default::default(),
+        }
+    }
+}
+
+impl Default for ValuesRelationBuilder {
+    fn default() -> Self {
+        Self::create_empty()
+    }
+}
+
 /// Runtime error when a `build()` method is called and one or more required fields
 /// do not have a value.
 #[derive(Debug, Clone)]
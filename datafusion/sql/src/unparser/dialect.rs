@@ -15,7 +15,10 @@
 // specific language governing permissions and limitations
 // under the License.
 
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::HashMap,
+    sync::{Arc, LazyLock},
+};
 
 use super::{
     utils::character_length_to_sql, utils::date_part_to_sql,
@@ -30,9 +33,49 @@ use sqlparser::{
     ast::{
         self, BinaryOperator, Function, Ident, ObjectName, TimezoneInfo, WindowFrameBound,
     },
-    keywords::ALL_KEYWORDS,
+    keywords::{
+        Keyword, ALL_KEYWORDS, RESERVED_FOR_COLUMN_ALIAS, RESERVED_FOR_IDENTIFIER,
+        RESERVED_FOR_TABLE_ALIAS,
+    },
 };
 
+/// Keywords that are not reserved by sqlparser for aliases, but are parsed as
+/// the start of an expression rather than as an identifier
+const RESERVED_FOR_EXPR: &[Keyword] = &[
+    Keyword::ALL,
+    Keyword::AND,
+    Keyword::ANY,
+    Keyword::AS,
+    Keyword::BETWEEN,
+    Keyword::CASE,
+    Keyword::CAST,
+    Keyword::DISTINCT,
+    Keyword::ELSE,
+    Keyword::FALSE,
+    Keyword::IN,
+    Keyword::IS,
+    Keyword::LIKE,
+    Keyword::NOT,
+    Keyword::NULL,
+    Keyword::OR,
+    Keyword::THEN,
+    Keyword::TRUE,
+    Keyword::WHEN,
+];
+
+static DEFAULT_RESERVED_KEYWORDS: LazyLock<Vec<Keyword>> = LazyLock::new(|| {
+    let mut keywords: Vec<Keyword> = [
+        RESERVED_FOR_TABLE_ALIAS,
+        RESERVED_FOR_COLUMN_ALIAS,
+        RESERVED_FOR_IDENTIFIER,
+        RESERVED_FOR_EXPR,
+    ]
+    .concat();
+    keywords.sort_unstable();
+    keywords.dedup();
+    keywords
+});
+
 pub type ScalarFnToSqlHandler =
     Box<dyn Fn(&Unparser, &[Expr]) -> Result<Option<ast::Expr>> + Send + Sync>;
 
@@ -47,8 +90,32 @@ pub type ScalarFnToSqlHandler =
 /// See also the discussion in <https://github.com/apache/datafusion/pull/10625>
 pub trait Dialect: Send + Sync {
     /// Return the character used to quote identifiers.
+    ///
+    /// When `None` is returned for an identifier the unparser can not write
+    /// unquoted, e.g. a [reserved keyword](Self::reserved_keywords), it is
+    /// quoted with [`Self::required_identifier_quote_style`].
     fn identifier_quote_style(&self, _identifier: &str) -> Option<char>;
 
+    /// Return the character used to quote an identifier that must be quoted
+    /// when [`Self::identifier_quote_style`] returns `None` for it.
+    fn required_identifier_quote_style(&self) -> char {
+        '"'
+    }
+
+    /// The keywords that can not be used as unquoted identifiers
+    ///
+    /// Defaults to the keywords sqlparser reserves for table and column
+    /// aliases, and the keywords an expression can start with
+    fn reserved_keywords(&self) -> &[Keyword] {
+        &DEFAULT_RESERVED_KEYWORDS
+    }
+
+    /// Whether the dialect folds unquoted identifiers to lowercase, so an
+    /// identifier with uppercase characters must be quoted to keep its case
+    fn folds_identifiers_to_lowercase(&self) -> bool {
+        true
+    }
+
     /// Does the dialect support specifying `NULLS FIRST/LAST` in `ORDER BY` clauses?
     fn supports_nulls_first_in_sort(&self) -> bool {
         true
@@ -126,6 +193,13 @@ pub trait Dialect: Send + Sync {
         ast::DataType::Date
     }
 
+    /// The style to use for unparsing casts of string literals to date and
+    /// timestamp types, such as the literals compared with a timestamp column
+    /// in `ts > '2024-01-01'`
+    fn date_time_literal_style(&self) -> DateTimeLiteralStyle {
+        DateTimeLiteralStyle::Cast
+    }
+
     /// Does the dialect support specifying column aliases as part of alias table definition?
     /// (SELECT col1, col2 from my_table) AS my_table_alias(col1_alias, col2_alias)
     fn supports_column_alias_in_table_alias(&self) -> bool {
@@ -169,6 +243,20 @@ pub trait Dialect: Send + Sync {
         true
     }
 
+    /// Allows the dialect to omit a window frame that is equal to the frame the
+    /// target engine uses when none is specified: `RANGE BETWEEN UNBOUNDED PRECEDING
+    /// AND CURRENT ROW` if the window has an `ORDER BY`, and the whole partition
+    /// otherwise. Returns false if the frame should always be unparsed.
+    fn omit_default_window_frame(&self) -> bool {
+        false
+    }
+
+    /// The style to use for emitting the window specifications of a SELECT list,
+    /// either inline in each `OVER` clause or as definitions in a `WINDOW` clause
+    fn named_window_style(&self) -> NamedWindowStyle {
+        NamedWindowStyle::Inline
+    }
+
     /// Extends the dialect's default rules for unparsing scalar functions.
     /// This is useful for supporting application-specific UDFs or custom engine extensions.
     fn with_custom_scalar_overrides(
@@ -198,6 +286,114 @@ pub trait Dialect: Send + Sync {
         false
     }
 
+    /// Whether a query without a relation, such as `SELECT 1` or a global
+    /// aggregate over an empty relation, must read from the `DUAL` table
+    /// (e.g. `SELECT 1 FROM DUAL`) because the dialect requires a `FROM` clause.
+    fn requires_from_dual(&self) -> bool {
+        false
+    }
+
+    /// Whether the dialect supports the case-insensitive `ILIKE` operator.
+    ///
+    /// When unsupported, `a ILIKE b` is unparsed as `LOWER(a) LIKE LOWER(b)`.
+    fn supports_ilike(&self) -> bool {
+        true
+    }
+
+    /// Whether the dialect supports as-of joins written as
+    /// `ASOF JOIN <relation> MATCH_CONDITION (<condition>) ON <condition>`.
+    ///
+    /// When unsupported, as-of joins are unparsed as a `LEFT JOIN` on both
+    /// conditions.
+    fn supports_asof_join(&self) -> bool {
+        false
+    }
+
+    /// Whether the dialect writes the `AS` keyword before column aliases, as in
+    /// `SELECT a AS b`, rather than `SELECT a b`. Table aliases are not affected.
+    ///
+    /// The AST can not represent an alias without `AS`, so this only applies to
+    /// the SQL text of [`Unparser::plan_to_sql_text`].
+    ///
+    /// [`Unparser::plan_to_sql_text`]: super::Unparser::plan_to_sql_text
+    fn use_as_for_column_alias(&self) -> bool {
+        true
+    }
+
+    /// Whether the dialect writes lateral joins as `CROSS APPLY` and
+    /// `OUTER APPLY`, as MSSQL does, rather than with `LATERAL`.
+    ///
+    /// An inner lateral join is unparsed as `CROSS APPLY`, with its condition
+    /// in the `WHERE` clause, and a left lateral join as `OUTER APPLY`.
+    fn supports_apply_join(&self) -> bool {
+        false
+    }
+
+    /// Whether the dialect supports filtering the results of window functions
+    /// with a `QUALIFY` clause.
+    ///
+    /// When unsupported, or when the filter also refers to columns that are not
+    /// window function results, the window functions are computed in a derived
+    /// table that is filtered in the `WHERE` clause of the enclosing query.
+    fn supports_qualify(&self) -> bool {
+        false
+    }
+
+    /// Whether the dialect supports collecting the rows of a subquery into an
+    /// array with `ARRAY(<subquery>)`.
+    ///
+    /// When unsupported, the subquery is unparsed as the scalar subquery that
+    /// aggregates its rows with `array_agg`, which it is planned as.
+    fn supports_array_subquery(&self) -> bool {
+        false
+    }
+
+    /// Whether the dialect supports the `IGNORE NULLS` and `RESPECT NULLS`
+    /// modifiers of function calls.
+    ///
+    /// When unsupported, `IGNORE NULLS` of an aggregate function that is not
+    /// called over a window is unparsed as a `FILTER (WHERE <first argument>
+    /// IS NOT NULL)` clause, if the dialect supports
+    /// [`Self::supports_aggregate_filter`].
+    fn supports_null_treatment(&self) -> bool {
+        true
+    }
+
+    /// Whether the dialect supports the `FILTER (WHERE ...)` clause of
+    /// aggregate function calls.
+    ///
+    /// This is used to decide whether `IGNORE NULLS` can be written as a
+    /// `FILTER` when [`Self::supports_null_treatment`] is false.
+    fn supports_aggregate_filter(&self) -> bool {
+        true
+    }
+
+    /// The style to use for emitting a `LIMIT` with an `OFFSET`
+    fn limit_style(&self) -> LimitStyle {
+        LimitStyle::LimitOffset
+    }
+
+    /// The style to use for emitting a sample of a number of rows of a table
+    fn table_sample_style(&self) -> TableSampleStyle {
+        TableSampleStyle::Limit
+    }
+
+    /// The style to use for emitting the expressions of a `GROUP BY`
+    fn group_by_style(&self) -> GroupByStyle {
+        GroupByStyle::Expressions
+    }
+
+    /// The style to use for emitting a projection of all but a few columns
+    /// of a table
+    fn column_exclusion_style(&self) -> ColumnExclusionStyle {
+        ColumnExclusionStyle::Columns
+    }
+
+    /// The style to use for emitting the `string_agg` aggregate function
+    fn string_agg_style(&self) -> StringAggStyle {
+        StringAggStyle::StringAgg
+    }
+
     /// Allows the dialect to override column alias unparsing if the dialect has specific rules.
     /// Returns None if the default unparsing should be used, or Some(String) if there is
     /// a custom implementation for the alias.
@@ -246,6 +442,101 @@ pub enum CharacterLengthStyle {
     CharacterLength,
 }
 
+/// `DateTimeLiteralStyle` to use for unparsing casts of string literals to date
+/// and timestamp types
+///
+/// `Cast` style uses CAST('2024-01-01' AS TIMESTAMP)
+/// `TypedString` style uses TIMESTAMP '2024-01-01 00:00:00', with the literal
+/// normalized to the value of the cast. Values the typed literal can not
+/// represent, such as timestamps with sub-microsecond precision, are unparsed
+/// with the `Cast` style
+#[derive(Clone, Copy, PartialEq)]
+pub enum DateTimeLiteralStyle {
+    Cast,
+    TypedString,
+}
+
+/// `NamedWindowStyle` to use for unparsing window specifications
+///
+/// `Inline` style repeats the specification in each window function,
+/// e.g. `sum(a) OVER (PARTITION BY b), max(a) OVER (PARTITION BY b)`
+/// `Named` style defines a specification used by several window functions once,
+/// e.g. `sum(a) OVER w1, max(a) OVER w1 ... WINDOW w1 AS (PARTITION BY b)`
+/// `Chained` style additionally factors a `PARTITION BY` shared by different
+/// specifications into a base window that the others extend,
+/// e.g. `WINDOW w1 AS (PARTITION BY b), w2 AS (w1 ORDER BY c)`
+#[derive(Clone, Copy, PartialEq)]
+pub enum NamedWindowStyle {
+    Inline,
+    Named,
+    Chained,
+}
+
+/// `LimitStyle` to use for unparsing a `LIMIT` with an `OFFSET`
+///
+/// `LimitOffset` style uses `LIMIT 10 OFFSET 5`
+/// `OffsetCommaLimit` style uses the MySQL form `LIMIT 5, 10`. As that form
+/// requires a count, an `OFFSET` without a `LIMIT` is unparsed with the largest
+/// count MySQL accepts, e.g. `LIMIT 5, 18446744073709551615`
+#[derive(Clone, Copy, PartialEq)]
+pub enum LimitStyle {
+    LimitOffset,
+    OffsetCommaLimit,
+}
+
+/// `TableSampleStyle` to use for unparsing a sample of a number of rows of a
+/// table, which is planned as the first rows of the table in a random order
+///
+/// `Limit` style unparses the plan as is, e.g.
+/// `SELECT * FROM (SELECT * FROM t ORDER BY random() ASC NULLS LAST LIMIT 10)`
+/// `TableSample` style uses the standard `SELECT * FROM t TABLESAMPLE (10 ROWS)`
+/// `Sample` style uses the Snowflake form `SELECT * FROM t SAMPLE (10 ROWS)`
+#[derive(Clone, Copy, PartialEq)]
+pub enum TableSampleStyle {
+    Limit,
+    TableSample,
+    Sample,
+}
+
+/// `GroupByStyle` to use for unparsing the expressions of a `GROUP BY`
+///
+/// `Expressions` style repeats the grouping expressions,
+/// e.g. `SELECT upper(a), count(*) FROM t GROUP BY upper(a)`
+/// `Ordinals` style refers to the grouping expressions by their position in
+/// the `SELECT` list, e.g. `SELECT upper(a), count(*) FROM t GROUP BY 1`.
+/// Expressions not in the `SELECT` list are still repeated
+#[derive(Clone, Copy, PartialEq)]
+pub enum GroupByStyle {
+    Expressions,
+    Ordinals,
+}
+
+/// `ColumnExclusionStyle` to use for unparsing a projection of all but a few
+/// columns of a table
+///
+/// `Columns` style lists the projected columns, e.g. `SELECT a, b FROM t`
+/// `Exclude` style uses `SELECT * EXCLUDE (c) FROM t`, as in DuckDB
+/// `Except` style uses `SELECT * EXCEPT (c) FROM t`, as in BigQuery
+/// Exclusion is only used when fewer columns are excluded than projected
+#[derive(Clone, Copy, PartialEq)]
+pub enum ColumnExclusionStyle {
+    Columns,
+    Exclude,
+    Except,
+}
+
+/// `StringAggStyle` to use for unparsing the `string_agg` aggregate function
+///
+/// `StringAgg` style uses `string_agg(DISTINCT x, ',' ORDER BY x)`
+/// `GroupConcat` style uses the MySQL form
+/// `GROUP_CONCAT(DISTINCT x ORDER BY x SEPARATOR ',')`, which requires the
+/// delimiter to be a string literal
+#[derive(Clone, Copy, PartialEq)]
+pub enum StringAggStyle {
+    StringAgg,
+    GroupConcat,
+}
+
 pub struct DefaultDialect {}
 
 impl Dialect for DefaultDialect {
@@ -279,6 +570,10 @@ impl Dialect for PostgreSqlDialect {
         ast::DataType::DoublePrecision
     }
 
+    fn date_time_literal_style(&self) -> DateTimeLiteralStyle {
+        DateTimeLiteralStyle::TypedString
+    }
+
     fn scalar_function_to_sql_overrides(
         &self,
         unparser: &Unparser,
@@ -293,6 +588,18 @@ impl Dialect for PostgreSqlDialect {
 
         Ok(None)
     }
+
+    fn omit_default_window_frame(&self) -> bool {
+        true
+    }
+
+    fn supports_array_subquery(&self) -> bool {
+        true
+    }
+
+    fn supports_null_treatment(&self) -> bool {
+        false
+    }
 }
 
 impl PostgreSqlDialect {
@@ -401,6 +708,22 @@ impl Dialect for DuckDBDialect {
 
         Ok(None)
     }
+
+    fn omit_default_window_frame(&self) -> bool {
+        true
+    }
+
+    fn supports_qualify(&self) -> bool {
+        true
+    }
+
+    fn supports_array_subquery(&self) -> bool {
+        true
+    }
+
+    fn column_exclusion_style(&self) -> ColumnExclusionStyle {
+        ColumnExclusionStyle::Exclude
+    }
 }
 
 pub struct MySqlDialect {}
@@ -414,6 +737,10 @@ impl Dialect for MySqlDialect {
         false
     }
 
+    fn supports_ilike(&self) -> bool {
+        false
+    }
+
     fn interval_style(&self) -> IntervalStyle {
         IntervalStyle::MySQL
     }
@@ -434,6 +761,10 @@ impl Dialect for MySqlDialect {
         ast::DataType::Custom(ObjectName::from(vec![Ident::new("SIGNED")]), vec![])
     }
 
+    fn string_agg_style(&self) -> StringAggStyle {
+        StringAggStyle::GroupConcat
+    }
+
     fn int32_cast_dtype(&self) -> ast::DataType {
         ast::DataType::Custom(ObjectName::from(vec![Ident::new("SIGNED")]), vec![])
     }
@@ -462,6 +793,18 @@ impl Dialect for MySqlDialect {
 
         Ok(None)
     }
+
+    fn omit_default_window_frame(&self) -> bool {
+        true
+    }
+
+    fn supports_null_treatment(&self) -> bool {
+        false
+    }
+
+    fn supports_aggregate_filter(&self) -> bool {
+        false
+    }
 }
 
 pub struct SqliteDialect {}
@@ -505,6 +848,14 @@ impl Dialect for SqliteDialect {
             _ => Ok(None),
         }
     }
+
+    fn omit_default_window_frame(&self) -> bool {
+        true
+    }
+
+    fn supports_null_treatment(&self) -> bool {
+        false
+    }
 }
 
 #[derive(Default)]
@@ -541,6 +892,22 @@ impl Dialect for BigQueryDialect {
     fn unnest_as_table_factor(&self) -> bool {
         true
     }
+
+    fn omit_default_window_frame(&self) -> bool {
+        true
+    }
+
+    fn group_by_style(&self) -> GroupByStyle {
+        GroupByStyle::Ordinals
+    }
+
+    fn supports_qualify(&self) -> bool {
+        true
+    }
+
+    fn column_exclusion_style(&self) -> ColumnExclusionStyle {
+        ColumnExclusionStyle::Except
+    }
 }
 
 impl BigQueryDialect {
@@ -565,12 +932,31 @@ pub struct CustomDialect {
     timestamp_cast_dtype: ast::DataType,
     timestamp_tz_cast_dtype: ast::DataType,
     date32_cast_dtype: ast::DataType,
+    date_time_literal_style: DateTimeLiteralStyle,
     supports_column_alias_in_table_alias: bool,
     requires_derived_table_alias: bool,
     division_operator: BinaryOperator,
     window_func_support_window_frame: bool,
+    omit_default_window_frame: bool,
+    named_window_style: NamedWindowStyle,
     full_qualified_col: bool,
     unnest_as_table_factor: bool,
+    requires_from_dual: bool,
+    supports_ilike: bool,
+    supports_asof_join: bool,
+    supports_apply_join: bool,
+    use_as_for_column_alias: bool,
+    supports_qualify: bool,
+    supports_array_subquery: bool,
+    supports_null_treatment: bool,
+    supports_aggregate_filter: bool,
+    limit_style: LimitStyle,
+    table_sample_style: TableSampleStyle,
+    group_by_style: GroupByStyle,
+    column_exclusion_style: ColumnExclusionStyle,
+    string_agg_style: StringAggStyle,
+    reserved_keywords: Vec<Keyword>,
+    folds_identifiers_to_lowercase: bool,
 }
 
 impl Default for CustomDialect {
@@ -593,12 +979,31 @@ impl Default for CustomDialect {
                 TimezoneInfo::WithTimeZone,
             ),
             date32_cast_dtype: ast::DataType::Date,
+            date_time_literal_style: DateTimeLiteralStyle::Cast,
             supports_column_alias_in_table_alias: true,
             requires_derived_table_alias: false,
             division_operator: BinaryOperator::Divide,
             window_func_support_window_frame: true,
+            omit_default_window_frame: false,
+            named_window_style: NamedWindowStyle::Inline,
             full_qualified_col: false,
             unnest_as_table_factor: false,
+            requires_from_dual: false,
+            supports_ilike: true,
+            supports_asof_join: false,
+            supports_apply_join: false,
+            use_as_for_column_alias: true,
+            supports_qualify: false,
+            supports_array_subquery: false,
+            supports_null_treatment: true,
+            supports_aggregate_filter: true,
+            limit_style: LimitStyle::LimitOffset,
+            table_sample_style: TableSampleStyle::Limit,
+            group_by_style: GroupByStyle::Expressions,
+            column_exclusion_style: ColumnExclusionStyle::Columns,
+            string_agg_style: StringAggStyle::StringAgg,
+            reserved_keywords: DEFAULT_RESERVED_KEYWORDS.clone(),
+            folds_identifiers_to_lowercase: true,
         }
     }
 }
@@ -664,6 +1069,10 @@ impl Dialect for CustomDialect {
         self.date32_cast_dtype.clone()
     }
 
+    fn date_time_literal_style(&self) -> DateTimeLiteralStyle {
+        self.date_time_literal_style
+    }
+
     fn supports_column_alias_in_table_alias(&self) -> bool {
         self.supports_column_alias_in_table_alias
     }
@@ -702,6 +1111,14 @@ impl Dialect for CustomDialect {
         self.window_func_support_window_frame
     }
 
+    fn omit_default_window_frame(&self) -> bool {
+        self.omit_default_window_frame
+    }
+
+    fn named_window_style(&self) -> NamedWindowStyle {
+        self.named_window_style
+    }
+
     fn full_qualified_col(&self) -> bool {
         self.full_qualified_col
     }
@@ -709,6 +1126,70 @@ impl Dialect for CustomDialect {
     fn unnest_as_table_factor(&self) -> bool {
         self.unnest_as_table_factor
     }
+
+    fn requires_from_dual(&self) -> bool {
+        self.requires_from_dual
+    }
+
+    fn supports_ilike(&self) -> bool {
+        self.supports_ilike
+    }
+
+    fn supports_asof_join(&self) -> bool {
+        self.supports_asof_join
+    }
+
+    fn supports_apply_join(&self) -> bool {
+        self.supports_apply_join
+    }
+
+    fn use_as_for_column_alias(&self) -> bool {
+        self.use_as_for_column_alias
+    }
+
+    fn supports_qualify(&self) -> bool {
+        self.supports_qualify
+    }
+
+    fn supports_array_subquery(&self) -> bool {
+        self.supports_array_subquery
+    }
+
+    fn supports_null_treatment(&self) -> bool {
+        self.supports_null_treatment
+    }
+
+    fn supports_aggregate_filter(&self) -> bool {
+        self.supports_aggregate_filter
+    }
+
+    fn limit_style(&self) -> LimitStyle {
+        self.limit_style
+    }
+
+    fn table_sample_style(&self) -> TableSampleStyle {
+        self.table_sample_style
+    }
+
+    fn group_by_style(&self) -> GroupByStyle {
+        self.group_by_style
+    }
+
+    fn column_exclusion_style(&self) -> ColumnExclusionStyle {
+        self.column_exclusion_style
+    }
+
+    fn string_agg_style(&self) -> StringAggStyle {
+        self.string_agg_style
+    }
+
+    fn reserved_keywords(&self) -> &[Keyword] {
+        &self.reserved_keywords
+    }
+
+    fn folds_identifiers_to_lowercase(&self) -> bool {
+        self.folds_identifiers_to_lowercase
+    }
 }
 
 /// `CustomDialectBuilder` to build `CustomDialect` using builder pattern
@@ -740,12 +1221,31 @@ pub struct CustomDialectBuilder {
     timestamp_cast_dtype: ast::DataType,
     timestamp_tz_cast_dtype: ast::DataType,
     date32_cast_dtype: ast::DataType,
+    date_time_literal_style: DateTimeLiteralStyle,
     supports_column_alias_in_table_alias: bool,
     requires_derived_table_alias: bool,
     division_operator: BinaryOperator,
     window_func_support_window_frame: bool,
+    omit_default_window_frame: bool,
+    named_window_style: NamedWindowStyle,
     full_qualified_col: bool,
     unnest_as_table_factor: bool,
+    requires_from_dual: bool,
+    supports_ilike: bool,
+    supports_asof_join: bool,
+    supports_apply_join: bool,
+    use_as_for_column_alias: bool,
+    supports_qualify: bool,
+    supports_array_subquery: bool,
+    supports_null_treatment: bool,
+    supports_aggregate_filter: bool,
+    limit_style: LimitStyle,
+    table_sample_style: TableSampleStyle,
+    group_by_style: GroupByStyle,
+    column_exclusion_style: ColumnExclusionStyle,
+    string_agg_style: StringAggStyle,
+    reserved_keywords: Vec<Keyword>,
+    folds_identifiers_to_lowercase: bool,
 }
 
 impl Default for CustomDialectBuilder {
@@ -774,12 +1274,31 @@ impl CustomDialectBuilder {
                 TimezoneInfo::WithTimeZone,
             ),
             date32_cast_dtype: ast::DataType::Date,
+            date_time_literal_style: DateTimeLiteralStyle::Cast,
             supports_column_alias_in_table_alias: true,
             requires_derived_table_alias: false,
             division_operator: BinaryOperator::Divide,
             window_func_support_window_frame: true,
+            omit_default_window_frame: false,
+            named_window_style: NamedWindowStyle::Inline,
             full_qualified_col: false,
             unnest_as_table_factor: false,
+            requires_from_dual: false,
+            supports_ilike: true,
+            supports_asof_join: false,
+            supports_apply_join: false,
+            use_as_for_column_alias: true,
+            supports_qualify: false,
+            supports_array_subquery: false,
+            supports_null_treatment: true,
+            supports_aggregate_filter: true,
+            limit_style: LimitStyle::LimitOffset,
+            table_sample_style: TableSampleStyle::Limit,
+            group_by_style: GroupByStyle::Expressions,
+            column_exclusion_style: ColumnExclusionStyle::Columns,
+            string_agg_style: StringAggStyle::StringAgg,
+            reserved_keywords: DEFAULT_RESERVED_KEYWORDS.clone(),
+            folds_identifiers_to_lowercase: true,
         }
     }
 
@@ -799,13 +1318,32 @@ impl CustomDialectBuilder {
             timestamp_cast_dtype: self.timestamp_cast_dtype,
             timestamp_tz_cast_dtype: self.timestamp_tz_cast_dtype,
             date32_cast_dtype: self.date32_cast_dtype,
+            date_time_literal_style: self.date_time_literal_style,
             supports_column_alias_in_table_alias: self
                 .supports_column_alias_in_table_alias,
             requires_derived_table_alias: self.requires_derived_table_alias,
             division_operator: self.division_operator,
             window_func_support_window_frame: self.window_func_support_window_frame,
+            omit_default_window_frame: self.omit_default_window_frame,
+            named_window_style: self.named_window_style,
             full_qualified_col: self.full_qualified_col,
             unnest_as_table_factor: self.unnest_as_table_factor,
+            requires_from_dual: self.requires_from_dual,
+            supports_ilike: self.supports_ilike,
+            supports_asof_join: self.supports_asof_join,
+            supports_apply_join: self.supports_apply_join,
+            use_as_for_column_alias: self.use_as_for_column_alias,
+            supports_qualify: self.supports_qualify,
+            supports_array_subquery: self.supports_array_subquery,
+            supports_null_treatment: self.supports_null_treatment,
+            supports_aggregate_filter: self.supports_aggregate_filter,
+            limit_style: self.limit_style,
+            table_sample_style: self.table_sample_style,
+            group_by_style: self.group_by_style,
+            column_exclusion_style: self.column_exclusion_style,
+            string_agg_style: self.string_agg_style,
+            reserved_keywords: self.reserved_keywords,
+            folds_identifiers_to_lowercase: self.folds_identifiers_to_lowercase,
         }
     }
 
@@ -906,6 +1444,15 @@ impl CustomDialectBuilder {
         self
     }
 
+    /// Customize the dialect with a specific style for casts of string literals to date and timestamp types
+    pub fn with_date_time_literal_style(
+        mut self,
+        date_time_literal_style: DateTimeLiteralStyle,
+    ) -> Self {
+        self.date_time_literal_style = date_time_literal_style;
+        self
+    }
+
     /// Customize the dialect to support column aliases as part of alias table definition
     pub fn with_supports_column_alias_in_table_alias(
         mut self,
@@ -936,6 +1483,24 @@ impl CustomDialectBuilder {
         self
     }
 
+    /// Customize the dialect to omit window frames equal to the default frame
+    pub fn with_omit_default_window_frame(
+        mut self,
+        omit_default_window_frame: bool,
+    ) -> Self {
+        self.omit_default_window_frame = omit_default_window_frame;
+        self
+    }
+
+    /// Customize the dialect with a specific style for emitting window specifications
+    pub fn with_named_window_style(
+        mut self,
+        named_window_style: NamedWindowStyle,
+    ) -> Self {
+        self.named_window_style = named_window_style;
+        self
+    }
+
     /// Customize the dialect to allow full qualified column names
     pub fn with_full_qualified_col(mut self, full_qualified_col: bool) -> Self {
         self.full_qualified_col = full_qualified_col;
@@ -946,4 +1511,114 @@ impl CustomDialectBuilder {
         self.unnest_as_table_factor = unnest_as_table_factor;
         self
     }
+
+    /// Customize the dialect to read queries without a relation from the `DUAL` table
+    pub fn with_requires_from_dual(mut self, requires_from_dual: bool) -> Self {
+        self.requires_from_dual = requires_from_dual;
+        self
+    }
+
+    /// Customize the dialect with support for the `ILIKE` operator
+    pub fn with_supports_ilike(mut self, supports_ilike: bool) -> Self {
+        self.supports_ilike = supports_ilike;
+        self
+    }
+
+    /// Customize the dialect with support for `ASOF JOIN ... MATCH_CONDITION (...)`
+    pub fn with_supports_asof_join(mut self, supports_asof_join: bool) -> Self {
+        self.supports_asof_join = supports_asof_join;
+        self
+    }
+
+    /// Customize the dialect to write lateral joins as `CROSS APPLY` and `OUTER APPLY`
+    pub fn with_supports_apply_join(mut self, supports_apply_join: bool) -> Self {
+        self.supports_apply_join = supports_apply_join;
+        self
+    }
+
+    /// Customize the dialect to write column aliases with or without the `AS` keyword
+    pub fn with_use_as_for_column_alias(mut self, use_as_for_column_alias: bool) -> Self {
+        self.use_as_for_column_alias = use_as_for_column_alias;
+        self
+    }
+
+    /// Customize the dialect with support for filtering window function results with `QUALIFY`
+    pub fn with_supports_qualify(mut self, supports_qualify: bool) -> Self {
+        self.supports_qualify = supports_qualify;
+        self
+    }
+
+    /// Customize the dialect with support for collecting subquery rows into an array with `ARRAY(<subquery>)`
+    pub fn with_supports_array_subquery(mut self, supports_array_subquery: bool) -> Self {
+        self.supports_array_subquery = supports_array_subquery;
+        self
+    }
+
+    /// Customize the dialect with support for the `IGNORE NULLS` and `RESPECT NULLS` modifiers
+    pub fn with_supports_null_treatment(mut self, supports_null_treatment: bool) -> Self {
+        self.supports_null_treatment = supports_null_treatment;
+        self
+    }
+
+    /// Customize the dialect with support for the `FILTER (WHERE ...)` clause of aggregate functions
+    pub fn with_supports_aggregate_filter(
+        mut self,
+        supports_aggregate_filter: bool,
+    ) -> Self {
+        self.supports_aggregate_filter = supports_aggregate_filter;
+        self
+    }
+
+    /// Customize the dialect with a specific style for emitting `LIMIT` and `OFFSET`
+    pub fn with_limit_style(mut self, limit_style: LimitStyle) -> Self {
+        self.limit_style = limit_style;
+        self
+    }
+
+    /// Customize the dialect with a specific style for emitting a sample of the rows of a table
+    pub fn with_table_sample_style(
+        mut self,
+        table_sample_style: TableSampleStyle,
+    ) -> Self {
+        self.table_sample_style = table_sample_style;
+        self
+    }
+
+    /// Customize the dialect with a specific style for emitting `GROUP BY` expressions
+    pub fn with_group_by_style(mut self, group_by_style: GroupByStyle) -> Self {
+        self.group_by_style = group_by_style;
+        self
+    }
+
+    /// Customize the dialect with a specific style for emitting column exclusions
+    pub fn with_column_exclusion_style(
+        mut self,
+        column_exclusion_style: ColumnExclusionStyle,
+    ) -> Self {
+        self.column_exclusion_style = column_exclusion_style;
+        self
+    }
+
+    /// Customize the dialect with a specific style for emitting `string_agg`
+    pub fn with_string_agg_style(mut self, string_agg_style: StringAggStyle) -> Self {
+        self.string_agg_style = string_agg_style;
+        self
+    }
+
+    /// Customize the dialect with the keywords that can not be used as unquoted
+    /// identifiers
+    pub fn with_reserved_keywords(mut self, reserved_keywords: Vec<Keyword>) -> Self {
+        self.reserved_keywords = reserved_keywords;
+        self
+    }
+
+    /// Customize the dialect to fold unquoted identifiers to lowercase, so that
+    /// identifiers with uppercase characters are quoted
+    pub fn with_folds_identifiers_to_lowercase(
+        mut self,
+        folds_identifiers_to_lowercase: bool,
+    ) -> Self {
+        self.folds_identifiers_to_lowercase = folds_identifiers_to_lowercase;
+        self
+    }
 }
@@ -19,33 +19,41 @@ use datafusion_expr::expr::{AggregateFunctionParams, Unnest, WindowFunctionParam
 use sqlparser::ast::Value::SingleQuotedString;
 use sqlparser::ast::{
     self, Array, BinaryOperator, CaseWhen, DuplicateTreatment, Expr as AstExpr, Function,
-    Ident, Interval, ObjectName, OrderByOptions, Subscript, TimezoneInfo, UnaryOperator,
-    ValueWithSpan,
+    Ident, Interval, NullTreatment, ObjectName, OrderByOptions, Subscript, TimezoneInfo,
+    UnaryOperator,
 };
 use std::sync::Arc;
 use std::vec;
 
-use super::dialect::IntervalStyle;
+use super::dialect::{DateTimeLiteralStyle, IntervalStyle, StringAggStyle};
+use super::utils::array_subquery_input;
 use super::Unparser;
+use arrow::array::temporal_conversions::{
+    as_date, as_datetime, as_datetime_with_timezone,
+};
+use arrow::array::timezone::Tz;
 use arrow::array::{
     types::{
-        ArrowTemporalType, Time32MillisecondType, Time32SecondType,
+        ArrowTemporalType, Date32Type, Time32MillisecondType, Time32SecondType,
         Time64MicrosecondType, Time64NanosecondType, TimestampMicrosecondType,
         TimestampMillisecondType, TimestampNanosecondType, TimestampSecondType,
     },
     ArrayRef, Date32Array, Date64Array, PrimitiveArray,
 };
-use arrow::datatypes::{DataType, Decimal128Type, Decimal256Type, DecimalType};
+use arrow::compute::kernels::cast_utils::parse_interval_month_day_nano;
+use arrow::datatypes::{DataType, Decimal128Type, Decimal256Type, DecimalType, TimeUnit};
 use arrow::util::display::array_value_to_string;
 use datafusion_common::{
     internal_datafusion_err, internal_err, not_impl_err, plan_err, Column, Result,
-    ScalarValue,
+    ScalarValue, TableReference,
 };
 use datafusion_expr::{
     expr::{Alias, Exists, InList, ScalarFunction, Sort, WindowFunction},
     Between, BinaryExpr, Case, Cast, Expr, GroupingSet, Like, Operator, TryCast,
+    WindowFrame, WindowFrameBound, WindowFrameUnits, WindowFunctionDefinition,
 };
 use sqlparser::ast::helpers::attached_token::AttachedToken;
+use sqlparser::keywords::{ALL_KEYWORDS, ALL_KEYWORDS_INDEX};
 use sqlparser::tokenizer::Span;
 
 /// Convert a DataFusion [`Expr`] to [`ast::Expr`]
@@ -142,6 +150,34 @@ impl Unparser<'_> {
                 ))))
             }
             Expr::Column(col) => self.col_to_sql(col),
+            Expr::BinaryExpr(BinaryExpr { left, op, right })
+                if matches!(op, Operator::ILikeMatch | Operator::NotILikeMatch)
+                    && !self.dialect.supports_ilike() =>
+            {
+                // `~~*` is only understood where ILIKE is, fall back to the
+                // same `LOWER(..) LIKE LOWER(..)` rewrite as `Expr::Like`
+                let like = Like::new(false, left.clone(), right.clone(), None, true);
+                let negated = *op == Operator::NotILikeMatch;
+                Ok(ast::Expr::Nested(Box::new(
+                    self.like_to_sql(&like, negated)?,
+                )))
+            }
+            Expr::BinaryExpr(BinaryExpr { left, op, right })
+                if matches!(
+                    op,
+                    Operator::IsDistinctFrom | Operator::IsNotDistinctFrom
+                ) =>
+            {
+                // sqlparser models these as dedicated expressions rather than
+                // binary operators
+                let l = Box::new(self.expr_to_sql_inner(left.as_ref())?);
+                let r = Box::new(self.expr_to_sql_inner(right.as_ref())?);
+                let expr = match op {
+                    Operator::IsDistinctFrom => ast::Expr::IsDistinctFrom(l, r),
+                    _ => ast::Expr::IsNotDistinctFrom(l, r),
+                };
+                Ok(ast::Expr::Nested(Box::new(expr)))
+            }
             Expr::BinaryExpr(BinaryExpr { left, op, right }) => {
                 let l = self.expr_to_sql_inner(left.as_ref())?;
                 let r = self.expr_to_sql_inner(right.as_ref())?;
@@ -201,48 +237,35 @@ impl Unparser<'_> {
                             partition_by,
                             order_by,
                             window_frame,
+                            null_treatment,
                             distinct,
-                            ..
                         },
                 } = window_fun.as_ref();
                 let func_name = fun.name();
 
+                // Filtering the rows of a window would also change its frame,
+                // so `IGNORE NULLS` can not be replaced by a `FILTER` here
+                let (filter, null_treatment) = self.null_treatment_to_sql(
+                    func_name,
+                    *null_treatment,
+                    args,
+                    None,
+                    false,
+                )?;
                 let args = self.function_args_to_sql(args)?;
 
-                let units = match window_frame.units {
-                    datafusion_expr::window_frame::WindowFrameUnits::Rows => {
-                        ast::WindowFrameUnits::Rows
-                    }
-                    datafusion_expr::window_frame::WindowFrameUnits::Range => {
-                        ast::WindowFrameUnits::Range
-                    }
-                    datafusion_expr::window_frame::WindowFrameUnits::Groups => {
-                        ast::WindowFrameUnits::Groups
-                    }
-                };
+                let window_frame = self.window_frame_to_sql(
+                    func_name,
+                    matches!(fun, WindowFunctionDefinition::AggregateUDF(_)),
+                    window_frame,
+                    order_by,
+                )?;
 
                 let order_by = order_by
                     .iter()
                     .map(|sort_expr| self.sort_to_sql(sort_expr))
                     .collect::<Result<Vec<_>>>()?;
 
-                let start_bound = self.convert_bound(&window_frame.start_bound)?;
-                let end_bound = self.convert_bound(&window_frame.end_bound)?;
-
-                let window_frame = if self.dialect.window_func_support_window_frame(
-                    func_name,
-                    &start_bound,
-                    &end_bound,
-                ) {
-                    Some(ast::WindowFrame {
-                        units,
-                        start_bound,
-                        end_bound: Some(end_bound),
-                    })
-                } else {
-                    None
-                };
-
                 let over = Some(ast::WindowType::WindowSpec(ast::WindowSpec {
                     window_name: None,
                     partition_by: partition_by
@@ -265,54 +288,16 @@ impl Unparser<'_> {
                         args,
                         clauses: vec![],
                     }),
-                    filter: None,
-                    null_treatment: None,
+                    filter,
+                    null_treatment,
                     over,
                     within_group: vec![],
                     parameters: ast::FunctionArguments::None,
                     uses_odbc_syntax: false,
                 }))
             }
-            Expr::SimilarTo(Like {
-                negated,
-                expr,
-                pattern,
-                escape_char,
-                case_insensitive: _,
-            }) => Ok(ast::Expr::Like {
-                negated: *negated,
-                expr: Box::new(self.expr_to_sql_inner(expr)?),
-                pattern: Box::new(self.expr_to_sql_inner(pattern)?),
-                escape_char: escape_char.map(|c| SingleQuotedString(c.to_string())),
-                any: false,
-            }),
-            Expr::Like(Like {
-                negated,
-                expr,
-                pattern,
-                escape_char,
-                case_insensitive,
-            }) => {
-                if *case_insensitive {
-                    Ok(ast::Expr::ILike {
-                        negated: *negated,
-                        expr: Box::new(self.expr_to_sql_inner(expr)?),
-                        pattern: Box::new(self.expr_to_sql_inner(pattern)?),
-                        escape_char: escape_char
-                            .map(|c| SingleQuotedString(c.to_string())),
-                        any: false,
-                    })
-                } else {
-                    Ok(ast::Expr::Like {
-                        negated: *negated,
-                        expr: Box::new(self.expr_to_sql_inner(expr)?),
-                        pattern: Box::new(self.expr_to_sql_inner(pattern)?),
-                        escape_char: escape_char
-                            .map(|c| SingleQuotedString(c.to_string())),
-                        any: false,
-                    })
-                }
-            }
+            Expr::SimilarTo(like) => self.similar_to_sql(like),
+            Expr::Like(like) => self.like_to_sql(like, like.negated),
 
             Expr::AggregateFunction(agg) => {
                 let func_name = agg.func.name();
@@ -321,23 +306,38 @@ impl Unparser<'_> {
                     args,
                     filter,
                     order_by,
-                    ..
+                    null_treatment,
                 } = &agg.params;
 
-                let args = self.function_args_to_sql(args)?;
-                let filter = match filter {
-                    Some(filter) => Some(Box::new(self.expr_to_sql_inner(filter)?)),
-                    None => None,
+                let (filter, null_treatment) = self.null_treatment_to_sql(
+                    func_name,
+                    *null_treatment,
+                    args,
+                    filter.as_deref(),
+                    true,
+                )?;
+                let order_by = order_by
+                    .iter()
+                    .map(|sort_expr| self.sort_to_sql(sort_expr))
+                    .collect::<Result<Vec<ast::OrderByExpr>>>()?;
+                let mut clauses = vec![];
+                let within_group = if agg.func.is_ordered_set_aggregate() {
+                    order_by
+                } else {
+                    if !order_by.is_empty() {
+                        clauses.push(ast::FunctionArgumentClause::OrderBy(order_by));
+                    }
+                    Vec::new()
+                };
+                let (func_name, args) = if func_name == "string_agg"
+                    && self.dialect.string_agg_style() == StringAggStyle::GroupConcat
+                {
+                    let (arg, separator) = self.group_concat_args_to_sql(args)?;
+                    clauses.push(ast::FunctionArgumentClause::Separator(separator));
+                    ("GROUP_CONCAT", vec![arg])
+                } else {
+                    (func_name, self.function_args_to_sql(args)?)
                 };
-                let within_group: Vec<ast::OrderByExpr> =
-                    if agg.func.is_ordered_set_aggregate() {
-                        order_by
-                            .iter()
-                            .map(|sort_expr| self.sort_to_sql(sort_expr))
-                            .collect::<Result<Vec<ast::OrderByExpr>>>()?
-                    } else {
-                        Vec::new()
-                    };
                 Ok(ast::Expr::Function(Function {
                     name: ObjectName::from(vec![Ident {
                         value: func_name.to_string(),
@@ -348,10 +348,10 @@ impl Unparser<'_> {
                         duplicate_treatment: distinct
                             .then_some(DuplicateTreatment::Distinct),
                         args,
-                        clauses: vec![],
+                        clauses,
                     }),
                     filter,
-                    null_treatment: None,
+                    null_treatment,
                     over: None,
                     within_group,
                     parameters: ast::FunctionArguments::None,
@@ -359,7 +359,14 @@ impl Unparser<'_> {
                 }))
             }
             Expr::ScalarSubquery(subq) => {
-                let sub_statement = self.plan_to_sql(subq.subquery.as_ref())?;
+                let array_subquery = if self.dialect.supports_array_subquery() {
+                    array_subquery_input(subq.subquery.as_ref())?
+                } else {
+                    None
+                };
+                let sub_statement = self.plan_to_sql_inner(
+                    array_subquery.as_ref().unwrap_or(subq.subquery.as_ref()),
+                )?;
                 let sub_query = if let ast::Statement::Query(inner_query) = sub_statement
                 {
                     inner_query
@@ -368,12 +375,24 @@ impl Unparser<'_> {
                         "Subquery must be a Query, but found {sub_statement:?}"
                     );
                 };
-                Ok(ast::Expr::Subquery(sub_query))
+                if array_subquery.is_none() {
+                    return Ok(ast::Expr::Subquery(sub_query));
+                }
+                Ok(ast::Expr::Function(Function {
+                    name: ObjectName::from(vec![Ident::new("ARRAY")]),
+                    args: ast::FunctionArguments::Subquery(sub_query),
+                    filter: None,
+                    null_treatment: None,
+                    over: None,
+                    within_group: vec![],
+                    parameters: ast::FunctionArguments::None,
+                    uses_odbc_syntax: false,
+                }))
             }
             Expr::InSubquery(insubq) => {
                 let inexpr = Box::new(self.expr_to_sql_inner(insubq.expr.as_ref())?);
                 let sub_statement =
-                    self.plan_to_sql(insubq.subquery.subquery.as_ref())?;
+                    self.plan_to_sql_inner(insubq.subquery.subquery.as_ref())?;
                 let sub_query = if let ast::Statement::Query(inner_query) = sub_statement
                 {
                     inner_query
@@ -389,7 +408,7 @@ impl Unparser<'_> {
                 })
             }
             Expr::Exists(Exists { subquery, negated }) => {
-                let sub_statement = self.plan_to_sql(subquery.subquery.as_ref())?;
+                let sub_statement = self.plan_to_sql_inner(subquery.subquery.as_ref())?;
                 let sub_query = if let ast::Statement::Query(inner_query) = sub_statement
                 {
                     inner_query
@@ -427,13 +446,18 @@ impl Unparser<'_> {
             Expr::IsNotUnknown(expr) => Ok(ast::Expr::IsNotUnknown(Box::new(
                 self.expr_to_sql_inner(expr)?,
             ))),
-            Expr::Not(expr) => {
-                let sql_parser_expr = self.expr_to_sql_inner(expr)?;
-                Ok(AstExpr::UnaryOp {
-                    op: UnaryOperator::Not,
-                    expr: Box::new(sql_parser_expr),
-                })
-            }
+            // Fold `NOT (a LIKE b)` into `a NOT LIKE b`, which keeps `ILIKE`
+            // intact and lets dialects without it negate the rewritten form
+            Expr::Not(expr) => match expr.as_ref() {
+                Expr::Like(like) => self.like_to_sql(like, !like.negated),
+                _ => {
+                    let sql_parser_expr = self.expr_to_sql_inner(expr)?;
+                    Ok(AstExpr::UnaryOp {
+                        op: UnaryOperator::Not,
+                        expr: Box::new(sql_parser_expr),
+                    })
+                }
+            },
             Expr::Negative(expr) => {
                 let sql_parser_expr = self.expr_to_sql_inner(expr)?;
                 Ok(AstExpr::UnaryOp {
@@ -546,7 +570,15 @@ impl Unparser<'_> {
         args: &[Expr],
     ) -> Result<ast::Expr> {
         let args = self.function_args_to_sql(args)?;
-        Ok(ast::Expr::Function(Function {
+        Ok(self.function_with_args_to_sql(func_name, args))
+    }
+
+    fn function_with_args_to_sql(
+        &self,
+        func_name: &str,
+        args: Vec<ast::FunctionArg>,
+    ) -> ast::Expr {
+        ast::Expr::Function(Function {
             name: ObjectName::from(vec![Ident {
                 value: func_name.to_string(),
                 quote_style: None,
@@ -563,7 +595,65 @@ impl Unparser<'_> {
             within_group: vec![],
             parameters: ast::FunctionArguments::None,
             uses_odbc_syntax: false,
-        }))
+        })
+    }
+
+    fn like_to_sql(&self, like: &Like, negated: bool) -> Result<ast::Expr> {
+        let escape_char = like.escape_char.map(|c| SingleQuotedString(c.to_string()));
+        let expr = self.expr_to_sql_inner(&like.expr)?;
+        let pattern = self.expr_to_sql_inner(&like.pattern)?;
+        if !like.case_insensitive {
+            return Ok(ast::Expr::Like {
+                negated,
+                expr: Box::new(expr),
+                pattern: Box::new(pattern),
+                escape_char,
+                any: false,
+            });
+        }
+
+        if self.dialect.supports_ilike() {
+            Ok(ast::Expr::ILike {
+                negated,
+                expr: Box::new(expr),
+                pattern: Box::new(pattern),
+                escape_char,
+                any: false,
+            })
+        } else {
+            Ok(ast::Expr::Like {
+                negated,
+                expr: Box::new(self.lower_to_sql(expr)),
+                pattern: Box::new(self.lower_to_sql(pattern)),
+                escape_char,
+                any: false,
+            })
+        }
+    }
+
+    /// There is no case-insensitive SIMILAR TO in SQL, so a case-insensitive
+    /// [`Expr::SimilarTo`] is emitted with both sides wrapped in `LOWER`.
+    fn similar_to_sql(&self, like: &Like) -> Result<ast::Expr> {
+        let escape_char = like.escape_char.map(|c| SingleQuotedString(c.to_string()));
+        let mut expr = self.expr_to_sql_inner(&like.expr)?;
+        let mut pattern = self.expr_to_sql_inner(&like.pattern)?;
+        if like.case_insensitive {
+            expr = self.lower_to_sql(expr);
+            pattern = self.lower_to_sql(pattern);
+        }
+        Ok(ast::Expr::SimilarTo {
+            negated: like.negated,
+            expr: Box::new(expr),
+            pattern: Box::new(pattern),
+            escape_char,
+        })
+    }
+
+    fn lower_to_sql(&self, arg: ast::Expr) -> ast::Expr {
+        self.function_with_args_to_sql(
+            "LOWER",
+            vec![ast::FunctionArg::Unnamed(ast::FunctionArgExpr::Expr(arg))],
+        )
     }
 
     fn make_array_to_sql(&self, args: &[Expr]) -> Result<ast::Expr> {
@@ -732,20 +822,10 @@ impl Unparser<'_> {
     }
 
     pub fn col_to_sql(&self, col: &Column) -> Result<ast::Expr> {
-        // Replace the column name if the dialect has an override
-        let col_name =
-            if let Some(rewritten_name) = self.dialect.col_alias_overrides(&col.name)? {
-                rewritten_name
-            } else {
-                col.name.to_string()
-            };
+        let col_name = self.col_name_to_sql(&col.name)?;
 
         if let Some(table_ref) = &col.relation {
-            let mut id = if self.dialect.full_qualified_col() {
-                table_ref.to_vec()
-            } else {
-                vec![table_ref.table().to_string()]
-            };
+            let mut id = self.col_qualifier_to_sql(table_ref);
             id.push(col_name);
             return Ok(ast::Expr::CompoundIdentifier(
                 id.iter()
@@ -758,45 +838,184 @@ impl Unparser<'_> {
         ))
     }
 
+    /// The name of a column, replaced if the dialect has an override
+    pub(super) fn col_name_to_sql(&self, name: &str) -> Result<String> {
+        Ok(self
+            .dialect
+            .col_alias_overrides(name)?
+            .unwrap_or_else(|| name.to_string()))
+    }
+
+    /// The identifiers qualifying a column of `table_ref`
+    pub(super) fn col_qualifier_to_sql(&self, table_ref: &TableReference) -> Vec<String> {
+        if self.dialect.full_qualified_col() {
+            table_ref.to_vec()
+        } else {
+            vec![table_ref.table().to_string()]
+        }
+    }
+
+    /// Unparse the frame of a window function, returning `None` if the frame
+    /// can be omitted.
+    ///
+    /// Returns an error rather than SQL that other engines would reject if the
+    /// bounds of the frame do not fit its units or `ORDER BY` clause, or
+    /// rather than SQL that computes the aggregate function `func_name` over
+    /// another frame if the dialect does not support its frame.
+    fn window_frame_to_sql(
+        &self,
+        func_name: &str,
+        is_aggregate: bool,
+        window_frame: &WindowFrame,
+        order_by: &[Sort],
+    ) -> Result<Option<ast::WindowFrame>> {
+        let units = match window_frame.units {
+            WindowFrameUnits::Rows => ast::WindowFrameUnits::Rows,
+            WindowFrameUnits::Range => ast::WindowFrameUnits::Range,
+            WindowFrameUnits::Groups => ast::WindowFrameUnits::Groups,
+        };
+
+        let start_offset = self.frame_offset(window_frame, &window_frame.start_bound)?;
+        let end_offset = self.frame_offset(window_frame, &window_frame.end_bound)?;
+        for offset in start_offset.iter().chain(end_offset.iter()) {
+            validate_frame_offset(window_frame, offset, order_by)?;
+        }
+        validate_frame_bounds(window_frame, order_by)?;
+
+        if self.dialect.omit_default_window_frame()
+            && is_default_window_frame(window_frame, order_by)
+        {
+            return Ok(None);
+        }
+
+        let start_bound =
+            self.convert_bound(&window_frame.start_bound, start_offset.as_ref())?;
+        let end_bound =
+            self.convert_bound(&window_frame.end_bound, end_offset.as_ref())?;
+
+        if !self.dialect.window_func_support_window_frame(
+            func_name,
+            &start_bound,
+            &end_bound,
+        ) {
+            // An aggregate function is computed over the implicit frame when
+            // its frame is omitted
+            if is_aggregate && !is_default_window_frame(window_frame, order_by) {
+                return not_impl_err!(
+                    "Cannot unparse window frame {window_frame} of {func_name}: the dialect does not support a window frame for it"
+                );
+            }
+            return Ok(None);
+        }
+
+        Ok(Some(ast::WindowFrame {
+            units,
+            start_bound,
+            end_bound: Some(end_bound),
+        }))
+    }
+
+    /// Returns the offset of a `PRECEDING` / `FOLLOWING` bound, or `None` for
+    /// `CURRENT ROW` and `UNBOUNDED` bounds.
+    ///
+    /// The SQL planner keeps `RANGE` offsets as strings until the analyzer coerces
+    /// them to the type of the `ORDER BY` expression, so these are parsed back
+    /// into a number or an interval.
+    fn frame_offset(
+        &self,
+        window_frame: &WindowFrame,
+        bound: &WindowFrameBound,
+    ) -> Result<Option<ScalarValue>> {
+        let (WindowFrameBound::Preceding(offset) | WindowFrameBound::Following(offset)) =
+            bound
+        else {
+            return Ok(None);
+        };
+        if offset.is_null() {
+            return Ok(None);
+        }
+        match offset {
+            ScalarValue::Utf8(Some(value))
+            | ScalarValue::Utf8View(Some(value))
+            | ScalarValue::LargeUtf8(Some(value)) => {
+                if let Ok(value) = value.parse::<i64>() {
+                    Ok(Some(ScalarValue::Int64(Some(value))))
+                } else if let Ok(value) = value.parse::<f64>() {
+                    Ok(Some(ScalarValue::Float64(Some(value))))
+                } else if let Ok(value) = parse_interval_month_day_nano(value) {
+                    Ok(Some(ScalarValue::IntervalMonthDayNano(Some(value))))
+                } else {
+                    plan_err!(
+                        "Cannot unparse window frame {window_frame}: offset '{value}' is neither a number nor an interval"
+                    )
+                }
+            }
+            offset => Ok(Some(offset.clone())),
+        }
+    }
+
     fn convert_bound(
         &self,
-        bound: &datafusion_expr::window_frame::WindowFrameBound,
+        bound: &WindowFrameBound,
+        offset: Option<&ScalarValue>,
     ) -> Result<ast::WindowFrameBound> {
+        let offset = offset
+            .map(|offset| self.scalar_to_sql(offset).map(Box::new))
+            .transpose()?;
         match bound {
-            datafusion_expr::window_frame::WindowFrameBound::Preceding(val) => {
-                Ok(ast::WindowFrameBound::Preceding({
-                    let val = self.scalar_to_sql(val)?;
-                    if let ast::Expr::Value(ValueWithSpan {
-                        value: ast::Value::Null,
-                        span: _,
-                    }) = &val
-                    {
-                        None
-                    } else {
-                        Some(Box::new(val))
-                    }
-                }))
+            WindowFrameBound::Preceding(_) => {
+                Ok(ast::WindowFrameBound::Preceding(offset))
             }
-            datafusion_expr::window_frame::WindowFrameBound::Following(val) => {
-                Ok(ast::WindowFrameBound::Following({
-                    let val = self.scalar_to_sql(val)?;
-                    if let ast::Expr::Value(ValueWithSpan {
-                        value: ast::Value::Null,
-                        span: _,
-                    }) = &val
-                    {
-                        None
-                    } else {
-                        Some(Box::new(val))
-                    }
-                }))
-            }
-            datafusion_expr::window_frame::WindowFrameBound::CurrentRow => {
-                Ok(ast::WindowFrameBound::CurrentRow)
+            WindowFrameBound::Following(_) => {
+                Ok(ast::WindowFrameBound::Following(offset))
             }
+            WindowFrameBound::CurrentRow => Ok(ast::WindowFrameBound::CurrentRow),
         }
     }
 
+    /// Returns the `FILTER` clause and null treatment modifier of a call to
+    /// `func_name`.
+    ///
+    /// When the dialect does not support `IGNORE NULLS`, it is replaced by
+    /// filtering out the rows where the first argument is null. Only
+    /// `filterable` functions, aggregate functions outside of a window, allow
+    /// this, in dialects supporting `FILTER`. `RESPECT NULLS` is the default
+    /// behavior, so it is omitted in that case.
+    fn null_treatment_to_sql(
+        &self,
+        func_name: &str,
+        null_treatment: Option<NullTreatment>,
+        args: &[Expr],
+        filter: Option<&Expr>,
+        filterable: bool,
+    ) -> Result<(Option<Box<ast::Expr>>, Option<NullTreatment>)> {
+        let (filter, null_treatment) = match null_treatment {
+            Some(_) if self.dialect.supports_null_treatment() => {
+                (filter.cloned(), null_treatment)
+            }
+            Some(NullTreatment::IgnoreNulls) => {
+                let filterable = filterable && self.dialect.supports_aggregate_filter();
+                let Some(arg) = args.first().filter(|_| filterable) else {
+                    return not_impl_err!(
+                        "Unsupported IGNORE NULLS for {func_name} in the dialect"
+                    );
+                };
+                let not_null = arg.clone().is_not_null();
+                let filter = match filter {
+                    Some(filter) => filter.clone().and(not_null),
+                    None => not_null,
+                };
+                (Some(filter), None)
+            }
+            Some(NullTreatment::RespectNulls) | None => (filter.cloned(), None),
+        };
+        let filter = match filter {
+            Some(filter) => Some(Box::new(self.expr_to_sql_inner(&filter)?)),
+            None => None,
+        };
+        Ok((filter, null_treatment))
+    }
+
     pub(crate) fn function_args_to_sql(
         &self,
         args: &[Expr],
@@ -820,9 +1039,44 @@ impl Unparser<'_> {
             .collect::<Result<Vec<_>>>()
     }
 
+    /// Splits the arguments of `string_agg` into the aggregated argument and
+    /// the `SEPARATOR` of the MySQL `GROUP_CONCAT` spelling, which only accepts
+    /// a string literal
+    fn group_concat_args_to_sql(
+        &self,
+        args: &[Expr],
+    ) -> Result<(ast::FunctionArg, ast::Value)> {
+        let [value, Expr::Literal(delimiter, _)] = args else {
+            return not_impl_err!(
+                "Unsupported GROUP_CONCAT with a non-literal delimiter: {args:?}"
+            );
+        };
+        let Some(Some(delimiter)) = delimiter.try_as_str() else {
+            return not_impl_err!(
+                "Unsupported GROUP_CONCAT with a non-string delimiter: {delimiter:?}"
+            );
+        };
+        let value = self.expr_to_sql(value)?;
+        Ok((
+            ast::FunctionArg::Unnamed(ast::FunctionArgExpr::Expr(value)),
+            SingleQuotedString(delimiter.to_string()),
+        ))
+    }
+
     /// This function can create an identifier with or without quotes based on the dialect rules
+    ///
+    /// With [`Unparser::with_quote_identifiers`], identifiers are always quoted,
+    /// with the dialect's [`required_identifier_quote_style`] if the dialect
+    /// would not quote them.
+    ///
+    /// [`required_identifier_quote_style`]: super::dialect::Dialect::required_identifier_quote_style
     pub(super) fn new_ident_quoted_if_needs(&self, ident: String) -> Ident {
-        let quote_style = self.dialect.identifier_quote_style(&ident);
+        let quote_style = match self.dialect.identifier_quote_style(&ident) {
+            None if self.quote_identifiers || self.requires_quotes(&ident) => {
+                Some(self.dialect.required_identifier_quote_style())
+            }
+            quote_style => quote_style,
+        };
         Ident {
             value: ident,
             quote_style,
@@ -830,6 +1084,28 @@ impl Unparser<'_> {
         }
     }
 
+    /// Whether `ident` can not be written unquoted in the dialect: it is a
+    /// reserved keyword, is not made of letters, digits and underscores, starts
+    /// with a digit, or would have its case folded
+    fn requires_quotes(&self, ident: &str) -> bool {
+        let mut chars = ident.chars();
+        let valid_chars = chars
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+        let is_reserved = ALL_KEYWORDS
+            .binary_search(&ident.to_uppercase().as_str())
+            .is_ok_and(|i| {
+                self.dialect
+                    .reserved_keywords()
+                    .contains(&ALL_KEYWORDS_INDEX[i])
+            });
+        !valid_chars
+            || is_reserved
+            || (self.dialect.folds_identifiers_to_lowercase()
+                && ident != ident.to_lowercase())
+    }
+
     pub(super) fn new_ident_without_quote_style(&self, str: String) -> Ident {
         Ident {
             value: str,
@@ -1127,6 +1403,9 @@ impl Unparser<'_> {
     // Explicit type cast on ast::Expr::Value is not needed by underlying engine for certain types
     // For example: CAST(Utf8("binary_value") AS Binary) and  CAST(Utf8("dictionary_value") AS Dictionary)
     fn cast_to_sql(&self, expr: &Expr, data_type: &DataType) -> Result<ast::Expr> {
+        if let Some(typed_string) = self.date_time_typed_string(expr, data_type)? {
+            return Ok(typed_string);
+        }
         let inner_expr = self.expr_to_sql_inner(expr)?;
         match inner_expr {
             ast::Expr::Value(_) => match data_type {
@@ -1149,6 +1428,93 @@ impl Unparser<'_> {
         }
     }
 
+    /// Unparse a cast of a string literal to a date or timestamp type as a typed
+    /// literal, e.g. `TIMESTAMP '2024-01-01 00:00:00'`, if the dialect uses the
+    /// [`DateTimeLiteralStyle::TypedString`] style.
+    ///
+    /// Returns `None` if the cast should be unparsed as is, including for
+    /// strings that are not valid for the type, so that they fail the same way
+    /// in the target engine.
+    fn date_time_typed_string(
+        &self,
+        expr: &Expr,
+        data_type: &DataType,
+    ) -> Result<Option<ast::Expr>> {
+        if self.dialect.date_time_literal_style() != DateTimeLiteralStyle::TypedString {
+            return Ok(None);
+        }
+        let Expr::Literal(
+            ScalarValue::Utf8(Some(value))
+            | ScalarValue::Utf8View(Some(value))
+            | ScalarValue::LargeUtf8(Some(value)),
+            _,
+        ) = expr
+        else {
+            return Ok(None);
+        };
+
+        let (typed_string_dtype, value) = match data_type {
+            DataType::Date32 => {
+                let Ok(ScalarValue::Date32(Some(days))) =
+                    ScalarValue::try_from_string(value.clone(), data_type)
+                else {
+                    return Ok(None);
+                };
+                let Some(date) = as_date::<Date32Type>(days.into()) else {
+                    return Ok(None);
+                };
+                (self.dialect.date32_cast_dtype(), date.to_string())
+            }
+            DataType::Timestamp(time_unit, tz) => {
+                // A timezone that can not be parsed is taken as UTC, the value is
+                // written with its offset so the literal keeps the same instant
+                const UTC: &str = "+00:00";
+                let parsed_tz = match tz {
+                    Some(tz) => Some(match tz.parse::<Tz>() {
+                        Ok(parsed) => (Arc::clone(tz), parsed),
+                        Err(_) => (Arc::from(UTC), UTC.parse()?),
+                    }),
+                    None => None,
+                };
+                // Cast to the type first so that the literal is truncated to its
+                // precision, then to nanoseconds to format the value
+                let cast_type = DataType::Timestamp(
+                    *time_unit,
+                    parsed_tz.as_ref().map(|(tz, _)| Arc::clone(tz)),
+                );
+                let Ok(ScalarValue::TimestampNanosecond(Some(nanos), _)) =
+                    ScalarValue::try_from_string(value.clone(), &cast_type).and_then(
+                        |v| v.cast_to(&DataType::Timestamp(TimeUnit::Nanosecond, None)),
+                    )
+                else {
+                    return Ok(None);
+                };
+                // Timestamp literals have at most microsecond precision in
+                // most engines, which would round the value
+                if nanos % 1_000 != 0 {
+                    return Ok(None);
+                }
+                let value = match parsed_tz {
+                    Some((_, tz)) => {
+                        as_datetime_with_timezone::<TimestampNanosecondType>(nanos, tz)
+                            .map(|ts| ts.format("%Y-%m-%d %H:%M:%S%.f%:z").to_string())
+                    }
+                    None => as_datetime::<TimestampNanosecondType>(nanos)
+                        .map(|ts| ts.format("%Y-%m-%d %H:%M:%S%.f").to_string()),
+                };
+                let Some(value) = value else {
+                    return Ok(None);
+                };
+                (self.dialect.timestamp_cast_dtype(time_unit, tz), value)
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(ast::Expr::TypedString {
+            data_type: typed_string_dtype,
+            value: SingleQuotedString(value).with_empty_span(),
+        }))
+    }
+
     /// DataFusion ScalarValues sometimes require a ast::Expr to construct.
     /// For example ScalarValue::Date32(d) corresponds to the ast::Expr CAST('datestr' as DATE)
     fn scalar_to_sql(&self, v: &ScalarValue) -> Result<ast::Expr> {
@@ -1751,6 +2117,120 @@ impl Unparser<'_> {
     }
 }
 
+/// Returns true if `window_frame` is the frame used when a window has no frame
+/// clause: `RANGE BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW` if it has an
+/// `ORDER BY`, and the whole partition otherwise.
+fn is_default_window_frame(window_frame: &WindowFrame, order_by: &[Sort]) -> bool {
+    if !window_frame.start_bound.is_unbounded() {
+        return false;
+    }
+    match &window_frame.end_bound {
+        WindowFrameBound::CurrentRow => window_frame.units == WindowFrameUnits::Range,
+        WindowFrameBound::Following(offset) => offset.is_null() && order_by.is_empty(),
+        WindowFrameBound::Preceding(_) => false,
+    }
+}
+
+/// Checks that the start bound of `window_frame` does not come after its end bound
+fn validate_frame_bounds(window_frame: &WindowFrame, order_by: &[Sort]) -> Result<()> {
+    fn position(bound: &WindowFrameBound) -> u8 {
+        match bound {
+            WindowFrameBound::Preceding(offset) if offset.is_null() => 0,
+            WindowFrameBound::Preceding(_) => 1,
+            WindowFrameBound::CurrentRow => 2,
+            WindowFrameBound::Following(offset) if offset.is_null() => 4,
+            WindowFrameBound::Following(_) => 3,
+        }
+    }
+
+    let start = position(&window_frame.start_bound);
+    let end = position(&window_frame.end_bound);
+    if start == 4 || end == 0 || start > end {
+        return plan_err!(
+            "Cannot unparse window frame {window_frame}: the start bound must not come after the end bound"
+        );
+    }
+    if window_frame.units == WindowFrameUnits::Groups && order_by.is_empty() {
+        return plan_err!(
+            "Cannot unparse window frame {window_frame}: GROUPS requires an ORDER BY clause"
+        );
+    }
+    Ok(())
+}
+
+/// Checks that a `PRECEDING` / `FOLLOWING` offset fits the units of the frame and,
+/// for `RANGE` frames, the type of the `ORDER BY` expression when it is known
+fn validate_frame_offset(
+    window_frame: &WindowFrame,
+    offset: &ScalarValue,
+    order_by: &[Sort],
+) -> Result<()> {
+    let offset_type = offset.data_type();
+    let is_interval = matches!(offset_type, DataType::Interval(_));
+    if !is_interval && !offset_type.is_numeric() {
+        return plan_err!(
+            "Cannot unparse window frame {window_frame}: offset {offset} must be a number or an interval"
+        );
+    }
+    if is_negative_offset(offset) {
+        return plan_err!(
+            "Cannot unparse window frame {window_frame}: offset {offset} must not be negative"
+        );
+    }
+
+    match window_frame.units {
+        WindowFrameUnits::Rows | WindowFrameUnits::Groups => {
+            if !offset_type.is_integer() {
+                return plan_err!(
+                    "Cannot unparse window frame {window_frame}: {} offsets must be integers, found {offset_type}",
+                    window_frame.units
+                );
+            }
+        }
+        WindowFrameUnits::Range => {
+            let [sort] = order_by else {
+                return plan_err!(
+                    "Cannot unparse window frame {window_frame}: RANGE with an offset requires exactly one ORDER BY expression, found {}",
+                    order_by.len()
+                );
+            };
+            let order_type = match &sort.expr {
+                Expr::Literal(value, _) => Some(value.data_type()),
+                Expr::Cast(Cast { data_type, .. })
+                | Expr::TryCast(TryCast { data_type, .. }) => Some(data_type.clone()),
+                _ => None,
+            };
+            match order_type {
+                Some(order_type) if is_interval && !order_type.is_temporal() => {
+                    return plan_err!(
+                        "Cannot unparse window frame {window_frame}: interval offsets require a temporal ORDER BY expression, found {order_type}"
+                    );
+                }
+                Some(order_type) if !is_interval && order_type.is_temporal() => {
+                    return plan_err!(
+                        "Cannot unparse window frame {window_frame}: ORDER BY expression of type {order_type} requires an interval offset, found {offset_type}"
+                    );
+                }
+                _ => {}
+            }
+        }
+    }
+    Ok(())
+}
+
+fn is_negative_offset(offset: &ScalarValue) -> bool {
+    match offset {
+        ScalarValue::IntervalYearMonth(Some(v)) => *v < 0,
+        ScalarValue::IntervalDayTime(Some(v)) => v.days < 0 || v.milliseconds < 0,
+        ScalarValue::IntervalMonthDayNano(Some(v)) => {
+            v.months < 0 || v.days < 0 || v.nanoseconds < 0
+        }
+        offset => {
+            ScalarValue::new_zero(&offset.data_type()).is_ok_and(|zero| offset < &zero)
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::ops::{Add, Sub};
@@ -1760,19 +2240,20 @@ mod tests {
     use arrow::array::{LargeListArray, ListArray};
     use arrow::datatypes::{DataType::Int8, Field, Int32Type, Schema, TimeUnit};
     use ast::ObjectName;
-    use datafusion_common::{Spans, TableReference};
+    use datafusion_common::{assert_contains, Spans, TableReference};
     use datafusion_expr::expr::WildcardOptions;
+    use datafusion_expr::{binary_expr, interval_month_day_nano_lit, ExprFunctionExt};
     use datafusion_expr::{
         case, cast, col, cube, exists, grouping_set, interval_datetime_lit,
         interval_year_month_lit, lit, not, not_exists, out_ref_col, placeholder, rollup,
         table_scan, try_cast, when, ColumnarValue, ScalarFunctionArgs, ScalarUDF,
         ScalarUDFImpl, Signature, Volatility, WindowFrame, WindowFunctionDefinition,
     };
-    use datafusion_expr::{interval_month_day_nano_lit, ExprFunctionExt};
     use datafusion_functions::datetime::from_unixtime::FromUnixtimeFunc;
     use datafusion_functions::expr_fn::{get_field, named_struct};
     use datafusion_functions_aggregate::count::count_udaf;
     use datafusion_functions_aggregate::expr_fn::sum;
+    use datafusion_functions_aggregate::string_agg::string_agg_udaf;
     use datafusion_functions_nested::expr_fn::{array_element, make_array};
     use datafusion_functions_nested::map::map;
     use datafusion_functions_window::rank::rank_udwf;
@@ -1781,7 +2262,8 @@ mod tests {
 
     use crate::unparser::dialect::{
         CharacterLengthStyle, CustomDialect, CustomDialectBuilder, DateFieldExtractStyle,
-        DefaultDialect, Dialect, DuckDBDialect, PostgreSqlDialect, ScalarFnToSqlHandler,
+        DefaultDialect, Dialect, DuckDBDialect, MySqlDialect, PostgreSqlDialect,
+        ScalarFnToSqlHandler, StringAggStyle,
     };
 
     use super::*;
@@ -1944,7 +2426,17 @@ mod tests {
                     escape_char: Some('o'),
                     case_insensitive: true,
                 }),
-                r#"a LIKE 'foo' ESCAPE 'o'"#,
+                r#"LOWER(a) SIMILAR TO LOWER('foo') ESCAPE 'o'"#,
+            ),
+            (
+                Expr::SimilarTo(Like {
+                    negated: true,
+                    expr: Box::new(col("a")),
+                    pattern: Box::new(lit("foo")),
+                    escape_char: None,
+                    case_insensitive: false,
+                }),
+                r#"a NOT SIMILAR TO 'foo'"#,
             ),
             (
                 Expr::Literal(ScalarValue::Date64(Some(0)), None),
@@ -2081,13 +2573,9 @@ mod tests {
                         partition_by: vec![],
                         order_by: vec![Sort::new(col("a"), false, true)],
                         window_frame: WindowFrame::new_bounds(
-                            datafusion_expr::WindowFrameUnits::Range,
-                            datafusion_expr::WindowFrameBound::Preceding(
-                                ScalarValue::UInt32(Some(6)),
-                            ),
-                            datafusion_expr::WindowFrameBound::Following(
-                                ScalarValue::UInt32(Some(2)),
-                            ),
+                            WindowFrameUnits::Range,
+                            WindowFrameBound::Preceding(ScalarValue::UInt32(Some(6))),
+                            WindowFrameBound::Following(ScalarValue::UInt32(Some(2))),
                         ),
                         null_treatment: None,
                         distinct: false,
@@ -2126,6 +2614,14 @@ mod tests {
                 Expr::between(col("a"), lit(1), lit(7)),
                 r#"(a BETWEEN 1 AND 7)"#,
             ),
+            (
+                binary_expr(col("a"), Operator::IsNotDistinctFrom, col("b")),
+                r#"(a IS NOT DISTINCT FROM b)"#,
+            ),
+            (
+                binary_expr(col("a"), Operator::IsDistinctFrom, lit(1)),
+                r#"(a IS DISTINCT FROM 1)"#,
+            ),
             (Expr::Negative(Box::new(col("a"))), r#"-a"#),
             (
                 exists(Arc::new(dummy_logical_plan.clone())),
@@ -2416,6 +2912,48 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_string_agg_style() -> Result<()> {
+        let expr = string_agg_udaf()
+            .call(vec![col("x"), lit(";")])
+            .order_by(vec![col("y").sort(false, true)])
+            .distinct()
+            .build()?;
+        let tests = [
+            (
+                StringAggStyle::StringAgg,
+                "string_agg(DISTINCT x, ';' ORDER BY y DESC NULLS FIRST)",
+            ),
+            (
+                StringAggStyle::GroupConcat,
+                "GROUP_CONCAT(DISTINCT x ORDER BY y DESC NULLS FIRST SEPARATOR ';')",
+            ),
+        ];
+
+        for (style, expected) in tests {
+            let dialect = CustomDialectBuilder::new()
+                .with_string_agg_style(style)
+                .build();
+            let unparser = Unparser::new(&dialect);
+            let actual = format!("{}", unparser.expr_to_sql(&expr)?);
+            assert_eq!(actual, expected);
+        }
+
+        // The SEPARATOR of GROUP_CONCAT must be a string literal
+        let dialect = CustomDialectBuilder::new()
+            .with_string_agg_style(StringAggStyle::GroupConcat)
+            .build();
+        let unparser = Unparser::new(&dialect);
+        let expr = string_agg_udaf().call(vec![col("x"), col("sep")]);
+        let err = unparser.expr_to_sql(&expr).unwrap_err();
+        assert_contains!(
+            err.to_string(),
+            "Unsupported GROUP_CONCAT with a non-literal delimiter"
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn test_interval_scalar_to_expr() {
         let tests = [
@@ -2856,6 +3394,152 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn custom_dialect_with_date_time_literal_style() -> Result<()> {
+        let cast_dialect = CustomDialectBuilder::new().build();
+        let typed_string_dialect = CustomDialectBuilder::new()
+            .with_date_time_literal_style(DateTimeLiteralStyle::TypedString)
+            .build();
+
+        let timestamp = DataType::Timestamp(TimeUnit::Nanosecond, None);
+        let timestamp_ms = DataType::Timestamp(TimeUnit::Millisecond, None);
+        let timestamp_with_tz =
+            DataType::Timestamp(TimeUnit::Nanosecond, Some("+08:00".into()));
+        let timestamp_with_invalid_tz =
+            DataType::Timestamp(TimeUnit::Nanosecond, Some("Invalid/Zone".into()));
+
+        for (dialect, value, data_type, expected) in [
+            (
+                &cast_dialect,
+                "2024-01-01",
+                &timestamp,
+                "CAST('2024-01-01' AS TIMESTAMP)",
+            ),
+            (
+                &typed_string_dialect,
+                "2024-01-01",
+                &timestamp,
+                "TIMESTAMP '2024-01-01 00:00:00'",
+            ),
+            (
+                &typed_string_dialect,
+                "2024-01-01 12:34:56.123456",
+                &timestamp,
+                "TIMESTAMP '2024-01-01 12:34:56.123456'",
+            ),
+            // Sub-microsecond precision can not be represented by the literal
+            (
+                &typed_string_dialect,
+                "2024-01-01 12:34:56.123456789",
+                &timestamp,
+                "CAST('2024-01-01 12:34:56.123456789' AS TIMESTAMP)",
+            ),
+            // The literal is truncated to the precision of the type
+            (
+                &typed_string_dialect,
+                "2024-01-01 12:34:56.123456789",
+                &timestamp_ms,
+                "TIMESTAMP '2024-01-01 12:34:56.123'",
+            ),
+            (
+                &typed_string_dialect,
+                "2024-01-01 00:00:00",
+                &timestamp_with_tz,
+                "TIMESTAMP WITH TIME ZONE '2024-01-01 00:00:00+08:00'",
+            ),
+            (
+                &typed_string_dialect,
+                "2024-01-01T00:00:00Z",
+                &timestamp_with_tz,
+                "TIMESTAMP WITH TIME ZONE '2024-01-01 08:00:00+08:00'",
+            ),
+            // A timezone that can not be parsed falls back to UTC
+            (
+                &typed_string_dialect,
+                "2024-01-01 00:00:00",
+                &timestamp_with_invalid_tz,
+                "TIMESTAMP WITH TIME ZONE '2024-01-01 00:00:00+00:00'",
+            ),
+            (
+                &typed_string_dialect,
+                "2024-01-01",
+                &DataType::Date32,
+                "DATE '2024-01-01'",
+            ),
+            (
+                &typed_string_dialect,
+                "not a date",
+                &DataType::Date32,
+                "CAST('not a date' AS DATE)",
+            ),
+        ] {
+            let unparser = Unparser::new(dialect);
+            let expr = Expr::Cast(Cast {
+                expr: Box::new(lit(value)),
+                data_type: data_type.clone(),
+            });
+            let ast = unparser.expr_to_sql(&expr)?;
+
+            let actual = format!("{ast}");
+
+            assert_eq!(actual, expected);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn custom_dialect_with_supports_ilike() -> Result<()> {
+        let ilike = Expr::Like(Like::new(
+            false,
+            Box::new(col("a")),
+            Box::new(lit("foo!_%")),
+            Some('!'),
+            true,
+        ));
+        let tests = [
+            (true, ilike.clone(), "a ILIKE 'foo!_%' ESCAPE '!'"),
+            (true, not(ilike.clone()), "a NOT ILIKE 'foo!_%' ESCAPE '!'"),
+            (
+                false,
+                ilike.clone(),
+                "LOWER(a) LIKE LOWER('foo!_%') ESCAPE '!'",
+            ),
+            (
+                false,
+                not(ilike),
+                "LOWER(a) NOT LIKE LOWER('foo!_%') ESCAPE '!'",
+            ),
+            (
+                true,
+                binary_expr(col("a"), Operator::ILikeMatch, lit("foo")),
+                "(a ~~* 'foo')",
+            ),
+            (
+                false,
+                binary_expr(col("a"), Operator::ILikeMatch, lit("foo")),
+                "(LOWER(a) LIKE LOWER('foo'))",
+            ),
+            (
+                false,
+                binary_expr(col("a"), Operator::NotILikeMatch, lit("foo")),
+                "(LOWER(a) NOT LIKE LOWER('foo'))",
+            ),
+            (false, col("a").like(lit("foo")), "a LIKE 'foo'"),
+            (false, not(col("a").not_like(lit("foo"))), "a LIKE 'foo'"),
+        ];
+
+        for (supports_ilike, expr, expected) in tests {
+            let dialect = CustomDialectBuilder::new()
+                .with_supports_ilike(supports_ilike)
+                .build();
+            let unparser = Unparser::new(&dialect);
+            let actual = format!("{}", unparser.expr_to_sql(&expr)?);
+
+            assert_eq!(actual, expected);
+        }
+        Ok(())
+    }
+
     #[test]
     fn custom_dialect_division_operator() -> Result<()> {
         let default_dialect = CustomDialectBuilder::new().build();
@@ -2956,7 +3640,7 @@ mod tests {
                 default_dialect,
                 "rank() OVER (ORDER BY a ASC NULLS FIRST ROWS BETWEEN UNBOUNDED PRECEDING AND UNBOUNDED FOLLOWING)",
             ),
-            (test_dialect, "rank() OVER (ORDER BY a ASC NULLS FIRST)"),
+            (test_dialect.clone(), "rank() OVER (ORDER BY a ASC NULLS FIRST)"),
         ] {
             let unparser = Unparser::new(dialect.as_ref());
             let func = WindowFunctionDefinition::WindowUDF(rank_udwf());
@@ -2970,9 +3654,208 @@ mod tests {
 
             assert_eq!(actual, expected);
         }
+
+        // The implicit frame of an aggregate function can be omitted, but
+        // omitting any other frame would change its result
+        let unparser = Unparser::new(test_dialect.as_ref());
+        let order_by = vec![Sort::new(col("a"), true, true)];
+        let expr = count_over(order_by.clone(), WindowFrame::new(Some(false)));
+        assert_eq!(
+            unparser.expr_to_sql(&expr)?.to_string(),
+            "count(b) OVER (ORDER BY a ASC NULLS FIRST)"
+        );
+        let expr = count_over(order_by, WindowFrame::new(Some(true)));
+        assert_eq!(
+            unparser.expr_to_sql(&expr).unwrap_err().strip_backtrace(),
+            "This feature is not implemented: Cannot unparse window frame ROWS BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW of count: the dialect does not support a window frame for it"
+        );
+        Ok(())
+    }
+
+    fn count_over(order_by: Vec<Sort>, window_frame: WindowFrame) -> Expr {
+        let mut window_func = WindowFunction::new(
+            WindowFunctionDefinition::AggregateUDF(count_udaf()),
+            vec![col("b")],
+        );
+        window_func.params.order_by = order_by;
+        window_func.params.window_frame = window_frame;
+        Expr::from(window_func)
+    }
+
+    #[test]
+    fn test_window_func_ignore_nulls() -> Result<()> {
+        let mut window_func = WindowFunction::new(
+            WindowFunctionDefinition::AggregateUDF(count_udaf()),
+            vec![col("b")],
+        );
+        window_func.params.null_treatment = Some(NullTreatment::IgnoreNulls);
+        let expr = Expr::from(window_func);
+
+        let dialect = CustomDialectBuilder::new().build();
+        let unparser = Unparser::new(&dialect);
+        assert_eq!(
+            unparser.expr_to_sql(&expr)?.to_string(),
+            "count(b) IGNORE NULLS OVER (ROWS BETWEEN UNBOUNDED PRECEDING AND UNBOUNDED FOLLOWING)"
+        );
+
+        // A FILTER would remove the rows from the frame of the other rows too
+        let dialect = CustomDialectBuilder::new()
+            .with_supports_null_treatment(false)
+            .build();
+        let unparser = Unparser::new(&dialect);
+        assert_eq!(
+            unparser.expr_to_sql(&expr).unwrap_err().strip_backtrace(),
+            "This feature is not implemented: Unsupported IGNORE NULLS for count in the dialect"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_omit_default_window_frame() -> Result<()> {
+        let dialect = CustomDialectBuilder::new()
+            .with_omit_default_window_frame(true)
+            .build();
+        let unparser = Unparser::new(&dialect);
+        let order_by = vec![Sort::new(col("a"), true, true)];
+
+        let tests = [
+            (
+                count_over(order_by.clone(), WindowFrame::new(Some(false))),
+                "count(b) OVER (ORDER BY a ASC NULLS FIRST)",
+            ),
+            (count_over(vec![], WindowFrame::new(None)), "count(b) OVER ()"),
+            (
+                count_over(
+                    order_by.clone(),
+                    WindowFrame::new_bounds(
+                        WindowFrameUnits::Rows,
+                        WindowFrameBound::Preceding(ScalarValue::UInt64(None)),
+                        WindowFrameBound::CurrentRow,
+                    ),
+                ),
+                "count(b) OVER (ORDER BY a ASC NULLS FIRST ROWS BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW)",
+            ),
+            (
+                count_over(order_by, WindowFrame::new(None)),
+                "count(b) OVER (ORDER BY a ASC NULLS FIRST ROWS BETWEEN UNBOUNDED PRECEDING AND UNBOUNDED FOLLOWING)",
+            ),
+        ];
+        for (expr, expected) in tests {
+            assert_eq!(unparser.expr_to_sql(&expr)?.to_string(), expected);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_window_frame_interval_offsets() -> Result<()> {
+        let frame = WindowFrame::new_bounds(
+            WindowFrameUnits::Range,
+            WindowFrameBound::Preceding(ScalarValue::Utf8(Some("1 day".to_string()))),
+            WindowFrameBound::Following(ScalarValue::new_interval_mdn(
+                0,
+                0,
+                7_200_000_000_000,
+            )),
+        );
+        let expr = count_over(vec![Sort::new(col("ts"), false, true)], frame);
+
+        let default_dialect: Arc<dyn Dialect> = Arc::new(DefaultDialect {});
+        let mysql_dialect: Arc<dyn Dialect> = Arc::new(MySqlDialect {});
+        for (dialect, expected) in [
+            (
+                default_dialect,
+                "count(b) OVER (ORDER BY ts DESC NULLS FIRST RANGE BETWEEN INTERVAL '1 DAYS' PRECEDING AND INTERVAL '2 HOURS' FOLLOWING)",
+            ),
+            (
+                mysql_dialect,
+                "count(`b`) OVER (ORDER BY `ts` DESC RANGE BETWEEN INTERVAL 1 DAY PRECEDING AND INTERVAL 2 HOUR FOLLOWING)",
+            ),
+        ] {
+            let unparser = Unparser::new(dialect.as_ref());
+            assert_eq!(unparser.expr_to_sql(&expr)?.to_string(), expected);
+        }
         Ok(())
     }
 
+    #[test]
+    fn test_invalid_window_frame() {
+        let range =
+            |start, end| WindowFrame::new_bounds(WindowFrameUnits::Range, start, end);
+        let rows =
+            |start, end| WindowFrame::new_bounds(WindowFrameUnits::Rows, start, end);
+        let one_day = || ScalarValue::new_interval_mdn(0, 1, 0);
+        let sort = |name| Sort::new(col(name), true, true);
+
+        let tests = [
+            (
+                vec![sort("a"), sort("b")],
+                range(
+                    WindowFrameBound::Preceding(ScalarValue::Int64(Some(1))),
+                    WindowFrameBound::CurrentRow,
+                ),
+                "RANGE with an offset requires exactly one ORDER BY expression, found 2",
+            ),
+            (
+                vec![sort("a")],
+                rows(
+                    WindowFrameBound::Preceding(one_day()),
+                    WindowFrameBound::CurrentRow,
+                ),
+                "ROWS offsets must be integers, found Interval(MonthDayNano)",
+            ),
+            (
+                vec![sort("a")],
+                range(
+                    WindowFrameBound::Preceding(ScalarValue::Int64(Some(-1))),
+                    WindowFrameBound::CurrentRow,
+                ),
+                "offset -1 must not be negative",
+            ),
+            (
+                vec![sort("a")],
+                range(
+                    WindowFrameBound::CurrentRow,
+                    WindowFrameBound::Preceding(one_day()),
+                ),
+                "the start bound must not come after the end bound",
+            ),
+            (
+                vec![Sort::new(cast(col("a"), DataType::Int32), true, true)],
+                range(
+                    WindowFrameBound::Preceding(one_day()),
+                    WindowFrameBound::CurrentRow,
+                ),
+                "interval offsets require a temporal ORDER BY expression, found Int32",
+            ),
+            (
+                vec![Sort::new(cast(col("a"), DataType::Date32), true, true)],
+                range(
+                    WindowFrameBound::Preceding(ScalarValue::Int64(Some(1))),
+                    WindowFrameBound::CurrentRow,
+                ),
+                "ORDER BY expression of type Date32 requires an interval offset, found Int64",
+            ),
+            (
+                vec![sort("a")],
+                range(
+                    WindowFrameBound::Preceding(ScalarValue::Utf8(Some(
+                        "yesterday".to_string(),
+                    ))),
+                    WindowFrameBound::CurrentRow,
+                ),
+                "offset 'yesterday' is neither a number nor an interval",
+            ),
+        ];
+
+        let unparser = Unparser::default();
+        for (order_by, frame, expected) in tests {
+            let err = unparser
+                .expr_to_sql(&count_over(order_by, frame))
+                .unwrap_err();
+            assert_contains!(err.to_string(), expected);
+        }
+    }
+
     #[test]
     fn test_from_unixtime() -> Result<()> {
         let default_dialect: Arc<dyn Dialect> = Arc::new(DefaultDialect {});
@@ -17,17 +17,22 @@
 
 //! [`Unparser`] for converting `Expr` to SQL text
 
+mod alias;
 pub mod ast;
 mod expr;
 mod plan;
+mod qualify;
 mod rewrite;
 mod utils;
 
 use self::dialect::{DefaultDialect, Dialect};
+use self::qualify::DerivedColumns;
 use crate::unparser::extension_unparser::UserDefinedLogicalNodeUnparser;
+pub use alias::{AliasGenerator, DefaultAliasGenerator, StatementAliasGenerator};
 pub use expr::expr_to_sql;
 pub use plan::plan_to_sql;
-use std::sync::Arc;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
 pub mod dialect;
 pub mod extension_unparser;
 
@@ -58,6 +63,23 @@ pub struct Unparser<'a> {
     dialect: &'a dyn Dialect,
     pretty: bool,
     extension_unparsers: Vec<Arc<dyn UserDefinedLogicalNodeUnparser>>,
+    alias_generator: Arc<dyn AliasGenerator>,
+    qualify_columns: bool,
+    quote_identifiers: bool,
+    unwrap_comparison_casts: bool,
+    order_by_aliases: bool,
+    statement: StatementState,
+}
+
+/// The state of the statement being unparsed. [`Unparser::plan_to_sql`]
+/// unparses each statement with a new state, so an [`Unparser`] can be shared
+/// by concurrent calls.
+#[derive(Debug, Default)]
+struct StatementState {
+    /// The aliases of the derived tables of the statement, created on first use
+    /// when unparsing a part of a statement, such as an expression
+    aliases: Mutex<Option<Box<dyn StatementAliasGenerator>>>,
+    derived_columns: DerivedColumns,
 }
 
 impl<'a> Unparser<'a> {
@@ -66,9 +88,42 @@ impl<'a> Unparser<'a> {
             dialect,
             pretty: false,
             extension_unparsers: vec![],
+            alias_generator: Arc::new(DefaultAliasGenerator::default()),
+            qualify_columns: false,
+            quote_identifiers: false,
+            unwrap_comparison_casts: true,
+            order_by_aliases: false,
+            statement: StatementState::default(),
         }
     }
 
+    /// A copy of this unparser to unparse a new statement, whose derived tables
+    /// are named by `aliases`
+    fn for_statement(&self, aliases: Box<dyn StatementAliasGenerator>) -> Self {
+        Self {
+            dialect: self.dialect,
+            pretty: self.pretty,
+            extension_unparsers: self.extension_unparsers.clone(),
+            alias_generator: Arc::clone(&self.alias_generator),
+            qualify_columns: self.qualify_columns,
+            quote_identifiers: self.quote_identifiers,
+            unwrap_comparison_casts: self.unwrap_comparison_casts,
+            order_by_aliases: self.order_by_aliases,
+            statement: StatementState {
+                aliases: Mutex::new(Some(aliases)),
+                derived_columns: DerivedColumns::default(),
+            },
+        }
+    }
+
+    /// Return the alias for a derived table of the given `kind`
+    fn next_alias(&self, kind: &str) -> String {
+        let mut aliases = self.statement.aliases.lock().unwrap();
+        aliases
+            .get_or_insert_with(|| self.alias_generator.begin_statement(HashSet::new()))
+            .next_alias(kind)
+    }
+
     /// Create pretty SQL output, better suited for human consumption
     ///
     /// See example on the struct level documentation
@@ -128,6 +183,97 @@ impl<'a> Unparser<'a> {
         self.extension_unparsers = extension_unparsers;
         self
     }
+
+    /// Use a custom [`AliasGenerator`] for the aliases of derived tables
+    ///
+    /// By default, [`DefaultAliasGenerator`] is used, which names derived tables
+    /// `derived_projection`, `derived_limit`, etc. and appends a counter when
+    /// such a name is already used by a table or CTE in the plan.
+    pub fn with_alias_generator(
+        mut self,
+        alias_generator: Arc<dyn AliasGenerator>,
+    ) -> Self {
+        self.alias_generator = alias_generator;
+        self
+    }
+
+    /// Qualify the column references of derived tables with the alias of the
+    /// derived table
+    ///
+    /// By default, a column read from a derived table keeps the qualifier of
+    /// the table it was read from inside the derived table, or stays
+    /// unqualified, which engines resolving names strictly reject. With this
+    /// option, every derived table gets an alias and the references to its
+    /// columns in the select items, `ON` conditions, `WHERE`, `GROUP BY`,
+    /// `HAVING` and `ORDER BY` clauses are qualified with that alias.
+    ///
+    /// The unqualified columns of a `SELECT` that reads a single table, besides
+    /// such derived tables, are qualified with the name or alias of that table,
+    /// except in `ORDER BY`, where they may refer to a select item.
+    ///
+    /// # Example
+    /// ```
+    /// use arrow::datatypes::{DataType, Field, Schema};
+    /// use datafusion_expr::{col, logical_plan::table_scan};
+    /// use datafusion_sql::unparser::Unparser;
+    /// let schema = Schema::new(vec![Field::new("id", DataType::Int32, false)]);
+    /// let plan = table_scan(Some("t"), &schema, None)
+    ///     .unwrap()
+    ///     .limit(0, Some(10))
+    ///     .unwrap()
+    ///     .project(vec![col("t.id")])
+    ///     .unwrap()
+    ///     .build()
+    ///     .unwrap();
+    /// let unparser = Unparser::default().with_qualify_columns(true);
+    /// let sql = unparser.plan_to_sql(&plan).unwrap();
+    /// assert_eq!(
+    ///     sql.to_string(),
+    ///     "SELECT derived_limit.id FROM (SELECT * FROM t LIMIT 10) AS derived_limit"
+    /// );
+    /// ```
+    pub fn with_qualify_columns(mut self, qualify_columns: bool) -> Self {
+        self.qualify_columns = qualify_columns;
+        self
+    }
+
+    /// Always quote identifiers, using the quote character of the dialect
+    ///
+    /// By default, identifiers are only quoted where the dialect requires it,
+    /// e.g. for keywords or special characters. Engines with case-sensitive
+    /// catalogs may need quoted identifiers to resolve mixed-case names.
+    /// Identifiers the dialect would not quote are quoted with its
+    /// [`required_identifier_quote_style`](dialect::Dialect::required_identifier_quote_style).
+    pub fn with_quote_identifiers(mut self, quote_identifiers: bool) -> Self {
+        self.quote_identifiers = quote_identifiers;
+        self
+    }
+
+    /// Move widening casts of columns in `WHERE` comparisons to the literal
+    /// the column is compared with
+    ///
+    /// Type coercion casts a column to the type of the literal it is compared
+    /// with, e.g. `CAST(a AS BIGINT) = 5` for an `INT` column `a`, which keeps
+    /// engines from using an index on the column. By default, such a
+    /// comparison is unparsed as `a = 5` if the literal is represented exactly
+    /// in the type of the column, for widening casts between integer types,
+    /// between floating point types, and between string types. Set this to
+    /// `false` to keep the casts as planned.
+    pub fn with_unwrap_comparison_casts(mut self, unwrap_comparison_casts: bool) -> Self {
+        self.unwrap_comparison_casts = unwrap_comparison_casts;
+        self
+    }
+
+    /// Refer to the alias of a projected expression in `ORDER BY`
+    ///
+    /// By default, `SELECT a + b AS s FROM t ORDER BY a + b` is unparsed with
+    /// `ORDER BY (t.a + t.b)`, repeating the projected expression. Set this to
+    /// `true` to emit `ORDER BY s` instead for sort expressions equal to an
+    /// aliased expression of the projection.
+    pub fn with_order_by_aliases(mut self, order_by_aliases: bool) -> Self {
+        self.order_by_aliases = order_by_aliases;
+        self
+    }
 }
 
 impl Default for Unparser<'_> {
@@ -136,6 +282,12 @@ impl Default for Unparser<'_> {
             dialect: &DefaultDialect {},
             pretty: false,
             extension_unparsers: vec![],
+            alias_generator: Arc::new(DefaultAliasGenerator::default()),
+            qualify_columns: false,
+            quote_identifiers: false,
+            unwrap_comparison_casts: true,
+            order_by_aliases: false,
+            statement: StatementState::default(),
         }
     }
 }
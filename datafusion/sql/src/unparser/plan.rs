@@ -16,38 +16,48 @@
 // under the License.
 
 use super::{
+    alias::collect_relation_names,
     ast::{
         BuilderError, DerivedRelationBuilder, QueryBuilder, RelationBuilder,
         SelectBuilder, TableRelationBuilder, TableWithJoinsBuilder,
     },
+    dialect::{ColumnExclusionStyle, GroupByStyle, TableSampleStyle},
     rewrite::{
-        inject_column_aliases_into_subquery, normalize_union_schema,
+        inject_column_aliases_into_subquery, normalize_union_schema, projection_alias,
+        references_window_output, rewrite_plan_for_filter_over_window,
         rewrite_plan_for_sort_on_non_projected_fields,
-        subquery_alias_inner_query_and_columns, TableAliasRewriter,
+        subquery_alias_inner_query_and_columns, subquery_alias_values_and_columns,
+        unwrap_cast_in_comparison, window_output_names, TableAliasRewriter,
     },
     utils::{
-        find_agg_node_within_select, find_unnest_node_within_select,
-        find_window_nodes_within_select, try_transform_to_simple_table_scan_with_filters,
+        find_agg_node_within_select, find_table_function_with_ordinality,
+        find_unnest_node_within_select, find_window_nodes_within_select,
+        set_operation_to_sql, try_transform_to_simple_table_scan_with_filters,
         unproject_sort_expr, unproject_unnest_expr, unproject_window_exprs,
+        SetOperationQuantifier,
     },
     Unparser,
 };
-use crate::unparser::ast::UnnestRelationBuilder;
+use crate::unparser::ast::{UnnestRelationBuilder, ValuesRelationBuilder};
 use crate::unparser::extension_unparser::{
     UnparseToStatementResult, UnparseWithinStatementResult,
 };
-use crate::unparser::utils::{find_unnest_node_until_relation, unproject_agg_exprs};
+use crate::unparser::utils::{
+    find_unnest_node_until_relation, is_global_grouping, name_window_specs,
+    sql_without_column_alias_as, unproject_agg_exprs,
+};
 use crate::utils::UNNEST_PLACEHOLDER;
 use datafusion_common::{
     internal_err, not_impl_err,
     tree_node::{TransformedResult, TreeNode},
-    Column, DataFusionError, Result, ScalarValue, TableReference,
+    Column, DataFusionError, NullEquality, Result, ScalarValue, TableReference,
 };
 use datafusion_expr::expr::OUTER_REFERENCE_COLUMN_PREFIX;
 use datafusion_expr::{
-    expr::Alias, BinaryExpr, Distinct, Expr, JoinConstraint, JoinType, LogicalPlan,
-    LogicalPlanBuilder, Operator, Projection, SortExpr, TableScan, Unnest,
-    UserDefinedLogicalNode,
+    expr::Alias, utils::grouping_set_to_exprlist, BinaryExpr, Distinct, Expr,
+    GroupingSet, JoinConstraint, JoinType, Limit, LogicalPlan, LogicalPlanBuilder,
+    Operator, Projection, SortExpr, TableScan, Union, Unnest, UserDefinedLogicalNode,
+    Values,
 };
 use sqlparser::ast::{self, Ident, OrderByKind, SetExpr, TableAliasColumnDef};
 use std::{sync::Arc, vec};
@@ -95,6 +105,30 @@ pub fn plan_to_sql(plan: &LogicalPlan) -> Result<ast::Statement> {
 
 impl Unparser<'_> {
     pub fn plan_to_sql(&self, plan: &LogicalPlan) -> Result<ast::Statement> {
+        let aliases = self
+            .alias_generator
+            .begin_statement(collect_relation_names(plan)?);
+        self.for_statement(aliases).plan_to_sql_inner(plan)
+    }
+
+    /// Convert `plan` to SQL text, like [`Self::plan_to_sql`] followed by
+    /// `to_string`, and apply the options of the dialect that the returned AST
+    /// can not represent, such as writing column aliases without `AS` when
+    /// [`Dialect::use_as_for_column_alias`] is false.
+    ///
+    /// [`Dialect::use_as_for_column_alias`]: super::dialect::Dialect::use_as_for_column_alias
+    pub fn plan_to_sql_text(&self, plan: &LogicalPlan) -> Result<String> {
+        let statement = self.plan_to_sql(plan)?;
+        if self.dialect.use_as_for_column_alias() {
+            Ok(statement.to_string())
+        } else {
+            Ok(sql_without_column_alias_as(&statement))
+        }
+    }
+
+    /// Convert a plan that is part of the statement currently being unparsed,
+    /// such as a derived table or a subquery expression
+    pub(super) fn plan_to_sql_inner(&self, plan: &LogicalPlan) -> Result<ast::Statement> {
         let plan = normalize_union_schema(plan)?;
 
         match plan {
@@ -176,7 +210,10 @@ impl Unparser<'_> {
 
         let body = self.select_to_sql_expr(plan, &mut query_builder)?;
 
-        let query = query_builder.unwrap().body(Box::new(body)).build()?;
+        let mut query = query_builder.unwrap().body(Box::new(body)).build()?;
+        if self.qualify_columns {
+            self.qualify_derived_columns(&mut query);
+        }
 
         Ok(ast::Statement::Query(Box::new(query)))
     }
@@ -213,7 +250,12 @@ impl Unparser<'_> {
         twj.relation(relation_builder);
         select_builder.push_from(twj);
 
-        Ok(SetExpr::Select(Box::new(select_builder.build()?)))
+        let mut select = select_builder.build()?;
+        let named_windows =
+            name_window_specs(&mut select.projection, self.dialect.named_window_style());
+        select.named_window.extend(named_windows);
+
+        Ok(SetExpr::Select(Box::new(select)))
     }
 
     /// Reconstructs a SELECT SQL statement from a logical plan by unprojecting column expressions
@@ -250,13 +292,7 @@ impl Unparser<'_> {
                     .collect::<Result<Vec<_>>>()?;
 
                 select.projection(items);
-                select.group_by(ast::GroupByExpr::Expressions(
-                    agg.group_expr
-                        .iter()
-                        .map(|expr| self.expr_to_sql(expr))
-                        .collect::<Result<Vec<_>>>()?,
-                    vec![],
-                ));
+                self.group_by_to_sql(&agg.group_expr, select)?;
             }
             (None, Some(window)) => {
                 let items = exprs
@@ -270,6 +306,10 @@ impl Unparser<'_> {
                 select.projection(items);
             }
             _ => {
+                if let Some(item) = self.column_exclusion_to_sql(&exprs, &p.input) {
+                    select.projection(vec![item]);
+                    return Ok(());
+                }
                 let items = exprs
                     .iter()
                     .map(|e| self.select_item_to_sql(e))
@@ -280,6 +320,76 @@ impl Unparser<'_> {
         Ok(())
     }
 
+    /// Unparse a projection of the columns `exprs` of a table as a wildcard
+    /// excluding the other columns of the table, e.g. `SELECT * EXCLUDE (c)`,
+    /// if the dialect has a [`ColumnExclusionStyle`] and fewer columns are
+    /// excluded than projected.
+    ///
+    /// The projected columns must keep the order of the table, which is
+    /// the order the wildcard expands to.
+    fn column_exclusion_to_sql(
+        &self,
+        exprs: &[Expr],
+        input: &LogicalPlan,
+    ) -> Option<ast::SelectItem> {
+        let style = self.dialect.column_exclusion_style();
+        if style == ColumnExclusionStyle::Columns {
+            return None;
+        }
+
+        // The FROM clause names the table itself, whatever columns the scan
+        // projects, so the wildcard expands to all of its columns
+        let mut input = input;
+        let scan = loop {
+            match input {
+                LogicalPlan::Filter(filter) => input = filter.input.as_ref(),
+                LogicalPlan::SubqueryAlias(alias) => input = alias.input.as_ref(),
+                LogicalPlan::TableScan(scan) => break scan,
+                _ => return None,
+            }
+        };
+
+        let projected = exprs
+            .iter()
+            .map(|e| match e {
+                Expr::Column(col) => Some(col.name.as_str()),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>()?;
+        let mut remaining = projected.iter().peekable();
+        let mut excluded = vec![];
+        for field in scan.source.schema().fields() {
+            if remaining
+                .next_if(|name| **name == field.name().as_str())
+                .is_none()
+            {
+                excluded.push(self.new_ident_quoted_if_needs(field.name().to_string()));
+            }
+        }
+        if remaining.next().is_some()
+            || excluded.is_empty()
+            || excluded.len() >= projected.len()
+        {
+            return None;
+        }
+
+        let mut options = ast::WildcardAdditionalOptions::default();
+        match style {
+            ColumnExclusionStyle::Exclude => {
+                options.opt_exclude = Some(ast::ExcludeSelectItem::Multiple(excluded));
+            }
+            ColumnExclusionStyle::Except => {
+                let mut excluded = excluded.into_iter();
+                options.opt_except = Some(ast::ExceptSelectItem {
+                    first_element: excluded.next()?,
+                    additional_elements: excluded.collect(),
+                });
+            }
+            ColumnExclusionStyle::Columns => return None,
+        }
+        Some(ast::SelectItem::Wildcard(options))
+    }
+
     fn derive(
         &self,
         plan: &LogicalPlan,
@@ -289,7 +399,7 @@ impl Unparser<'_> {
     ) -> Result<()> {
         let mut derived_builder = DerivedRelationBuilder::default();
         derived_builder.lateral(lateral).alias(alias).subquery({
-            let inner_statement = self.plan_to_sql(plan)?;
+            let inner_statement = self.plan_to_sql_inner(plan)?;
             if let ast::Statement::Query(inner_query) = inner_statement {
                 inner_query
             } else {
@@ -303,19 +413,140 @@ impl Unparser<'_> {
         Ok(())
     }
 
+    /// Unparse an input of a set operation with its own query context, so its
+    /// `DISTINCT`, `ORDER BY` or `LIMIT` do not leak into the enclosing query
+    fn set_operand_to_sql(&self, plan: &LogicalPlan) -> Result<SetExpr> {
+        let mut query = Some(QueryBuilder::default());
+        let body = self.select_to_sql_expr(plan, &mut query)?;
+        let query = query.unwrap().body(Box::new(body)).build()?;
+
+        let has_modifiers = query.with.is_some()
+            || query.order_by.is_some()
+            || query.fetch.is_some()
+            || match &query.limit_clause {
+                Some(ast::LimitClause::LimitOffset {
+                    limit,
+                    offset,
+                    limit_by,
+                }) => limit.is_some() || offset.is_some() || !limit_by.is_empty(),
+                Some(ast::LimitClause::OffsetCommaLimit { .. }) => true,
+                None => false,
+            };
+        if has_modifiers {
+            Ok(SetExpr::Query(Box::new(query)))
+        } else {
+            Ok(*query.body)
+        }
+    }
+
+    /// Set the body of `query` to the `UNION` of the inputs of `union`, with
+    /// the given quantifier
+    fn union_to_sql(
+        &self,
+        union: &Union,
+        query: &mut Option<QueryBuilder>,
+        quantifier: SetOperationQuantifier,
+    ) -> Result<()> {
+        let input_exprs: Vec<SetExpr> = union
+            .inputs
+            .iter()
+            .map(|input| self.set_operand_to_sql(input))
+            .collect::<Result<Vec<_>>>()?;
+
+        if input_exprs.len() < 2 {
+            return internal_err!("UNION operator requires at least 2 inputs");
+        }
+
+        // Build the union expression tree left-deep, matching the left
+        // associativity of set operators in SQL
+        let union_expr = input_exprs
+            .into_iter()
+            .reduce(|left, right| {
+                set_operation_to_sql(ast::SetOperator::Union, quantifier, left, right)
+            })
+            .unwrap();
+
+        let Some(query) = query.as_mut() else {
+            return internal_err!("UNION ALL operator only valid in a statement context");
+        };
+        query.body(Box::new(union_expr));
+
+        Ok(())
+    }
+
+    /// A set operation used as a join input has to be a derived table. It is
+    /// aliased with the qualifier of its columns, which the join condition
+    /// refers to.
+    fn derive_set_operation(
+        &self,
+        plan: &LogicalPlan,
+        relation: &mut RelationBuilder,
+    ) -> Result<()> {
+        match plan.schema().iter().next() {
+            Some((Some(qualifier), _)) => self.derive(
+                plan,
+                relation,
+                Some(self.new_table_alias(qualifier.table().to_string(), vec![])),
+                false,
+            ),
+            _ => self.derive_with_dialect_alias("union", plan, relation, false, vec![]),
+        }
+    }
+
+    /// Unparse an input of a join as a derived table, named after the relation
+    /// its columns belong to so that the join condition still refers to them
+    fn derive_join_input(
+        &self,
+        plan: &LogicalPlan,
+        relation: &mut RelationBuilder,
+    ) -> Result<()> {
+        let mut qualifiers = plan.schema().iter().map(|(qualifier, _)| qualifier);
+        match qualifiers.next() {
+            Some(Some(qualifier)) if qualifiers.all(|q| q == Some(qualifier)) => self
+                .derive(
+                    plan,
+                    relation,
+                    Some(self.new_table_alias(qualifier.table().to_string(), vec![])),
+                    false,
+                ),
+            _ => self.derive_with_dialect_alias("join", plan, relation, false, vec![]),
+        }
+    }
+
     fn derive_with_dialect_alias(
         &self,
-        alias: &str,
+        kind: &str,
         plan: &LogicalPlan,
         relation: &mut RelationBuilder,
         lateral: bool,
         columns: Vec<Ident>,
     ) -> Result<()> {
-        if self.dialect.requires_derived_table_alias() || !columns.is_empty() {
+        if self.dialect.requires_derived_table_alias()
+            || self.qualify_columns
+            || !columns.is_empty()
+        {
+            let alias = self.next_alias(kind);
+            if self.qualify_columns && columns.is_empty() {
+                let exposed = plan
+                    .schema()
+                    .iter()
+                    .map(|(qualifier, field)| {
+                        Ok((
+                            qualifier
+                                .map(|q| self.col_qualifier_to_sql(q))
+                                .unwrap_or_default(),
+                            self.col_name_to_sql(field.name())?,
+                        ))
+                    })
+                    .collect::<Result<_>>()?;
+                self.statement
+                    .derived_columns
+                    .insert(alias.clone(), exposed);
+            }
             self.derive(
                 plan,
                 relation,
-                Some(self.new_table_alias(alias.to_string(), columns)),
+                Some(self.new_table_alias(alias, columns)),
                 lateral,
             )
         } else {
@@ -359,12 +590,30 @@ impl Unparser<'_> {
                     self.new_ident_quoted_if_needs(scan.table_name.table().to_string()),
                 );
                 builder.name(ast::ObjectName::from(table_parts));
+                builder
+                    .index_hints(scan.hints.index_hints.clone())
+                    .with_hints(scan.hints.with_hints.clone());
+                if let Some(version) = scan.source.version() {
+                    builder.version(Some(ast::TableVersion::ForSystemTimeAsOf(
+                        self.expr_to_sql(&Expr::Literal(version.clone(), None))?,
+                    )));
+                }
                 relation.table(builder);
 
                 Ok(())
             }
             LogicalPlan::Projection(p) => {
-                if let Some(new_plan) = rewrite_plan_for_sort_on_non_projected_fields(p) {
+                if let Some(new_plan) = rewrite_plan_for_sort_on_non_projected_fields(
+                    p,
+                    self.order_by_aliases,
+                ) {
+                    return self
+                        .select_to_sql_recursively(&new_plan, query, select, relation);
+                }
+                if let Some(new_plan) = rewrite_plan_for_filter_over_window(
+                    p,
+                    self.dialect.supports_qualify(),
+                )? {
                     return self
                         .select_to_sql_recursively(&new_plan, query, select, relation);
                 }
@@ -408,7 +657,7 @@ impl Unparser<'_> {
                 // Projection can be top-level plan for derived table
                 if select.already_projected() {
                     return self.derive_with_dialect_alias(
-                        "derived_projection",
+                        "projection",
                         plan,
                         relation,
                         unnest_input_type
@@ -417,11 +666,46 @@ impl Unparser<'_> {
                         columns,
                     );
                 }
+                if let Some((scan, exprs)) = find_table_function_with_ordinality(p)? {
+                    let items = exprs
+                        .iter()
+                        .map(|e| self.select_item_to_sql(e))
+                        .collect::<Result<Vec<_>>>()?;
+                    select.projection(items);
+                    self.select_to_sql_recursively(scan, query, select, relation)?;
+                    relation.with_ordinality(true);
+                    return Ok(());
+                }
                 self.reconstruct_select_statement(plan, p, select)?;
                 self.select_to_sql_recursively(p.input.as_ref(), query, select, relation)
             }
             LogicalPlan::Filter(filter) => {
-                if let Some(agg) =
+                let window_outputs = window_output_names(filter.input.as_ref());
+                if self.dialect.supports_qualify()
+                    && references_window_output(&filter.predicate, &window_outputs)
+                {
+                    let windows = find_window_nodes_within_select(
+                        plan,
+                        None,
+                        select.already_projected(),
+                    )
+                    .unwrap_or_default();
+                    let unprojected = match find_agg_node_within_select(
+                        plan,
+                        select.already_projected(),
+                    ) {
+                        Some(agg) => unproject_agg_exprs(
+                            filter.predicate.clone(),
+                            agg,
+                            Some(&windows),
+                        )?,
+                        None => {
+                            unproject_window_exprs(filter.predicate.clone(), &windows)?
+                        }
+                    };
+                    let filter_expr = self.expr_to_sql(&unprojected)?;
+                    select.qualify(Some(filter_expr));
+                } else if let Some(agg) =
                     find_agg_node_within_select(plan, select.already_projected())
                 {
                     let unprojected =
@@ -429,7 +713,15 @@ impl Unparser<'_> {
                     let filter_expr = self.expr_to_sql(&unprojected)?;
                     select.having(Some(filter_expr));
                 } else {
-                    let filter_expr = self.expr_to_sql(&filter.predicate)?;
+                    let predicate = if self.unwrap_comparison_casts {
+                        unwrap_cast_in_comparison(
+                            filter.predicate.clone(),
+                            filter.input.schema(),
+                        )?
+                    } else {
+                        filter.predicate.clone()
+                    };
+                    let filter_expr = self.expr_to_sql(&predicate)?;
                     select.selection(Some(filter_expr));
                 }
 
@@ -441,10 +733,19 @@ impl Unparser<'_> {
                 )
             }
             LogicalPlan::Limit(limit) => {
-                // Limit can be top-level plan for derived table
-                if select.already_projected() {
+                if let Some((scan, sample)) = self.table_sample_to_sql(limit)? {
+                    self.select_to_sql_recursively(scan, query, select, relation)?;
+                    relation.sample(Some(sample));
+                    return Ok(());
+                }
+                // Limit can be top-level plan for derived table. A Sort above
+                // the Limit orders the limited rows, so the Limit can not be
+                // unparsed after the `ORDER BY` of the same query either.
+                if select.already_projected()
+                    || query.as_ref().is_some_and(|q| q.has_order_by())
+                {
                     return self.derive_with_dialect_alias(
-                        "derived_limit",
+                        "limit",
                         plan,
                         relation,
                         false,
@@ -472,6 +773,9 @@ impl Unparser<'_> {
                         value: self.expr_to_sql(skip)?,
                     }));
                 }
+                if let Some(query) = query.as_mut() {
+                    query.limit_style(self.dialect.limit_style());
+                }
 
                 self.select_to_sql_recursively(
                     limit.input.as_ref(),
@@ -484,7 +788,7 @@ impl Unparser<'_> {
                 // Sort can be top-level plan for derived table
                 if select.already_projected() {
                     return self.derive_with_dialect_alias(
-                        "derived_sort",
+                        "sort",
                         plan,
                         relation,
                         false,
@@ -513,6 +817,20 @@ impl Unparser<'_> {
                         unproject_sort_expr(sort_expr.clone(), agg, sort.input.as_ref())
                     })
                     .collect::<Result<Vec<_>>>()?;
+                let sort_exprs = match sort.input.as_ref() {
+                    LogicalPlan::Projection(p) if self.order_by_aliases => sort_exprs
+                        .into_iter()
+                        .map(|mut sort_expr| {
+                            if let Some(alias) =
+                                projection_alias(&sort_expr.expr, &p.expr)
+                            {
+                                sort_expr.expr = alias;
+                            }
+                            sort_expr
+                        })
+                        .collect(),
+                    _ => sort_exprs,
+                };
 
                 query_ref.order_by(self.sorts_to_sql(&sort_exprs)?);
 
@@ -531,18 +849,12 @@ impl Unparser<'_> {
                     let exprs: Vec<_> = agg
                         .aggr_expr
                         .iter()
-                        .chain(agg.group_expr.iter())
+                        .chain(grouping_set_to_exprlist(&agg.group_expr)?)
                         .map(|expr| self.select_item_to_sql(expr))
                         .collect::<Result<Vec<_>>>()?;
                     select.projection(exprs);
 
-                    select.group_by(ast::GroupByExpr::Expressions(
-                        agg.group_expr
-                            .iter()
-                            .map(|expr| self.expr_to_sql(expr))
-                            .collect::<Result<Vec<_>>>()?,
-                        vec![],
-                    ));
+                    self.group_by_to_sql(&agg.group_expr, select)?;
                 }
 
                 self.select_to_sql_recursively(
@@ -556,7 +868,7 @@ impl Unparser<'_> {
                 // Distinct can be top-level plan for derived table
                 if select.already_projected() {
                     return self.derive_with_dialect_alias(
-                        "derived_distinct",
+                        "distinct",
                         plan,
                         relation,
                         false,
@@ -567,14 +879,12 @@ impl Unparser<'_> {
                 // If this distinct is the parent of a Union and we're in a query context,
                 // then we need to unparse as a `UNION` rather than a `UNION ALL`.
                 if let Distinct::All(input) = distinct {
-                    if matches!(input.as_ref(), LogicalPlan::Union(_)) {
-                        if let Some(query_mut) = query.as_mut() {
-                            query_mut.distinct_union();
-                            return self.select_to_sql_recursively(
-                                input.as_ref(),
+                    if let LogicalPlan::Union(union) = input.as_ref() {
+                        if query.is_some() {
+                            return self.union_to_sql(
+                                union,
                                 query,
-                                select,
-                                relation,
+                                SetOperationQuantifier::Distinct,
                             );
                         }
                     }
@@ -611,32 +921,69 @@ impl Unparser<'_> {
             }
             LogicalPlan::Join(join) => {
                 let mut table_scan_filters = vec![];
+                let mut where_filters = vec![];
                 let (left_plan, right_plan) = match join.join_type {
                     JoinType::RightSemi | JoinType::RightAnti => {
                         (&join.right, &join.left)
                     }
                     _ => (&join.left, &join.right),
                 };
+                // The rows of a preserved input are all kept by an outer join,
+                // so filters of this input only apply in the `WHERE` clause,
+                // while the filters of a null-supplying input have to stay in
+                // the `ON` clause to keep the rows of the other input
+                let (left_preserved, right_preserved) = match join.join_type {
+                    JoinType::Left => (true, false),
+                    JoinType::Right => (false, true),
+                    JoinType::Full => (true, true),
+                    _ => (false, false),
+                };
+                let (left_null_supplying, right_null_supplying) =
+                    (right_preserved, left_preserved);
                 // If there's an outer projection plan, it will already set up the projection.
                 // In that case, we don't need to worry about setting up the projection here.
                 // The outer projection plan will handle projecting the correct columns.
                 let already_projected = select.already_projected();
 
-                let left_plan =
-                    match try_transform_to_simple_table_scan_with_filters(left_plan)? {
-                        Some((plan, filters)) => {
-                            table_scan_filters.extend(filters);
-                            Arc::new(plan)
-                        }
-                        None => Arc::clone(left_plan),
+                let mut split_filters =
+                    |plan: &Arc<LogicalPlan>, preserved: bool, null_supplying: bool| {
+                        Ok::<_, DataFusionError>(
+                            match try_transform_to_simple_table_scan_with_filters(plan)? {
+                                // Filters of an input that is both preserved and
+                                // null-supplying apply neither in `ON` nor in
+                                // `WHERE`, the input is kept as a derived table
+                                Some(_) if preserved && null_supplying => {
+                                    Arc::clone(plan)
+                                }
+                                Some((plan, filters)) => {
+                                    if preserved {
+                                        where_filters.extend(filters);
+                                    } else {
+                                        table_scan_filters.extend(filters);
+                                    }
+                                    Arc::new(plan)
+                                }
+                                None => Arc::clone(plan),
+                            },
+                        )
                     };
+                let left_plan =
+                    split_filters(left_plan, left_preserved, left_null_supplying)?;
+                let right_plan =
+                    split_filters(right_plan, right_preserved, right_null_supplying)?;
 
-                self.select_to_sql_recursively(
-                    left_plan.as_ref(),
-                    query,
-                    select,
-                    relation,
-                )?;
+                if is_set_operation(left_plan.as_ref()) {
+                    self.derive_set_operation(left_plan.as_ref(), relation)?;
+                } else if left_null_supplying && has_selection(left_plan.as_ref()) {
+                    self.derive_join_input(left_plan.as_ref(), relation)?;
+                } else {
+                    self.select_to_sql_recursively(
+                        left_plan.as_ref(),
+                        query,
+                        select,
+                        relation,
+                    )?;
+                }
 
                 let left_projection: Option<Vec<ast::SelectItem>> = if !already_projected
                 {
@@ -645,23 +992,20 @@ impl Unparser<'_> {
                     None
                 };
 
-                let right_plan =
-                    match try_transform_to_simple_table_scan_with_filters(right_plan)? {
-                        Some((plan, filters)) => {
-                            table_scan_filters.extend(filters);
-                            Arc::new(plan)
-                        }
-                        None => Arc::clone(right_plan),
-                    };
-
                 let mut right_relation = RelationBuilder::default();
+                let derive_right =
+                    right_null_supplying && has_selection(right_plan.as_ref());
 
-                self.select_to_sql_recursively(
-                    right_plan.as_ref(),
-                    query,
-                    select,
-                    &mut right_relation,
-                )?;
+                if derive_right {
+                    self.derive_join_input(right_plan.as_ref(), &mut right_relation)?;
+                } else {
+                    self.select_to_sql_recursively(
+                        right_plan.as_ref(),
+                        query,
+                        select,
+                        &mut right_relation,
+                    )?;
+                }
 
                 let join_filters = if table_scan_filters.is_empty() {
                     join.filter.clone()
@@ -694,14 +1038,17 @@ impl Unparser<'_> {
                     join.join_constraint,
                     &join.on,
                     join_filters.as_ref(),
+                    join.null_equality,
                 )?;
 
-                self.select_to_sql_recursively(
-                    right_plan.as_ref(),
-                    query,
-                    select,
-                    &mut right_relation,
-                )?;
+                if !derive_right {
+                    self.select_to_sql_recursively(
+                        right_plan.as_ref(),
+                        query,
+                        select,
+                        &mut right_relation,
+                    )?;
+                }
 
                 let right_projection: Option<Vec<ast::SelectItem>> = if !already_projected
                 {
@@ -777,18 +1124,31 @@ impl Unparser<'_> {
                     | JoinType::Left
                     | JoinType::Right
                     | JoinType::Full => {
+                        let mut join_operator =
+                            self.join_operator_to_sql(join.join_type, join_constraint)?;
+                        if self.dialect.supports_apply_join()
+                            && right_relation.is_lateral()
+                        {
+                            let (apply_operator, condition) =
+                                self.apply_join_operator_to_sql(join_operator)?;
+                            join_operator = apply_operator;
+                            right_relation.lateral(false);
+                            select.selection(condition);
+                        }
                         let Ok(Some(relation)) = right_relation.build() else {
                             return internal_err!("Failed to build right relation");
                         };
                         let ast_join = ast::Join {
                             relation,
                             global: false,
-                            join_operator: self
-                                .join_operator_to_sql(join.join_type, join_constraint)?,
+                            join_operator,
                         };
                         let mut from = select.pop_from().unwrap();
                         from.push_join(ast_join);
                         select.push_from(from);
+                        for filter in &where_filters {
+                            select.selection(Some(self.expr_to_sql(filter)?));
+                        }
                         if !already_projected {
                             let Some(left_projection) = left_projection else {
                                 return internal_err!("Left projection is missing");
@@ -810,6 +1170,23 @@ impl Unparser<'_> {
                 Ok(())
             }
             LogicalPlan::SubqueryAlias(plan_alias) => {
+                // A VALUES list named with its column aliases is unparsed as
+                // is, rather than as a derived table selecting from it
+                if let Some((values, columns)) =
+                    subquery_alias_values_and_columns(plan_alias)
+                        .filter(|_| self.dialect.supports_column_alias_in_table_alias())
+                {
+                    let mut builder = self.values_to_sql(values)?;
+                    builder.alias(Some(
+                        self.new_table_alias(
+                            plan_alias.alias.table().to_string(),
+                            columns,
+                        ),
+                    ));
+                    relation.values(builder);
+                    return Ok(());
+                }
+
                 let (plan, mut columns) =
                     subquery_alias_inner_query_and_columns(plan_alias);
                 let unparsed_table_scan = Self::unparse_table_scan_pushdown(
@@ -861,7 +1238,7 @@ impl Unparser<'_> {
                 // Covers cases where the UNION is a subquery and the projection is at the top level
                 if select.already_projected() {
                     return self.derive_with_dialect_alias(
-                        "derived_union",
+                        "union",
                         plan,
                         relation,
                         false,
@@ -869,46 +1246,7 @@ impl Unparser<'_> {
                     );
                 }
 
-                let input_exprs: Vec<SetExpr> = union
-                    .inputs
-                    .iter()
-                    .map(|input| self.select_to_sql_expr(input, query))
-                    .collect::<Result<Vec<_>>>()?;
-
-                if input_exprs.len() < 2 {
-                    return internal_err!("UNION operator requires at least 2 inputs");
-                }
-
-                let set_quantifier =
-                    if query.as_ref().is_some_and(|q| q.is_distinct_union()) {
-                        // Setting the SetQuantifier to None will unparse as a `UNION`
-                        // rather than a `UNION ALL`.
-                        ast::SetQuantifier::None
-                    } else {
-                        ast::SetQuantifier::All
-                    };
-
-                // Build the union expression tree bottom-up by reversing the order
-                // note that we are also swapping left and right inputs because of the rev
-                let union_expr = input_exprs
-                    .into_iter()
-                    .rev()
-                    .reduce(|a, b| SetExpr::SetOperation {
-                        op: ast::SetOperator::Union,
-                        set_quantifier,
-                        left: Box::new(b),
-                        right: Box::new(a),
-                    })
-                    .unwrap();
-
-                let Some(query) = query.as_mut() else {
-                    return internal_err!(
-                        "UNION ALL operator only valid in a statement context"
-                    );
-                };
-                query.body(Box::new(union_expr));
-
-                Ok(())
+                self.union_to_sql(union, query, SetOperationQuantifier::All)
             }
             LogicalPlan::Window(window) => {
                 // Window nodes are handled simultaneously with Projection nodes
@@ -919,11 +1257,33 @@ impl Unparser<'_> {
                     relation,
                 )
             }
+            LogicalPlan::Values(values) => {
+                let mut builder = self.values_to_sql(values)?;
+                if self.dialect.requires_derived_table_alias() {
+                    let columns = values
+                        .schema
+                        .fields()
+                        .iter()
+                        .map(|field| self.new_ident_quoted_if_needs(field.name().clone()))
+                        .collect();
+                    builder.alias(Some(
+                        self.new_table_alias(self.next_alias("values"), columns),
+                    ));
+                }
+                relation.values(builder);
+                Ok(())
+            }
             LogicalPlan::EmptyRelation(_) => {
                 // An EmptyRelation could be behind an UNNEST node. If the dialect supports UNNEST as a table factor,
                 // a TableRelationBuilder will be created for the UNNEST node first.
                 if !relation.has_relation() {
-                    relation.empty();
+                    if self.dialect.requires_from_dual() {
+                        let mut builder = TableRelationBuilder::default();
+                        builder.name(ast::ObjectName::from(vec![Ident::new("DUAL")]));
+                        relation.table(builder);
+                    } else {
+                        relation.empty();
+                    }
                 }
                 Ok(())
             }
@@ -979,7 +1339,7 @@ impl Unparser<'_> {
                     )
                 } else {
                     self.derive_with_dialect_alias(
-                        "derived_unnest",
+                        "unnest",
                         subquery.subquery.as_ref(),
                         relation,
                         true,
@@ -987,6 +1347,15 @@ impl Unparser<'_> {
                     )
                 }
             }
+            // A subquery in the FROM clause is a LATERAL derived table that
+            // refers to the relations before it
+            LogicalPlan::Subquery(subquery) => self.derive_with_dialect_alias(
+                "subquery",
+                subquery.subquery.as_ref(),
+                relation,
+                true,
+                vec![],
+            ),
             _ => {
                 not_impl_err!("Unsupported operator: {plan:?}")
             }
@@ -1046,6 +1415,56 @@ impl Unparser<'_> {
         Ok(Some(unnest_relation))
     }
 
+    /// Unparse a sample of a number of rows of a table, which is planned as the
+    /// first rows of the table in a random order, as the `TABLESAMPLE` clause of
+    /// the table in the [`TableSampleStyle`] of the dialect. Returns the table
+    /// scan and the clause, or `None` if the limit is not such a sample.
+    fn table_sample_to_sql<'a>(
+        &self,
+        limit: &'a Limit,
+    ) -> Result<Option<(&'a LogicalPlan, ast::TableSampleKind)>> {
+        let modifier = match self.dialect.table_sample_style() {
+            TableSampleStyle::Limit => return Ok(None),
+            TableSampleStyle::TableSample => ast::TableSampleModifier::TableSample,
+            TableSampleStyle::Sample => ast::TableSampleModifier::Sample,
+        };
+        let (Some(fetch), None, LogicalPlan::Sort(sort)) =
+            (&limit.fetch, &limit.skip, limit.input.as_ref())
+        else {
+            return Ok(None);
+        };
+        let is_random_order = match sort.expr.as_slice() {
+            [SortExpr {
+                expr: Expr::ScalarFunction(func),
+                ..
+            }] => func.name() == "random" && func.args.is_empty(),
+            _ => false,
+        };
+        match sort.input.as_ref() {
+            LogicalPlan::TableScan(scan)
+                if is_random_order
+                    && sort.fetch.is_none()
+                    && !Self::is_scan_with_pushdown(scan) => {}
+            _ => return Ok(None),
+        }
+        let sample = ast::TableSample {
+            modifier,
+            name: None,
+            quantity: Some(ast::TableSampleQuantity {
+                parenthesized: true,
+                value: self.expr_to_sql(fetch)?,
+                unit: Some(ast::TableSampleUnit::Rows),
+            }),
+            seed: None,
+            bucket: None,
+            offset: None,
+        };
+        Ok(Some((
+            sort.input.as_ref(),
+            ast::TableSampleKind::AfterTableAlias(Box::new(sample)),
+        )))
+    }
+
     fn is_scan_with_pushdown(scan: &TableScan) -> bool {
         scan.projection.is_some() || !scan.filters.is_empty() || scan.fetch.is_some()
     }
@@ -1069,11 +1488,15 @@ impl Unparser<'_> {
                         alias_name: alias_name.clone(),
                     });
 
-                let mut builder = LogicalPlanBuilder::scan(
+                let scan = TableScan::try_new(
                     table_scan.table_name.clone(),
                     Arc::clone(&table_scan.source),
                     None,
-                )?;
+                    vec![],
+                    None,
+                )?
+                .with_hints(table_scan.hints.clone());
+                let mut builder = LogicalPlanBuilder::from(LogicalPlan::TableScan(scan));
                 // We will rebase the column references to the new alias if it exists.
                 // If the projection or filters are empty, we will append alias to the table scan.
                 //
@@ -1209,6 +1632,63 @@ impl Unparser<'_> {
         }
     }
 
+    /// Set the `GROUP BY` clause of `select` from the group expressions of an
+    /// aggregate, unparsing a single grouping set as `ROLLUP`, `CUBE` or
+    /// `GROUPING SETS`
+    ///
+    /// With [`GroupByStyle::Ordinals`], expressions of the already set
+    /// projection are referred to by their position
+    fn group_by_to_sql(
+        &self,
+        group_expr: &[Expr],
+        select: &mut SelectBuilder,
+    ) -> Result<()> {
+        let projections = select.projections().to_vec();
+        let expr_to_sql = |expr: &Expr| {
+            let sql_expr = self.expr_to_sql(expr)?;
+            if self.dialect.group_by_style() != GroupByStyle::Ordinals {
+                return Ok(sql_expr);
+            }
+            let position = projections.iter().position(|item| match item {
+                ast::SelectItem::UnnamedExpr(e)
+                | ast::SelectItem::ExprWithAlias { expr: e, .. } => *e == sql_expr,
+                _ => false,
+            });
+            Ok(match position {
+                Some(index) => {
+                    ast::Expr::value(ast::Value::Number((index + 1).to_string(), false))
+                }
+                None => sql_expr,
+            })
+        };
+        let exprs_to_sql =
+            |exprs: &[Expr]| exprs.iter().map(expr_to_sql).collect::<Result<Vec<_>>>();
+        match group_expr {
+            // A global aggregate needs no `GROUP BY` clause at all
+            _ if is_global_grouping(group_expr) => {}
+            [Expr::GroupingSet(GroupingSet::Rollup(exprs))] => {
+                select.group_by_rollup(exprs_to_sql(exprs)?);
+            }
+            [Expr::GroupingSet(GroupingSet::Cube(exprs))] => {
+                select.group_by_cube(exprs_to_sql(exprs)?);
+            }
+            [Expr::GroupingSet(GroupingSet::GroupingSets(sets))] => {
+                let sets = sets
+                    .iter()
+                    .map(|set| exprs_to_sql(set))
+                    .collect::<Result<Vec<_>>>()?;
+                select.group_by_grouping_sets(sets);
+            }
+            _ => {
+                select.group_by(ast::GroupByExpr::Expressions(
+                    exprs_to_sql(group_expr)?,
+                    vec![],
+                ));
+            }
+        }
+        Ok(())
+    }
+
     fn select_item_to_sql(&self, expr: &Expr) -> Result<ast::SelectItem> {
         match expr {
             Expr::Alias(Alias { expr, name, .. }) => {
@@ -1274,6 +1754,44 @@ impl Unparser<'_> {
         })
     }
 
+    /// Convert the operator of a join with a `LATERAL` derived table to
+    /// `CROSS APPLY` or `OUTER APPLY`. As `CROSS APPLY` has no `ON` clause, the
+    /// condition of an inner join is returned to be applied in the `WHERE`
+    /// clause instead.
+    fn apply_join_operator_to_sql(
+        &self,
+        join_operator: ast::JoinOperator,
+    ) -> Result<(ast::JoinOperator, Option<ast::Expr>)> {
+        let condition = |constraint: ast::JoinConstraint| -> Result<Option<ast::Expr>> {
+            match constraint {
+                ast::JoinConstraint::None => Ok(None),
+                ast::JoinConstraint::On(ast::Expr::Value(ast::ValueWithSpan {
+                    value: ast::Value::Boolean(true),
+                    ..
+                })) => Ok(None),
+                ast::JoinConstraint::On(expr) => Ok(Some(expr)),
+                constraint => {
+                    not_impl_err!("Unsupported APPLY join with constraint {constraint:?}")
+                }
+            }
+        };
+        match join_operator {
+            ast::JoinOperator::CrossJoin => Ok((ast::JoinOperator::CrossApply, None)),
+            ast::JoinOperator::Inner(constraint) => {
+                Ok((ast::JoinOperator::CrossApply, condition(constraint)?))
+            }
+            ast::JoinOperator::LeftOuter(constraint) => match condition(constraint)? {
+                None => Ok((ast::JoinOperator::OuterApply, None)),
+                Some(expr) => {
+                    not_impl_err!("Unsupported OUTER APPLY with join condition {expr}")
+                }
+            },
+            join_operator => {
+                not_impl_err!("Unsupported APPLY join for operator {join_operator:?}")
+            }
+        }
+    }
+
     /// Convert the components of a USING clause to the USING AST. Returns
     /// 'None' if the conditions are not compatible with a USING expression,
     /// e.g. non-column expressions or non-matching names.
@@ -1316,6 +1834,7 @@ impl Unparser<'_> {
         constraint: JoinConstraint,
         conditions: &[(Expr, Expr)],
         filter: Option<&Expr>,
+        null_equality: NullEquality,
     ) -> Result<ast::JoinConstraint> {
         match (constraint, conditions, filter) {
             // No constraints
@@ -1323,35 +1842,45 @@ impl Unparser<'_> {
                 Ok(ast::JoinConstraint::None)
             }
 
-            (JoinConstraint::Using, conditions, None) => {
+            // USING always compares keys with `=`, so it can only express
+            // joins where NULL keys never match
+            (JoinConstraint::Using, conditions, None)
+                if null_equality == NullEquality::NullEqualsNothing =>
+            {
                 match self.join_using_to_sql(conditions) {
                     Some(using) => Ok(using),
                     // As above, this should not be reachable from parsed SQL,
                     // but a user could create this; we "downgrade" to ON.
-                    None => self.join_conditions_to_sql_on(conditions, None),
+                    None => {
+                        self.join_conditions_to_sql_on(conditions, None, null_equality)
+                    }
                 }
             }
 
-            // Two cases here:
+            // Three cases here:
             // 1. Straightforward ON case, with possible equi-join conditions
             //    and additional filters
             // 2. USING with additional filters; we "downgrade" to ON, because
             //    you can't use USING with arbitrary filters. (This should not
             //    be accessible from parsed SQL, but may have been a
             //    custom-built JOIN by a user.)
+            // 3. USING whose keys match NULLs, which can only be expressed
+            //    with `IS NOT DISTINCT FROM` in ON
             (JoinConstraint::On | JoinConstraint::Using, conditions, filter) => {
-                self.join_conditions_to_sql_on(conditions, filter)
+                self.join_conditions_to_sql_on(conditions, filter, null_equality)
             }
         }
     }
 
     // Convert a list of equi0join conditions and an optional filter to a SQL ON
     // AST node, with the equi-join conditions and the filter merged into a
-    // single conditional expression
+    // single conditional expression. Keys of a join where NULLs match each
+    // other are compared with `IS NOT DISTINCT FROM` instead of `=`
     fn join_conditions_to_sql_on(
         &self,
         join_conditions: &[(Expr, Expr)],
         filter: Option<&Expr>,
+        null_equality: NullEquality,
     ) -> Result<ast::JoinConstraint> {
         let mut condition = None;
         // AND the join conditions together to create the overall condition
@@ -1359,7 +1888,14 @@ impl Unparser<'_> {
             // Parse left and right
             let l = self.expr_to_sql(left)?;
             let r = self.expr_to_sql(right)?;
-            let e = self.binary_op_to_sql(l, r, ast::BinaryOperator::Eq);
+            let e = match null_equality {
+                NullEquality::NullEqualsNothing => {
+                    self.binary_op_to_sql(l, r, ast::BinaryOperator::Eq)
+                }
+                NullEquality::NullEqualsNull => {
+                    ast::Expr::IsNotDistinctFrom(Box::new(l), Box::new(r))
+                }
+            };
             condition = match condition {
                 Some(expr) => Some(self.and_op_to_sql(expr, e)),
                 None => Some(e),
@@ -1388,11 +1924,30 @@ impl Unparser<'_> {
         self.binary_op_to_sql(lhs, rhs, ast::BinaryOperator::And)
     }
 
+    /// Unparse the rows of a [`Values`] plan as a `VALUES` table factor
+    fn values_to_sql(&self, values: &Values) -> Result<ValuesRelationBuilder> {
+        let rows = values
+            .values
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|e| self.expr_to_sql(e))
+                    .collect::<Result<_>>()
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let mut builder = ValuesRelationBuilder::default();
+        builder.rows(rows);
+        Ok(builder)
+    }
+
     fn new_table_alias(&self, alias: String, columns: Vec<Ident>) -> ast::TableAlias {
         let columns = columns
             .into_iter()
             .map(|ident| TableAliasColumnDef {
-                name: ident,
+                name: match ident.quote_style {
+                    Some(_) => ident,
+                    None => self.new_ident_quoted_if_needs(ident.value),
+                },
                 data_type: None,
             })
             .collect();
@@ -1407,6 +1962,25 @@ impl Unparser<'_> {
     }
 }
 
+/// Returns true if `plan` is unparsed as a set operation (e.g. `UNION`) rather than a `SELECT`
+fn is_set_operation(plan: &LogicalPlan) -> bool {
+    match plan {
+        LogicalPlan::Union(_) => true,
+        LogicalPlan::Distinct(Distinct::All(input)) => is_set_operation(input),
+        _ => false,
+    }
+}
+
+/// Returns true if unparsing `plan` adds a condition to the `WHERE` clause of
+/// the enclosing `SELECT`
+fn has_selection(plan: &LogicalPlan) -> bool {
+    match plan {
+        LogicalPlan::Filter(_) => true,
+        LogicalPlan::TableScan(scan) => !scan.filters.is_empty(),
+        _ => false,
+    }
+}
+
 impl From<BuilderError> for DataFusionError {
     fn from(e: BuilderError) -> Self {
         DataFusionError::External(Box::new(e))
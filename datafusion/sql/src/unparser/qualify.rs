@@ -0,0 +1,302 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Qualification of the column references of derived tables, see
+//! [`Unparser::with_qualify_columns`]
+
+use std::collections::{HashMap, HashSet};
+use std::ops::ControlFlow;
+use std::sync::Mutex;
+
+use sqlparser::ast::{self, VisitMut, VisitorMut};
+
+use super::Unparser;
+
+/// A column exposed by a derived table: the qualifier it had inside the
+/// derived table, if any, and its name
+type DerivedColumn = (Vec<String>, String);
+
+/// The columns exposed by the derived tables of the statement currently being
+/// unparsed, keyed by the alias generated for the derived table
+#[derive(Debug, Default)]
+pub(super) struct DerivedColumns(Mutex<HashMap<String, Vec<DerivedColumn>>>);
+
+impl DerivedColumns {
+    pub(super) fn insert(&self, alias: String, columns: Vec<DerivedColumn>) {
+        self.0.lock().unwrap().insert(alias, columns);
+    }
+
+    fn get(&self, alias: &str) -> Option<Vec<DerivedColumn>> {
+        self.0.lock().unwrap().get(alias).cloned()
+    }
+}
+
+impl Unparser<'_> {
+    /// Qualify the references to the columns of the derived tables in the
+    /// `FROM` clauses of `query` with the aliases of the derived tables.
+    ///
+    /// Nested queries are left untouched, they are qualified when they are
+    /// unparsed.
+    pub(super) fn qualify_derived_columns(&self, query: &mut ast::Query) {
+        let output_names = match query.body.as_ref() {
+            ast::SetExpr::Select(select) => output_names(&select.projection),
+            _ => HashSet::new(),
+        };
+        let scope = match query.body.as_ref() {
+            ast::SetExpr::Select(select) => Some(self.scope(select)),
+            _ => None,
+        };
+        self.qualify_set_expr(&mut query.body);
+
+        let (Some(scope), Some(order_by)) = (scope, query.order_by.as_mut()) else {
+            return;
+        };
+        if let ast::OrderByKind::Expressions(exprs) = &mut order_by.kind {
+            // An unqualified name in `ORDER BY` may also refer to an unaliased
+            // select item, so it is not attributed to a table
+            let mut visitor = QualifyVisitor {
+                unparser: self,
+                scope: &scope,
+                output_names: &output_names,
+                qualify_table_columns: false,
+                query_depth: 0,
+            };
+            for expr in exprs {
+                let _ = expr.expr.visit(&mut visitor);
+            }
+        }
+    }
+
+    fn qualify_set_expr(&self, set_expr: &mut ast::SetExpr) {
+        match set_expr {
+            ast::SetExpr::Select(select) => {
+                let scope = self.scope(select);
+                if scope.derived.is_empty() && scope.table().is_none() {
+                    return;
+                }
+                let mut visitor = QualifyVisitor {
+                    unparser: self,
+                    scope: &scope,
+                    output_names: &HashSet::new(),
+                    qualify_table_columns: true,
+                    query_depth: 0,
+                };
+                let _ = select.visit(&mut visitor);
+            }
+            ast::SetExpr::SetOperation { left, right, .. } => {
+                self.qualify_set_expr(left);
+                self.qualify_set_expr(right);
+            }
+            ast::SetExpr::Query(query) => self.qualify_derived_columns(query),
+            _ => {}
+        }
+    }
+
+    /// Collect the relations of the `FROM` clause of `select`
+    fn scope(&self, select: &ast::Select) -> Scope {
+        let mut scope = Scope::default();
+        for twj in &select.from {
+            self.collect_relation(&twj.relation, &mut scope);
+            for join in &twj.joins {
+                self.collect_relation(&join.relation, &mut scope);
+            }
+        }
+        scope
+    }
+
+    fn collect_relation(&self, relation: &ast::TableFactor, scope: &mut Scope) {
+        match relation {
+            ast::TableFactor::Derived {
+                alias: Some(alias), ..
+            } => match self.statement.derived_columns.get(&alias.name.value) {
+                Some(columns) => scope.derived.push((alias.name.value.clone(), columns)),
+                None => {
+                    scope.relations.insert(alias.name.value.clone());
+                }
+            },
+            ast::TableFactor::Table { name, alias, .. } => {
+                let name = match alias {
+                    Some(alias) => Some(alias.name.value.clone()),
+                    None => name
+                        .0
+                        .last()
+                        .and_then(|part| part.as_ident())
+                        .map(|ident| ident.value.clone()),
+                };
+                scope.relations.extend(name);
+            }
+            ast::TableFactor::NestedJoin {
+                table_with_joins,
+                alias,
+            } => {
+                if let Some(alias) = alias {
+                    scope.relations.insert(alias.name.value.clone());
+                }
+                self.collect_relation(&table_with_joins.relation, scope);
+                for join in &table_with_joins.joins {
+                    self.collect_relation(&join.relation, scope);
+                }
+            }
+            _ => match relation_alias(relation) {
+                Some(alias) => {
+                    scope.relations.insert(alias.name.value.clone());
+                }
+                None => scope.unnamed = true,
+            },
+        }
+    }
+}
+
+/// The relations visible in a `SELECT`
+#[derive(Default)]
+struct Scope {
+    /// The derived tables with a generated alias and the columns they expose
+    derived: Vec<(String, Vec<DerivedColumn>)>,
+    /// The names of the other relations
+    relations: HashSet<String>,
+    /// Whether there are relations without a name, such as an unaliased
+    /// `UNNEST`, whose columns can only be referenced unqualified
+    unnamed: bool,
+}
+
+impl Scope {
+    /// The name of the only relation other than the derived tables with a
+    /// generated alias, which the columns that none of these derived tables
+    /// expose belong to
+    fn table(&self) -> Option<&str> {
+        let mut relations = self.relations.iter();
+        match (relations.next(), relations.next()) {
+            (Some(relation), None) if !self.unnamed => Some(relation.as_str()),
+            _ => None,
+        }
+    }
+
+    /// The relation of the unqualified column `name`, see [`Self::resolve`]
+    /// and [`Self::table`]
+    fn resolve_unqualified(&self, name: &str, include_table: bool) -> Option<&str> {
+        let derived = self
+            .derived
+            .iter()
+            .any(|(_, columns)| columns.iter().any(|(_, col_name)| col_name == name));
+        if derived {
+            self.resolve(None, name)
+        } else if include_table {
+            self.table()
+        } else {
+            None
+        }
+    }
+
+    /// The alias of the only derived table exposing the column `name`
+    /// qualified by `qualifier`, or any qualifier if `qualifier` is `None`
+    fn resolve(&self, qualifier: Option<&[String]>, name: &str) -> Option<&str> {
+        let mut matches = self.derived.iter().filter(|(_, columns)| {
+            columns.iter().any(|(col_qualifier, col_name)| {
+                col_name == name
+                    && qualifier
+                        .is_none_or(|qualifier| col_qualifier.as_slice() == qualifier)
+            })
+        });
+        match (matches.next(), matches.next()) {
+            (Some((alias, _)), None) => Some(alias.as_str()),
+            _ => None,
+        }
+    }
+}
+
+struct QualifyVisitor<'a, 'b> {
+    unparser: &'a Unparser<'b>,
+    scope: &'a Scope,
+    /// Names of the select items, which `ORDER BY` may refer to unqualified
+    output_names: &'a HashSet<String>,
+    /// Whether to qualify the unqualified columns of a table, see
+    /// [`Scope::table`]
+    qualify_table_columns: bool,
+    query_depth: usize,
+}
+
+impl VisitorMut for QualifyVisitor<'_, '_> {
+    type Break = ();
+
+    fn pre_visit_query(&mut self, _query: &mut ast::Query) -> ControlFlow<()> {
+        self.query_depth += 1;
+        ControlFlow::Continue(())
+    }
+
+    fn post_visit_query(&mut self, _query: &mut ast::Query) -> ControlFlow<()> {
+        self.query_depth -= 1;
+        ControlFlow::Continue(())
+    }
+
+    fn post_visit_expr(&mut self, expr: &mut ast::Expr) -> ControlFlow<()> {
+        if self.query_depth > 0 {
+            return ControlFlow::Continue(());
+        }
+        let alias = match expr {
+            ast::Expr::Identifier(ident) if !self.output_names.contains(&ident.value) => {
+                self.scope
+                    .resolve_unqualified(&ident.value, self.qualify_table_columns)
+            }
+            ast::Expr::CompoundIdentifier(idents) if idents.len() > 1 => {
+                let (name, qualifier) = idents.split_last().unwrap();
+                let qualifier: Vec<String> =
+                    qualifier.iter().map(|ident| ident.value.clone()).collect();
+                if self.scope.relations.contains(qualifier.last().unwrap()) {
+                    None
+                } else {
+                    self.scope.resolve(Some(&qualifier), &name.value)
+                }
+            }
+            _ => None,
+        };
+        if let Some(alias) = alias {
+            let name = match expr {
+                ast::Expr::Identifier(ident) => ident.clone(),
+                ast::Expr::CompoundIdentifier(idents) => idents.last().unwrap().clone(),
+                _ => unreachable!(),
+            };
+            *expr = ast::Expr::CompoundIdentifier(vec![
+                self.unparser.new_ident_quoted_if_needs(alias.to_string()),
+                name,
+            ]);
+        }
+        ControlFlow::Continue(())
+    }
+}
+
+/// The names of the aliased select items of `projection`
+fn output_names(projection: &[ast::SelectItem]) -> HashSet<String> {
+    projection
+        .iter()
+        .filter_map(|item| match item {
+            ast::SelectItem::ExprWithAlias { alias, .. } => Some(alias.value.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+fn relation_alias(relation: &ast::TableFactor) -> Option<&ast::TableAlias> {
+    match relation {
+        ast::TableFactor::Derived { alias, .. }
+        | ast::TableFactor::TableFunction { alias, .. }
+        | ast::TableFactor::Function { alias, .. }
+        | ast::TableFactor::UNNEST { alias, .. }
+        | ast::TableFactor::Pivot { alias, .. }
+        | ast::TableFactor::Unpivot { alias, .. } => alias.as_ref(),
+        _ => None,
+    }
+}
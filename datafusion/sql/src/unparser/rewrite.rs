@@ -17,14 +17,17 @@
 
 use std::{collections::HashSet, sync::Arc};
 
-use arrow::datatypes::Schema;
+use arrow::datatypes::{DataType, Schema};
 use datafusion_common::tree_node::TreeNodeContainer;
 use datafusion_common::{
     tree_node::{Transformed, TransformedResult, TreeNode, TreeNodeRewriter},
-    Column, HashMap, Result, TableReference,
+    Column, DFSchema, HashMap, Result, ScalarValue, TableReference,
+};
+use datafusion_expr::expr::{Alias, BinaryExpr, Cast, UNNEST_COLUMN_PREFIX};
+use datafusion_expr::{
+    binary_expr, lit, Distinct, Expr, ExprSchemable, Filter, LogicalPlan, Operator,
+    Projection, Sort, SortExpr, Values,
 };
-use datafusion_expr::expr::{Alias, UNNEST_COLUMN_PREFIX};
-use datafusion_expr::{Expr, LogicalPlan, Projection, Sort, SortExpr};
 use sqlparser::ast::Ident;
 
 /// Normalize the schema of a union plan to remove qualifiers from the schema fields and sort expressions.
@@ -62,6 +65,12 @@ pub(super) fn normalize_union_schema(plan: &LogicalPlan) -> Result<LogicalPlan>
             let schema = Arc::unwrap_or_clone(union.schema);
             let schema = schema.strip_qualifiers();
 
+            union.inputs = union
+                .inputs
+                .into_iter()
+                .enumerate()
+                .map(|(i, input)| alias_union_input(input, &schema, i == 0))
+                .collect::<Result<_>>()?;
             union.schema = Arc::new(schema);
             Ok(Transformed::yes(LogicalPlan::Union(union)))
         }
@@ -82,6 +91,74 @@ pub(super) fn normalize_union_schema(plan: &LogicalPlan) -> Result<LogicalPlan>
     transformed_plan.data()
 }
 
+/// Alias the columns of a UNION input that are named differently from the
+/// UNION output, as the enclosing query refers to them by the output names.
+///
+/// The projection of an input is aliased in place. Other inputs are only
+/// wrapped into an aliasing projection if they are the first input, as that
+/// is the one naming the columns of a UNION in SQL.
+fn alias_union_input(
+    input: Arc<LogicalPlan>,
+    schema: &DFSchema,
+    is_first: bool,
+) -> Result<Arc<LogicalPlan>> {
+    if input
+        .schema()
+        .fields()
+        .iter()
+        .zip(schema.fields())
+        .all(|(input_field, field)| input_field.name() == field.name())
+    {
+        return Ok(input);
+    }
+
+    let plan = match Arc::unwrap_or_clone(input) {
+        LogicalPlan::Projection(projection) => {
+            let exprs = alias_to_schema(projection.expr, &projection.schema, schema);
+            LogicalPlan::Projection(Projection::try_new(exprs, projection.input)?)
+        }
+        LogicalPlan::Distinct(Distinct::All(input))
+            if matches!(input.as_ref(), LogicalPlan::Projection(_)) =>
+        {
+            LogicalPlan::Distinct(Distinct::All(alias_union_input(
+                input, schema, is_first,
+            )?))
+        }
+        plan if is_first => {
+            let exprs = plan
+                .schema()
+                .columns()
+                .into_iter()
+                .map(Expr::Column)
+                .collect();
+            let exprs = alias_to_schema(exprs, plan.schema(), schema);
+            LogicalPlan::Projection(Projection::try_new(exprs, Arc::new(plan))?)
+        }
+        plan => plan,
+    };
+    Ok(Arc::new(plan))
+}
+
+/// Alias the expressions producing `input_schema` that are named differently
+/// from the corresponding fields of `schema`
+fn alias_to_schema(
+    exprs: Vec<Expr>,
+    input_schema: &DFSchema,
+    schema: &DFSchema,
+) -> Vec<Expr> {
+    exprs
+        .into_iter()
+        .zip(input_schema.fields().iter().zip(schema.fields()))
+        .map(|(expr, (input_field, field))| {
+            if input_field.name() == field.name() {
+                expr
+            } else {
+                expr.unalias().alias(field.name())
+            }
+        })
+        .collect()
+}
+
 /// Rewrite sort expressions that have a UNION plan as their input to remove the table reference.
 fn rewrite_sort_expr_for_union(exprs: Vec<SortExpr>) -> Result<Vec<SortExpr>> {
     let sort_exprs = exprs
@@ -119,8 +196,13 @@ fn rewrite_sort_expr_for_union(exprs: Vec<SortExpr>) -> Result<Vec<SortExpr>> {
 ///       TableScan: j2
 ///
 /// This prevents the original plan generate query with derived table but missing alias.
+///
+/// If `order_by_aliases` is true, sort expressions equal to an aliased
+/// expression of the inner projection are replaced by the alias, and the
+/// columns only these sort expressions refer to are no longer projected.
 pub(super) fn rewrite_plan_for_sort_on_non_projected_fields(
     p: &Projection,
+    order_by_aliases: bool,
 ) -> Option<LogicalPlan> {
     let LogicalPlan::Sort(sort) = p.input.as_ref() else {
         return None;
@@ -156,6 +238,22 @@ pub(super) fn rewrite_plan_for_sort_on_non_projected_fields(
         })
         .collect::<Vec<_>>();
 
+    let mut sort = sort.clone();
+    let mut aliased_columns = HashSet::new();
+    if order_by_aliases {
+        for sort_expr in sort.expr.iter_mut() {
+            if let Some(alias) = projection_alias(&sort_expr.expr, &inner_p.expr) {
+                aliased_columns.extend(sort_expr.expr.column_refs().into_iter().cloned());
+                sort_expr.expr = alias;
+            }
+        }
+        for sort_expr in &sort.expr {
+            for column in sort_expr.expr.column_refs() {
+                aliased_columns.remove(column);
+            }
+        }
+    }
+
     let mut collects = p.expr.clone();
     for sort in &sort.expr {
         collects.push(sort.expr.clone());
@@ -168,11 +266,17 @@ pub(super) fn rewrite_plan_for_sort_on_non_projected_fields(
     let outer_collects = collects.iter().map(Expr::to_string).collect::<HashSet<_>>();
     let inner_collects = inner_exprs
         .iter()
+        .filter(|expr| match expr {
+            Expr::Column(column) => {
+                !aliased_columns.contains(column)
+                    || outer_collects.contains(&expr.to_string())
+            }
+            _ => true,
+        })
         .map(Expr::to_string)
         .collect::<HashSet<_>>();
 
     if outer_collects == inner_collects {
-        let mut sort = sort.clone();
         let mut inner_p = inner_p.clone();
 
         let new_exprs = p
@@ -190,6 +294,174 @@ pub(super) fn rewrite_plan_for_sort_on_non_projected_fields(
     }
 }
 
+/// If `expr` is equal to an aliased expression of `projection`, returns a
+/// column referring to the alias, e.g. `s` for `t.a + t.b` and the projection
+/// `t.a + t.b AS s`. The alias must be the name of only one projected column,
+/// so that an `ORDER BY` on the alias is not ambiguous.
+pub(super) fn projection_alias(expr: &Expr, projection: &[Expr]) -> Option<Expr> {
+    let name = projection.iter().find_map(|projected| match projected {
+        Expr::Alias(alias) if alias.relation.is_none() && alias.expr.as_ref() == expr => {
+            Some(alias.name.clone())
+        }
+        _ => None,
+    })?;
+    let same_name = projection
+        .iter()
+        .filter(|projected| projected.qualified_name().1 == name)
+        .count();
+    (same_name == 1).then(|| Expr::Column(Column::new_unqualified(name)))
+}
+
+/// The names of the columns computed by the window functions of the Window
+/// nodes at the top of `plan`
+pub(super) fn window_output_names(plan: &LogicalPlan) -> HashSet<String> {
+    let mut names = HashSet::new();
+    let mut plan = plan;
+    while let LogicalPlan::Window(window) = plan {
+        names.extend(
+            window
+                .window_expr
+                .iter()
+                .map(|e| e.schema_name().to_string()),
+        );
+        plan = window.input.as_ref();
+    }
+    names
+}
+
+/// Whether `expr` refers to any of the window function outputs in `names`
+pub(super) fn references_window_output(expr: &Expr, names: &HashSet<String>) -> bool {
+    expr.column_refs()
+        .iter()
+        .any(|c| c.relation.is_none() && names.contains(&c.name))
+}
+
+/// Rewrite logic plan for query that filters the output of window functions
+/// (i.e. `QUALIFY`) where the filter can not be unparsed to a `QUALIFY` clause,
+/// because the dialect does not support it or because the predicate also refers
+/// to columns of the window input.
+/// Plan before rewrite:
+///
+/// Projection: person.id, row_number() ORDER BY [person.id ASC NULLS LAST] ... AS rn
+///   Filter: row_number() ORDER BY [person.id ASC NULLS LAST] ... = Int64(1) OR person.age > Int64(30)
+///     WindowAggr: windowExpr=[[row_number() ORDER BY [person.id ASC NULLS LAST] ...]]
+///       TableScan: person
+///
+/// Plan after rewrite
+///
+/// Projection: person.id, rn
+///   Filter: rn = Int64(1) OR person.age > Int64(30)
+///     Projection: person.id, person.age, row_number() ORDER BY [person.id ASC NULLS LAST] ... AS rn
+///       WindowAggr: windowExpr=[[row_number() ORDER BY [person.id ASC NULLS LAST] ...]]
+///         TableScan: person
+///
+/// The window functions are then computed in a derived table and filtered in
+/// the `WHERE` clause of the enclosing query. Window outputs are named after
+/// their alias in the outer projection, or `window_<n>` if they have none.
+pub(super) fn rewrite_plan_for_filter_over_window(
+    p: &Projection,
+    supports_qualify: bool,
+) -> Result<Option<LogicalPlan>> {
+    let LogicalPlan::Filter(filter) = p.input.as_ref() else {
+        return Ok(None);
+    };
+    let window_outputs = window_output_names(filter.input.as_ref());
+    if !references_window_output(&filter.predicate, &window_outputs) {
+        return Ok(None);
+    }
+    let only_window_outputs = filter
+        .predicate
+        .column_refs()
+        .iter()
+        .all(|c| c.relation.is_none() && window_outputs.contains(&c.name));
+    if supports_qualify && only_window_outputs {
+        return Ok(None);
+    }
+
+    let input_schema = filter.input.schema();
+    let mut used_names = input_schema
+        .fields()
+        .iter()
+        .filter(|f| !window_outputs.contains(f.name()))
+        .map(|f| f.name().clone())
+        .collect::<HashSet<_>>();
+    let mut aliases = HashMap::new();
+    for output in input_schema
+        .fields()
+        .iter()
+        .map(|f| f.name())
+        .filter(|name| window_outputs.contains(*name))
+    {
+        let projected_alias = p.expr.iter().find_map(|e| match e {
+            Expr::Alias(Alias { expr, name, .. }) => match expr.as_ref() {
+                Expr::Column(c) if c.relation.is_none() && &c.name == output => {
+                    Some(name.clone())
+                }
+                _ => None,
+            },
+            _ => None,
+        });
+        let alias = match projected_alias {
+            Some(name) if !used_names.contains(&name) => name,
+            _ => (0..)
+                .map(|i| format!("window_{i}"))
+                .find(|name| !used_names.contains(name))
+                .unwrap(),
+        };
+        used_names.insert(alias.clone());
+        aliases.insert(output.clone(), alias);
+    }
+
+    let rename = |expr: Expr| {
+        expr.transform(|e| match e {
+            Expr::Column(c) if c.relation.is_none() && aliases.contains_key(&c.name) => {
+                Ok(Transformed::yes(Expr::Column(Column::new_unqualified(
+                    &aliases[&c.name],
+                ))))
+            }
+            _ => Ok(Transformed::no(e)),
+        })
+        .data()
+    };
+
+    let inner_exprs = input_schema
+        .columns()
+        .into_iter()
+        .map(|c| match aliases.get(&c.name) {
+            Some(alias) if c.relation.is_none() => Expr::Column(c).alias(alias),
+            _ => Expr::Column(c),
+        })
+        .collect();
+    let inner = Projection::try_new(inner_exprs, Arc::clone(&filter.input))?;
+    let filter = Filter::try_new(
+        rename(filter.predicate.clone())?,
+        Arc::new(LogicalPlan::Projection(inner)),
+    )?;
+    let outer_exprs = p
+        .expr
+        .iter()
+        .map(|e| {
+            let e = rename(e.clone())?;
+            // Drop the now redundant alias of `rn AS rn`
+            Ok(match &e {
+                Expr::Alias(Alias { expr, name, .. })
+                    if expr
+                        .try_as_col()
+                        .is_some_and(|c| c.relation.is_none() && &c.name == name) =>
+                {
+                    expr.as_ref().clone()
+                }
+                _ => e,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(Some(LogicalPlan::Projection(Projection::try_new(
+        outer_exprs,
+        Arc::new(LogicalPlan::Filter(filter)),
+    )?)))
+}
+
 /// This logic is to work out the columns and inner query for SubqueryAlias plan for some types of
 /// subquery or unnest
 /// - `(SELECT column_a as a from table) AS A`
@@ -267,6 +539,60 @@ pub(super) fn subquery_alias_inner_query_and_columns(
     (outer_projections.input.as_ref(), columns)
 }
 
+/// Returns the `VALUES` list named by a [`SubqueryAlias`], with the column
+/// aliases of the table alias. The following plan of
+/// `(VALUES (1), (2)) AS t (x)` returns the column alias `x`:
+///
+/// ```text
+/// SubqueryAlias: t
+///   Projection: column1 AS x
+///     Values: (Int64(1)), (Int64(2))
+/// ```
+///
+/// Without column aliases, the columns are named after those of the `VALUES`
+/// list, `column1`, `column2`, ...
+///
+/// [`SubqueryAlias`]: datafusion_expr::SubqueryAlias
+pub(super) fn subquery_alias_values_and_columns(
+    subquery_alias: &datafusion_expr::SubqueryAlias,
+) -> Option<(&Values, Vec<Ident>)> {
+    let (values, exprs) = match subquery_alias.input.as_ref() {
+        LogicalPlan::Values(values) => (values, None),
+        LogicalPlan::Projection(projection) => match projection.input.as_ref() {
+            LogicalPlan::Values(values) => (values, Some(&projection.expr)),
+            _ => return None,
+        },
+        _ => return None,
+    };
+    let fields = values.schema.fields();
+    let Some(exprs) = exprs else {
+        return Some((
+            values,
+            fields.iter().map(|f| f.name().as_str().into()).collect(),
+        ));
+    };
+    if exprs.len() != fields.len() {
+        return None;
+    }
+
+    // Each column of the VALUES list is projected in order, possibly renamed
+    let columns = exprs
+        .iter()
+        .zip(fields)
+        .map(|(expr, field)| {
+            let (expr, name) = match expr {
+                Expr::Alias(alias) => (alias.expr.as_ref(), alias.name.as_str()),
+                expr => (expr, field.name().as_str()),
+            };
+            match expr {
+                Expr::Column(column) if column.name == *field.name() => Some(name.into()),
+                _ => None,
+            }
+        })
+        .collect::<Option<Vec<_>>>()?;
+    Some((values, columns))
+}
+
 /// Try to find the column alias for UNNEST in the inner projection.
 /// For example:
 /// ```sql
@@ -418,3 +744,86 @@ impl TreeNodeRewriter for TableAliasRewriter<'_> {
         }
     }
 }
+
+/// Rewrite comparisons between a column cast to a wider type and a literal to
+/// compare the column with the literal converted to the type of the column,
+/// e.g. `CAST(a AS BIGINT) = 5` to `a = 5` for an `INT` column `a`.
+///
+/// Casts inserted by type coercion otherwise end up on the column in the
+/// unparsed SQL, which keeps the target engine from using an index on it. A
+/// comparison is only rewritten if the literal is represented exactly in the
+/// type of the column, so the result of the comparison does not change.
+pub(super) fn unwrap_cast_in_comparison(expr: Expr, schema: &DFSchema) -> Result<Expr> {
+    expr.transform(|expr| {
+        let Expr::BinaryExpr(BinaryExpr { left, op, right }) = &expr else {
+            return Ok(Transformed::no(expr));
+        };
+        if !matches!(
+            op,
+            Operator::Eq
+                | Operator::NotEq
+                | Operator::Lt
+                | Operator::LtEq
+                | Operator::Gt
+                | Operator::GtEq
+                | Operator::IsDistinctFrom
+                | Operator::IsNotDistinctFrom
+        ) {
+            return Ok(Transformed::no(expr));
+        }
+        let unwrapped = match (left.as_ref(), right.as_ref()) {
+            (Expr::Cast(cast), Expr::Literal(value, _)) => {
+                unwrap_cast(cast, value, schema)
+                    .map(|(left, right)| binary_expr(left, *op, right))
+            }
+            (Expr::Literal(value, _), Expr::Cast(cast)) => {
+                unwrap_cast(cast, value, schema)
+                    .map(|(right, left)| binary_expr(left, *op, right))
+            }
+            _ => None,
+        };
+        Ok(match unwrapped {
+            Some(unwrapped) => Transformed::yes(unwrapped),
+            None => Transformed::no(expr),
+        })
+    })
+    .data()
+}
+
+/// Returns the expression inside `cast` and `value` converted to its type, if
+/// `cast` is widening and `value` is represented exactly in the narrower type
+fn unwrap_cast(
+    cast: &Cast,
+    value: &ScalarValue,
+    schema: &DFSchema,
+) -> Option<(Expr, Expr)> {
+    let source_type = cast.expr.get_type(schema).ok()?;
+    if !is_widening_cast(&source_type, &cast.data_type)
+        || value.data_type() != cast.data_type
+    {
+        return None;
+    }
+    let narrowed = value.cast_to(&source_type).ok()?;
+    if narrowed.cast_to(&cast.data_type).ok()? != *value {
+        return None;
+    }
+    Some((cast.expr.as_ref().clone(), lit(narrowed)))
+}
+
+/// Returns true if every value of `from` is represented exactly in `to`, in
+/// the same order, so comparisons give the same result in either type
+fn is_widening_cast(from: &DataType, to: &DataType) -> bool {
+    use DataType::*;
+    matches!(
+        (from, to),
+        (Int8, Int16 | Int32 | Int64)
+            | (Int16, Int32 | Int64)
+            | (Int32, Int64)
+            | (UInt8, UInt16 | UInt32 | UInt64 | Int16 | Int32 | Int64)
+            | (UInt16, UInt32 | UInt64 | Int32 | Int64)
+            | (UInt32, UInt64 | Int64)
+            | (Float16, Float32 | Float64)
+            | (Float32, Float64)
+            | (Utf8 | LargeUtf8 | Utf8View, Utf8 | LargeUtf8 | Utf8View)
+    )
+}
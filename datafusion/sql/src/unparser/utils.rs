@@ -15,24 +15,30 @@
 // specific language governing permissions and limitations
 // under the License.
 
-use std::{cmp::Ordering, sync::Arc, vec};
+use std::{
+    any::Any, cmp::Ordering, collections::BTreeMap, ops::ControlFlow, sync::Arc, vec,
+};
 
 use super::{
     dialect::CharacterLengthStyle, dialect::DateFieldExtractStyle,
-    rewrite::TableAliasRewriter, Unparser,
+    dialect::NamedWindowStyle, rewrite::TableAliasRewriter, Unparser,
 };
+use arrow::datatypes::DataType;
 use datafusion_common::{
     internal_err,
-    tree_node::{Transformed, TransformedResult, TreeNode},
+    tree_node::{Transformed, TransformedResult, TreeNode, TreeNodeRecursion},
     Column, DataFusionError, Result, ScalarValue,
 };
 use datafusion_expr::{
-    expr, utils::grouping_set_to_exprlist, Aggregate, Expr, LogicalPlan,
-    LogicalPlanBuilder, Projection, SortExpr, Unnest, Window,
+    expr::{self, ScalarFunction},
+    utils::grouping_set_to_exprlist,
+    Aggregate, BinaryExpr, Cast, ColumnarValue, Expr, GroupingSet, LogicalPlan,
+    LogicalPlanBuilder, Operator, Projection, ScalarFunctionArgs, ScalarUDF,
+    ScalarUDFImpl, Signature, SortExpr, TableScan, Unnest, Volatility, Window,
 };
 
 use indexmap::IndexSet;
-use sqlparser::ast;
+use sqlparser::ast::{self, VisitMut, VisitorMut};
 use sqlparser::tokenizer::Span;
 
 /// Recursively searches children of [LogicalPlan] to find an Aggregate node if exists
@@ -156,6 +162,70 @@ pub(crate) fn find_window_nodes_within_select<'a>(
     }
 }
 
+/// Find the table function scan below the projection planned for a table
+/// function called `WITH ORDINALITY`, i.e. a projection of the output of the
+/// function and of a `row_number()` over all of its rows.
+///
+/// Returns the scan and the projection with the `row_number()` column replaced
+/// by the ordinality column of the table function.
+pub(crate) fn find_table_function_with_ordinality(
+    p: &Projection,
+) -> Result<Option<(&LogicalPlan, Vec<Expr>)>> {
+    let LogicalPlan::Window(window) = p.input.as_ref() else {
+        return Ok(None);
+    };
+    // Table function scans are named after the function, see `create_relation`
+    let is_table_function = matches!(
+        window.input.as_ref(),
+        LogicalPlan::TableScan(scan) if scan.table_name.table().ends_with("()")
+    );
+    let [Expr::WindowFunction(func)] = window.window_expr.as_slice() else {
+        return Ok(None);
+    };
+    if !is_table_function
+        || func.fun.name() != "row_number"
+        || !func.params.args.is_empty()
+        || !func.params.partition_by.is_empty()
+        || !func.params.order_by.is_empty()
+    {
+        return Ok(None);
+    }
+
+    let row_number = window.window_expr[0].schema_name().to_string();
+    // The ordinality column is named `ordinality`, with a numeric suffix if the
+    // function returns a column of that name
+    let Some(ordinality_name) =
+        p.expr
+            .iter()
+            .zip(p.schema.fields())
+            .find_map(|(expr, field)| match expr.clone().unalias() {
+                Expr::Column(c) if c.name == row_number => Some(field.name().clone()),
+                _ => None,
+            })
+    else {
+        return Ok(None);
+    };
+    let exprs = p
+        .expr
+        .iter()
+        .zip(p.schema.fields())
+        .map(|(expr, field)| {
+            let ordinality = expr.clone().unalias().transform(|e| match e {
+                Expr::Column(c) if c.name == row_number => Ok(Transformed::yes(
+                    Expr::Column(Column::from_name(&ordinality_name)),
+                )),
+                e => Ok(Transformed::no(e)),
+            })?;
+            if ordinality.transformed {
+                ordinality.data.alias_if_changed(field.name().to_string())
+            } else {
+                Ok(expr.clone())
+            }
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(Some((window.input.as_ref(), exprs)))
+}
+
 /// Recursively identify Column expressions and transform them into the appropriate unnest expression
 ///
 /// For example, if expr contains the column expr "__unnest_placeholder(make_array(Int64(1),Int64(2),Int64(2),Int64(5),NULL),depth=1)"
@@ -195,6 +265,7 @@ pub(crate) fn unproject_agg_exprs(
     agg: &Aggregate,
     windows: Option<&[&Window]>,
 ) -> Result<Expr> {
+    let expr = unproject_grouping_functions(expr, agg)?;
     expr.transform(|sub_expr| {
             if let Expr::Column(c) = sub_expr {
                 if let Some(unprojected_expr) = find_agg_expr(agg, &c)? {
@@ -236,6 +307,171 @@ pub(crate) fn unproject_window_exprs(expr: Expr, windows: &[&Window]) -> Result<
     .map(|e| e.data)
 }
 
+/// Whether the group expressions of an aggregate put all input rows into a
+/// single group, i.e. there are no grouping keys or a single empty grouping set
+/// such as `GROUPING SETS (())`
+pub(crate) fn is_global_grouping(group_expr: &[Expr]) -> bool {
+    match group_expr {
+        [] => true,
+        [Expr::GroupingSet(GroupingSet::Rollup(exprs) | GroupingSet::Cube(exprs))] => {
+            exprs.is_empty()
+        }
+        [Expr::GroupingSet(GroupingSet::GroupingSets(sets))] => {
+            matches!(sets.as_slice(), [set] if set.is_empty())
+        }
+        _ => false,
+    }
+}
+
+/// Replace the expressions computed from the internal grouping id of `agg` by
+/// the analyzer with the `GROUPING(...)` calls they were derived from.
+///
+/// See [`Aggregate::INTERNAL_GROUPING_ID`] for the layout of the grouping id.
+fn unproject_grouping_functions(expr: Expr, agg: &Aggregate) -> Result<Expr> {
+    if !matches!(agg.group_expr.as_slice(), [Expr::GroupingSet(_)]) {
+        return Ok(expr);
+    }
+    let grouping_exprs = grouping_set_to_exprlist(agg.group_expr.as_slice())?;
+    expr.transform_down(|sub_expr| {
+        match grouping_function_args(&sub_expr, &grouping_exprs) {
+            Some(args) => {
+                let grouping = Expr::ScalarFunction(ScalarFunction::new_udf(
+                    Arc::new(ScalarUDF::new_from_impl(GroupingFunction::default())),
+                    args,
+                ));
+                Ok(Transformed::new(grouping, true, TreeNodeRecursion::Jump))
+            }
+            None => Ok(Transformed::no(sub_expr)),
+        }
+    })
+    .data()
+}
+
+/// Return the arguments of the `GROUPING(...)` call `expr` was derived from,
+/// if it has the form `CAST(grouping id bits AS INT)`.
+///
+/// Each argument is a grouping expression whose bit is masked out of the
+/// grouping id and shifted to the position of the argument, where the last
+/// argument is the least significant bit.
+fn grouping_function_args(expr: &Expr, grouping_exprs: &[&Expr]) -> Option<Vec<Expr>> {
+    let Expr::Cast(Cast {
+        expr,
+        data_type: DataType::Int32,
+    }) = expr
+    else {
+        return None;
+    };
+    if is_internal_grouping_id(expr) {
+        return Some(grouping_exprs.iter().map(|e| (*e).clone()).collect());
+    }
+
+    let mut terms = vec![];
+    collect_bitwise_or_terms(expr, &mut terms);
+    let mut args = BTreeMap::new();
+    for term in terms {
+        let (masked, shift) = match term {
+            Expr::BinaryExpr(BinaryExpr {
+                left,
+                op: Operator::BitwiseShiftLeft,
+                right,
+            }) => (left.as_ref(), literal_as_i64(right)?),
+            Expr::BinaryExpr(BinaryExpr {
+                left,
+                op: Operator::BitwiseShiftRight,
+                right,
+            }) => (left.as_ref(), -literal_as_i64(right)?),
+            _ => (term, 0),
+        };
+        let Expr::BinaryExpr(BinaryExpr {
+            left,
+            op: Operator::BitwiseAnd,
+            right,
+        }) = masked
+        else {
+            return None;
+        };
+        let mask = literal_as_i64(right)?;
+        if !is_internal_grouping_id(left) || mask <= 0 || mask.count_ones() != 1 {
+            return None;
+        }
+        let bit = i64::from(mask.trailing_zeros());
+        let grouping_expr =
+            grouping_exprs.get(grouping_exprs.len().checked_sub(bit as usize + 1)?)?;
+        args.insert(bit + shift, (*grouping_expr).clone());
+    }
+
+    // the argument positions must be exactly 0..n
+    if args.keys().copied().ne(0..args.len() as i64) {
+        return None;
+    }
+    Some(args.into_values().rev().collect())
+}
+
+fn collect_bitwise_or_terms<'a>(expr: &'a Expr, terms: &mut Vec<&'a Expr>) {
+    match expr {
+        Expr::BinaryExpr(BinaryExpr {
+            left,
+            op: Operator::BitwiseOr,
+            right,
+        }) => {
+            collect_bitwise_or_terms(left, terms);
+            collect_bitwise_or_terms(right, terms);
+        }
+        _ => terms.push(expr),
+    }
+}
+
+fn is_internal_grouping_id(expr: &Expr) -> bool {
+    matches!(expr, Expr::Column(c) if c.relation.is_none() && c.name == Aggregate::INTERNAL_GROUPING_ID)
+}
+
+fn literal_as_i64(expr: &Expr) -> Option<i64> {
+    match expr {
+        Expr::Literal(value, _) => match value.cast_to(&DataType::Int64).ok()? {
+            ScalarValue::Int64(value) => value,
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Stand-in for the `GROUPING` aggregate function, used to unparse the
+/// expressions derived from the internal grouping id. It is never evaluated.
+#[derive(Debug, PartialEq, Eq, Hash)]
+struct GroupingFunction {
+    signature: Signature,
+}
+
+impl Default for GroupingFunction {
+    fn default() -> Self {
+        Self {
+            signature: Signature::variadic_any(Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for GroupingFunction {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "grouping"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Int32)
+    }
+
+    fn invoke_with_args(&self, _args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        internal_err!("GROUPING cannot be evaluated while unparsing")
+    }
+}
+
 fn find_agg_expr<'a>(agg: &'a Aggregate, column: &Column) -> Result<Option<&'a Expr>> {
     if let Ok(index) = agg.schema.index_of_column(column) {
         if matches!(agg.group_expr.as_slice(), [Expr::GroupingSet(_)]) {
@@ -325,6 +561,102 @@ pub(crate) fn unproject_sort_expr(
     Ok(sort_expr)
 }
 
+/// If `plan` collects the rows of a subquery into a list, as planned for an
+/// `ARRAY(<subquery>)` expression, returns the plan of that subquery:
+///
+/// ```text
+/// Projection: CASE WHEN array_agg(t.a) ORDER BY [..] IS NULL THEN [] ELSE array_agg(t.a) ORDER BY [..] END AS a
+///   Aggregate: groupBy=[[]], aggr=[[array_agg(t.a) ORDER BY [t.b ASC NULLS LAST]]]
+///     TableScan: t
+/// ```
+///
+/// is the plan of `SELECT t.a FROM t ORDER BY t.b ASC NULLS LAST`.
+pub(crate) fn array_subquery_input(plan: &LogicalPlan) -> Result<Option<LogicalPlan>> {
+    let LogicalPlan::Projection(projection) = plan else {
+        return Ok(None);
+    };
+    let LogicalPlan::Aggregate(agg) = projection.input.as_ref() else {
+        return Ok(None);
+    };
+    let [Expr::Alias(expr::Alias { expr, name, .. })] = projection.expr.as_slice() else {
+        return Ok(None);
+    };
+    let [Expr::AggregateFunction(expr::AggregateFunction {
+        func,
+        params:
+            expr::AggregateFunctionParams {
+                args,
+                distinct: false,
+                filter: None,
+                order_by,
+                null_treatment: None,
+            },
+    })] = agg.aggr_expr.as_slice()
+    else {
+        return Ok(None);
+    };
+    if !agg.group_expr.is_empty() || func.name() != "array_agg" || args.len() != 1 {
+        return Ok(None);
+    }
+
+    // The empty list replaces the `NULL` of aggregating no rows
+    let list = Expr::Column(Column::from(agg.schema.qualified_field(0)));
+    let Expr::Case(expr::Case {
+        expr: None,
+        when_then_expr,
+        else_expr: Some(else_expr),
+    }) = expr.as_ref()
+    else {
+        return Ok(None);
+    };
+    let defaults_to_empty_list = match when_then_expr.as_slice() {
+        [(when, then)] => {
+            **when == list.clone().is_null()
+                && matches!(
+                    then.as_ref(),
+                    Expr::Literal(ScalarValue::List(empty), _) if empty.values().is_empty()
+                )
+        }
+        _ => false,
+    };
+    if !defaults_to_empty_list || **else_expr != list {
+        return Ok(None);
+    }
+
+    // The input of a `LIMIT` is the complete subquery, including its ordering
+    let input = &agg.input;
+    let input_has_ordering = order_by.is_empty()
+        || matches!(input.as_ref(), LogicalPlan::Sort(_) | LogicalPlan::Limit(_));
+    if input_has_ordering
+        && input.schema().fields().len() == 1
+        && args[0] == Expr::Column(Column::from(input.schema().qualified_field(0)))
+        && input.schema().field(0).name() == name
+    {
+        return Ok(Some(input.as_ref().clone()));
+    }
+
+    // Columns selected only for the ordering are selected again by the sort
+    let input = match input.as_ref() {
+        LogicalPlan::Projection(projection)
+            if projection.expr.iter().all(|e| matches!(e, Expr::Column(_))) =>
+        {
+            Arc::clone(&projection.input)
+        }
+        _ => Arc::clone(input),
+    };
+    let arg = match &args[0] {
+        Expr::Column(column) if &column.name == name => args[0].clone(),
+        arg => arg.clone().alias(name),
+    };
+    let subquery = LogicalPlanBuilder::from(input).project(vec![arg])?;
+    let subquery = if order_by.is_empty() {
+        subquery
+    } else {
+        subquery.sort(order_by.clone())?
+    };
+    subquery.build().map(Some)
+}
+
 /// Iterates through the children of a [LogicalPlan] to find a TableScan node before encountering
 /// a Projection or any unexpected node that indicates the presence of a Projection (SELECT) in the plan.
 /// If a TableScan node is found, returns the TableScan node without filters, along with the collected filters separately.
@@ -389,11 +721,12 @@ pub(crate) fn try_transform_to_simple_table_scan_with_filters(
                     }
                 }
 
-                let mut builder = LogicalPlanBuilder::scan(
-                    table_scan.table_name.clone(),
-                    Arc::clone(&table_scan.source),
-                    table_scan.projection.clone(),
-                )?;
+                let mut builder =
+                    LogicalPlanBuilder::from(LogicalPlan::TableScan(TableScan {
+                        filters: vec![],
+                        fetch: None,
+                        ..table_scan.clone()
+                    }));
 
                 if let Some(alias) = table_alias.take() {
                     builder = builder.alias(alias)?;
@@ -576,3 +909,327 @@ pub(crate) fn sqlite_date_trunc_to_sql(
 
     Ok(None)
 }
+
+/// Whether a set operation keeps or removes duplicate rows, e.g. `UNION ALL`
+/// or `UNION`. Each set operation carries its own quantifier, so nested set
+/// operations of different kinds unparse independently.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SetOperationQuantifier {
+    All,
+    Distinct,
+}
+
+impl From<SetOperationQuantifier> for ast::SetQuantifier {
+    fn from(quantifier: SetOperationQuantifier) -> Self {
+        match quantifier {
+            SetOperationQuantifier::All => ast::SetQuantifier::All,
+            // `DISTINCT` is the default of set operations, so it is left out
+            SetOperationQuantifier::Distinct => ast::SetQuantifier::None,
+        }
+    }
+}
+
+/// Builds a [`ast::SetExpr::SetOperation`], wrapping operands that are themselves
+/// set operations in parentheses so the SQL text keeps the grouping of the plan.
+///
+/// `INTERSECT` binds tighter than `UNION` and `EXCEPT` in standard SQL, and set
+/// operators associate to the left, so a left operand is parenthesized when its
+/// precedence differs from `op`'s and a right operand is always parenthesized.
+pub(crate) fn set_operation_to_sql(
+    op: ast::SetOperator,
+    quantifier: SetOperationQuantifier,
+    left: ast::SetExpr,
+    right: ast::SetExpr,
+) -> ast::SetExpr {
+    let left = match &left {
+        ast::SetExpr::SetOperation { op: left_op, .. }
+            if set_operator_precedence(left_op) != set_operator_precedence(&op) =>
+        {
+            parenthesize_set_expr(left)
+        }
+        _ => left,
+    };
+    let right = match &right {
+        ast::SetExpr::SetOperation { .. } => parenthesize_set_expr(right),
+        _ => right,
+    };
+    ast::SetExpr::SetOperation {
+        op,
+        set_quantifier: quantifier.into(),
+        left: Box::new(left),
+        right: Box::new(right),
+    }
+}
+
+fn set_operator_precedence(op: &ast::SetOperator) -> u8 {
+    match op {
+        ast::SetOperator::Intersect => 2,
+        ast::SetOperator::Union | ast::SetOperator::Except | ast::SetOperator::Minus => 1,
+    }
+}
+
+fn parenthesize_set_expr(expr: ast::SetExpr) -> ast::SetExpr {
+    ast::SetExpr::Query(Box::new(ast::Query {
+        with: None,
+        body: Box::new(expr),
+        order_by: None,
+        limit_clause: None,
+        fetch: None,
+        locks: vec![],
+        for_clause: None,
+        settings: None,
+        format_clause: None,
+        pipe_operators: vec![],
+    }))
+}
+
+/// Moves the window specifications of a SELECT list into the definitions of
+/// its `WINDOW` clause according to `style`, and returns the definitions.
+///
+/// A specification is named once it is used by more than one window function.
+/// With [`NamedWindowStyle::Chained`], specifications sharing a `PARTITION BY`
+/// extend a base window holding only that partitioning. Window functions of
+/// nested subqueries are left untouched, as the names are scoped to this SELECT.
+pub(crate) fn name_window_specs(
+    projection: &mut [ast::SelectItem],
+    style: NamedWindowStyle,
+) -> Vec<ast::NamedWindowDefinition> {
+    if style == NamedWindowStyle::Inline {
+        return vec![];
+    }
+
+    // The distinct specifications in order of first use, with their use count
+    let mut specs: Vec<(ast::WindowSpec, usize)> = vec![];
+    visit_window_types(projection, |window| {
+        if let ast::WindowType::WindowSpec(spec) = window {
+            match specs.iter_mut().find(|(s, _)| s == spec) {
+                Some((_, count)) => *count += 1,
+                None => specs.push((spec.clone(), 1)),
+            }
+        }
+    });
+
+    let mut definitions = vec![];
+    let mut define = |spec: ast::WindowSpec| {
+        let name = ast::Ident::new(format!("w{}", definitions.len() + 1));
+        definitions.push(ast::NamedWindowDefinition(
+            name.clone(),
+            ast::NamedWindowExpr::WindowSpec(spec),
+        ));
+        name
+    };
+    let mut bases: Vec<(&[ast::Expr], ast::Ident)> = vec![];
+    let mut replacements = vec![];
+    for (spec, count) in &specs {
+        let shares_partition = style == NamedWindowStyle::Chained
+            && !spec.partition_by.is_empty()
+            && specs
+                .iter()
+                .filter(|(s, _)| s.partition_by == spec.partition_by)
+                .count()
+                > 1;
+        let replacement = if shares_partition {
+            let base = match bases.iter().find(|(p, _)| *p == spec.partition_by) {
+                Some((_, name)) => name.clone(),
+                None => {
+                    let name = define(ast::WindowSpec {
+                        window_name: None,
+                        partition_by: spec.partition_by.clone(),
+                        order_by: vec![],
+                        window_frame: None,
+                    });
+                    bases.push((&spec.partition_by, name.clone()));
+                    name
+                }
+            };
+            let extended = ast::WindowSpec {
+                window_name: Some(base.clone()),
+                partition_by: vec![],
+                order_by: spec.order_by.clone(),
+                window_frame: spec.window_frame.clone(),
+            };
+            if spec.order_by.is_empty() && spec.window_frame.is_none() {
+                ast::WindowType::NamedWindow(base)
+            } else if *count > 1 {
+                ast::WindowType::NamedWindow(define(extended))
+            } else {
+                ast::WindowType::WindowSpec(extended)
+            }
+        } else if *count > 1 {
+            ast::WindowType::NamedWindow(define(spec.clone()))
+        } else {
+            continue;
+        };
+        replacements.push((spec, replacement));
+    }
+
+    visit_window_types(projection, |window| {
+        if let ast::WindowType::WindowSpec(spec) = window {
+            if let Some((_, replacement)) = replacements.iter().find(|(s, _)| *s == spec)
+            {
+                *window = replacement.clone();
+            }
+        }
+    });
+    definitions
+}
+
+/// Calls `f` with the `OVER` clause of every window function in `projection`,
+/// without descending into subqueries
+fn visit_window_types(
+    projection: &mut [ast::SelectItem],
+    f: impl FnMut(&mut ast::WindowType),
+) {
+    struct WindowTypeVisitor<F> {
+        query_depth: usize,
+        f: F,
+    }
+
+    impl<F: FnMut(&mut ast::WindowType)> VisitorMut for WindowTypeVisitor<F> {
+        type Break = ();
+
+        fn pre_visit_query(&mut self, _query: &mut ast::Query) -> ControlFlow<()> {
+            self.query_depth += 1;
+            ControlFlow::Continue(())
+        }
+
+        fn post_visit_query(&mut self, _query: &mut ast::Query) -> ControlFlow<()> {
+            self.query_depth -= 1;
+            ControlFlow::Continue(())
+        }
+
+        fn post_visit_expr(&mut self, expr: &mut ast::Expr) -> ControlFlow<()> {
+            if let ast::Expr::Function(ast::Function {
+                over: Some(window), ..
+            }) = expr
+            {
+                if self.query_depth == 0 {
+                    (self.f)(window);
+                }
+            }
+            ControlFlow::Continue(())
+        }
+    }
+
+    let mut visitor = WindowTypeVisitor { query_depth: 0, f };
+    for item in projection {
+        let _ = item.visit(&mut visitor);
+    }
+}
+
+/// Render `statement` as SQL text with the aliases of the select items of
+/// every query written without the `AS` keyword, as in `SELECT a b`.
+///
+/// sqlparser always displays an aliased select item with `AS`, so the items of
+/// a copy of `statement` are replaced by identifiers holding their rendered
+/// SQL. `statement` itself is left unchanged.
+pub(crate) fn sql_without_column_alias_as(statement: &ast::Statement) -> String {
+    struct ColumnAliasVisitor;
+
+    impl VisitorMut for ColumnAliasVisitor {
+        type Break = ();
+
+        fn post_visit_query(&mut self, query: &mut ast::Query) -> ControlFlow<()> {
+            omit_set_expr_alias_as(&mut query.body);
+            ControlFlow::Continue(())
+        }
+    }
+
+    fn omit_set_expr_alias_as(set_expr: &mut ast::SetExpr) {
+        match set_expr {
+            ast::SetExpr::Select(select) => {
+                for item in select.projection.iter_mut() {
+                    if let ast::SelectItem::ExprWithAlias { expr, alias } = item {
+                        let sql = format!("{expr} {alias}");
+                        *item = ast::SelectItem::UnnamedExpr(ast::Expr::Identifier(
+                            ast::Ident::new(sql),
+                        ));
+                    }
+                }
+            }
+            ast::SetExpr::SetOperation { left, right, .. } => {
+                omit_set_expr_alias_as(left);
+                omit_set_expr_alias_as(right);
+            }
+            // Nested queries are visited on their own
+            _ => {}
+        }
+    }
+
+    let mut statement = statement.clone();
+    let _ = statement.visit(&mut ColumnAliasVisitor);
+    statement.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn select(table: &str) -> ast::SetExpr {
+        let sql = format!("SELECT * FROM {table}");
+        let statement = sqlparser::parser::Parser::parse_sql(
+            &sqlparser::dialect::GenericDialect {},
+            &sql,
+        )
+        .unwrap()
+        .remove(0);
+        let ast::Statement::Query(query) = statement else {
+            unreachable!()
+        };
+        *query.body
+    }
+
+    fn set_op(op: ast::SetOperator, left: ast::SetExpr, right: ast::SetExpr) -> String {
+        set_operation_to_sql(op, SetOperationQuantifier::Distinct, left, right)
+            .to_string()
+    }
+
+    #[test]
+    fn test_set_operation_to_sql_precedence() {
+        let union = |l, r| set_op(ast::SetOperator::Union, l, r);
+        let union_ab = set_operation_to_sql(
+            ast::SetOperator::Union,
+            SetOperationQuantifier::Distinct,
+            select("a"),
+            select("b"),
+        );
+        let intersect_bc = set_operation_to_sql(
+            ast::SetOperator::Intersect,
+            SetOperationQuantifier::Distinct,
+            select("b"),
+            select("c"),
+        );
+        let except_bc = set_operation_to_sql(
+            ast::SetOperator::Except,
+            SetOperationQuantifier::Distinct,
+            select("b"),
+            select("c"),
+        );
+
+        // (a UNION b) INTERSECT c
+        assert_eq!(
+            set_op(ast::SetOperator::Intersect, union_ab.clone(), select("c")),
+            "(SELECT * FROM a UNION SELECT * FROM b) INTERSECT SELECT * FROM c"
+        );
+        // (a UNION b) EXCEPT c: same precedence on the left keeps its grouping
+        assert_eq!(
+            set_op(ast::SetOperator::Except, union_ab.clone(), select("c")),
+            "SELECT * FROM a UNION SELECT * FROM b EXCEPT SELECT * FROM c"
+        );
+        // (b INTERSECT c) UNION a
+        assert_eq!(
+            union(intersect_bc.clone(), select("a")),
+            "(SELECT * FROM b INTERSECT SELECT * FROM c) UNION SELECT * FROM a"
+        );
+        // a UNION (b INTERSECT c)
+        assert_eq!(
+            union(select("a"), intersect_bc),
+            "SELECT * FROM a UNION (SELECT * FROM b INTERSECT SELECT * FROM c)"
+        );
+        // a UNION (b EXCEPT c)
+        assert_eq!(
+            union(select("a"), except_bc),
+            "SELECT * FROM a UNION (SELECT * FROM b EXCEPT SELECT * FROM c)"
+        );
+    }
+}
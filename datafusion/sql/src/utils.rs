@@ -31,16 +31,97 @@ use datafusion_common::{
 };
 use datafusion_expr::builder::get_struct_unnested_columns;
 use datafusion_expr::expr::{
-    Alias, GroupingSet, Unnest, WindowFunction, WindowFunctionParams,
+    Alias, Case, GroupingSet, Unnest, WindowFunction, WindowFunctionParams,
 };
 use datafusion_expr::utils::{expr_as_column_expr, find_column_exprs};
 use datafusion_expr::{
-    col, expr_vec_fmt, ColumnUnnestList, Expr, ExprSchemable, LogicalPlan,
+    col, expr_vec_fmt, ColumnUnnestList, Expr, ExprSchemable, JoinConstraint, JoinType,
+    LogicalPlan,
 };
 
 use indexmap::IndexMap;
 use sqlparser::ast::{Ident, Value};
 
+/// A column joined by a `USING` join
+#[derive(Debug, Clone)]
+pub(crate) struct UsingJoinColumn {
+    /// The column of the left input of the join
+    pub left: Column,
+    /// The column of the right input of the join
+    pub right: Column,
+    /// The value of the column in the output of the join, as in the SQL
+    /// standard: the left column for inner and left joins, the right column
+    /// for right joins, and the first non-null of them for full joins. The
+    /// column of an input that is itself a `USING` join of the column has the
+    /// value of that join.
+    pub value: Expr,
+}
+
+/// Returns the columns joined by the `USING` joins of the join tree of a FROM
+/// clause planned as `plan`, outermost join first. The joins of derived tables
+/// are not included.
+pub(crate) fn using_join_columns(plan: &LogicalPlan) -> Result<Vec<UsingJoinColumn>> {
+    fn collect_joins(
+        plan: &LogicalPlan,
+        joins: &mut Vec<(Column, Column, JoinType)>,
+    ) -> Result<()> {
+        let LogicalPlan::Join(join) = plan else {
+            return Ok(());
+        };
+        if join.join_constraint == JoinConstraint::Using {
+            for (left, right) in &join.on {
+                // The join keys in using-join must be columns.
+                let (Some(left), Some(right)) =
+                    (left.get_as_join_column(), right.get_as_join_column())
+                else {
+                    return internal_err!(
+                        "Invalid join key. Expected columns, found {left:?} and {right:?}"
+                    );
+                };
+                joins.push((left.clone(), right.clone(), join.join_type));
+            }
+        }
+        collect_joins(&join.left, joins)?;
+        collect_joins(&join.right, joins)
+    }
+    let mut joins = vec![];
+    collect_joins(plan, &mut joins)?;
+
+    // A join is collected before the joins of its inputs, whose values are
+    // needed first
+    let mut values: HashMap<Column, Expr> = HashMap::new();
+    let mut columns = joins
+        .into_iter()
+        .rev()
+        .map(|(left, right, join_type)| {
+            let value_of = |column: &Column| {
+                values
+                    .get(column)
+                    .cloned()
+                    .unwrap_or_else(|| Expr::Column(column.clone()))
+            };
+            let (left_value, right_value) = (value_of(&left), value_of(&right));
+            let value = match join_type {
+                JoinType::Right => right_value,
+                JoinType::Full => Expr::Case(Case::new(
+                    None,
+                    vec![(
+                        Box::new(left_value.clone().is_not_null()),
+                        Box::new(left_value),
+                    )],
+                    Some(Box::new(right_value)),
+                )),
+                _ => left_value,
+            };
+            values.insert(left.clone(), value.clone());
+            values.insert(right.clone(), value.clone());
+            UsingJoinColumn { left, right, value }
+        })
+        .collect::<Vec<_>>();
+    columns.reverse();
+    Ok(columns)
+}
+
 /// Make a best-effort attempt at resolving all columns in the expression tree
 pub(crate) fn resolve_columns(expr: &Expr, plan: &LogicalPlan) -> Result<Expr> {
     expr.clone()
@@ -302,6 +383,23 @@ pub(crate) fn make_decimal_type(
     }
 }
 
+/// Return an error if `observed` exceeds `limit`, the value of the
+/// [`ParserOptions`] field `name` that bounds the `quantity` of a statement.
+/// A limit of 0 disables the check.
+///
+/// [`ParserOptions`]: crate::planner::ParserOptions
+pub(crate) fn check_limit(
+    name: &str,
+    limit: usize,
+    quantity: &str,
+    observed: usize,
+) -> Result<()> {
+    if limit > 0 && observed > limit {
+        return plan_err!("{quantity} {observed} exceeds the {name} limit of {limit}");
+    }
+    Ok(())
+}
+
 /// Normalize an owned identifier to a lowercase string, unless the identifier is quoted.
 pub(crate) fn normalize_ident(id: Ident) -> String {
     match id.quote_style {
@@ -19,8 +19,10 @@ use std::sync::Arc;
 
 use crate::planner::{ContextProvider, PlannerContext, SqlToRel};
 use datafusion_common::{DFSchema, Result};
-use datafusion_expr::{LogicalPlan, LogicalPlanBuilder};
-use sqlparser::ast::Values as SQLValues;
+use datafusion_expr::{Expr, LogicalPlan, LogicalPlanBuilder};
+use sqlparser::ast::{
+    Expr as SQLExpr, UnaryOperator, Value, ValueWithSpan, Values as SQLValues,
+};
 
 impl<S: ContextProvider> SqlToRel<'_, S> {
     pub(super) fn sql_values_to_plan(
@@ -38,7 +40,7 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
             .into_iter()
             .map(|row| {
                 row.into_iter()
-                    .map(|v| self.sql_to_expr(v, &empty_schema, planner_context))
+                    .map(|v| self.values_expr_to_expr(v, &empty_schema, planner_context))
                     .collect::<Result<Vec<_>>>()
             })
             .collect::<Result<Vec<_>>>()?;
@@ -50,4 +52,39 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
             LogicalPlanBuilder::values_with_schema(values, &schema)?.build()
         }
     }
+
+    /// Plan an expression of a VALUES list. Long lists mostly consist of
+    /// literals, which are planned directly rather than with the generic
+    /// [`Self::sql_to_expr`], as they neither depend on the schema nor are
+    /// recorded in the metadata of the statement like placeholders.
+    fn values_expr_to_expr(
+        &self,
+        sql: SQLExpr,
+        schema: &DFSchema,
+        planner_context: &mut PlannerContext,
+    ) -> Result<Expr> {
+        match sql {
+            SQLExpr::Value(value) if !matches!(value.value, Value::Placeholder(_)) => {
+                self.parse_value(value.into(), &[])
+            }
+            SQLExpr::UnaryOp {
+                op: UnaryOperator::Minus,
+                expr,
+            } => match *expr {
+                SQLExpr::Value(ValueWithSpan {
+                    value: Value::Number(n, _),
+                    ..
+                }) => self.parse_sql_number(&n, true),
+                expr => self.sql_to_expr(
+                    SQLExpr::UnaryOp {
+                        op: UnaryOperator::Minus,
+                        expr: Box::new(expr),
+                    },
+                    schema,
+                    planner_context,
+                ),
+            },
+            sql => self.sql_to_expr(sql, schema, planner_context),
+        }
+    }
 }
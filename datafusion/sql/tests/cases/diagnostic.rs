@@ -200,6 +200,63 @@ fn test_ambiguous_reference() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_ambiguous_reference_candidates() -> Result<()> {
+    let messages = |query: &'static str| {
+        let diag = do_query(query);
+        std::iter::once(diag.message)
+            .chain(diag.notes.into_iter().map(|note| note.message))
+            .chain(diag.helps.into_iter().map(|help| help.message))
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    assert_snapshot!(
+        messages("SELECT a.id FROM person a, person b WHERE age > 30"),
+        @r"
+    column 'age' is ambiguous
+    possible column a.age
+    possible column b.age
+    "
+    );
+    assert_snapshot!(
+        messages("SELECT max(a.id) FROM person a, person b GROUP BY a.age HAVING age > 30"),
+        @r"
+    column 'age' is ambiguous
+    possible column a.age
+    possible column b.age
+    "
+    );
+    // Derived tables are candidates by their alias
+    assert_snapshot!(
+        messages("SELECT id FROM (SELECT id FROM person) AS d, person"),
+        @r"
+    column 'id' is ambiguous
+    possible column d.id
+    possible column person.id
+    "
+    );
+    assert_snapshot!(
+        messages("SELECT first_name FROM person a JOIN person b USING (id) JOIN person c USING (id)"),
+        @r"
+    column 'first_name' is ambiguous
+    possible column a.first_name
+    possible column b.first_name
+    possible column c.first_name
+    "
+    );
+    assert_snapshot!(
+        messages("SELECT id FROM person a JOIN person b USING (id) JOIN person c ON a.id = c.id"),
+        @r"
+    column 'id' is ambiguous
+    possible column a.id
+    possible column b.id
+    possible column c.id
+    a.id and b.id are joined by USING or NATURAL, so the unqualified name would refer to a.id if it did not also match c.id
+    "
+    );
+    Ok(())
+}
+
 #[test]
 fn test_incompatible_types_binary_arithmetic() -> Result<()> {
     let query =
@@ -17,30 +17,46 @@
 
 use arrow::datatypes::{DataType, Field, Schema};
 use datafusion_common::{
-    assert_contains, Column, DFSchema, DFSchemaRef, DataFusionError, Result,
-    TableReference,
+    assert_contains, Column, DFSchema, DFSchemaRef, DataFusionError, NullEquality,
+    Result, ScalarValue, TableReference,
 };
 use datafusion_expr::test::function_stub::{
     count_udaf, max_udaf, min_udaf, sum, sum_udaf,
 };
 use datafusion_expr::{
-    cast, col, lit, table_scan, wildcard, EmptyRelation, Expr, Extension, LogicalPlan,
-    LogicalPlanBuilder, Union, UserDefinedLogicalNode, UserDefinedLogicalNodeCore,
+    bitwise_and, bitwise_or, bitwise_shift_left, bitwise_shift_right, cast, col, lit,
+    table_scan, wildcard, Aggregate, EmptyRelation, Expr, Extension, Filter, GroupingSet,
+    LogicalPlan, LogicalPlanBuilder, Projection, Union, UserDefinedLogicalNode,
+    UserDefinedLogicalNodeCore,
 };
 use datafusion_functions::unicode;
+use datafusion_functions_aggregate::array_agg::array_agg_udaf;
+use datafusion_functions_aggregate::first_last::{first_value_udaf, last_value_udaf};
 use datafusion_functions_aggregate::grouping::grouping_udaf;
+use datafusion_functions_aggregate::string_agg::string_agg_udaf;
 use datafusion_functions_nested::make_array::make_array_udf;
 use datafusion_functions_nested::map::map_udf;
+use datafusion_functions_window::expr_fn::row_number;
 use datafusion_functions_window::rank::rank_udwf;
+use datafusion_functions_window::row_number::row_number_udwf;
 use datafusion_sql::planner::{ContextProvider, PlannerContext, SqlToRel};
 use datafusion_sql::unparser::dialect::{
-    BigQueryDialect, CustomDialectBuilder, DefaultDialect as UnparserDefaultDialect,
-    DefaultDialect, Dialect as UnparserDialect, MySqlDialect as UnparserMySqlDialect,
-    PostgreSqlDialect as UnparserPostgreSqlDialect, SqliteDialect,
+    BigQueryDialect, ColumnExclusionStyle, CustomDialectBuilder,
+    DefaultDialect as UnparserDefaultDialect, DefaultDialect, Dialect as UnparserDialect,
+    DuckDBDialect, GroupByStyle, LimitStyle, MySqlDialect as UnparserMySqlDialect,
+    NamedWindowStyle, PostgreSqlDialect as UnparserPostgreSqlDialect, SqliteDialect,
+    TableSampleStyle,
+};
+use datafusion_sql::unparser::{
+    expr_to_sql, plan_to_sql, AliasGenerator, StatementAliasGenerator, Unparser,
 };
-use datafusion_sql::unparser::{expr_to_sql, plan_to_sql, Unparser};
 use insta::assert_snapshot;
-use sqlparser::ast::Statement;
+use sqlparser::ast::{
+    Expr as SQLExpr, FunctionArg, FunctionArgExpr, FunctionArgOperator, Ident,
+    ObjectName, Statement, Value,
+};
+use sqlparser::keywords::Keyword;
+use std::collections::HashSet;
 use std::hash::Hash;
 use std::ops::Add;
 use std::sync::Arc;
@@ -56,12 +72,15 @@ use datafusion_functions_nested::extract::array_element_udf;
 use datafusion_functions_nested::planner::{FieldAccessPlanner, NestedFunctionPlanner};
 use datafusion_sql::unparser::ast::{
     DerivedRelationBuilder, QueryBuilder, RelationBuilder, SelectBuilder,
+    TableRelationBuilder, TableWithJoinsBuilder,
 };
 use datafusion_sql::unparser::extension_unparser::{
     UnparseToStatementResult, UnparseWithinStatementResult,
     UserDefinedLogicalNodeUnparser,
 };
-use sqlparser::dialect::{Dialect, GenericDialect, MySqlDialect};
+use sqlparser::dialect::{
+    Dialect, GenericDialect, MsSqlDialect, MySqlDialect, PostgreSqlDialect,
+};
 use sqlparser::parser::Parser;
 
 #[test]
@@ -118,12 +137,17 @@ fn roundtrip_statement() -> Result<()> {
             "select ta.j1_id from j1 ta order by ta.j1_id;",
             "select * from j1 ta order by ta.j1_id, ta.j1_string desc;",
             "select * from j1 limit 10;",
+            "select ta.j1_id from j1 ta order by ta.j1_id fetch first 2 rows with ties;",
+            "select ta.j1_id from j1 ta order by ta.j1_id offset 1 rows fetch next 2 rows with ties;",
             "select ta.j1_id from j1 ta where ta.j1_id > 1;",
             "select ta.j1_id, tb.j2_string from j1 ta join j2 tb on (ta.j1_id = tb.j2_id);",
             "select ta.j1_id, tb.j2_string, tc.j3_string from j1 ta join j2 tb on (ta.j1_id = tb.j2_id) join j3 tc on (ta.j1_id = tc.j3_id);",
+            "select ta.j1_id, tb.j2_string from j1 ta left join j2 tb on ta.j1_id is not distinct from tb.j2_id and ta.j1_id between tb.j2_id and 10;",
             "select * from (select id, first_name from person)",
             "select * from (select id, first_name from (select * from person))",
             "select id, count(*) as cnt from (select id from person) group by id",
+            "select count(*) from j1 having count(*) > 0",
+            "select count(*)",
             "select (id-1)/2, count(*) / (sum(id/10)-1) as agg_expr from (select (id-1) as id from person) group by id",
             "select CAST(id/2 as VARCHAR) NOT LIKE 'foo*' from person where NOT EXISTS (select ta.j1_id, tb.j2_string from j1 ta join j2 tb on (ta.j1_id = tb.j2_id))",
             r#"select "First Name" from person_quoted_cols"#,
@@ -134,6 +158,7 @@ fn roundtrip_statement() -> Result<()> {
             "select id, count(*) as cnt from (select p1.id as id from person p1 inner join person p2 on p1.id=p2.id) group by id",
             "select id, count(*), first_name from person group by first_name, id",
             "select id, sum(age), first_name from person group by first_name, id",
+            "select age + 1 as age, count(*) from person group by age",
             "select id, count(*), first_name
             from person
             where id!=3 and first_name=='test'
@@ -202,6 +227,9 @@ fn roundtrip_statement() -> Result<()> {
             FROM person
             JOIN orders ON person.id = orders.customer_id
             GROUP BY ROLLUP(id, first_name, last_name, customer_id)"#,
+            r#"SELECT id, first_name, SUM(id) AS total_sum
+            FROM person
+            GROUP BY CUBE(id, first_name)"#,
             r#"SELECT id, first_name, last_name,
             SUM(id) AS total_sum,
             COUNT(*) AS total_count,
@@ -215,7 +243,11 @@ fn roundtrip_statement() -> Result<()> {
             "SELECT left[1] FROM array",
             "SELECT {a:1, b:2}",
             "SELECT s.a FROM (SELECT {a:1, b:2} AS s)",
-            "SELECT MAP {'a': 1, 'b': 2}"
+            "SELECT MAP {'a': 1, 'b': 2}",
+            "SELECT max(age) OVER (ORDER BY birth_date RANGE BETWEEN INTERVAL '1 DAYS' PRECEDING AND CURRENT ROW) FROM person",
+            "SELECT max(age) OVER (ORDER BY birth_date DESC RANGE BETWEEN INTERVAL '1 DAYS' PRECEDING AND INTERVAL '2 HOURS' FOLLOWING) FROM person",
+            "SELECT max(age) OVER (ORDER BY age DESC RANGE BETWEEN 1 PRECEDING AND 2 FOLLOWING) FROM person",
+            "SELECT max(age) OVER (ORDER BY birth_date RANGE BETWEEN INTERVAL '1 DAY 2 HOURS' PRECEDING AND INTERVAL '30 MINUTES' PRECEDING) FROM person"
     ];
 
     // For each test sql string, we transform as follows:
@@ -254,6 +286,55 @@ fn roundtrip_statement() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn roundtrip_statement_with_named_windows() -> Result<()> {
+    let query = "SELECT \
+        sum(age) OVER (PARTITION BY state ORDER BY id), \
+        max(age) OVER (PARTITION BY state ORDER BY id), \
+        sum(age) OVER (PARTITION BY state), \
+        max(age) OVER (PARTITION BY state ORDER BY age ROWS BETWEEN 1 PRECEDING AND CURRENT ROW), \
+        sum(age) OVER (ORDER BY id), \
+        max(age) OVER (ORDER BY id) \
+        FROM person";
+    let statement = Parser::new(&GenericDialect {})
+        .try_with_sql(query)?
+        .parse_statement()?;
+    let state = MockSessionState::default()
+        .with_aggregate_function(sum_udaf())
+        .with_aggregate_function(max_udaf());
+    let context = MockContextProvider { state };
+    let sql_to_rel = SqlToRel::new(&context);
+    let plan = sql_to_rel.sql_statement_to_plan(statement)?;
+
+    let mut roundtrip_statements = vec![];
+    for style in [
+        NamedWindowStyle::Inline,
+        NamedWindowStyle::Named,
+        NamedWindowStyle::Chained,
+    ] {
+        let dialect = CustomDialectBuilder::new()
+            .with_named_window_style(style)
+            .build();
+        let roundtrip_statement = Unparser::new(&dialect).plan_to_sql(&plan)?;
+
+        let plan_roundtrip =
+            sql_to_rel.sql_statement_to_plan(roundtrip_statement.clone())?;
+        assert_eq!(plan, plan_roundtrip);
+
+        roundtrip_statements.push(roundtrip_statement.to_string());
+    }
+
+    assert_snapshot!(
+        roundtrip_statements.join("\n"),
+        @r#"
+    SELECT sum(person.age) OVER (PARTITION BY person.state ORDER BY person.id ASC NULLS LAST RANGE BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW), max(person.age) OVER (PARTITION BY person.state ORDER BY person.id ASC NULLS LAST RANGE BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW), sum(person.age) OVER (PARTITION BY person.state ROWS BETWEEN UNBOUNDED PRECEDING AND UNBOUNDED FOLLOWING), max(person.age) OVER (PARTITION BY person.state ORDER BY person.age ASC NULLS LAST ROWS BETWEEN 1 PRECEDING AND CURRENT ROW), sum(person.age) OVER (ORDER BY person.id ASC NULLS LAST RANGE BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW), max(person.age) OVER (ORDER BY person.id ASC NULLS LAST RANGE BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW) FROM person
+    SELECT sum(person.age) OVER w1, max(person.age) OVER w1, sum(person.age) OVER (PARTITION BY person.state ROWS BETWEEN UNBOUNDED PRECEDING AND UNBOUNDED FOLLOWING), max(person.age) OVER (PARTITION BY person.state ORDER BY person.age ASC NULLS LAST ROWS BETWEEN 1 PRECEDING AND CURRENT ROW), sum(person.age) OVER w2, max(person.age) OVER w2 FROM person WINDOW w1 AS (PARTITION BY person.state ORDER BY person.id ASC NULLS LAST RANGE BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW), w2 AS (ORDER BY person.id ASC NULLS LAST RANGE BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW)
+    SELECT sum(person.age) OVER w2, max(person.age) OVER w2, sum(person.age) OVER (w1 ROWS BETWEEN UNBOUNDED PRECEDING AND UNBOUNDED FOLLOWING), max(person.age) OVER (w1 ORDER BY person.age ASC NULLS LAST ROWS BETWEEN 1 PRECEDING AND CURRENT ROW), sum(person.age) OVER w3, max(person.age) OVER w3 FROM person WINDOW w1 AS (PARTITION BY person.state), w2 AS (w1 ORDER BY person.id ASC NULLS LAST RANGE BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW), w3 AS (ORDER BY person.id ASC NULLS LAST RANGE BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW)
+    "#
+    );
+    Ok(())
+}
+
 #[test]
 fn roundtrip_crossjoin() -> Result<()> {
     let query = "select j1.j1_id, j2.j2_string from j1, j2";
@@ -307,6 +388,12 @@ macro_rules! roundtrip_statement_with_dialect_helper {
         let state = MockSessionState::default()
             .with_aggregate_function(max_udaf())
             .with_aggregate_function(min_udaf())
+            .with_aggregate_function(array_agg_udaf())
+            .with_aggregate_function(first_value_udaf())
+            .with_aggregate_function(last_value_udaf())
+            .with_aggregate_function(string_agg_udaf())
+            .with_window_function(rank_udwf())
+            .with_window_function(row_number_udwf())
             .with_expr_planner(Arc::new(CoreFunctionPlanner::default()))
             .with_expr_planner(Arc::new(NestedFunctionPlanner))
             .with_expr_planner(Arc::new(FieldAccessPlanner));
@@ -325,6 +412,61 @@ macro_rules! roundtrip_statement_with_dialect_helper {
     }};
 }
 
+#[test]
+fn roundtrip_statement_with_table_hints() -> Result<(), DataFusionError> {
+    let context = MockContextProvider {
+        state: MockSessionState::default(),
+    };
+    let plan = |sql: &str| -> Result<LogicalPlan> {
+        let statement = Parser::new(&MySqlDialect {})
+            .try_with_sql(sql)?
+            .parse_statement()?;
+        SqlToRel::new(&context).sql_statement_to_plan(statement)
+    };
+    let unparser = Unparser::new(&UnparserMySqlDialect {});
+
+    let sql = "select ta.j1_id from j1 ta use index (idx_a) \
+        join j2 force index for join (idx_b, idx_c) on ta.j1_id = j2.j2_id";
+    assert_snapshot!(
+        unparser.plan_to_sql(&plan(sql)?)?,
+        @"SELECT `ta`.`j1_id` FROM `j1` AS `ta` USE INDEX (idx_a) INNER JOIN `j2` FORCE INDEX FOR JOIN (idx_b, idx_c) ON (`ta`.`j1_id` = `j2`.`j2_id`)"
+    );
+
+    // The hints are only emitted for the reference of the table they were given for
+    let sql = "select a.j1_id from j1 a join j1 b use index (idx_b) on a.j1_id = b.j1_id";
+    assert_snapshot!(
+        unparser.plan_to_sql(&plan(sql)?)?,
+        @"SELECT `a`.`j1_id` FROM `j1` AS `a` INNER JOIN `j1` AS `b` USE INDEX (idx_b) ON (`a`.`j1_id` = `b`.`j1_id`)"
+    );
+    Ok(())
+}
+
+#[test]
+fn roundtrip_statement_with_order_by_aliases() -> Result<(), DataFusionError> {
+    let state = MockSessionState::default().with_aggregate_function(min_udaf());
+    let context = MockContextProvider { state };
+    let sql_to_rel = SqlToRel::new(&context);
+    let unparser = Unparser::default().with_order_by_aliases(true);
+    let unparse = |sql: &str| -> Result<String> {
+        let statement = Parser::new(&GenericDialect {})
+            .try_with_sql(sql)?
+            .parse_statement()?;
+        let plan = sql_to_rel.sql_statement_to_plan(statement)?;
+        Ok(unparser.plan_to_sql(&plan)?.to_string())
+    };
+
+    // The sorted expression is not projected without its alias
+    assert_snapshot!(
+        unparse("select min(ta.j1_id) as j1_min from j1 ta order by min(ta.j1_id) limit 10")?,
+        @"SELECT min(ta.j1_id) AS j1_min FROM j1 AS ta ORDER BY j1_min ASC NULLS LAST LIMIT 10"
+    );
+    assert_snapshot!(
+        unparse("select j1_id + 1 as s, j1_id from j1 order by j1_id + 1 desc")?,
+        @"SELECT (j1.j1_id + 1) AS s, j1.j1_id FROM j1 ORDER BY s DESC NULLS FIRST"
+    );
+    Ok(())
+}
+
 #[test]
 fn roundtrip_statement_with_dialect_1() -> Result<(), DataFusionError> {
     roundtrip_statement_with_dialect_helper!(
@@ -395,6 +537,178 @@ fn roundtrip_statement_with_dialect_6() -> Result<(), DataFusionError> {
     Ok(())
 }
 
+#[test]
+fn roundtrip_statement_with_dialect_fetch() -> Result<(), DataFusionError> {
+    roundtrip_statement_with_dialect_helper!(
+        sql: "select ta.j1_id from j1 ta order by j1_id fetch first 10 rows only;",
+        parser_dialect: GenericDialect {},
+        unparser_dialect: UnparserDefaultDialect {},
+        expected: @"SELECT ta.j1_id FROM j1 AS ta ORDER BY ta.j1_id ASC NULLS LAST LIMIT 10",
+    );
+    Ok(())
+}
+
+#[test]
+fn roundtrip_statement_with_dialect_qualify() -> Result<(), DataFusionError> {
+    roundtrip_statement_with_dialect_helper!(
+        sql: "SELECT id, row_number() OVER (PARTITION BY age ORDER BY id) AS rn FROM person QUALIFY rn = 1",
+        parser_dialect: GenericDialect {},
+        unparser_dialect: BigQueryDialect {},
+        expected: @r#"
+    SELECT `person`.`id`, row_number() OVER (PARTITION BY `person`.`age` ORDER BY `person`.`id` ASC NULLS LAST) AS `rn` FROM `person` QUALIFY (row_number() OVER (PARTITION BY `person`.`age` ORDER BY `person`.`id` ASC NULLS LAST) = 1)
+    "#,
+    );
+    roundtrip_statement_with_dialect_helper!(
+        sql: "SELECT id FROM person QUALIFY rank() OVER (ORDER BY salary) <= 5",
+        parser_dialect: GenericDialect {},
+        unparser_dialect: BigQueryDialect {},
+        expected: @r#"
+    SELECT `person`.`id` FROM `person` QUALIFY (rank() OVER (ORDER BY `person`.`salary` ASC NULLS LAST) <= 5)
+    "#,
+    );
+    roundtrip_statement_with_dialect_helper!(
+        sql: "SELECT id, row_number() OVER (PARTITION BY age ORDER BY id) AS rn FROM person QUALIFY rn = 1 OR age > 30",
+        parser_dialect: GenericDialect {},
+        unparser_dialect: BigQueryDialect {},
+        expected: @r#"
+    SELECT `person`.`id`, `rn` FROM (SELECT `person`.`id`, `person`.`first_name`, `person`.`last_name`, `person`.`age`, `person`.`state`, `person`.`salary`, `person`.`birth_date`, `person`.`😀`, row_number() OVER (PARTITION BY `person`.`age` ORDER BY `person`.`id` ASC NULLS LAST) AS `rn` FROM `person`) WHERE ((`rn` = 1) OR (`person`.`age` > 30))
+    "#,
+    );
+    roundtrip_statement_with_dialect_helper!(
+        sql: "SELECT id, row_number() OVER (PARTITION BY age ORDER BY id) AS rn FROM person QUALIFY rn = 1",
+        parser_dialect: GenericDialect {},
+        unparser_dialect: UnparserDefaultDialect {},
+        expected: @r#"
+    SELECT person.id, rn FROM (SELECT person.id, person.first_name, person.last_name, person.age, person.state, person.salary, person.birth_date, person."😀", row_number() OVER (PARTITION BY person.age ORDER BY person.id ASC NULLS LAST RANGE BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW) AS rn FROM person) WHERE (rn = 1)
+    "#,
+    );
+    Ok(())
+}
+
+#[test]
+fn roundtrip_statement_with_dialect_array_subquery() -> Result<(), DataFusionError> {
+    roundtrip_statement_with_dialect_helper!(
+        sql: "SELECT p.id, ARRAY(SELECT first_name FROM person WHERE last_name = p.last_name ORDER BY id) FROM person p",
+        parser_dialect: GenericDialect {},
+        unparser_dialect: UnparserPostgreSqlDialect {},
+        expected: @r#"SELECT "p"."id", ARRAY(SELECT "person"."first_name" FROM "person" WHERE ("person"."last_name" = "p"."last_name") ORDER BY "person"."id" ASC NULLS LAST) FROM "person" AS "p""#,
+    );
+    roundtrip_statement_with_dialect_helper!(
+        sql: "SELECT id, ARRAY(SELECT j1_id FROM j1) FROM person",
+        parser_dialect: GenericDialect {},
+        unparser_dialect: UnparserPostgreSqlDialect {},
+        expected: @r#"SELECT "person"."id", ARRAY(SELECT "j1"."j1_id" FROM "j1") FROM "person""#,
+    );
+    roundtrip_statement_with_dialect_helper!(
+        sql: "SELECT id, ARRAY(SELECT j1_id FROM j1) FROM person",
+        parser_dialect: GenericDialect {},
+        unparser_dialect: UnparserDefaultDialect {},
+        expected: @"SELECT person.id, (SELECT CASE WHEN array_agg(j1.j1_id) IS NULL THEN [] ELSE array_agg(j1.j1_id) END AS j1_id FROM (SELECT j1.j1_id FROM j1)) FROM person",
+    );
+    Ok(())
+}
+
+#[test]
+fn roundtrip_statement_with_dialect_null_treatment() -> Result<(), DataFusionError> {
+    roundtrip_statement_with_dialect_helper!(
+        sql: "SELECT last_value(age) IGNORE NULLS OVER (ORDER BY birth_date ROWS BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW) FROM person",
+        parser_dialect: GenericDialect {},
+        unparser_dialect: UnparserDefaultDialect {},
+        expected: @"SELECT last_value(person.age) IGNORE NULLS OVER (ORDER BY person.birth_date ASC NULLS LAST ROWS BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW) FROM person",
+    );
+    // IGNORE NULLS of an aggregate falls back to filtering out the null values
+    roundtrip_statement_with_dialect_helper!(
+        sql: "SELECT first_value(age) FILTER (WHERE id > 1) IGNORE NULLS FROM person",
+        parser_dialect: GenericDialect {},
+        unparser_dialect: UnparserPostgreSqlDialect {},
+        expected: @r#"SELECT first_value("person"."age") FILTER (WHERE (("person"."id" > 1) AND "person"."age" IS NOT NULL)) FROM "person""#,
+    );
+    // RESPECT NULLS is the default behavior
+    roundtrip_statement_with_dialect_helper!(
+        sql: "SELECT first_value(age) RESPECT NULLS FROM person",
+        parser_dialect: GenericDialect {},
+        unparser_dialect: UnparserPostgreSqlDialect {},
+        expected: @r#"SELECT first_value("person"."age") FROM "person""#,
+    );
+
+    // MySQL supports neither IGNORE NULLS nor FILTER
+    let statement = Parser::new(&GenericDialect {})
+        .try_with_sql("SELECT first_value(age) IGNORE NULLS FROM person")?
+        .parse_statement()?;
+    let context = MockContextProvider {
+        state: MockSessionState::default().with_aggregate_function(first_value_udaf()),
+    };
+    let plan = SqlToRel::new(&context).sql_statement_to_plan(statement)?;
+    let err = Unparser::new(&UnparserMySqlDialect {})
+        .plan_to_sql(&plan)
+        .unwrap_err();
+    assert_snapshot!(
+        err.strip_backtrace(),
+        @"This feature is not implemented: Unsupported IGNORE NULLS for first_value in the dialect"
+    );
+    Ok(())
+}
+
+#[test]
+fn roundtrip_statement_with_dialect_string_agg() -> Result<(), DataFusionError> {
+    roundtrip_statement_with_dialect_helper!(
+        sql: "SELECT string_agg(first_name, ',' ORDER BY first_name) FROM person",
+        parser_dialect: PostgreSqlDialect {},
+        unparser_dialect: UnparserPostgreSqlDialect {},
+        expected: @r#"SELECT string_agg("person"."first_name", ',' ORDER BY "person"."first_name" ASC NULLS LAST) FROM "person""#,
+    );
+    roundtrip_statement_with_dialect_helper!(
+        sql: "SELECT string_agg(DISTINCT first_name, ',' ORDER BY first_name DESC) FROM person",
+        parser_dialect: PostgreSqlDialect {},
+        unparser_dialect: UnparserPostgreSqlDialect {},
+        expected: @r#"SELECT string_agg(DISTINCT "person"."first_name", ',' ORDER BY "person"."first_name" DESC NULLS FIRST) FROM "person""#,
+    );
+    roundtrip_statement_with_dialect_helper!(
+        sql: "SELECT GROUP_CONCAT(DISTINCT first_name ORDER BY first_name) FROM person",
+        parser_dialect: MySqlDialect {},
+        unparser_dialect: UnparserMySqlDialect {},
+        expected: @"SELECT GROUP_CONCAT(DISTINCT `person`.`first_name` ORDER BY `person`.`first_name` ASC SEPARATOR ',') FROM `person`",
+    );
+    roundtrip_statement_with_dialect_helper!(
+        sql: "SELECT GROUP_CONCAT(first_name ORDER BY id SEPARATOR ';') FROM person",
+        parser_dialect: MySqlDialect {},
+        unparser_dialect: UnparserMySqlDialect {},
+        expected: @"SELECT GROUP_CONCAT(`person`.`first_name` ORDER BY `person`.`id` ASC SEPARATOR ';') FROM `person`",
+    );
+    // The MySQL spelling is planned as `string_agg`
+    roundtrip_statement_with_dialect_helper!(
+        sql: "SELECT GROUP_CONCAT(DISTINCT first_name ORDER BY first_name SEPARATOR ';') FROM person",
+        parser_dialect: MySqlDialect {},
+        unparser_dialect: UnparserPostgreSqlDialect {},
+        expected: @r#"SELECT string_agg(DISTINCT "person"."first_name", ';' ORDER BY "person"."first_name" ASC NULLS LAST) FROM "person""#,
+    );
+    Ok(())
+}
+
+#[test]
+fn roundtrip_statement_with_dialect_values() -> Result<(), DataFusionError> {
+    roundtrip_statement_with_dialect_helper!(
+        sql: "SELECT * FROM (VALUES (1, 'a'), (2, 'b')) AS t(x, y)",
+        parser_dialect: GenericDialect {},
+        unparser_dialect: UnparserDefaultDialect {},
+        expected: @"SELECT t.x, t.y FROM (VALUES (1, 'a'), (2, 'b')) AS t (x, y)",
+    );
+    roundtrip_statement_with_dialect_helper!(
+        sql: "SELECT x FROM (VALUES (1), (2)) AS t(x) WHERE x > 1",
+        parser_dialect: GenericDialect {},
+        unparser_dialect: UnparserPostgreSqlDialect {},
+        expected: @r#"SELECT "t"."x" FROM (VALUES (1), (2)) AS "t" ("x") WHERE ("t"."x" > 1)"#,
+    );
+    // Without column aliases, the columns are named after those of VALUES
+    roundtrip_statement_with_dialect_helper!(
+        sql: "SELECT column1 FROM (VALUES (1), (2)) AS t",
+        parser_dialect: GenericDialect {},
+        unparser_dialect: UnparserDefaultDialect {},
+        expected: @"SELECT t.column1 FROM (VALUES (1), (2)) AS t (column1)",
+    );
+    Ok(())
+}
+
 #[test]
 fn roundtrip_statement_with_dialect_7() -> Result<(), DataFusionError> {
     roundtrip_statement_with_dialect_helper!(
@@ -758,7 +1072,7 @@ fn roundtrip_statement_with_dialect_32() -> Result<(), DataFusionError> {
         sql: "SELECT * FROM UNNEST([1,2,3])",
         parser_dialect: GenericDialect {},
         unparser_dialect: unparser,
-        expected: @r#"SELECT UNNEST(make_array(Int64(1),Int64(2),Int64(3))) FROM UNNEST([1, 2, 3])"#,
+        expected: @r#"SELECT "UNNEST(make_array(Int64(1),Int64(2),Int64(3)))" FROM UNNEST([1, 2, 3])"#,
     );
     Ok(())
 }
@@ -797,7 +1111,7 @@ fn roundtrip_statement_with_dialect_35() -> Result<(), DataFusionError> {
         sql: "SELECT * FROM UNNEST([1,2,3]), j1",
         parser_dialect: GenericDialect {},
         unparser_dialect: unparser,
-        expected: @r#"SELECT UNNEST(make_array(Int64(1),Int64(2),Int64(3))), j1.j1_id, j1.j1_string FROM UNNEST([1, 2, 3]) CROSS JOIN j1"#,
+        expected: @r#"SELECT "UNNEST(make_array(Int64(1),Int64(2),Int64(3)))", j1.j1_id, j1.j1_string FROM UNNEST([1, 2, 3]) CROSS JOIN j1"#,
     );
     Ok(())
 }
@@ -867,7 +1181,7 @@ fn roundtrip_statement_with_dialect_40() -> Result<(), DataFusionError> {
         sql: "SELECT UNNEST([1,2,3]), 1",
         parser_dialect: GenericDialect {},
         unparser_dialect: unparser,
-        expected: @r#"SELECT UNNEST([1, 2, 3]) AS UNNEST(make_array(Int64(1),Int64(2),Int64(3))), Int64(1)"#,
+        expected: @r#"SELECT UNNEST([1, 2, 3]) AS "UNNEST(make_array(Int64(1),Int64(2),Int64(3)))", "Int64(1)""#,
     );
     Ok(())
 }
@@ -881,7 +1195,7 @@ fn roundtrip_statement_with_dialect_41() -> Result<(), DataFusionError> {
         sql: "SELECT * FROM unnest_table u, UNNEST(u.array_col)",
         parser_dialect: GenericDialect {},
         unparser_dialect: unparser,
-        expected: @r#"SELECT u.array_col, u.struct_col, UNNEST(outer_ref(u.array_col)) FROM unnest_table AS u CROSS JOIN UNNEST(u.array_col)"#,
+        expected: @r#"SELECT u.array_col, u.struct_col, "UNNEST(outer_ref(u.array_col))" FROM unnest_table AS u CROSS JOIN UNNEST(u.array_col)"#,
     );
     Ok(())
 }
@@ -909,7 +1223,7 @@ fn roundtrip_statement_with_dialect_43() -> Result<(), DataFusionError> {
         sql: "SELECT unnest([1, 2, 3, 4]) from unnest([1, 2, 3]);",
         parser_dialect: GenericDialect {},
         unparser_dialect: unparser,
-        expected: @r#"SELECT UNNEST([1, 2, 3, 4]) AS UNNEST(make_array(Int64(1),Int64(2),Int64(3),Int64(4))) FROM UNNEST([1, 2, 3])"#,
+        expected: @r#"SELECT UNNEST([1, 2, 3, 4]) AS "UNNEST(make_array(Int64(1),Int64(2),Int64(3),Int64(4)))" FROM UNNEST([1, 2, 3])"#,
     );
     Ok(())
 }
@@ -926,115 +1240,480 @@ fn roundtrip_statement_with_dialect_45() -> Result<(), DataFusionError> {
 }
 
 #[test]
-fn roundtrip_statement_with_dialect_special_char_alias() -> Result<(), DataFusionError> {
+fn roundtrip_lateral_join() -> Result<(), DataFusionError> {
     roundtrip_statement_with_dialect_helper!(
-        sql: "select min(a) as \"min(a)\" from (select 1 as a)",
+        sql: "SELECT p.id, o.order_id FROM person p, LATERAL (SELECT * FROM orders WHERE orders.customer_id = p.id) AS o",
         parser_dialect: GenericDialect {},
-        unparser_dialect: BigQueryDialect {},
-        expected: @r#"SELECT min(`a`) AS `min_40a_41` FROM (SELECT 1 AS `a`)"#,
+        unparser_dialect: UnparserDefaultDialect {},
+        expected: @r#"SELECT p.id, o.order_id FROM person AS p CROSS JOIN LATERAL (SELECT orders.order_id, orders.customer_id, orders.o_item_id, orders.qty, orders.price, orders.delivered FROM orders WHERE (orders.customer_id = p.id)) AS o"#,
     );
+    Ok(())
+}
+
+#[test]
+fn roundtrip_lateral_join_as_apply() -> Result<(), DataFusionError> {
+    let apply_dialect = CustomDialectBuilder::new()
+        .with_supports_apply_join(true)
+        .build();
     roundtrip_statement_with_dialect_helper!(
-        sql: "select a as \"a*\", b as \"b@\" from (select 1 as a , 2 as b)",
-        parser_dialect: GenericDialect {},
-        unparser_dialect: BigQueryDialect {},
-        expected: @r#"SELECT `a` AS `a_42`, `b` AS `b_64` FROM (SELECT 1 AS `a`, 2 AS `b`)"#,
+        sql: "SELECT p.id, o.order_id FROM person p CROSS APPLY (SELECT * FROM orders WHERE orders.customer_id = p.id) AS o",
+        parser_dialect: MsSqlDialect {},
+        unparser_dialect: apply_dialect,
+        expected: @r#"SELECT p.id, o.order_id FROM person AS p CROSS APPLY (SELECT orders.order_id, orders.customer_id, orders.o_item_id, orders.qty, orders.price, orders.delivered FROM orders WHERE (orders.customer_id = p.id)) AS o"#,
     );
     roundtrip_statement_with_dialect_helper!(
-        sql: "select a as \"a*\", b , c as \"c@\" from (select 1 as a , 2 as b, 3 as c)",
-        parser_dialect: GenericDialect {},
-        unparser_dialect: BigQueryDialect {},
-        expected: @r#"SELECT `a` AS `a_42`, `b`, `c` AS `c_64` FROM (SELECT 1 AS `a`, 2 AS `b`, 3 AS `c`)"#,
+        sql: "SELECT p.id, o.order_id FROM person p OUTER APPLY (SELECT * FROM orders WHERE orders.customer_id = p.id) AS o",
+        parser_dialect: MsSqlDialect {},
+        unparser_dialect: apply_dialect,
+        expected: @r#"SELECT p.id, o.order_id FROM person AS p OUTER APPLY (SELECT orders.order_id, orders.customer_id, orders.o_item_id, orders.qty, orders.price, orders.delivered FROM orders WHERE (orders.customer_id = p.id)) AS o"#,
     );
+    // Lateral joins written with `LATERAL` are unparsed as APPLY as well
     roundtrip_statement_with_dialect_helper!(
-        sql: "select * from (select a as \"a*\", b as \"b@\" from (select 1 as a , 2 as b)) where \"a*\" = 1",
+        sql: "SELECT p.id, o.order_id FROM person p LEFT JOIN LATERAL (SELECT * FROM orders WHERE orders.customer_id = p.id) AS o ON true",
         parser_dialect: GenericDialect {},
-        unparser_dialect: BigQueryDialect {},
-        expected: @r#"SELECT `a_42`, `b_64` FROM (SELECT `a` AS `a_42`, `b` AS `b_64` FROM (SELECT 1 AS `a`, 2 AS `b`)) WHERE (`a_42` = 1)"#,
+        unparser_dialect: apply_dialect,
+        expected: @r#"SELECT p.id, o.order_id FROM person AS p OUTER APPLY (SELECT orders.order_id, orders.customer_id, orders.o_item_id, orders.qty, orders.price, orders.delivered FROM orders WHERE (orders.customer_id = p.id)) AS o"#,
     );
+    // The condition of an inner join moves to the `WHERE` clause
     roundtrip_statement_with_dialect_helper!(
-        sql: "select * from (select a as \"a*\", b as \"b@\" from (select 1 as a , 2 as b)) where \"a*\" = 1",
+        sql: "SELECT p.id, o.order_id FROM person p JOIN LATERAL (SELECT * FROM orders WHERE orders.customer_id = p.id) AS o ON o.qty > 1",
         parser_dialect: GenericDialect {},
-        unparser_dialect: UnparserDefaultDialect {},
-        expected: @r#"SELECT "a*", "b@" FROM (SELECT a AS "a*", b AS "b@" FROM (SELECT 1 AS a, 2 AS b)) WHERE ("a*" = 1)"#,
+        unparser_dialect: apply_dialect,
+        expected: @r#"SELECT p.id, o.order_id FROM person AS p CROSS APPLY (SELECT orders.order_id, orders.customer_id, orders.o_item_id, orders.qty, orders.price, orders.delivered FROM orders WHERE (orders.customer_id = p.id)) AS o WHERE (o.qty > 1)"#,
     );
     Ok(())
 }
 
 #[test]
-fn test_unnest_logical_plan() -> Result<()> {
-    let query = "select unnest(struct_col), unnest(array_col), struct_col, array_col from unnest_table";
-
-    let dialect = GenericDialect {};
-    let statement = Parser::new(&dialect)
-        .try_with_sql(query)?
+fn test_unparse_table_sample() -> Result<()> {
+    let sql = "SELECT p.id FROM person AS p TABLESAMPLE (10 ROWS) WHERE p.age > 30";
+    let statement = Parser::new(&GenericDialect {})
+        .try_with_sql(sql)?
         .parse_statement()?;
-
     let context = MockContextProvider {
-        state: MockSessionState::default(),
+        state: MockSessionState::default()
+            .with_scalar_function(datafusion_functions::math::random()),
+    };
+    let plan = SqlToRel::new(&context).sql_statement_to_plan(statement)?;
+
+    let unparse = |style| -> Result<String> {
+        let dialect = CustomDialectBuilder::new()
+            .with_table_sample_style(style)
+            .build();
+        Ok(Unparser::new(&dialect).plan_to_sql(&plan)?.to_string())
     };
-    let sql_to_rel = SqlToRel::new(&context);
-    let plan = sql_to_rel.sql_statement_to_plan(statement).unwrap();
     assert_snapshot!(
-        plan,
-        @r#"
-Projection: __unnest_placeholder(unnest_table.struct_col).field1, __unnest_placeholder(unnest_table.struct_col).field2, __unnest_placeholder(unnest_table.array_col,depth=1) AS UNNEST(unnest_table.array_col), unnest_table.struct_col, unnest_table.array_col
-  Unnest: lists[__unnest_placeholder(unnest_table.array_col)|depth=1] structs[__unnest_placeholder(unnest_table.struct_col)]
-    Projection: unnest_table.struct_col AS __unnest_placeholder(unnest_table.struct_col), unnest_table.array_col AS __unnest_placeholder(unnest_table.array_col), unnest_table.struct_col, unnest_table.array_col
-      TableScan: unnest_table"#
+        unparse(TableSampleStyle::Limit)?,
+        @"SELECT p.id FROM (SELECT * FROM person ORDER BY random() ASC NULLS LAST LIMIT 10) AS p WHERE (p.age > 30)"
+    );
+    assert_snapshot!(
+        unparse(TableSampleStyle::TableSample)?,
+        @"SELECT p.id FROM person AS p TABLESAMPLE (10 ROWS) WHERE (p.age > 30)"
+    );
+    assert_snapshot!(
+        unparse(TableSampleStyle::Sample)?,
+        @"SELECT p.id FROM person AS p SAMPLE (10 ROWS) WHERE (p.age > 30)"
     );
-
     Ok(())
 }
 
 #[test]
-fn test_aggregation_without_projection() -> Result<()> {
-    let schema = Schema::new(vec![
-        Field::new("name", DataType::Utf8, false),
-        Field::new("age", DataType::UInt8, false),
-    ]);
-
-    let plan = LogicalPlanBuilder::from(
-        table_scan(Some("users"), &schema, Some(vec![0, 1]))?.build()?,
-    )
-    .aggregate(vec![col("name")], vec![sum(col("age"))])?
-    .build()?;
+fn test_unparse_column_alias_without_as() -> Result<()> {
+    let unparse = |sql: &str, use_as: bool| -> Result<String> {
+        let statement = Parser::new(&GenericDialect {})
+            .try_with_sql(sql)?
+            .parse_statement()?;
+        let context = MockContextProvider {
+            state: MockSessionState::default(),
+        };
+        let plan = SqlToRel::new(&context).sql_statement_to_plan(statement)?;
+        let dialect = CustomDialectBuilder::new()
+            .with_use_as_for_column_alias(use_as)
+            .build();
+        let unparser = Unparser::new(&dialect);
+        // The AST keeps the aliases, only the SQL text omits `AS`
+        assert_eq!(
+            unparser.plan_to_sql(&plan)?,
+            Unparser::new(&CustomDialectBuilder::new().build()).plan_to_sql(&plan)?
+        );
+        unparser.plan_to_sql_text(&plan)
+    };
 
-    let unparser = Unparser::default();
-    let statement = unparser.plan_to_sql(&plan)?;
+    let sql = "SELECT p.id AS pid, p.age + 1 AS next_age FROM person AS p";
     assert_snapshot!(
-        statement,
-        @r#"SELECT sum(users.age), users."name" FROM users GROUP BY users."name""#
+        unparse(sql, true)?,
+        @"SELECT p.id AS pid, (p.age + 1) AS next_age FROM person AS p"
+    );
+    // Table aliases keep their `AS`
+    assert_snapshot!(
+        unparse(sql, false)?,
+        @"SELECT p.id pid, (p.age + 1) next_age FROM person AS p"
     );
 
+    let sql = "SELECT t.pid FROM (SELECT id AS pid FROM person) AS t";
+    assert_snapshot!(
+        unparse(sql, true)?,
+        @"SELECT t.pid FROM (SELECT person.id AS pid FROM person) AS t"
+    );
+    assert_snapshot!(
+        unparse(sql, false)?,
+        @"SELECT t.pid FROM (SELECT person.id pid FROM person) AS t"
+    );
     Ok(())
 }
 
-/// return a schema with two string columns: "id" and "value"
-fn test_schema() -> Schema {
-    Schema::new(vec![
-        Field::new("id", DataType::Utf8, false),
-        Field::new("value", DataType::Utf8, false),
-    ])
+#[test]
+fn test_unparse_left_lateral_join_with_condition_as_apply() -> Result<()> {
+    let sql = "SELECT p.id, o.order_id FROM person p LEFT JOIN LATERAL (SELECT * FROM orders WHERE orders.customer_id = p.id) AS o ON o.qty > 1";
+    let statement = Parser::new(&GenericDialect {})
+        .try_with_sql(sql)?
+        .parse_statement()?;
+    let context = MockContextProvider {
+        state: MockSessionState::default(),
+    };
+    let plan = SqlToRel::new(&context).sql_statement_to_plan(statement)?;
+    let apply_dialect = CustomDialectBuilder::new()
+        .with_supports_apply_join(true)
+        .build();
+    let err = Unparser::new(&apply_dialect)
+        .plan_to_sql(&plan)
+        .unwrap_err();
+    assert_contains!(
+        err.to_string(),
+        "Unsupported OUTER APPLY with join condition (o.qty > 1)"
+    );
+    Ok(())
 }
 
 #[test]
-fn test_table_references_in_plan_to_sql_1() {
-    let table_name = "catalog.schema.table";
-    let schema = test_schema();
-    let sql = table_references_in_plan_helper(
-        table_name,
-        schema,
-        vec![col("id"), col("value")],
-        &DefaultDialect {},
-    );
-    assert_snapshot!(
-        sql,
-        @r#"SELECT "table".id, "table"."value" FROM "catalog"."schema"."table""#
+fn roundtrip_statement_with_dialect_46() -> Result<(), DataFusionError> {
+    roundtrip_statement_with_dialect_helper!(
+        sql: "SELECT id FROM person WHERE birth_date > TIMESTAMP '2024-01-01' AND birth_date < DATE '2024-06-01'",
+        parser_dialect: GenericDialect {},
+        unparser_dialect: UnparserPostgreSqlDialect {},
+        expected: @r#"SELECT "person"."id" FROM "person" WHERE (("person"."birth_date" > TIMESTAMP '2024-01-01 00:00:00') AND ("person"."birth_date" < DATE '2024-06-01'))"#,
     );
+    Ok(())
 }
 
 #[test]
-fn test_table_references_in_plan_to_sql_2() {
+fn roundtrip_statement_with_dialect_47() -> Result<(), DataFusionError> {
+    roundtrip_statement_with_dialect_helper!(
+        sql: "SELECT id FROM person WHERE first_name ILIKE 'a!_%' ESCAPE '!'",
+        parser_dialect: GenericDialect {},
+        unparser_dialect: UnparserMySqlDialect {},
+        expected: @r#"SELECT `person`.`id` FROM `person` WHERE LOWER(`person`.`first_name`) LIKE LOWER('a!_%') ESCAPE '!'"#,
+    );
+    roundtrip_statement_with_dialect_helper!(
+        sql: "SELECT id FROM person WHERE NOT (first_name ILIKE 'a%')",
+        parser_dialect: GenericDialect {},
+        unparser_dialect: UnparserMySqlDialect {},
+        expected: @r#"SELECT `person`.`id` FROM `person` WHERE LOWER(`person`.`first_name`) NOT LIKE LOWER('a%')"#,
+    );
+    roundtrip_statement_with_dialect_helper!(
+        sql: "SELECT id FROM person WHERE NOT (first_name ILIKE 'a!_%' ESCAPE '!')",
+        parser_dialect: GenericDialect {},
+        unparser_dialect: UnparserPostgreSqlDialect {},
+        expected: @r#"SELECT "person"."id" FROM "person" WHERE "person"."first_name" NOT ILIKE 'a!_%' ESCAPE '!'"#,
+    );
+    roundtrip_statement_with_dialect_helper!(
+        sql: "SELECT id FROM person WHERE first_name NOT ILIKE 'a%'",
+        parser_dialect: GenericDialect {},
+        unparser_dialect: UnparserDefaultDialect {},
+        expected: @r#"SELECT person.id FROM person WHERE person.first_name NOT ILIKE 'a%'"#,
+    );
+    roundtrip_statement_with_dialect_helper!(
+        sql: "SELECT id FROM person WHERE first_name ILIKE '%x%'",
+        parser_dialect: PostgreSqlDialect {},
+        unparser_dialect: UnparserPostgreSqlDialect {},
+        expected: @r#"SELECT "person"."id" FROM "person" WHERE "person"."first_name" ILIKE '%x%'"#,
+    );
+    roundtrip_statement_with_dialect_helper!(
+        sql: "SELECT id FROM person WHERE first_name ~~* '%x%'",
+        parser_dialect: PostgreSqlDialect {},
+        unparser_dialect: UnparserPostgreSqlDialect {},
+        expected: @r#"SELECT "person"."id" FROM "person" WHERE ("person"."first_name" ~~* '%x%')"#,
+    );
+    roundtrip_statement_with_dialect_helper!(
+        sql: "SELECT id FROM person WHERE first_name !~~* '%x%'",
+        parser_dialect: PostgreSqlDialect {},
+        unparser_dialect: UnparserMySqlDialect {},
+        expected: @r#"
+    SELECT `person`.`id` FROM `person` WHERE (LOWER(`person`.`first_name`) NOT LIKE LOWER('%x%'))
+    "#,
+    );
+    roundtrip_statement_with_dialect_helper!(
+        sql: "SELECT id FROM person WHERE first_name SIMILAR TO '%x%'",
+        parser_dialect: PostgreSqlDialect {},
+        unparser_dialect: UnparserPostgreSqlDialect {},
+        expected: @r#"SELECT "person"."id" FROM "person" WHERE "person"."first_name" SIMILAR TO '%x%'"#,
+    );
+    Ok(())
+}
+
+#[test]
+fn roundtrip_statement_with_dialect_48() -> Result<(), DataFusionError> {
+    roundtrip_statement_with_dialect_helper!(
+        sql: "SELECT array_agg(first_name ORDER BY age, id DESC) FROM person",
+        parser_dialect: GenericDialect {},
+        unparser_dialect: UnparserDefaultDialect {},
+        expected: @"SELECT array_agg(person.first_name ORDER BY person.age ASC NULLS LAST, person.id DESC NULLS FIRST) FROM person",
+    );
+    roundtrip_statement_with_dialect_helper!(
+        sql: "SELECT array_agg(first_name ORDER BY age DESC NULLS LAST, id ASC NULLS FIRST) FROM person GROUP BY state",
+        parser_dialect: GenericDialect {},
+        unparser_dialect: UnparserDefaultDialect {},
+        expected: @"SELECT array_agg(person.first_name ORDER BY person.age DESC NULLS LAST, person.id ASC NULLS FIRST) FROM person GROUP BY person.state",
+    );
+    Ok(())
+}
+
+#[test]
+fn roundtrip_statement_with_dialect_special_char_alias() -> Result<(), DataFusionError> {
+    roundtrip_statement_with_dialect_helper!(
+        sql: "select min(a) as \"min(a)\" from (select 1 as a)",
+        parser_dialect: GenericDialect {},
+        unparser_dialect: BigQueryDialect {},
+        expected: @r#"SELECT min(`a`) AS `min_40a_41` FROM (SELECT 1 AS `a`)"#,
+    );
+    roundtrip_statement_with_dialect_helper!(
+        sql: "select a as \"a*\", b as \"b@\" from (select 1 as a , 2 as b)",
+        parser_dialect: GenericDialect {},
+        unparser_dialect: BigQueryDialect {},
+        expected: @r#"SELECT `a` AS `a_42`, `b` AS `b_64` FROM (SELECT 1 AS `a`, 2 AS `b`)"#,
+    );
+    roundtrip_statement_with_dialect_helper!(
+        sql: "select a as \"a*\", b , c as \"c@\" from (select 1 as a , 2 as b, 3 as c)",
+        parser_dialect: GenericDialect {},
+        unparser_dialect: BigQueryDialect {},
+        expected: @r#"SELECT `a` AS `a_42`, `b`, `c` AS `c_64` FROM (SELECT 1 AS `a`, 2 AS `b`, 3 AS `c`)"#,
+    );
+    roundtrip_statement_with_dialect_helper!(
+        sql: "select * from (select a as \"a*\", b as \"b@\" from (select 1 as a , 2 as b)) where \"a*\" = 1",
+        parser_dialect: GenericDialect {},
+        unparser_dialect: BigQueryDialect {},
+        expected: @r#"SELECT `a_42`, `b_64` FROM (SELECT `a` AS `a_42`, `b` AS `b_64` FROM (SELECT 1 AS `a`, 2 AS `b`)) WHERE (`a_42` = 1)"#,
+    );
+    roundtrip_statement_with_dialect_helper!(
+        sql: "select * from (select a as \"a*\", b as \"b@\" from (select 1 as a , 2 as b)) where \"a*\" = 1",
+        parser_dialect: GenericDialect {},
+        unparser_dialect: UnparserDefaultDialect {},
+        expected: @r#"SELECT "a*", "b@" FROM (SELECT a AS "a*", b AS "b@" FROM (SELECT 1 AS a, 2 AS b)) WHERE ("a*" = 1)"#,
+    );
+    Ok(())
+}
+
+#[test]
+fn roundtrip_statement_with_dialect_time_travel() -> Result<(), DataFusionError> {
+    roundtrip_statement_with_dialect_helper!(
+        sql: "SELECT p.id FROM person FOR SYSTEM_TIME AS OF TIMESTAMP '2024-01-01 00:00:00' AS p",
+        parser_dialect: sqlparser::dialect::BigQueryDialect {},
+        unparser_dialect: BigQueryDialect {},
+        expected: @"SELECT `p`.`id` FROM `person` AS `p` FOR SYSTEM_TIME AS OF CAST('2024-01-01 00:00:00' AS TIMESTAMP)",
+    );
+    roundtrip_statement_with_dialect_helper!(
+        sql: "SELECT id FROM person FOR SYSTEM_TIME AS OF 3 WHERE id > 1",
+        parser_dialect: sqlparser::dialect::BigQueryDialect {},
+        unparser_dialect: UnparserDefaultDialect {},
+        expected: @"SELECT person.id FROM person FOR SYSTEM_TIME AS OF 3 WHERE (person.id > 1)",
+    );
+    Ok(())
+}
+
+#[test]
+fn roundtrip_statement_with_dialect_offset_comma_limit() -> Result<(), DataFusionError> {
+    let dialect = CustomDialectBuilder::new()
+        .with_limit_style(LimitStyle::OffsetCommaLimit)
+        .build();
+    roundtrip_statement_with_dialect_helper!(
+        sql: "SELECT id FROM person LIMIT 10 OFFSET 5",
+        parser_dialect: GenericDialect {},
+        unparser_dialect: dialect,
+        expected: @"SELECT person.id FROM person LIMIT 5, 10",
+    );
+    roundtrip_statement_with_dialect_helper!(
+        sql: "SELECT id FROM person LIMIT 10",
+        parser_dialect: GenericDialect {},
+        unparser_dialect: dialect,
+        expected: @"SELECT person.id FROM person LIMIT 10",
+    );
+    roundtrip_statement_with_dialect_helper!(
+        sql: "SELECT id FROM person OFFSET 5",
+        parser_dialect: GenericDialect {},
+        unparser_dialect: dialect,
+        expected: @"SELECT person.id FROM person LIMIT 5, 18446744073709551615",
+    );
+    roundtrip_statement_with_dialect_helper!(
+        sql: "(SELECT id FROM person LIMIT 2 OFFSET 1) UNION ALL (SELECT id FROM person LIMIT 3)",
+        parser_dialect: GenericDialect {},
+        unparser_dialect: dialect,
+        expected: @"(SELECT person.id FROM person LIMIT 1, 2) UNION ALL (SELECT person.id FROM person LIMIT 3)",
+    );
+    Ok(())
+}
+
+#[test]
+fn roundtrip_statement_with_dialect_group_by_ordinals() -> Result<(), DataFusionError> {
+    let dialect = CustomDialectBuilder::new()
+        .with_group_by_style(GroupByStyle::Ordinals)
+        .build();
+    roundtrip_statement_with_dialect_helper!(
+        sql: "SELECT age * 2 AS double_age, max(id) FROM person GROUP BY age * 2",
+        parser_dialect: GenericDialect {},
+        unparser_dialect: dialect,
+        expected: @"SELECT (person.age * 2) AS double_age, max(person.id) FROM person GROUP BY 1",
+    );
+    // Grouping expressions not in the SELECT list are repeated
+    roundtrip_statement_with_dialect_helper!(
+        sql: "SELECT state, max(id) FROM person GROUP BY state, age",
+        parser_dialect: GenericDialect {},
+        unparser_dialect: dialect,
+        expected: @"SELECT person.state, max(person.id) FROM person GROUP BY 1, person.age",
+    );
+    roundtrip_statement_with_dialect_helper!(
+        sql: "SELECT max(id), age, state FROM person GROUP BY ROLLUP (age, state)",
+        parser_dialect: GenericDialect {},
+        unparser_dialect: dialect,
+        expected: @"SELECT max(person.id), person.age, person.state FROM person GROUP BY ROLLUP (2, 3)",
+    );
+    // The expression whose alias shadows the `age` column is grouped by
+    roundtrip_statement_with_dialect_helper!(
+        sql: "SELECT age + 1 AS age, max(id) FROM person GROUP BY age + 1",
+        parser_dialect: GenericDialect {},
+        unparser_dialect: BigQueryDialect {},
+        expected: @"SELECT (`person`.`age` + 1) AS `age`, max(`person`.`id`) FROM `person` GROUP BY 1",
+    );
+    roundtrip_statement_with_dialect_helper!(
+        sql: "SELECT age + 1 AS age, max(id) FROM person GROUP BY age + 1",
+        parser_dialect: GenericDialect {},
+        unparser_dialect: UnparserDefaultDialect {},
+        expected: @"SELECT (person.age + 1) AS age, max(person.id) FROM person GROUP BY (person.age + 1)",
+    );
+    Ok(())
+}
+
+#[test]
+fn roundtrip_statement_with_dialect_column_exclusion() -> Result<(), DataFusionError> {
+    roundtrip_statement_with_dialect_helper!(
+        sql: "SELECT * EXCLUDE (age, salary) FROM person",
+        parser_dialect: sqlparser::dialect::DuckDbDialect {},
+        unparser_dialect: DuckDBDialect::new(),
+        expected: @r#"SELECT * EXCLUDE ("age", "salary") FROM "person""#,
+    );
+    roundtrip_statement_with_dialect_helper!(
+        sql: "SELECT * EXCEPT (age) FROM person WHERE age > 30",
+        parser_dialect: sqlparser::dialect::BigQueryDialect {},
+        unparser_dialect: BigQueryDialect {},
+        expected: @"SELECT * EXCEPT (`age`) FROM `person` WHERE (`person`.`age` > 30)",
+    );
+    roundtrip_statement_with_dialect_helper!(
+        sql: "SELECT p.id, p.first_name, p.last_name, p.age, p.state, p.salary FROM person AS p",
+        parser_dialect: GenericDialect {},
+        unparser_dialect: CustomDialectBuilder::new()
+            .with_column_exclusion_style(ColumnExclusionStyle::Except)
+            .build(),
+        expected: @"SELECT * EXCEPT (birth_date, 😀) FROM person AS p",
+    );
+    // Columns out of the order of the table are listed
+    roundtrip_statement_with_dialect_helper!(
+        sql: "SELECT first_name, id, last_name, age, state, salary FROM person",
+        parser_dialect: GenericDialect {},
+        unparser_dialect: CustomDialectBuilder::new()
+            .with_column_exclusion_style(ColumnExclusionStyle::Exclude)
+            .build(),
+        expected: @"SELECT person.first_name, person.id, person.last_name, person.age, person.state, person.salary FROM person",
+    );
+    // Excluding as many columns as are projected is not shorter
+    roundtrip_statement_with_dialect_helper!(
+        sql: "SELECT id, first_name, last_name, age FROM person",
+        parser_dialect: GenericDialect {},
+        unparser_dialect: DuckDBDialect::new(),
+        expected: @r#"SELECT "person"."id", "person"."first_name", "person"."last_name", "person"."age" FROM "person""#,
+    );
+    roundtrip_statement_with_dialect_helper!(
+        sql: "SELECT * EXCLUDE (age) FROM person",
+        parser_dialect: GenericDialect {},
+        unparser_dialect: UnparserDefaultDialect {},
+        expected: @r#"SELECT person.id, person.first_name, person.last_name, person.state, person.salary, person.birth_date, person."😀" FROM person"#,
+    );
+    Ok(())
+}
+
+#[test]
+fn test_unnest_logical_plan() -> Result<()> {
+    let query = "select unnest(struct_col), unnest(array_col), struct_col, array_col from unnest_table";
+
+    let dialect = GenericDialect {};
+    let statement = Parser::new(&dialect)
+        .try_with_sql(query)?
+        .parse_statement()?;
+
+    let context = MockContextProvider {
+        state: MockSessionState::default(),
+    };
+    let sql_to_rel = SqlToRel::new(&context);
+    let plan = sql_to_rel.sql_statement_to_plan(statement).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+Projection: __unnest_placeholder(unnest_table.struct_col).field1, __unnest_placeholder(unnest_table.struct_col).field2, __unnest_placeholder(unnest_table.array_col,depth=1) AS UNNEST(unnest_table.array_col), unnest_table.struct_col, unnest_table.array_col
+  Unnest: lists[__unnest_placeholder(unnest_table.array_col)|depth=1] structs[__unnest_placeholder(unnest_table.struct_col)]
+    Projection: unnest_table.struct_col AS __unnest_placeholder(unnest_table.struct_col), unnest_table.array_col AS __unnest_placeholder(unnest_table.array_col), unnest_table.struct_col, unnest_table.array_col
+      TableScan: unnest_table"#
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_aggregation_without_projection() -> Result<()> {
+    let schema = Schema::new(vec![
+        Field::new("name", DataType::Utf8, false),
+        Field::new("age", DataType::UInt8, false),
+    ]);
+
+    let plan = LogicalPlanBuilder::from(
+        table_scan(Some("users"), &schema, Some(vec![0, 1]))?.build()?,
+    )
+    .aggregate(vec![col("name")], vec![sum(col("age"))])?
+    .build()?;
+
+    let unparser = Unparser::default();
+    let statement = unparser.plan_to_sql(&plan)?;
+    assert_snapshot!(
+        statement,
+        @r#"SELECT sum(users.age), users."name" FROM users GROUP BY users."name""#
+    );
+
+    Ok(())
+}
+
+/// return a schema with two string columns: "id" and "value"
+fn test_schema() -> Schema {
+    Schema::new(vec![
+        Field::new("id", DataType::Utf8, false),
+        Field::new("value", DataType::Utf8, false),
+    ])
+}
+
+#[test]
+fn test_table_references_in_plan_to_sql_1() {
+    let table_name = "catalog.schema.table";
+    let schema = test_schema();
+    let sql = table_references_in_plan_helper(
+        table_name,
+        schema,
+        vec![col("id"), col("value")],
+        &DefaultDialect {},
+    );
+    assert_snapshot!(
+        sql,
+        @r#"SELECT "table".id, "table"."value" FROM "catalog"."schema"."table""#
+    );
+}
+
+#[test]
+fn test_table_references_in_plan_to_sql_2() {
     let table_name = "schema.table";
     let schema = test_schema();
     let sql = table_references_in_plan_helper(
@@ -1322,33 +2001,736 @@ fn test_pretty_roundtrip() -> Result<()> {
     Ok(())
 }
 
-fn generate_round_trip_statement<D>(dialect: D, sql: &str) -> Statement
-where
-    D: Dialect,
-{
-    let statement = Parser::new(&dialect)
-        .try_with_sql(sql)
-        .unwrap()
-        .parse_statement()
-        .unwrap();
+fn generate_round_trip_statement<D>(dialect: D, sql: &str) -> Statement
+where
+    D: Dialect,
+{
+    let statement = Parser::new(&dialect)
+        .try_with_sql(sql)
+        .unwrap()
+        .parse_statement()
+        .unwrap();
+
+    let context = MockContextProvider {
+        state: MockSessionState::default()
+            .with_aggregate_function(sum_udaf())
+            .with_aggregate_function(count_udaf())
+            .with_aggregate_function(max_udaf())
+            .with_aggregate_function(grouping_udaf())
+            .with_window_function(rank_udwf())
+            .with_window_function(row_number_udwf())
+            .with_scalar_function(Arc::new(unicode::substr().as_ref().clone()))
+            .with_scalar_function(make_array_udf())
+            .with_expr_planner(Arc::new(CoreFunctionPlanner::default()))
+            .with_expr_planner(Arc::new(UserDefinedFunctionPlanner))
+            .with_expr_planner(Arc::new(NestedFunctionPlanner))
+            .with_expr_planner(Arc::new(FieldAccessPlanner)),
+    };
+    let sql_to_rel = SqlToRel::new(&context);
+    let plan = sql_to_rel.sql_statement_to_plan(statement).unwrap();
+
+    plan_to_sql(&plan).unwrap()
+}
+
+#[test]
+fn test_unparse_resolved_grouping_function() -> Result<()> {
+    // The projection the analyzer creates for GROUPING calls over ROLLUP(a, b)
+    let schema = Schema::new(vec![
+        Field::new("a", DataType::Utf8, true),
+        Field::new("b", DataType::Utf8, true),
+        Field::new("c", DataType::Int32, true),
+    ]);
+    let grouping_id = || col(Aggregate::INTERNAL_GROUPING_ID);
+    let plan = table_scan(Some("t"), &schema, None)?
+        .aggregate(
+            vec![Expr::GroupingSet(GroupingSet::Rollup(vec![
+                col("a"),
+                col("b"),
+            ]))],
+            vec![sum(col("c"))],
+        )?
+        .project(vec![
+            col("t.a"),
+            col("t.b"),
+            cast(
+                bitwise_shift_right(bitwise_and(grouping_id(), lit(2u8)), lit(1u8)),
+                DataType::Int32,
+            )
+            .alias("ga"),
+            cast(bitwise_and(grouping_id(), lit(1u8)), DataType::Int32).alias("gb"),
+            cast(grouping_id(), DataType::Int32).alias("gab"),
+            cast(
+                bitwise_or(
+                    bitwise_shift_left(bitwise_and(grouping_id(), lit(1u8)), lit(1u8)),
+                    bitwise_shift_right(bitwise_and(grouping_id(), lit(2u8)), lit(1u8)),
+                ),
+                DataType::Int32,
+            )
+            .alias("gba"),
+            col("sum(t.c)"),
+        ])?
+        .build()?;
+    assert_snapshot!(
+        plan_to_sql(&plan)?,
+        @"SELECT t.a, t.b, grouping(t.a) AS ga, grouping(t.b) AS gb, grouping(t.a, t.b) AS gab, grouping(t.b, t.a) AS gba, sum(t.c) FROM t GROUP BY ROLLUP (t.a, t.b)"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_query_builder_offset_value() -> Result<()> {
+    let statement = Parser::new(&GenericDialect {})
+        .try_with_sql("SELECT 1")?
+        .parse_statement()?;
+    let Statement::Query(query) = statement else {
+        unreachable!()
+    };
+    let query = QueryBuilder::default()
+        .body(query.body)
+        .offset_value(5)
+        .build()
+        .unwrap();
+    assert_eq!(query.to_string(), "SELECT 1 OFFSET 5");
+    Ok(())
+}
+
+#[test]
+fn test_query_builder_offset_fetch() -> Result<()> {
+    let statement = Parser::new(&GenericDialect {})
+        .try_with_sql("SELECT 1")?
+        .parse_statement()?;
+    let Statement::Query(query) = statement else {
+        unreachable!()
+    };
+    let query = QueryBuilder::default()
+        .body(query.body)
+        .limit(Some(SQLExpr::value(Value::Number("3".to_string(), false))))
+        .offset_fetch(10, 5)
+        .build()
+        .unwrap();
+    assert_eq!(
+        query.to_string(),
+        "SELECT 1 OFFSET 10 ROWS FETCH FIRST 5 ROWS ONLY"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_query_builder_hint() -> Result<()> {
+    let statement = Parser::new(&GenericDialect {})
+        .try_with_sql("SELECT 1")?
+        .parse_statement()?;
+    let Statement::Query(query) = statement else {
+        unreachable!()
+    };
+    let mut builder = QueryBuilder::default();
+    builder.body(query.body);
+    assert_eq!(builder.build_sql().unwrap(), "SELECT 1");
+
+    builder.hint(Some("INDEX(t idx_a)".to_string()));
+    assert_eq!(
+        builder.build_sql().unwrap(),
+        "/*+ INDEX(t idx_a) */ SELECT 1"
+    );
+    // The hint is only part of the SQL text
+    assert_eq!(builder.build().unwrap().to_string(), "SELECT 1");
+
+    builder.hint(Some("INDEX(t idx_a) */ DROP TABLE t; /*".to_string()));
+    assert_eq!(
+        builder.build_sql().unwrap_err().to_string(),
+        "Query hint can not contain the end of a comment: INDEX(t idx_a) */ DROP TABLE t; /*"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_unparse_union_with_differing_column_names() -> Result<()> {
+    let context = MockContextProvider {
+        state: MockSessionState::default(),
+    };
+    let scan = |table: &str, column: &str| -> Result<LogicalPlan> {
+        let schema = context
+            .get_table_source(TableReference::bare(table))?
+            .schema();
+        table_scan(Some(table), &schema, None)?
+            .project(vec![col(format!("{table}.{column}"))])?
+            .build()
+    };
+    let j1 = scan("j1", "j1_id")?;
+    let j2 = scan("j2", "j2_id")?;
+
+    // The union output is named after the second input here, which the
+    // first input has to be aliased to
+    let plan = LogicalPlanBuilder::from(LogicalPlan::Union(Union {
+        inputs: vec![Arc::new(j2.clone()), Arc::new(j1.clone())],
+        schema: Arc::clone(j1.schema()),
+    }))
+    .sort(vec![col("j1_id").sort(true, false)])?
+    .build()?;
+    let sql = plan_to_sql(&plan)?;
+    assert_snapshot!(
+        sql,
+        @"SELECT j2.j2_id AS j1_id FROM j2 UNION ALL SELECT j1.j1_id FROM j1 ORDER BY j1_id ASC NULLS LAST"
+    );
+
+    let plan_roundtrip = SqlToRel::new(&context).sql_statement_to_plan(sql)?;
+    assert_eq!(plan_roundtrip.schema().field_names(), vec!["j1_id"]);
+
+    // Only the inputs named differently from the union output are aliased
+    let plan = LogicalPlanBuilder::from(j1).union(j2)?.build()?;
+    assert_snapshot!(
+        plan_to_sql(&plan)?,
+        @"SELECT j1.j1_id FROM j1 UNION ALL SELECT j2.j2_id AS j1_id FROM j2"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_unparse_sort_over_limit() -> Result<()> {
+    let schema = Schema::new(vec![
+        Field::new("a", DataType::Int32, false),
+        Field::new("b", DataType::Int32, false),
+    ]);
+    // The rows are limited before they are sorted
+    let plan = table_scan(Some("t"), &schema, None)?
+        .limit(0, Some(3))?
+        .sort(vec![col("b").sort(true, true)])?
+        .build()?;
+    assert_snapshot!(
+        plan_to_sql(&plan)?,
+        @"SELECT * FROM (SELECT * FROM t LIMIT 3) ORDER BY t.b ASC NULLS FIRST"
+    );
+
+    let plan = table_scan(Some("t"), &schema, None)?
+        .sort(vec![col("b").sort(true, true)])?
+        .limit(0, Some(3))?
+        .build()?;
+    assert_snapshot!(
+        plan_to_sql(&plan)?,
+        @"SELECT * FROM t ORDER BY t.b ASC NULLS FIRST LIMIT 3"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_unparse_unwrap_comparison_casts() -> Result<()> {
+    let schema = Schema::new(vec![
+        Field::new("i8", DataType::Int8, false),
+        Field::new("i16", DataType::Int16, false),
+        Field::new("i32", DataType::Int32, false),
+        Field::new("i64", DataType::Int64, false),
+        Field::new("u8", DataType::UInt8, false),
+        Field::new("u32", DataType::UInt32, false),
+        Field::new("f32", DataType::Float32, false),
+        Field::new("s", DataType::Utf8, false),
+    ]);
+    let large_utf8 = |s: &str| lit(ScalarValue::LargeUtf8(Some(s.to_string())));
+    let tests = [
+        // Widening casts compared with a literal of the column type
+        (
+            cast(col("i8"), DataType::Int64).eq(lit(5_i64)),
+            "(t.i8 = 5)",
+        ),
+        (
+            cast(col("i16"), DataType::Int32).gt_eq(lit(-5_i32)),
+            "(t.i16 >= -5)",
+        ),
+        (
+            cast(col("i32"), DataType::Int64).not_eq(lit(5_i64)),
+            "(t.i32 <> 5)",
+        ),
+        (
+            cast(col("u8"), DataType::Int16).lt(lit(200_i16)),
+            "(t.u8 < 200)",
+        ),
+        (
+            cast(col("u32"), DataType::UInt64).lt_eq(lit(5_u64)),
+            "(t.u32 <= 5)",
+        ),
+        (
+            cast(col("f32"), DataType::Float64).gt(lit(1.5_f64)),
+            "(t.f32 > 1.5)",
+        ),
+        (
+            cast(col("s"), DataType::LargeUtf8).eq(large_utf8("a")),
+            "(t.s = 'a')",
+        ),
+        // The literal may be on either side
+        (
+            lit(5_i64).lt(cast(col("i32"), DataType::Int64)),
+            "(5 < t.i32)",
+        ),
+        // Literals that do not fit the column type keep the cast
+        (
+            cast(col("i32"), DataType::Int64).eq(lit(5_000_000_000_i64)),
+            "(CAST(t.i32 AS BIGINT) = 5000000000)",
+        ),
+        (
+            cast(col("u8"), DataType::Int16).gt(lit(-1_i16)),
+            "(CAST(t.u8 AS SMALLINT) > -1)",
+        ),
+        (
+            cast(col("f32"), DataType::Float64).eq(lit(0.1_f64)),
+            "(CAST(t.f32 AS DOUBLE) = 0.1)",
+        ),
+        // So do narrowing casts
+        (
+            cast(col("i64"), DataType::Int32).eq(lit(5_i32)),
+            "(CAST(t.i64 AS INTEGER) = 5)",
+        ),
+    ];
+    for (predicate, expected) in tests {
+        let plan = table_scan(Some("t"), &schema, None)?
+            .filter(predicate)?
+            .build()?;
+        let sql = Unparser::default().plan_to_sql(&plan)?.to_string();
+        assert_eq!(sql, format!("SELECT * FROM t WHERE {expected}"));
+    }
+
+    // The casts are kept as planned when the option is disabled
+    let plan = table_scan(Some("t"), &schema, None)?
+        .filter(cast(col("i32"), DataType::Int64).eq(lit(5_i64)))?
+        .build()?;
+    let sql = Unparser::default()
+        .with_unwrap_comparison_casts(false)
+        .plan_to_sql(&plan)?;
+    assert_snapshot!(sql, @"SELECT * FROM t WHERE (CAST(t.i32 AS BIGINT) = 5)");
+    Ok(())
+}
+
+#[test]
+fn test_unparse_keyword_identifiers() -> Result<()> {
+    let schema = Schema::new(vec![
+        Field::new("order", DataType::Int32, false),
+        Field::new("group", DataType::Int32, false),
+        Field::new("from", DataType::Int32, false),
+        Field::new("case", DataType::Int32, false),
+    ]);
+    let plan = table_scan(Some("select"), &schema, None)?
+        .filter(col("from").gt(lit(1)))?
+        .project(vec![col("order"), col("group").alias("where"), col("case")])?
+        .alias("limit")?
+        .project(vec![
+            Expr::Column(Column::new(Some("limit"), "order")),
+            Expr::Column(Column::new(Some("limit"), "where")),
+        ])?
+        .build()?;
+    let custom_dialect = CustomDialectBuilder::new().build();
+
+    let dialects: Vec<&dyn UnparserDialect> = vec![&DefaultDialect {}, &custom_dialect];
+    for dialect in dialects {
+        let sql = Unparser::new(dialect).plan_to_sql(&plan)?.to_string();
+        assert_eq!(
+            sql,
+            r#"SELECT "limit"."order", "limit"."where" FROM (SELECT "select"."order", "select"."group" AS "where", "select"."case" FROM "select" WHERE ("select"."from" > 1)) AS "limit""#
+        );
+        Parser::new(&GenericDialect {})
+            .try_with_sql(&sql)?
+            .parse_statement()?;
+    }
+
+    // The column aliases of a table alias, and identifiers whose case would be
+    // folded, are quoted as well
+    let statement = generate_round_trip_statement(
+        GenericDialect {},
+        r#"SELECT t."Order", t."from" FROM (SELECT 1, 2) AS t("Order", "from")"#,
+    );
+    assert_snapshot!(
+        statement,
+        @r#"
+    SELECT t."Order", t."from" FROM (SELECT 1, 2) AS t ("Order", "from")
+    "#
+    );
+    Ok(())
+}
+
+#[test]
+fn test_unparse_identifiers_with_dialect_quoting_rules() -> Result<()> {
+    let schema = Schema::new(vec![
+        Field::new("order", DataType::Int32, false),
+        Field::new("Amount", DataType::Int32, false),
+        Field::new("user", DataType::Int32, false),
+    ]);
+    let plan = table_scan(Some("t"), &schema, None)?
+        .project(vec![col("order"), col("Amount"), col("user")])?
+        .build()?;
+    let dialect = CustomDialectBuilder::new()
+        .with_reserved_keywords(vec![Keyword::ORDER, Keyword::USER])
+        .with_folds_identifiers_to_lowercase(false)
+        .build();
+    assert_snapshot!(
+        Unparser::new(&dialect).plan_to_sql(&plan)?,
+        @r#"SELECT t."order", t.Amount, t."user" FROM t"#
+    );
+
+    // Identifiers that must be quoted use the quote character of the dialect
+    struct BacktickDialect {}
+
+    impl UnparserDialect for BacktickDialect {
+        fn identifier_quote_style(&self, _: &str) -> Option<char> {
+            None
+        }
+
+        fn required_identifier_quote_style(&self) -> char {
+            '`'
+        }
+    }
+
+    let plan = table_scan(Some("t"), &schema, None)?
+        .project(vec![col("order"), col("Amount")])?
+        .build()?;
+    assert_snapshot!(
+        Unparser::new(&BacktickDialect {}).plan_to_sql(&plan)?,
+        @"SELECT t.`order`, t.`Amount` FROM t"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_unparse_qualify_columns_and_quote_identifiers() -> Result<()> {
+    let schema = Schema::new(vec![
+        Field::new("id", DataType::Int32, false),
+        Field::new("Amount", DataType::Int32, false),
+    ]);
+    let upper = table_scan(Some(TableReference::bare("Orders")), &schema, None)?
+        .limit(0, Some(3))?
+        .build()?;
+    let lower = table_scan(Some("orders"), &schema, None)?.build()?;
+    let plan = LogicalPlanBuilder::from(upper)
+        .join(
+            lower,
+            datafusion_expr::JoinType::Inner,
+            (vec![r#""Orders".id"#], vec!["orders.id"]),
+            None,
+        )?
+        .aggregate(
+            vec![col(r#""Orders".id"#)],
+            vec![sum(col(r#"orders."Amount""#)).alias("total")],
+        )?
+        .sort(vec![col(r#""Orders".id"#).sort(true, false)])?
+        .build()?;
+    let dialect = CustomDialectBuilder::new().build();
+
+    // By default, the columns of the derived table keep the qualifier of the
+    // table they were read from inside the derived table
+    let unparser = Unparser::new(&dialect);
+    assert_snapshot!(
+        unparser.plan_to_sql(&plan)?,
+        @r#"
+    SELECT sum(orders."Amount") AS total, "Orders".id FROM (SELECT * FROM "Orders" LIMIT 3) INNER JOIN orders ON "Orders".id = orders.id GROUP BY "Orders".id ORDER BY "Orders".id ASC NULLS LAST
+    "#
+    );
+
+    let unparser = Unparser::new(&dialect).with_qualify_columns(true);
+    assert_snapshot!(
+        unparser.plan_to_sql(&plan)?,
+        @r#"
+    SELECT sum(orders."Amount") AS total, derived_limit.id FROM (SELECT * FROM "Orders" LIMIT 3) AS derived_limit INNER JOIN orders ON derived_limit.id = orders.id GROUP BY derived_limit.id ORDER BY derived_limit.id ASC NULLS LAST
+    "#
+    );
+
+    let unparser = Unparser::new(&dialect)
+        .with_qualify_columns(true)
+        .with_quote_identifiers(true);
+    assert_snapshot!(
+        unparser.plan_to_sql(&plan)?,
+        @r#"
+    SELECT sum("orders"."Amount") AS "total", "derived_limit"."id" FROM (SELECT * FROM "Orders" LIMIT 3) AS "derived_limit" INNER JOIN "orders" ON "derived_limit"."id" = "orders"."id" GROUP BY "derived_limit"."id" ORDER BY "derived_limit"."id" ASC NULLS LAST
+    "#
+    );
+    Ok(())
+}
+
+#[test]
+fn test_unparse_qualify_table_columns() -> Result<()> {
+    let schema = Schema::new(vec![
+        Field::new("id", DataType::Int32, false),
+        Field::new("amount", DataType::Int32, false),
+    ]);
+    // The columns are not qualified in the plan
+    let scan = table_scan(Some("t"), &schema, None)?.build()?;
+    let filter = LogicalPlan::Filter(Filter::try_new(
+        Expr::Column(Column::from_name("amount")).gt(lit(10)),
+        Arc::new(scan),
+    )?);
+    let plan = LogicalPlan::Projection(Projection::try_new(
+        vec![Expr::Column(Column::from_name("id"))],
+        Arc::new(filter),
+    )?);
+    let dialect = CustomDialectBuilder::new().build();
+
+    let unparser = Unparser::new(&dialect);
+    assert_snapshot!(
+        unparser.plan_to_sql(&plan)?,
+        @"SELECT id FROM t WHERE (amount > 10)"
+    );
+    let unparser = Unparser::new(&dialect).with_qualify_columns(true);
+    assert_snapshot!(
+        unparser.plan_to_sql(&plan)?,
+        @"SELECT t.id FROM t WHERE (t.amount > 10)"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_table_with_joins_builder_asof_join() -> Result<()> {
+    let table = |name: &str| {
+        TableRelationBuilder::default()
+            .name(ObjectName::from(vec![Ident::new(name)]))
+            .build()
+            .unwrap()
+    };
+    let parse_expr = |sql: &str| {
+        Parser::new(&GenericDialect {})
+            .try_with_sql(sql)?
+            .parse_expr()
+    };
+    let asof_dialect = CustomDialectBuilder::new()
+        .with_supports_asof_join(true)
+        .build();
+
+    let tests: Vec<(&dyn UnparserDialect, Option<&str>, &str)> = vec![
+        (
+            &asof_dialect,
+            Some("trades.sym = quotes.sym"),
+            "trades ASOF JOIN quotes MATCH_CONDITION (trades.ts >= quotes.ts) ON trades.sym = quotes.sym",
+        ),
+        (
+            &asof_dialect,
+            None,
+            "trades ASOF JOIN quotes MATCH_CONDITION (trades.ts >= quotes.ts)",
+        ),
+        (
+            &UnparserDefaultDialect {},
+            Some("trades.sym = quotes.sym OR quotes.sym IS NULL"),
+            "trades LEFT OUTER JOIN quotes ON (trades.sym = quotes.sym OR quotes.sym IS NULL) AND trades.ts >= quotes.ts",
+        ),
+        (
+            &UnparserDefaultDialect {},
+            None,
+            "trades LEFT OUTER JOIN quotes ON trades.ts >= quotes.ts",
+        ),
+    ];
+
+    for (dialect, on, expected) in tests {
+        let mut relation = RelationBuilder::default();
+        relation.table(
+            TableRelationBuilder::default()
+                .name(ObjectName::from(vec![Ident::new("trades")]))
+                .clone(),
+        );
+        let table_with_joins = TableWithJoinsBuilder::default()
+            .relation(relation)
+            .push_asof_join(
+                table("quotes"),
+                parse_expr("trades.ts >= quotes.ts")?,
+                on.map(parse_expr).transpose()?,
+                dialect,
+            )
+            .build()
+            .unwrap()
+            .unwrap();
+        assert_eq!(table_with_joins.to_string(), expected);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_unparse_filter_over_window() -> Result<()> {
+    let schema = Schema::new(vec![
+        Field::new("id", DataType::Int32, false),
+        Field::new("amount", DataType::Int32, false),
+    ]);
+    let row_number = row_number();
+    let rn = col(row_number.schema_name().to_string());
+    let filter_over_window = |predicate: Expr| {
+        table_scan(Some("t"), &schema, None)?
+            .window(vec![row_number.clone()])?
+            .filter(predicate)?
+            .project(vec![col("t.id")])?
+            .build()
+    };
+    let only_window = filter_over_window(rn.clone().lt_eq(lit(2)))?;
+    let mixed = filter_over_window(rn.lt_eq(lit(2)).or(col("t.amount").gt(lit(10))))?;
+
+    let qualify_dialect = CustomDialectBuilder::new()
+        .with_supports_qualify(true)
+        .build();
+    let unparser = Unparser::new(&qualify_dialect).with_qualify_columns(true);
+    assert_snapshot!(
+        unparser.plan_to_sql(&only_window)?,
+        @r#"
+    SELECT t.id FROM t QUALIFY (row_number() OVER (ROWS BETWEEN UNBOUNDED PRECEDING AND UNBOUNDED FOLLOWING) <= 2)
+    "#
+    );
+    assert_snapshot!(
+        unparser.plan_to_sql(&mixed)?,
+        @r#"
+    SELECT derived_projection.id FROM (SELECT t.id, t.amount, row_number() OVER (ROWS BETWEEN UNBOUNDED PRECEDING AND UNBOUNDED FOLLOWING) AS window_0 FROM t) AS derived_projection WHERE ((derived_projection.window_0 <= 2) OR (derived_projection.amount > 10))
+    "#
+    );
+
+    let dialect = CustomDialectBuilder::new().build();
+    let unparser = Unparser::new(&dialect).with_qualify_columns(true);
+    assert_snapshot!(
+        unparser.plan_to_sql(&only_window)?,
+        @r#"
+    SELECT derived_projection.id FROM (SELECT t.id, t.amount, row_number() OVER (ROWS BETWEEN UNBOUNDED PRECEDING AND UNBOUNDED FOLLOWING) AS window_0 FROM t) AS derived_projection WHERE (derived_projection.window_0 <= 2)
+    "#
+    );
+    Ok(())
+}
+
+#[test]
+fn test_table_relation_builder_named_args() -> Result<()> {
+    let number = |n: &str| {
+        FunctionArgExpr::Expr(SQLExpr::value(Value::Number(n.to_string(), false)))
+    };
+    let named = |name: &str, n: &str| FunctionArg::Named {
+        name: Ident::new(name),
+        arg: number(n),
+        operator: FunctionArgOperator::RightArrow,
+    };
+    let relation = TableRelationBuilder::default()
+        .name(ObjectName::from(vec![Ident::new("echo_args")]))
+        .args(Some(vec![
+            FunctionArg::Unnamed(number("1")),
+            named("c", "3"),
+            named("b", "2"),
+        ]))
+        .build()
+        .unwrap();
+    assert_eq!(relation.to_string(), "echo_args(1, c => 3, b => 2)");
+
+    // Named arguments are planned at the position of their parameter
+    let context = MockContextProvider {
+        state: MockSessionState::default(),
+    };
+    let sql_to_rel = SqlToRel::new(&context);
+    let plan = |sql: &str| -> Result<LogicalPlan> {
+        let statement = Parser::new(&GenericDialect {})
+            .try_with_sql(sql)?
+            .parse_statement()?;
+        sql_to_rel.sql_statement_to_plan(statement)
+    };
+    assert_eq!(
+        plan(&format!("SELECT * FROM {relation}"))?,
+        plan("SELECT * FROM echo_args(1, 2, 3)")?
+    );
+    Ok(())
+}
+
+#[test]
+fn test_unparse_grouping_sets() -> Result<()> {
+    let schema = Schema::new(vec![
+        Field::new("a", DataType::Utf8, true),
+        Field::new("b", DataType::Utf8, true),
+        Field::new("c", DataType::Int32, true),
+    ]);
+    let unparse = |grouping_set: GroupingSet| -> Result<String> {
+        let plan = table_scan(Some("t"), &schema, None)?
+            .aggregate(vec![Expr::GroupingSet(grouping_set)], vec![sum(col("c"))])?
+            .project(vec![col("t.a"), col("t.b"), col("sum(t.c)")])?
+            .build()?;
+        Ok(plan_to_sql(&plan)?.to_string())
+    };
+
+    assert_snapshot!(
+        unparse(GroupingSet::Rollup(vec![col("a"), col("b")]))?,
+        @"SELECT t.a, t.b, sum(t.c) FROM t GROUP BY ROLLUP (t.a, t.b)"
+    );
+    assert_snapshot!(
+        unparse(GroupingSet::Cube(vec![col("a"), col("b")]))?,
+        @"SELECT t.a, t.b, sum(t.c) FROM t GROUP BY CUBE (t.a, t.b)"
+    );
+    assert_snapshot!(
+        unparse(GroupingSet::GroupingSets(vec![
+            vec![col("a"), col("b")],
+            vec![col("a")],
+            vec![],
+        ]))?,
+        @"SELECT t.a, t.b, sum(t.c) FROM t GROUP BY GROUPING SETS ((t.a, t.b), (t.a), ())"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_unparse_table_function_with_ordinality() {
+    let statement = generate_round_trip_statement(
+        GenericDialect {},
+        "SELECT * FROM generate_series(1, 3) WITH ORDINALITY",
+    );
+    assert_snapshot!(
+        statement,
+        @r#"
+    SELECT "generate_series()"."value", "generate_series()"."ordinality" FROM (SELECT "generate_series()"."value", "ordinality" FROM "generate_series()" WITH ORDINALITY)
+    "#
+    );
+
+    let statement = generate_round_trip_statement(
+        GenericDialect {},
+        "SELECT v, i FROM generate_series(1, 3) WITH ORDINALITY AS t(v, i)",
+    );
+    assert_snapshot!(
+        statement,
+        @r#"
+    SELECT t.v, t.i FROM (SELECT "generate_series()"."value" AS v, "generate_series()"."ordinality" AS i FROM (SELECT "generate_series()"."value", "ordinality" FROM "generate_series()" WITH ORDINALITY)) AS t
+    "#
+    );
+
+    let statement = generate_round_trip_statement(
+        GenericDialect {},
+        "SELECT * FROM enumerate('a') WITH ORDINALITY",
+    );
+    assert_snapshot!(
+        statement,
+        @r#"
+    SELECT "enumerate()"."value", "enumerate()"."ordinality", "enumerate()".ordinality_1 FROM (SELECT "enumerate()"."value", "enumerate()"."ordinality", ordinality_1 FROM "enumerate()" WITH ORDINALITY)
+    "#
+    );
+}
+
+#[test]
+fn test_unparse_aggregate_without_group_by() -> Result<()> {
+    let statement = generate_round_trip_statement(
+        GenericDialect {},
+        "SELECT count(*) FROM j1 HAVING count(*) > 0",
+    );
+    assert_snapshot!(
+        statement,
+        @"SELECT COUNT(*) FROM j1 HAVING (COUNT(*) > 0)"
+    );
 
-    let context = MockContextProvider {
-        state: MockSessionState::default()
-            .with_aggregate_function(sum_udaf())
-            .with_aggregate_function(max_udaf())
-            .with_aggregate_function(grouping_udaf())
-            .with_window_function(rank_udwf())
-            .with_scalar_function(Arc::new(unicode::substr().as_ref().clone()))
-            .with_scalar_function(make_array_udf())
-            .with_expr_planner(Arc::new(CoreFunctionPlanner::default()))
-            .with_expr_planner(Arc::new(UserDefinedFunctionPlanner))
-            .with_expr_planner(Arc::new(NestedFunctionPlanner))
-            .with_expr_planner(Arc::new(FieldAccessPlanner)),
-    };
-    let sql_to_rel = SqlToRel::new(&context);
-    let plan = sql_to_rel.sql_statement_to_plan(statement).unwrap();
+    let statement = generate_round_trip_statement(GenericDialect {}, "SELECT count(*)");
+    assert_snapshot!(
+        statement,
+        @"SELECT COUNT(*)"
+    );
 
-    plan_to_sql(&plan).unwrap()
+    let plan = LogicalPlanBuilder::empty(true)
+        .aggregate(Vec::<Expr>::new(), vec![count_udaf().call(vec![lit(1)])])?
+        .build()?;
+    let dialect = CustomDialectBuilder::new()
+        .with_requires_from_dual(true)
+        .build();
+    let statement = Unparser::new(&dialect).plan_to_sql(&plan)?;
+    assert_snapshot!(
+        statement,
+        @"SELECT COUNT(1) FROM DUAL"
+    );
+
+    let schema = Schema::new(vec![Field::new("c", DataType::Int32, true)]);
+    let plan = table_scan(Some("t"), &schema, None)?
+        .aggregate(
+            vec![Expr::GroupingSet(GroupingSet::GroupingSets(vec![vec![]]))],
+            vec![sum(col("c"))],
+        )?
+        .build()?;
+    assert_snapshot!(
+        plan_to_sql(&plan)?,
+        @"SELECT sum(t.c) FROM t"
+    );
+    Ok(())
 }
 
 #[test]
@@ -1779,6 +3161,148 @@ fn test_join_with_table_scan_filters() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_unparse_left_join_with_null_equal_keys_and_range_filter() -> Result<()> {
+    let schema_left = Schema::new(vec![
+        Field::new("id", DataType::Int32, true),
+        Field::new("ts", DataType::Int32, false),
+    ]);
+
+    let schema_right = Schema::new(vec![
+        Field::new("id", DataType::Int32, true),
+        Field::new("lo", DataType::Int32, false),
+        Field::new("hi", DataType::Int32, false),
+    ]);
+
+    let left_plan = table_scan_with_filters(
+        Some("left_table"),
+        &schema_left,
+        None,
+        vec![col("ts").gt(lit(0))],
+    )?
+    .build()?;
+
+    let right_plan = table_scan_with_filters(
+        Some("right_table"),
+        &schema_right,
+        None,
+        vec![col("hi").lt(lit(100))],
+    )?
+    .build()?;
+
+    let range_filter =
+        col("left_table.ts").between(col("right_table.lo"), col("right_table.hi"));
+
+    // Keys of a join where NULLs match are compared with IS NOT DISTINCT FROM,
+    // the range filter and the null-supplying side's scan filter stay in ON
+    let null_equal_join = LogicalPlanBuilder::from(left_plan.clone())
+        .join_detailed(
+            right_plan.clone(),
+            datafusion_expr::JoinType::Left,
+            (vec!["left_table.id"], vec!["right_table.id"]),
+            Some(range_filter.clone()),
+            NullEquality::NullEqualsNull,
+        )?
+        .build()?;
+
+    let sql = plan_to_sql(&null_equal_join)?;
+    assert_snapshot!(
+        sql,
+        @r#"SELECT * FROM left_table LEFT OUTER JOIN right_table ON left_table.id IS NOT DISTINCT FROM right_table.id AND ((left_table.ts BETWEEN right_table.lo AND right_table.hi) AND (hi < 100)) WHERE (ts > 0)"#
+    );
+
+    let null_unequal_join = LogicalPlanBuilder::from(left_plan)
+        .join_detailed(
+            right_plan,
+            datafusion_expr::JoinType::Left,
+            (vec!["left_table.id"], vec!["right_table.id"]),
+            Some(range_filter),
+            NullEquality::NullEqualsNothing,
+        )?
+        .build()?;
+
+    let sql = plan_to_sql(&null_unequal_join)?;
+    assert_snapshot!(
+        sql,
+        @r#"SELECT * FROM left_table LEFT OUTER JOIN right_table ON left_table.id = right_table.id AND ((left_table.ts BETWEEN right_table.lo AND right_table.hi) AND (hi < 100)) WHERE (ts > 0)"#
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_outer_join_with_input_filters() -> Result<()> {
+    let schema_left = Schema::new(vec![
+        Field::new("id", DataType::Utf8, false),
+        Field::new("name", DataType::Utf8, false),
+    ]);
+    let schema_right = Schema::new(vec![
+        Field::new("id", DataType::Utf8, false),
+        Field::new("age", DataType::Utf8, false),
+    ]);
+    let left_plan = table_scan_with_filters(
+        Some("left_table"),
+        &schema_left,
+        None,
+        vec![col("name").like(lit("some_name"))],
+    )?
+    .build()?;
+    let right_plan = table_scan(Some("right_table"), &schema_right, None)?
+        .filter(col("right_table.age").gt(lit(10)))?
+        .build()?;
+    let join = |join_type, left: &LogicalPlan, right: &LogicalPlan| {
+        LogicalPlanBuilder::from(left.clone())
+            .join(
+                right.clone(),
+                join_type,
+                (vec!["left_table.id"], vec!["right_table.id"]),
+                None,
+            )?
+            .build()
+    };
+
+    // The filter of the null-supplying input stays in `ON`, the filter of the
+    // preserved input applies in `WHERE`
+    let plan = join(datafusion_expr::JoinType::Left, &left_plan, &right_plan)?;
+    assert_snapshot!(
+        plan_to_sql(&plan)?,
+        @r#"
+    SELECT * FROM left_table LEFT OUTER JOIN right_table ON left_table.id = right_table.id AND (right_table.age > 10) WHERE "name" LIKE 'some_name'
+    "#
+    );
+
+    let plan = join(datafusion_expr::JoinType::Right, &left_plan, &right_plan)?;
+    assert_snapshot!(
+        plan_to_sql(&plan)?,
+        @r#"
+    SELECT * FROM left_table RIGHT OUTER JOIN right_table ON left_table.id = right_table.id AND "name" LIKE 'some_name' WHERE (right_table.age > 10)
+    "#
+    );
+
+    // The filters of the inputs of a full join apply within derived tables
+    let plan = join(datafusion_expr::JoinType::Full, &left_plan, &right_plan)?;
+    assert_snapshot!(
+        plan_to_sql(&plan)?,
+        @r#"
+    SELECT * FROM (SELECT * FROM left_table WHERE left_table."name" LIKE 'some_name') AS left_table FULL JOIN (SELECT * FROM right_table WHERE (right_table.age > 10)) AS right_table ON left_table.id = right_table.id
+    "#
+    );
+
+    // A null-supplying input that is not a filtered table scan is derived too
+    let right_plan = table_scan(Some("right_table"), &schema_right, None)?
+        .project(vec![col("right_table.id"), col("right_table.age")])?
+        .filter(col("right_table.age").gt(lit(10)))?
+        .build()?;
+    let plan = join(datafusion_expr::JoinType::Left, &left_plan, &right_plan)?;
+    assert_snapshot!(
+        plan_to_sql(&plan)?,
+        @r#"
+    SELECT * FROM left_table LEFT OUTER JOIN (SELECT right_table.id, right_table.age FROM right_table WHERE (right_table.age > 10)) AS right_table ON left_table.id = right_table.id WHERE "name" LIKE 'some_name'
+    "#
+    );
+    Ok(())
+}
+
 #[test]
 fn test_interval_lhs_eq() {
     let statement = generate_round_trip_statement(
@@ -1803,6 +3327,19 @@ fn test_interval_lhs_lt() {
     )
 }
 
+#[test]
+fn test_interval_iso8601_duration() {
+    // ISO 8601 durations are unparsed in the verbose form
+    let statement = generate_round_trip_statement(
+        GenericDialect {},
+        "select interval 'P1DT2H30M', interval '-PT0.5S'",
+    );
+    assert_snapshot!(
+        statement,
+        @r#"SELECT INTERVAL '1 DAYS 2 HOURS 30 MINS', INTERVAL '-0.500000000 SECS'"#
+    )
+}
+
 #[test]
 fn test_without_offset() {
     let statement = generate_round_trip_statement(MySqlDialect {}, "select 1");
@@ -2192,6 +3729,96 @@ fn test_unparse_optimized_multi_union() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_unparse_mixed_set_operations() {
+    // INTERSECT binds tighter than UNION, so the union has to stay grouped
+    let statement = generate_round_trip_statement(
+        GenericDialect {},
+        "(select j1_id from j1 union select j2_id from j2) intersect select j3_id from j3",
+    );
+    assert_snapshot!(
+        statement,
+        @"SELECT * FROM (SELECT j1.j1_id FROM j1 UNION SELECT j2.j2_id AS j1_id FROM j2) WHERE EXISTS (SELECT 1 FROM (SELECT j3.j3_id FROM j3) WHERE (j1_id = j3.j3_id))"
+    );
+
+    let statement = generate_round_trip_statement(
+        GenericDialect {},
+        "select j1_id from j1 union all (select j2_id from j2 except select j3_id from j3)",
+    );
+    assert_snapshot!(
+        statement,
+        @"SELECT j1.j1_id FROM j1 UNION ALL SELECT DISTINCT j2.j2_id FROM j2 WHERE NOT EXISTS (SELECT 1 FROM (SELECT j3.j3_id FROM j3) WHERE (j2.j2_id = j3.j3_id))"
+    );
+
+    let statement = generate_round_trip_statement(
+        GenericDialect {},
+        "(select j1_id from j1 union select j2_id from j2) union all select j3_id from j3",
+    );
+    assert_snapshot!(
+        statement,
+        @"SELECT j1.j1_id FROM j1 UNION SELECT j2.j2_id AS j1_id FROM j2 UNION ALL SELECT j3.j3_id AS j1_id FROM j3"
+    );
+
+    let statement = generate_round_trip_statement(
+        GenericDialect {},
+        "select j1_id from j1 union all (select j2_id from j2 union select j3_id from j3)",
+    );
+    assert_snapshot!(
+        statement,
+        @"SELECT j1.j1_id FROM j1 UNION ALL (SELECT j2.j2_id FROM j2 UNION SELECT j3.j3_id AS j2_id FROM j3)"
+    );
+
+    let statement = generate_round_trip_statement(
+        GenericDialect {},
+        "select j1_id from j1 union (select j2_id from j2 union all select j3_id from j3)",
+    );
+    assert_snapshot!(
+        statement,
+        @"SELECT j1.j1_id FROM j1 UNION (SELECT j2.j2_id FROM j2 UNION ALL SELECT j3.j3_id AS j2_id FROM j3)"
+    );
+
+    let statement = generate_round_trip_statement(
+        GenericDialect {},
+        "select j1_id from j1 union all ((select j2_id from j2 union select j3_id from j3) union all (select j3_id from j3 union select j1_id from j1))",
+    );
+    assert_snapshot!(
+        statement,
+        @"SELECT j1.j1_id FROM j1 UNION ALL (SELECT j2.j2_id FROM j2 UNION SELECT j3.j3_id AS j2_id FROM j3 UNION ALL (SELECT j3.j3_id FROM j3 UNION SELECT j1.j1_id AS j3_id FROM j1))"
+    );
+}
+
+#[test]
+fn test_unparse_derived_set_operation() {
+    // The alias of a derived table applies to the whole set operation, not
+    // to its last input
+    let statement = generate_round_trip_statement(
+        GenericDialect {},
+        "select s.j1_id from (select j1_id from j1 union all select j2_id from j2) as s",
+    );
+    assert_snapshot!(
+        statement,
+        @"SELECT s.j1_id FROM (SELECT j1.j1_id FROM j1 UNION ALL SELECT j2.j2_id AS j1_id FROM j2) AS s"
+    );
+
+    let statement = generate_round_trip_statement(
+        GenericDialect {},
+        "select * from (select j1_id from j1 union select j2_id from j2) as s where s.j1_id > 1",
+    );
+    assert_snapshot!(
+        statement,
+        @"SELECT s.j1_id FROM (SELECT j1.j1_id FROM j1 UNION SELECT j2.j2_id AS j1_id FROM j2) AS s WHERE (s.j1_id > 1)"
+    );
+
+    let statement = generate_round_trip_statement(
+        GenericDialect {},
+        "select s.j1_id, j3.j3_string from (select j1_id from j1 union all select j2_id from j2) as s join j3 on s.j1_id = j3.j3_id",
+    );
+    assert_snapshot!(
+        statement,
+        @"SELECT s.j1_id, j3.j3_string FROM (SELECT j1.j1_id FROM j1 UNION ALL SELECT j2.j2_id AS j1_id FROM j2) AS s INNER JOIN j3 ON (s.j1_id = j3.j3_id)"
+    );
+}
+
 /// Test unparse the optimized plan from the following SQL:
 /// ```
 /// SELECT
@@ -2659,3 +4286,121 @@ fn test_struct_expr3() {
         @r#"SELECT test.c1."metadata".product."name" FROM (SELECT {"metadata": {product: {"name": 'Product Name'}}} AS c1) AS test"#
     );
 }
+
+#[test]
+fn test_derived_table_alias_avoids_table_names() -> Result<(), DataFusionError> {
+    roundtrip_statement_with_dialect_helper!(
+        sql: "select id, one from derived_projection, (select 1 as one)",
+        parser_dialect: MySqlDialect {},
+        unparser_dialect: UnparserMySqlDialect {},
+        expected: @"SELECT `derived_projection`.`id`, `one` FROM `derived_projection` CROSS JOIN (SELECT 1 AS `one`) AS `derived_projection_2`",
+    );
+    roundtrip_statement_with_dialect_helper!(
+        sql: "select one, two from (select 1 as one), (select 2 as two) as derived_projection_1, derived_projection",
+        parser_dialect: MySqlDialect {},
+        unparser_dialect: UnparserMySqlDialect {},
+        expected: @"SELECT `one`, `derived_projection_1`.`two` FROM (SELECT 1 AS `one`) AS `derived_projection_2` CROSS JOIN (SELECT 2 AS `two`) AS `derived_projection_1` CROSS JOIN `derived_projection`",
+    );
+    Ok(())
+}
+
+#[test]
+fn test_custom_alias_generator() -> Result<(), DataFusionError> {
+    #[derive(Debug)]
+    struct SequentialAliasGenerator;
+
+    impl AliasGenerator for SequentialAliasGenerator {
+        fn begin_statement(
+            &self,
+            _reserved: HashSet<String>,
+        ) -> Box<dyn StatementAliasGenerator> {
+            Box::new(SequentialAliases::default())
+        }
+    }
+
+    #[derive(Debug, Default)]
+    struct SequentialAliases {
+        counter: usize,
+    }
+
+    impl StatementAliasGenerator for SequentialAliases {
+        fn next_alias(&mut self, _kind: &str) -> String {
+            let alias = format!("t{}", self.counter);
+            self.counter += 1;
+            alias
+        }
+    }
+
+    let dialect = UnparserMySqlDialect {};
+    let generator: Arc<dyn AliasGenerator> = Arc::new(SequentialAliasGenerator);
+    let unparser = Unparser::new(&dialect).with_alias_generator(Arc::clone(&generator));
+    let context = MockContextProvider {
+        state: MockSessionState::default(),
+    };
+    let sql_to_rel = SqlToRel::new(&context);
+    let statement = Parser::new(&MySqlDialect {})
+        .try_with_sql("select j1_id from (select j1_id from (select j1_id from j1 limit 10) limit 5)")?
+        .parse_statement()?;
+    let plan = sql_to_rel.sql_statement_to_plan(statement)?;
+
+    // the same plan is unparsed to the same SQL text every time, also when
+    // the generator is shared by unparsers running concurrently
+    let first = unparser.plan_to_sql(&plan)?.to_string();
+    let second = unparser.plan_to_sql(&plan)?.to_string();
+    assert_eq!(first, second);
+    std::thread::scope(|scope| {
+        let handles = (0..4)
+            .map(|_| {
+                scope.spawn(|| {
+                    Unparser::new(&dialect)
+                        .with_alias_generator(Arc::clone(&generator))
+                        .plan_to_sql(&plan)
+                        .unwrap()
+                        .to_string()
+                })
+            })
+            .collect::<Vec<_>>();
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), first);
+        }
+    });
+    assert_snapshot!(first, @"SELECT `j1`.`j1_id` FROM (SELECT `j1`.`j1_id` FROM (SELECT `j1`.`j1_id` FROM `j1` LIMIT 10) AS `t1` LIMIT 5) AS `t0`");
+    Ok(())
+}
+
+#[test]
+fn test_window_range_frame_with_interval_offsets() -> Result<(), DataFusionError> {
+    roundtrip_statement_with_dialect_helper!(
+        sql: "SELECT max(age) OVER (ORDER BY birth_date RANGE BETWEEN INTERVAL '1' DAY PRECEDING AND CURRENT ROW) FROM person",
+        parser_dialect: GenericDialect {},
+        unparser_dialect: UnparserPostgreSqlDialect {},
+        expected: @r#"SELECT max("person"."age") OVER (ORDER BY "person"."birth_date" ASC NULLS LAST RANGE BETWEEN INTERVAL '1 DAYS' PRECEDING AND CURRENT ROW) FROM "person""#,
+    );
+    roundtrip_statement_with_dialect_helper!(
+        sql: "SELECT max(age) OVER (ORDER BY birth_date DESC RANGE BETWEEN INTERVAL '1' DAY PRECEDING AND INTERVAL '12' HOUR FOLLOWING) FROM person",
+        parser_dialect: GenericDialect {},
+        unparser_dialect: UnparserPostgreSqlDialect {},
+        expected: @r#"SELECT max("person"."age") OVER (ORDER BY "person"."birth_date" DESC NULLS FIRST RANGE BETWEEN INTERVAL '1 DAYS' PRECEDING AND INTERVAL '12 HOURS' FOLLOWING) FROM "person""#,
+    );
+    // both bounds before the current row, and an interval of several units
+    roundtrip_statement_with_dialect_helper!(
+        sql: "SELECT max(age) OVER (ORDER BY birth_date RANGE BETWEEN INTERVAL '1 DAY 2 HOURS' PRECEDING AND INTERVAL '30' MINUTE PRECEDING) FROM person",
+        parser_dialect: GenericDialect {},
+        unparser_dialect: UnparserPostgreSqlDialect {},
+        expected: @r#"SELECT max("person"."age") OVER (ORDER BY "person"."birth_date" ASC NULLS LAST RANGE BETWEEN INTERVAL '1 DAYS 2 HOURS' PRECEDING AND INTERVAL '30 MINS' PRECEDING) FROM "person""#,
+    );
+    roundtrip_statement_with_dialect_helper!(
+        sql: "SELECT max(age) OVER (ORDER BY birth_date RANGE BETWEEN INTERVAL '1' DAY PRECEDING AND CURRENT ROW) FROM person",
+        parser_dialect: MySqlDialect {},
+        unparser_dialect: UnparserMySqlDialect {},
+        expected: @"SELECT max(`person`.`age`) OVER (ORDER BY `person`.`birth_date` ASC RANGE BETWEEN INTERVAL 1 DAY PRECEDING AND CURRENT ROW) FROM `person`",
+    );
+    // the default frame is omitted
+    roundtrip_statement_with_dialect_helper!(
+        sql: "SELECT max(age) OVER (ORDER BY birth_date DESC RANGE BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW) FROM person",
+        parser_dialect: GenericDialect {},
+        unparser_dialect: UnparserPostgreSqlDialect {},
+        expected: @r#"SELECT max("person"."age") OVER (ORDER BY "person"."birth_date" DESC NULLS FIRST) FROM "person""#,
+    );
+    Ok(())
+}
@@ -24,9 +24,17 @@ use std::{sync::Arc, vec};
 use arrow::datatypes::*;
 use datafusion_common::config::ConfigOptions;
 use datafusion_common::file_options::file_type::FileType;
-use datafusion_common::{plan_err, DFSchema, GetExt, Result, TableReference};
-use datafusion_expr::planner::{ExprPlanner, PlannerResult, TypePlanner};
-use datafusion_expr::{AggregateUDF, Expr, ScalarUDF, TableSource, WindowUDF};
+use datafusion_common::{
+    plan_err, DFSchema, GetExt, Result, ScalarValue, TableReference,
+};
+use datafusion_expr::planner::{
+    ExprPlanner, PlannerResult, RegisteredFunction, ShowStatementArgs, ShowStatementKind,
+    TypePlanner,
+};
+use datafusion_expr::{
+    col, lit, AggregateUDF, Expr, LogicalPlan, LogicalPlanBuilder, ScalarUDF,
+    TableSource, WindowUDF,
+};
 use datafusion_functions_nested::expr_fn::make_array;
 use datafusion_sql::planner::ContextProvider;
 
@@ -57,6 +65,8 @@ pub(crate) struct MockSessionState {
     expr_planners: Vec<Arc<dyn ExprPlanner>>,
     type_planner: Option<Arc<dyn TypePlanner>>,
     window_functions: HashMap<String, Arc<WindowUDF>>,
+    /// The tables listed by `SHOW TABLES`, if it is answered by the provider
+    show_tables: Option<Vec<String>>,
     pub config_options: ConfigOptions,
 }
 
@@ -94,6 +104,11 @@ impl MockSessionState {
             .insert(window_function.name().to_string(), window_function);
         self
     }
+
+    pub fn with_show_tables(mut self, tables: Vec<String>) -> Self {
+        self.show_tables = Some(tables);
+        self
+    }
 }
 
 pub(crate) struct MockContextProvider {
@@ -177,6 +192,18 @@ impl ContextProvider for MockContextProvider {
                     false,
                 ),
             ])),
+            // Partitioned by `dt` and `region`
+            "sales" => Ok(Schema::new(vec![
+                Field::new("id", DataType::Int32, false),
+                Field::new("amount", DataType::Float64, false),
+                Field::new("dt", DataType::Date32, false),
+                Field::new("region", DataType::Utf8, false),
+            ])),
+            "wide" => Ok(Schema::new(
+                (0..1_000)
+                    .map(|i| Field::new(format!("c{i}"), DataType::Int32, false))
+                    .collect::<Vec<_>>(),
+            )),
             "lineitem" => Ok(Schema::new(vec![
                 Field::new("l_item_id", DataType::UInt32, false),
                 Field::new("l_description", DataType::Utf8, false),
@@ -197,6 +224,10 @@ impl ContextProvider for MockContextProvider {
                 Field::new("c12", DataType::Float64, false),
                 Field::new("c13", DataType::Utf8, false),
             ])),
+            "derived_projection" => Ok(Schema::new(vec![
+                Field::new("id", DataType::Int32, false),
+                Field::new("name", DataType::Utf8, false),
+            ])),
             "UPPERCASE_test" => Ok(Schema::new(vec![
                 Field::new("Id", DataType::UInt32, false),
                 Field::new("lower", DataType::UInt32, false),
@@ -222,12 +253,31 @@ impl ContextProvider for MockContextProvider {
             _ => plan_err!("No table named: {} found", name.table()),
         };
 
+        let partition_columns = match name.table() {
+            "sales" => vec!["dt".to_string(), "region".to_string()],
+            _ => vec![],
+        };
         match schema {
-            Ok(t) => Ok(Arc::new(EmptyTable::new(Arc::new(t)))),
+            Ok(t) => Ok(Arc::new(
+                EmptyTable::new(Arc::new(t)).with_partition_columns(partition_columns),
+            )),
             Err(e) => Err(e),
         }
     }
 
+    fn get_table_source_with_version(
+        &self,
+        name: TableReference,
+        version: ScalarValue,
+    ) -> Result<Arc<dyn TableSource>> {
+        let table_schema = self.get_table_source(name)?.schema();
+        Ok(Arc::new(EmptyTable {
+            table_schema,
+            version: Some(version),
+            partition_columns: vec![],
+        }))
+    }
+
     fn get_function_meta(&self, name: &str) -> Option<Arc<ScalarUDF>> {
         self.state.scalar_functions.get(name).cloned()
     }
@@ -252,6 +302,53 @@ impl ContextProvider for MockContextProvider {
         Ok(Arc::new(MockCsvType {}))
     }
 
+    fn get_table_function_source(
+        &self,
+        name: &str,
+        args: Vec<Expr>,
+    ) -> Result<Arc<dyn TableSource>> {
+        let schema = match name {
+            "generate_series" => {
+                Schema::new(vec![Field::new("value", DataType::Int64, false)])
+            }
+            "enumerate" => Schema::new(vec![
+                Field::new("value", DataType::Utf8, false),
+                Field::new("ordinality", DataType::UInt64, false),
+            ]),
+            // Has a column named after each argument, in the order they are passed
+            "echo_args" => Schema::new(
+                args.iter()
+                    .map(|arg| {
+                        Field::new(arg.schema_name().to_string(), DataType::Int64, true)
+                    })
+                    .collect::<Vec<_>>(),
+            ),
+            _ => return plan_err!("No table function named: {name} found"),
+        };
+        Ok(Arc::new(EmptyTable::new(Arc::new(schema))))
+    }
+
+    fn get_table_function_parameter_names(&self, name: &str) -> Result<Vec<String>> {
+        match name {
+            "generate_series" => Ok(vec![
+                "start".to_string(),
+                "stop".to_string(),
+                "step".to_string(),
+            ]),
+            "echo_args" => Ok(vec!["a".to_string(), "b".to_string(), "c".to_string()]),
+            "enumerate" => Ok(vec![]),
+            _ => plan_err!("No table function named: {name} found"),
+        }
+    }
+
+    fn get_table_function_required_parameter_count(&self, name: &str) -> Result<usize> {
+        match name {
+            "generate_series" => Ok(2),
+            "echo_args" | "enumerate" => Ok(0),
+            _ => plan_err!("No table function named: {name} found"),
+        }
+    }
+
     fn create_cte_work_table(
         &self,
         _name: &str,
@@ -272,6 +369,45 @@ impl ContextProvider for MockContextProvider {
         Vec::new()
     }
 
+    fn registered_functions(&self) -> Vec<RegisteredFunction> {
+        let scalar = self
+            .state
+            .scalar_functions
+            .values()
+            .map(|f| RegisteredFunction::Scalar(Arc::clone(f)));
+        let aggregate = self
+            .state
+            .aggregate_functions
+            .values()
+            .map(|f| RegisteredFunction::Aggregate(Arc::clone(f)));
+        let window = self
+            .state
+            .window_functions
+            .values()
+            .map(|f| RegisteredFunction::Window(Arc::clone(f)));
+        scalar.chain(aggregate).chain(window).collect()
+    }
+
+    fn plan_show_statement(
+        &self,
+        kind: ShowStatementKind,
+        args: &ShowStatementArgs,
+    ) -> Result<Option<LogicalPlan>> {
+        let (ShowStatementKind::Tables, Some(tables)) = (kind, &self.state.show_tables)
+        else {
+            return Ok(None);
+        };
+        let rows = tables
+            .iter()
+            .map(|table| vec![lit(table.as_str())])
+            .collect();
+        let mut plan = LogicalPlanBuilder::values(rows)?;
+        if let Some(pattern) = &args.pattern {
+            plan = plan.filter(col("column1").like(lit(pattern.as_str())))?;
+        }
+        plan.build().map(Some)
+    }
+
     fn get_expr_planners(&self) -> &[Arc<dyn ExprPlanner>] {
         &self.state.expr_planners
     }
@@ -287,11 +423,22 @@ impl ContextProvider for MockContextProvider {
 
 struct EmptyTable {
     table_schema: SchemaRef,
+    version: Option<ScalarValue>,
+    partition_columns: Vec<String>,
 }
 
 impl EmptyTable {
     fn new(table_schema: SchemaRef) -> Self {
-        Self { table_schema }
+        Self {
+            table_schema,
+            version: None,
+            partition_columns: vec![],
+        }
+    }
+
+    fn with_partition_columns(mut self, partition_columns: Vec<String>) -> Self {
+        self.partition_columns = partition_columns;
+        self
     }
 }
 
@@ -303,6 +450,14 @@ impl TableSource for EmptyTable {
     fn schema(&self) -> SchemaRef {
         Arc::clone(&self.table_schema)
     }
+
+    fn version(&self) -> Option<&ScalarValue> {
+        self.version.as_ref()
+    }
+
+    fn partition_columns(&self) -> Vec<&str> {
+        self.partition_columns.iter().map(String::as_str).collect()
+    }
 }
 
 #[derive(Debug)]
@@ -23,30 +23,43 @@ use std::vec;
 
 use arrow::datatypes::{TimeUnit::Nanosecond, *};
 use common::MockContextProvider;
-use datafusion_common::{assert_contains, DataFusionError, Result};
+use datafusion_common::diagnostic::DiagnosticKind;
+use datafusion_common::tree_node::{TreeNode, TreeNodeRecursion};
+use datafusion_common::{
+    assert_contains, Column, DFSchema, DataFusionError, Result, ScalarValue,
+};
 use datafusion_expr::{
-    col, logical_plan::LogicalPlan, test::function_stub::sum_udaf, ColumnarValue,
-    CreateIndex, DdlStatement, ScalarFunctionArgs, ScalarUDF, ScalarUDFImpl, Signature,
-    Volatility,
+    col, lit,
+    logical_plan::{FetchType, LogicalPlan, SkipType},
+    test::function_stub::sum_udaf,
+    ColumnarValue, CreateExternalTable, CreateFunction, CreateIndex, CreateMemoryTable,
+    DdlStatement, Expr, ScalarFunctionArgs, ScalarUDF, ScalarUDFImpl, Signature,
+    TableHints, Volatility,
 };
-use datafusion_functions::{string, unicode};
+use datafusion_functions::{math, string, unicode};
 use datafusion_sql::{
-    parser::DFParser,
-    planner::{NullOrdering, ParserOptions, SqlToRel},
+    parser::{DFParser, ScriptParser},
+    planner::{
+        DuplicateColumnNames, NullOrdering, ParserOptions, PlanWithMetadata, SqlToRel,
+        StatementSource,
+    },
 };
 
 use crate::common::{CustomExprPlanner, CustomTypePlanner, MockSessionState};
 use datafusion_functions::core::planner::CoreFunctionPlanner;
 use datafusion_functions_aggregate::{
-    approx_median::approx_median_udaf, count::count_udaf, min_max::max_udaf,
-    min_max::min_udaf,
+    approx_median::approx_median_udaf, array_agg::array_agg_udaf, count::count_udaf,
+    min_max::max_udaf, min_max::min_udaf,
 };
 use datafusion_functions_aggregate::{average::avg_udaf, grouping::grouping_udaf};
 use datafusion_functions_nested::make_array::make_array_udf;
 use datafusion_functions_window::{rank::rank_udwf, row_number::row_number_udwf};
 use insta::{allow_duplicates, assert_snapshot};
 use rstest::rstest;
-use sqlparser::dialect::{Dialect, GenericDialect, HiveDialect, MySqlDialect};
+use sqlparser::dialect::{
+    BigQueryDialect, Dialect, DuckDbDialect, GenericDialect, HiveDialect, MsSqlDialect,
+    MySqlDialect, PostgreSqlDialect, SnowflakeDialect,
+};
 
 mod cases;
 mod common;
@@ -469,6 +482,82 @@ fn plan_create_table_with_multi_pk() {
     );
 }
 
+fn create_memory_table(plan: LogicalPlan) -> CreateMemoryTable {
+    match plan {
+        LogicalPlan::Ddl(DdlStatement::CreateMemoryTable(create)) => create,
+        _ => panic!("expected CreateMemoryTable, got {plan}"),
+    }
+}
+
+#[test]
+fn plan_create_table_on_commit() {
+    let cases = [
+        "create temporary table t (id int) on commit drop",
+        "create table t (id int) on commit delete rows",
+        "create table t (id int) on commit preserve rows",
+    ];
+    let errors = cases
+        .iter()
+        .map(|sql| logical_plan(sql).unwrap_err().strip_backtrace())
+        .collect::<Vec<_>>()
+        .join("\n");
+    assert_snapshot!(
+        errors,
+        @r#"
+    This feature is not implemented: Temporary tables not supported
+    This feature is not implemented: ON COMMIT DELETE ROWS not supported
+    This feature is not implemented: ON COMMIT PRESERVE ROWS not supported
+    "#
+    );
+}
+
+#[test]
+fn plan_select_into_temporary_table() {
+    // Temporary tables are not supported, `SELECT INTO TEMPORARY` creates a
+    // regular table
+    let sql = "select id into temporary t from person";
+    let create = create_memory_table(logical_plan(sql).unwrap());
+    assert!(!create.temporary);
+
+    let sql = "select id into t from person";
+    let create = create_memory_table(logical_plan(sql).unwrap());
+    assert!(!create.temporary);
+}
+
+#[test]
+fn plan_create_table_as_select_with_columns() {
+    // The query columns are renamed and cast to the declared columns, even
+    // when the cast narrows the type or a NOT NULL column is nullable
+    let sql = "create table t (a smallint not null, b text default 'x') \
+               as select age, first_name from person";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+    CreateMemoryTable: Bare { table: "t" }
+      Projection: CAST(person.age AS Int16) AS a, person.first_name AS b
+        Projection: person.age, person.first_name
+          TableScan: person
+    "#
+    );
+    let create = create_memory_table(plan);
+    assert_eq!(create.column_defaults, vec![("b".to_string(), lit("x"))]);
+
+    let sql = "create table t (a int) as select id, age from person";
+    let err = logical_plan(sql).expect_err("query should have failed");
+    assert_snapshot!(
+        err.strip_backtrace(),
+        @"Error during planning: Mismatch: 1 columns specified, but result has 2 columns"
+    );
+
+    let sql = r#"create table t (a int) as select "left" from "array""#;
+    let err = logical_plan(sql).expect_err("query should have failed");
+    assert_contains!(
+        err.strip_backtrace(),
+        "Error during planning: Cannot cast column left of type List("
+    );
+}
+
 #[test]
 fn plan_create_table_with_unique() {
     let sql = "create table person (id int unique, name string)";
@@ -690,10 +779,229 @@ fn plan_insert_no_target_columns() {
     );
 }
 
+#[test]
+fn plan_insert_select_reordered_columns() {
+    let sql = "INSERT INTO test_decimal (price, id) SELECT salary, id FROM person";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+    Dml: op=[Insert Into] table=[test_decimal]
+      Projection: CAST(person.id AS Int32) AS id, CAST(person.salary AS Decimal128(10, 2)) AS price
+        Projection: person.salary, person.id
+          TableScan: person
+    "#
+    );
+}
+
+#[test]
+fn plan_insert_select_with_order_by() {
+    // The order of the source query is kept in the input of the insert
+    let sql =
+        "INSERT INTO test_decimal (price, id) SELECT salary, id FROM person ORDER BY id";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+    Dml: op=[Insert Into] table=[test_decimal]
+      Projection: CAST(person.id AS Int32) AS id, CAST(person.salary AS Decimal128(10, 2)) AS price
+        Sort: person.id ASC NULLS LAST
+          Projection: person.salary, person.id
+            TableScan: person
+    "#
+    );
+
+    let sql = "INSERT INTO test_decimal SELECT id, salary FROM person ORDER BY age DESC";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+    Dml: op=[Insert Into] table=[test_decimal]
+      Projection: CAST(person.id AS Int32) AS id, CAST(person.salary AS Decimal128(10, 2)) AS price
+        Projection: person.id, person.salary
+          Sort: person.age DESC NULLS FIRST
+            Projection: person.id, person.salary, person.age
+              TableScan: person
+    "#
+    );
+}
+
+#[test]
+fn plan_insert_overwrite_static_partition() {
+    let sql =
+        "INSERT OVERWRITE TABLE sales PARTITION (dt = '2024-01-01', region = 'eu') \
+        SELECT id, salary FROM person";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+    Dml: op=[Insert Overwrite] table=[sales]
+      Projection: CAST(person.id AS Int32) AS id, person.salary AS amount, CAST(dt AS Date32) AS dt, region AS region
+        Projection: person.id, person.salary, Utf8("2024-01-01") AS dt, Utf8("eu") AS region
+          Projection: person.id, person.salary
+            TableScan: person
+    "#
+    );
+}
+
+#[test]
+fn plan_insert_dynamic_partition() {
+    // The dynamic partition column is the last column of the source, matched by name
+    let sql = "INSERT INTO sales PARTITION (dt = '2024-01-01', region) \
+        SELECT id, salary AS amount, state AS region FROM person";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+    Dml: op=[Insert Into] table=[sales]
+      Projection: CAST(person.id AS Int32) AS id, amount AS amount, CAST(dt AS Date32) AS dt, region AS region
+        Projection: person.id, amount, region, Utf8("2024-01-01") AS dt
+          Projection: person.id, person.salary AS amount, person.state AS region
+            TableScan: person
+    "#
+    );
+
+    // The dynamic partition columns are matched by name in any order
+    let sql = "INSERT INTO sales (amount, id) PARTITION (region, dt) \
+        SELECT salary, id, birth_date AS dt, state AS region FROM person";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+    Dml: op=[Insert Into] table=[sales]
+      Projection: CAST(person.id AS Int32) AS id, person.salary AS amount, CAST(dt AS Date32) AS dt, region AS region
+        Projection: person.salary, person.id, person.birth_date AS dt, person.state AS region
+          TableScan: person
+    "#
+    );
+}
+
+#[rstest]
+#[case::not_partitioned(
+    "INSERT INTO test_decimal PARTITION (id = 1) SELECT 1.0",
+    "Error during planning: Table test_decimal is not partitioned"
+)]
+#[case::not_a_partition_column(
+    "INSERT INTO sales PARTITION (amount = 1, dt = '2024-01-01', region = 'eu') SELECT 1",
+    "Error during planning: Column amount is not a partition column of table sales, partition columns are: dt, region"
+)]
+#[case::missing_partition_column(
+    "INSERT INTO sales PARTITION (dt = '2024-01-01') SELECT 1, 1.0",
+    "Error during planning: PARTITION of table sales is missing partition columns: region"
+)]
+#[case::duplicate_partition_column(
+    "INSERT INTO sales PARTITION (dt = '2024-01-01', region, dt) SELECT 1, 1.0, 'eu'",
+    "Error during planning: Partition column dt is specified more than once in PARTITION"
+)]
+#[case::incompatible_partition_value(
+    "INSERT INTO sales PARTITION (dt = true, region = 'eu') SELECT 1, 1.0",
+    "Error during planning: Partition value Boolean(true) of type Boolean is incompatible with partition column dt of type Date32"
+)]
+#[case::partition_column_in_columns(
+    "INSERT INTO sales (id, dt) PARTITION (dt = '2024-01-01', region = 'eu') SELECT 1, 1.0",
+    "Error during planning: Partition column dt can not be listed in the INSERT columns as it is assigned in the PARTITION clause"
+)]
+#[case::dynamic_partition_column_not_found(
+    "INSERT INTO sales PARTITION (dt = '2024-01-01', region) SELECT id, salary, state FROM person",
+    "Error during planning: Dynamic partition column region not found in the last 1 columns of the INSERT source: state"
+)]
+#[case::dynamic_partition_column_count_mismatch(
+    "INSERT INTO sales PARTITION (dt, region) SELECT id, salary, state AS region FROM person",
+    "Error during planning: INSERT has more target columns (4) than expressions (3)"
+)]
+#[test]
+fn test_insert_partition_errors(#[case] sql: &str, #[case] error: &str) {
+    let err = logical_plan(sql).unwrap_err();
+    assert_eq!(err.strip_backtrace(), error)
+}
+
+#[rstest]
+#[case::placeholders(
+    "SELECT id FROM person WHERE id = $1 OR age = $2 OR id = $1 OR age = $3",
+    ParserOptions::default().with_max_placeholders(2),
+    "Error during planning: Number of placeholders 3 exceeds the max_placeholders limit of 2"
+)]
+#[case::column_identifier(
+    "SELECT first_name FROM person",
+    ParserOptions::default().with_max_identifier_length(8),
+    "Error during planning: Identifier length 10 exceeds the max_identifier_length limit of 8"
+)]
+#[case::alias_identifier(
+    "SELECT id AS identifier FROM person",
+    ParserOptions::default().with_max_identifier_length(8),
+    "Error during planning: Identifier length 10 exceeds the max_identifier_length limit of 8"
+)]
+#[case::table_identifier(
+    "SELECT 1 FROM test_decimal",
+    ParserOptions::default().with_max_identifier_length(8),
+    "Error during planning: Identifier length 12 exceeds the max_identifier_length limit of 8"
+)]
+#[case::ctes(
+    "WITH a AS (SELECT 1), b AS (SELECT 2), c AS (SELECT 3) SELECT * FROM a",
+    ParserOptions::default().with_max_ctes(2),
+    "Error during planning: Number of CTEs 3 exceeds the max_ctes limit of 2"
+)]
+#[case::set_operation_inputs(
+    "SELECT 1 UNION ALL SELECT 2 UNION SELECT 3 EXCEPT SELECT 4",
+    ParserOptions::default().with_max_set_operation_inputs(3),
+    "Error during planning: Number of set operation inputs 4 exceeds the max_set_operation_inputs limit of 3"
+)]
+#[case::nested_set_operation_inputs(
+    "SELECT * FROM (SELECT 1 UNION ALL SELECT 2 UNION ALL SELECT 3 UNION ALL SELECT 4)",
+    ParserOptions::default().with_max_set_operation_inputs(3),
+    "Error during planning: Number of set operation inputs 4 exceeds the max_set_operation_inputs limit of 3"
+)]
+#[case::projection_width(
+    "SELECT id AS i, * FROM person",
+    ParserOptions::default().with_max_projection_width(8),
+    "Error during planning: Projection width 9 exceeds the max_projection_width limit of 8"
+)]
+#[case::wide_wildcard(
+    "SELECT * FROM wide",
+    ParserOptions::default().with_max_projection_width(100),
+    "Error during planning: Projection width 1000 exceeds the max_projection_width limit of 100"
+)]
+#[test]
+fn test_planning_limits(
+    #[case] sql: &str,
+    #[case] options: ParserOptions,
+    #[case] error: &str,
+) {
+    let err = logical_plan_with_options(sql, options).unwrap_err();
+    assert_eq!(err.strip_backtrace(), error)
+}
+
+#[rstest]
+#[case::placeholders(
+    "SELECT id FROM person WHERE id = $1 OR age = $2 OR id = $1",
+    ParserOptions::default().with_max_placeholders(2)
+)]
+#[case::ctes(
+    "WITH a AS (SELECT 1), b AS (WITH c AS (SELECT 3) SELECT * FROM c) SELECT * FROM a, b",
+    ParserOptions::default().with_max_ctes(2)
+)]
+#[case::parenthesized_set_operation_inputs(
+    "SELECT 1 UNION ALL (SELECT 2 UNION ALL SELECT 3) UNION ALL SELECT 4",
+    ParserOptions::default().with_max_set_operation_inputs(3)
+)]
+#[case::default_wide_wildcard("SELECT * FROM wide", ParserOptions::default())]
+#[case::disabled_limits(
+    "SELECT $1, first_name FROM person UNION ALL SELECT $2, last_name FROM person",
+    ParserOptions::default()
+        .with_max_placeholders(0)
+        .with_max_identifier_length(0)
+        .with_max_set_operation_inputs(0)
+        .with_max_projection_width(0)
+)]
+#[test]
+fn test_planning_limits_not_exceeded(#[case] sql: &str, #[case] options: ParserOptions) {
+    logical_plan_with_options(sql, options).unwrap();
+}
+
 #[rstest]
 #[case::duplicate_columns(
     "INSERT INTO test_decimal (id, price, price) VALUES (1, 2, 3), (4, 5, 6)",
-    "Schema error: Schema contains duplicate unqualified field name price"
+    "Error during planning: Column price is specified more than once in INSERT"
 )]
 #[case::non_existing_column(
     "INSERT INTO test_decimal (nonexistent, price) VALUES (1, 2), (4, 5)",
@@ -708,6 +1016,14 @@ fn plan_insert_no_target_columns() {
     "INSERT INTO person VALUES ($1, $2)",
     "Error during planning: Inconsistent data length across values list: got 2 values in row 0 but expected 8"
 )]
+#[case::select_target_column_count_mismatch(
+    "INSERT INTO test_decimal (id, price) SELECT id FROM person",
+    "Error during planning: INSERT has more target columns (2) than expressions (1)"
+)]
+#[case::select_source_column_count_mismatch(
+    "INSERT INTO test_decimal SELECT id, age, salary FROM person",
+    "Error during planning: INSERT has more expressions (3) than target columns (2)"
+)]
 #[case::extra_placeholder(
     "INSERT INTO person (id, first_name, last_name) VALUES ($1, $2, $3, $4)",
     "Error during planning: Placeholder $4 refers to a non existent column"
@@ -1028,7 +1344,46 @@ fn select_with_ambiguous_column() {
 
     assert_snapshot!(
         err.strip_backtrace(),
-        @r"Schema error: Ambiguous reference to unqualified field id"
+        @r"Schema error: Ambiguous reference to unqualified field id, which could refer to a.id or b.id"
+    );
+}
+
+#[test]
+fn ambiguous_column_candidates() {
+    let err = |sql: &str| logical_plan(sql).unwrap_err().strip_backtrace();
+
+    assert_snapshot!(
+        err("SELECT a.id FROM person a, person b WHERE age > 30"),
+        @r"Schema error: Ambiguous reference to unqualified field age, which could refer to a.age or b.age"
+    );
+    assert_snapshot!(
+        err("SELECT a.id FROM person a JOIN person b ON age = 30"),
+        @r"Schema error: Ambiguous reference to unqualified field age, which could refer to a.age or b.age"
+    );
+    assert_snapshot!(
+        err("SELECT max(a.id) FROM person a, person b GROUP BY age"),
+        @r"Schema error: Ambiguous reference to unqualified field age, which could refer to a.age or b.age"
+    );
+    assert_snapshot!(
+        err("SELECT max(a.id) FROM person a, person b GROUP BY a.age HAVING age > 30"),
+        @r"Schema error: Ambiguous reference to unqualified field age, which could refer to a.age or b.age"
+    );
+    assert_snapshot!(
+        err("SELECT a.id FROM person a, person b ORDER BY age"),
+        @r"Schema error: Ambiguous reference to unqualified field age, which could refer to a.age or b.age"
+    );
+    // Derived tables are candidates by their alias
+    assert_snapshot!(
+        err("SELECT id FROM (SELECT id FROM person) AS d, person"),
+        @r"Schema error: Ambiguous reference to unqualified field id, which could refer to d.id or person.id"
+    );
+    assert_snapshot!(
+        err("SELECT first_name FROM person a JOIN person b USING (id) JOIN person c USING (id)"),
+        @r"Schema error: Ambiguous reference to unqualified field first_name, which could refer to a.first_name, b.first_name or c.first_name"
+    );
+    assert_snapshot!(
+        err("SELECT id FROM person a JOIN person b USING (id) JOIN person c ON a.id = c.id"),
+        @r"Schema error: Ambiguous reference to unqualified field id, which could refer to a.id, b.id or c.id"
     );
 }
 
@@ -1074,7 +1429,7 @@ fn natural_right_join() {
     assert_snapshot!(
         plan,
         @r#"
-        Projection: a.l_item_id
+        Projection: b.l_item_id
           Right Join: Using a.l_item_id = b.l_item_id, a.l_description = b.l_description, a.price = b.price
             SubqueryAlias: a
               TableScan: lineitem
@@ -1084,6 +1439,57 @@ fn natural_right_join() {
     );
 }
 
+#[test]
+fn natural_full_join() {
+    let sql = "SELECT l_item_id, a.price FROM lineitem a NATURAL FULL JOIN lineitem b \
+            WHERE l_item_id > 1";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+        Projection: CASE WHEN a.l_item_id IS NOT NULL THEN a.l_item_id ELSE b.l_item_id END AS l_item_id, a.price
+          Filter: CASE WHEN a.l_item_id IS NOT NULL THEN a.l_item_id ELSE b.l_item_id END > Int64(1)
+            Full Join: Using a.l_item_id = b.l_item_id, a.l_description = b.l_description, a.price = b.price
+              SubqueryAlias: a
+                TableScan: lineitem
+              SubqueryAlias: b
+                TableScan: lineitem
+        "#
+    );
+}
+
+#[test]
+fn right_join_nested_in_full_using_join() {
+    // The left value of the full join is the value of the right join
+    let sql = "SELECT l_item_id FROM lineitem a RIGHT JOIN lineitem b USING (l_item_id) \
+            FULL JOIN lineitem c USING (l_item_id)";
+    let plan = logical_plan(sql).unwrap();
+    let LogicalPlan::Projection(projection) = &plan else {
+        panic!("Expected a Projection, got {plan}");
+    };
+    assert_eq!(
+        projection.expr[0].to_string(),
+        "CASE WHEN b.l_item_id IS NOT NULL THEN b.l_item_id ELSE c.l_item_id END AS l_item_id"
+    );
+
+    let sql = "SELECT * FROM lineitem a RIGHT JOIN lineitem b USING (l_item_id) \
+            FULL JOIN lineitem c USING (l_item_id)";
+    let plan = logical_plan(sql).unwrap();
+    let LogicalPlan::Projection(projection) = &plan else {
+        panic!("Expected a Projection, got {plan}");
+    };
+    assert_eq!(
+        projection.expr[0].to_string(),
+        "CASE WHEN b.l_item_id IS NOT NULL THEN b.l_item_id ELSE c.l_item_id END AS l_item_id"
+    );
+    let count = plan
+        .schema()
+        .iter()
+        .filter(|(_, f)| f.name() == "l_item_id")
+        .count();
+    assert_eq!(count, 1);
+}
+
 #[test]
 fn select_with_having() {
     let sql = "SELECT id, age
@@ -1595,325 +2001,925 @@ fn select_from_typed_string_values() {
 }
 
 #[test]
-fn select_simple_aggregate_repeated_aggregate_with_repeated_aliases() {
-    let sql = "SELECT MIN(age) AS a, MIN(age) AS a FROM person";
-    let err = logical_plan(sql).expect_err("query should have failed");
-
-    assert_snapshot!(
-        err.strip_backtrace(),
-        @r#"
-        Error during planning: Projections require unique expression names but the expression "min(person.age) AS a" at position 0 and "min(person.age) AS a" at position 1 have the same name. Consider aliasing ("AS") one of them.
-        "#
-    );
-}
-
-#[test]
-fn select_simple_aggregate_with_groupby() {
-    let plan =
-        logical_plan("SELECT state, MIN(age), MAX(age) FROM person GROUP BY state")
-            .unwrap();
+fn select_from_values_with_mixed_numeric_types() {
+    let plan = logical_plan(
+        "SELECT column1, column2 FROM (VALUES (1, 'a'), (NULL, 'b'), (2.5, 'c'))",
+    )
+    .unwrap();
     assert_snapshot!(
         plan,
         @r#"
-        Projection: person.state, min(person.age), max(person.age)
-          Aggregate: groupBy=[[person.state]], aggr=[[min(person.age), max(person.age)]]
-            TableScan: person
+        Projection: column1, column2
+          Values: (CAST(Int64(1) AS Float64), Utf8("a")), (Float64(NULL), Utf8("b")), (Float64(2.5), Utf8("c"))
         "#
     );
+    assert_eq!(plan.schema().field(0).data_type(), &DataType::Float64);
 }
 
 #[test]
-fn select_simple_aggregate_with_groupby_with_aliases() {
-    let plan =
-        logical_plan("SELECT state AS a, MIN(age) AS b FROM person GROUP BY state")
-            .unwrap();
+fn select_from_values_with_literals_and_expressions() {
+    // Literal rows are planned directly, the other rows as expressions, with
+    // the same types for both
+    let plan = logical_plan(
+        "SELECT * FROM (VALUES (1, 'a', NULL), (-2, 'b' || 'c', NULL), (1 + 2, NULL, NULL))",
+    )
+    .unwrap();
     assert_snapshot!(
         plan,
         @r#"
-        Projection: person.state AS a, min(person.age) AS b
-          Aggregate: groupBy=[[person.state]], aggr=[[min(person.age)]]
-            TableScan: person
+        Projection: column1, column2, column3
+          Values: (Int64(1), Utf8("a"), NULL), (Int64(-2), Utf8("b") || Utf8("c"), NULL), (Int64(1) + Int64(2), Utf8(NULL), NULL)
         "#
     );
+    let types = plan
+        .schema()
+        .fields()
+        .iter()
+        .map(|f| f.data_type().clone())
+        .collect::<Vec<_>>();
+    assert_eq!(types, [DataType::Int64, DataType::Utf8, DataType::Null]);
 }
 
 #[test]
-fn select_simple_aggregate_with_groupby_with_aliases_repeated() {
-    let sql = "SELECT state AS a, MIN(age) AS a FROM person GROUP BY state";
-    let err = logical_plan(sql).expect_err("query should have failed");
+fn plan_script_statements() -> Result<()> {
+    let context = MockContextProvider {
+        state: MockSessionState::default(),
+    };
+    let planner = SqlToRel::new(&context);
 
+    let sql = "SELECT ';' AS semi FROM person; -- SELECT 1;\n\
+               SELECT first_name FROM person WHERE last_name = 'a;b'";
+    let plans = planner.script_to_plans(ScriptParser::new(sql)?)?;
+    assert_eq!(plans.len(), 2);
+    assert_eq!(
+        &sql[plans[0].range.clone()],
+        "SELECT ';' AS semi FROM person"
+    );
     assert_snapshot!(
-        err.strip_backtrace(),
+        plans[1].plan,
         @r#"
-        Error during planning: Projections require unique expression names but the expression "person.state AS a" at position 0 and "min(person.age) AS a" at position 1 have the same name. Consider aliasing ("AS") one of them.
-        "#
+    Projection: person.first_name
+      Filter: person.last_name = Utf8("a;b")
+        TableScan: person
+    "#
     );
-}
 
-#[test]
-fn select_simple_aggregate_with_groupby_column_unselected() {
-    let plan =
-        logical_plan("SELECT MIN(age), MAX(age) FROM person GROUP BY state").unwrap();
+    let sql = "SELECT 1;\n  SELECT nope FROM person";
+    let mut script = ScriptParser::new(sql)?;
+    let first = planner.script_statement_to_plan(script.next().unwrap()?)?;
+    assert_eq!(first.range, 0..8);
+    let err = planner
+        .script_statement_to_plan(script.next().unwrap()?)
+        .unwrap_err();
     assert_snapshot!(
-        plan,
+        err.strip_backtrace(),
         @r#"
-        Projection: min(person.age), max(person.age)
-          Aggregate: groupBy=[[person.state]], aggr=[[min(person.age), max(person.age)]]
-            TableScan: person
-        "#
+    Error planning statement at line 2, column 3
+    caused by
+    Schema error: No field named nope. Valid fields are person.id, person.first_name, person.last_name, person.age, person.state, person.salary, person.birth_date, person."😀".
+    "#
     );
+    Ok(())
 }
 
-#[test]
-fn select_simple_aggregate_with_groupby_and_column_in_group_by_does_not_exist() {
-    let sql = "SELECT sum(age) FROM person GROUP BY doesnotexist";
-    let err = logical_plan(sql).expect_err("query should have failed");
+fn plan_with_metadata(sql: &str) -> Result<PlanWithMetadata> {
+    let context = MockContextProvider {
+        state: MockSessionState::default(),
+    };
+    let planner = SqlToRel::new(&context);
+    let mut ast = DFParser::parse_sql(sql)?;
+    planner.statement_to_plan_with_metadata(ast.pop_front().unwrap())
+}
 
-    assert_snapshot!(
-        err.strip_backtrace(),
-        @r#"
-        Schema error: No field named doesnotexist. Valid fields are "sum(person.age)", person.id, person.first_name, person.last_name, person.age, person.state, person.salary, person.birth_date, person."😀".
-        "#
-    );
+/// The tables of `metadata` along with the columns used of each
+fn table_usages(metadata: &PlanWithMetadata) -> Vec<(String, Vec<String>)> {
+    metadata
+        .tables
+        .iter()
+        .map(|usage| (usage.table.to_string(), usage.columns.clone()))
+        .collect()
 }
 
 #[test]
-fn select_simple_aggregate_with_groupby_and_column_in_aggregate_does_not_exist() {
-    let sql = "SELECT sum(doesnotexist) FROM person GROUP BY first_name";
-    let err = logical_plan(sql).expect_err("query should have failed");
-    assert_field_not_found(err, "doesnotexist");
+fn plan_with_source() -> Result<()> {
+    let context = MockContextProvider {
+        state: MockSessionState::default(),
+    };
+    let planner = SqlToRel::new(&context);
+    let plan_with_source = |sql: &str| -> Result<PlanWithMetadata> {
+        let mut ast = DFParser::parse_sql_with_dialect(sql, &PostgreSqlDialect {})?;
+        let source = StatementSource::new(sql, "postgresql");
+        planner.statement_to_plan_with_source(ast.pop_front().unwrap(), source)
+    };
+
+    let view = "/* audit: v1 */ CREATE VIEW adults AS\n\
+        SELECT id,   first_name -- the name\n\
+        FROM person WHERE age >= 18";
+    let metadata = plan_with_source(&format!("\n  {view};\n"))?;
+    // The surrounding whitespace and the semicolon are not part of the statement
+    assert_eq!(
+        metadata.source,
+        Some(StatementSource {
+            sql: view.to_string(),
+            dialect: "postgresql".to_string(),
+        })
+    );
+    let LogicalPlan::Ddl(DdlStatement::CreateView(create_view)) = &metadata.plan else {
+        panic!("expected a CREATE VIEW plan, got {}", metadata.plan);
+    };
+    assert_eq!(create_view.definition.as_deref(), Some(view));
+
+    let query = "SELECT id /* the id */ FROM person";
+    let metadata = plan_with_source(query)?;
+    assert_eq!(
+        metadata.source.map(|source| source.sql).as_deref(),
+        Some(query)
+    );
+
+    // Without the source, the view is defined by the displayed statement
+    let mut ast = DFParser::parse_sql_with_dialect(view, &PostgreSqlDialect {})?;
+    let metadata = planner.statement_to_plan_with_metadata(ast.pop_front().unwrap())?;
+    assert!(metadata.source.is_none());
+    let LogicalPlan::Ddl(DdlStatement::CreateView(create_view)) = &metadata.plan else {
+        panic!("expected a CREATE VIEW plan, got {}", metadata.plan);
+    };
+    assert_eq!(
+        create_view.definition.as_deref(),
+        Some("CREATE VIEW adults AS SELECT id, first_name FROM person WHERE age >= 18")
+    );
+    Ok(())
+}
+
+/// The hints of the table scans of `plan`, in the order of the scans
+fn table_scan_hints(plan: &LogicalPlan) -> Vec<(String, TableHints)> {
+    let mut hints = vec![];
+    plan.apply(|plan| {
+        if let LogicalPlan::TableScan(scan) = plan {
+            hints.push((scan.table_name.to_string(), scan.hints.clone()));
+        }
+        Ok(TreeNodeRecursion::Continue)
+    })
+    .unwrap();
+    hints
+}
+
+#[test]
+fn plan_table_hints() -> Result<()> {
+    let sql = "SELECT p.id FROM person p USE INDEX (idx_age) \
+        JOIN orders IGNORE INDEX FOR ORDER BY (idx_qty) ON p.id = orders.customer_id \
+        JOIN person q ON p.id = q.id";
+    let plan = logical_plan_with_dialect(sql, &MySqlDialect {})?;
+    // The hints are kept on the scan they were given for
+    let hints = table_scan_hints(&plan)
+        .into_iter()
+        .map(|(table, hints)| {
+            let index_hints = hints.index_hints.iter().map(ToString::to_string);
+            (table, index_hints.collect::<Vec<_>>())
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        hints,
+        vec![
+            (
+                "person".to_string(),
+                vec!["USE INDEX (idx_age)".to_string()]
+            ),
+            (
+                "orders".to_string(),
+                vec!["IGNORE INDEX FOR ORDER BY (idx_qty)".to_string()]
+            ),
+            ("person".to_string(), vec![]),
+        ]
+    );
+
+    // Hints that are not known to DataFusion are kept as written
+    let sql = "SELECT id FROM person WITH (NOLOCK, INDEX(idx_age))";
+    let plan = logical_plan_with_dialect(sql, &MsSqlDialect {})?;
+    let [(_, hints)] = &table_scan_hints(&plan)[..] else {
+        panic!("expected one table scan");
+    };
+    assert!(hints.index_hints.is_empty());
+    let with_hints = hints.with_hints.iter().map(ToString::to_string);
+    assert_eq!(
+        with_hints.collect::<Vec<_>>(),
+        vec!["NOLOCK".to_string(), "INDEX(idx_age)".to_string()]
+    );
+    Ok(())
 }
 
 #[test]
-fn select_interval_out_of_range() {
-    let sql = "SELECT INTERVAL '100000000000000000 day'";
-    let err = logical_plan(sql).expect_err("query should have failed");
+fn plan_query_with_metadata() -> Result<()> {
+    let sql = "WITH adults AS (SELECT id, first_name FROM person WHERE age > $1) \
+               SELECT a.first_name FROM adults a \
+               WHERE a.id IN (SELECT customer_id FROM orders WHERE qty = $2)";
+    let metadata = plan_with_metadata(sql)?;
+    assert_eq!(
+        metadata.placeholders,
+        vec![
+            ("$1".to_string(), Some(DataType::Int32)),
+            ("$2".to_string(), Some(DataType::Int32)),
+        ]
+    );
+    assert_eq!(
+        table_usages(&metadata),
+        vec![
+            (
+                "person".to_string(),
+                vec![
+                    "age".to_string(),
+                    "id".to_string(),
+                    "first_name".to_string()
+                ]
+            ),
+            (
+                "orders".to_string(),
+                vec!["qty".to_string(), "customer_id".to_string()]
+            ),
+        ]
+    );
+    assert_eq!(metadata.ctes, vec!["adults".to_string()]);
+    assert!(metadata.read_only);
+    // The plan is the same as the one of `statement_to_plan`
+    assert_eq!(metadata.plan, logical_plan(sql)?);
+    Ok(())
+}
 
-    assert_snapshot!(
-        err.strip_backtrace(),
-        @r#"
-        Arrow error: Invalid argument error: Unable to represent 100000000000000000 days in a signed 32-bit integer
-        "#
+#[test]
+fn plan_metadata_with_reused_qualifier() -> Result<()> {
+    // The qualifier `t` names a different table in the subquery, whose
+    // outer reference `t.age` is a column of the outer `t`
+    let metadata = plan_with_metadata(
+        "SELECT t.first_name FROM person t \
+         WHERE EXISTS (SELECT 1 FROM orders t WHERE t.qty > t.age)",
+    )?;
+    assert_eq!(
+        table_usages(&metadata),
+        vec![
+            (
+                "person".to_string(),
+                vec!["age".to_string(), "first_name".to_string()]
+            ),
+            ("orders".to_string(), vec!["qty".to_string()]),
+        ]
+    );
+
+    let metadata = plan_with_metadata(
+        "SELECT t.first_name FROM person t, (SELECT t.qty FROM orders t) d",
+    )?;
+    assert_eq!(
+        table_usages(&metadata),
+        vec![
+            ("person".to_string(), vec!["first_name".to_string()]),
+            ("orders".to_string(), vec!["qty".to_string()]),
+        ]
     );
+    Ok(())
 }
 
 #[test]
-fn select_simple_aggregate_with_groupby_and_column_is_in_aggregate_and_groupby() {
-    let plan =
-        logical_plan("SELECT MAX(first_name) FROM person GROUP BY first_name").unwrap();
+fn plan_show_functions() -> Result<()> {
+    let context = MockContextProvider {
+        state: MockSessionState::default()
+            .with_scalar_function(Arc::new(make_udf(
+                "array_length",
+                vec![DataType::Int64],
+                DataType::UInt64,
+            )))
+            .with_scalar_function(Arc::new(make_udf(
+                "sqrt",
+                vec![DataType::Float64],
+                DataType::Float64,
+            )))
+            .with_aggregate_function(sum_udaf())
+            .with_window_function(row_number_udwf()),
+    };
+    let planner = SqlToRel::new(&context);
+    let plan = |sql: &str| -> Result<LogicalPlan> {
+        planner.statement_to_plan(DFParser::parse_sql(sql)?.pop_front().unwrap())
+    };
+
+    let all = plan("SHOW FUNCTIONS")?;
+    let columns = all
+        .schema()
+        .fields()
+        .iter()
+        .map(|field| field.name().as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        columns,
+        [
+            "function_name",
+            "return_type",
+            "parameters",
+            "parameter_types",
+            "function_type",
+            "description",
+            "syntax_example"
+        ]
+    );
+    let LogicalPlan::Values(values) = &all else {
+        panic!("Expected a Values plan, got {all}");
+    };
+    assert_eq!(values.values.len(), 4);
+
+    let filtered = plan("SHOW FUNCTIONS LIKE 'array%'")?;
     assert_snapshot!(
-        plan,
+        filtered,
         @r#"
-        Projection: max(person.first_name)
-          Aggregate: groupBy=[[person.first_name]], aggr=[[max(person.first_name)]]
-            TableScan: person
-        "#
+    Filter: function_name LIKE Utf8("array%")
+      Values: (Utf8("array_length"), Utf8("UInt64"), List([]), List([Int64]), Utf8("SCALAR"), Utf8(NULL), Utf8(NULL))
+    "#
     );
+
+    let err = plan("SHOW FUNCTIONS WHERE function_name = 'sqrt'").unwrap_err();
+    assert_contains!(err.to_string(), "Unsupported SHOW FUNCTIONS filter");
+
+    let context = MockContextProvider {
+        state: MockSessionState::default(),
+    };
+    let empty = SqlToRel::new(&context)
+        .statement_to_plan(DFParser::parse_sql("SHOW FUNCTIONS")?.pop_front().unwrap())?;
+    assert!(matches!(empty, LogicalPlan::EmptyRelation(_)));
+    assert_eq!(empty.schema().fields().len(), 7);
+    Ok(())
 }
 
 #[test]
-fn select_simple_aggregate_with_groupby_can_use_positions() {
-    let plan = logical_plan("SELECT state, age AS b, count(1) FROM person GROUP BY 1, 2")
-        .unwrap();
+fn plan_show_tables_from_provider() -> Result<()> {
+    let context = MockContextProvider {
+        state: MockSessionState::default()
+            .with_show_tables(vec!["orders".to_string(), "person".to_string()]),
+    };
+    let planner = SqlToRel::new(&context);
+    let plan = |sql: &str| -> Result<LogicalPlan> {
+        planner.statement_to_plan(DFParser::parse_sql(sql)?.pop_front().unwrap())
+    };
+
     assert_snapshot!(
-        plan,
-        @r#"
-        Projection: person.state, person.age AS b, count(Int64(1))
-          Aggregate: groupBy=[[person.state, person.age]], aggr=[[count(Int64(1))]]
-            TableScan: person
-        "#
+        plan("SHOW TABLES")?,
+        @r#"Values: (Utf8("orders")), (Utf8("person"))"#
     );
-    let plan = logical_plan("SELECT state, age AS b, count(1) FROM person GROUP BY 2, 1")
-        .unwrap();
+    // The pattern is passed to the provider
     assert_snapshot!(
-        plan,
+        plan("SHOW TABLES LIKE 'p%'")?,
         @r#"
-        Projection: person.state, person.age AS b, count(Int64(1))
-          Aggregate: groupBy=[[person.age, person.state]], aggr=[[count(Int64(1))]]
-            TableScan: person
-        "#
+    Filter: column1 LIKE Utf8("p%")
+      Values: (Utf8("orders")), (Utf8("person"))
+    "#
+    );
+    // Statements the provider does not answer are planned as before
+    assert!(matches!(
+        plan("DESCRIBE person")?,
+        LogicalPlan::DescribeTable(_)
+    ));
+
+    let context = MockContextProvider {
+        state: MockSessionState::default(),
+    };
+    let planner = SqlToRel::new(&context);
+    let plan = |sql: &str| -> Result<LogicalPlan> {
+        planner.statement_to_plan(DFParser::parse_sql(sql)?.pop_front().unwrap())
+    };
+    let err = plan("SHOW TABLES").unwrap_err();
+    assert_contains!(
+        err.to_string(),
+        "SHOW TABLES is not supported unless information_schema is enabled"
     );
+    let err = plan("SHOW TABLES LIKE 'p%'").unwrap_err();
+    assert_contains!(err.to_string(), "SHOW TABLES FILTER not supported");
+    Ok(())
 }
 
 #[test]
-fn select_simple_aggregate_with_groupby_position_out_of_range() {
-    let sql = "SELECT state, MIN(age) FROM person GROUP BY 0";
-    let err = logical_plan(sql).expect_err("query should have failed");
+fn plan_standalone_expr() -> Result<()> {
+    let context = MockContextProvider {
+        state: MockSessionState::default().with_aggregate_function(sum_udaf()),
+    };
+    let planner = SqlToRel::new(&context);
+    let schema = DFSchema::try_from_qualified_schema(
+        "t",
+        &Schema::new(vec![
+            Field::new("a", DataType::Int32, false),
+            Field::new("b", DataType::Utf8, false),
+        ]),
+    )?;
+
+    let expr = planner.parse_sql_expr("a > 1 AND b LIKE 'x%'", &schema)?;
+    assert_snapshot!(expr, @r#"t.a > Int64(1) AND t.b LIKE Utf8("x%")"#);
+
+    // The types of the placeholders are inferred from the schema
+    let expr = planner.parse_sql_expr("a = $1 OR b = $2", &schema)?;
+    let mut placeholders = vec![];
+    expr.apply(|expr| {
+        if let Expr::Placeholder(placeholder) = expr {
+            placeholders.push((placeholder.id.clone(), placeholder.data_type.clone()));
+        }
+        Ok(TreeNodeRecursion::Continue)
+    })?;
+    assert_eq!(
+        placeholders,
+        vec![
+            ("$1".to_string(), Some(DataType::Int32)),
+            ("$2".to_string(), Some(DataType::Utf8)),
+        ]
+    );
 
+    let err = planner
+        .parse_sql_expr("sum(a) > 1", &schema)
+        .expect_err("aggregate should be rejected");
     assert_snapshot!(
         err.strip_backtrace(),
-        @r#"
-        Error during planning: Cannot find column with position 0 in SELECT clause. Valid columns: 1 to 2
-        "#
+        @"Error during planning: Aggregate function sum(t.a) is not allowed in a standalone expression"
     );
 
-    let sql2 = "SELECT state, MIN(age) FROM person GROUP BY 5";
-    let err2 = logical_plan(sql2).expect_err("query should have failed");
-
-    assert_snapshot!(
-        err2.strip_backtrace(),
-        @r#"
-        Error during planning: Cannot find column with position 5 in SELECT clause. Valid columns: 1 to 2
-        "#
+    let err = planner
+        .parse_sql_expr("a > 1)", &schema)
+        .expect_err("trailing tokens should be rejected");
+    assert_contains!(
+        err.to_string(),
+        "Expected: end of expression, found: ) at Line: 1, Column: 6"
     );
+    Ok(())
 }
 
 #[test]
-fn select_simple_aggregate_with_groupby_can_use_alias() {
-    let plan =
-        logical_plan("SELECT state AS a, MIN(age) AS b FROM person GROUP BY a").unwrap();
-    assert_snapshot!(
-        plan,
-        @r#"
-        Projection: person.state AS a, min(person.age) AS b
-          Aggregate: groupBy=[[person.state]], aggr=[[min(person.age)]]
-            TableScan: person
-        "#
+fn plan_dml_with_metadata() -> Result<()> {
+    let metadata = plan_with_metadata("UPDATE person SET age = $1 WHERE state = $2")?;
+    assert_eq!(
+        metadata.placeholders,
+        vec![
+            ("$1".to_string(), Some(DataType::Int32)),
+            ("$2".to_string(), Some(DataType::Utf8)),
+        ]
+    );
+    assert_eq!(
+        table_usages(&metadata),
+        vec![(
+            "person".to_string(),
+            vec!["age".to_string(), "state".to_string()]
+        )]
+    );
+    assert!(metadata.ctes.is_empty());
+    assert!(!metadata.read_only);
+
+    let metadata = plan_with_metadata(
+        "INSERT INTO person (id, first_name) SELECT customer_id, o_item_id FROM orders",
+    )?;
+    assert_eq!(
+        table_usages(&metadata),
+        vec![
+            (
+                "person".to_string(),
+                vec!["id".to_string(), "first_name".to_string()]
+            ),
+            (
+                "orders".to_string(),
+                vec!["customer_id".to_string(), "o_item_id".to_string()]
+            ),
+        ]
     );
+    assert!(!metadata.read_only);
+
+    let metadata = plan_with_metadata("EXPLAIN SELECT id FROM person")?;
+    assert!(metadata.read_only);
+    let metadata = plan_with_metadata(
+        "SELECT id FROM person UNION ALL SELECT customer_id FROM orders",
+    )?;
+    assert!(metadata.read_only);
+    let metadata = plan_with_metadata("SELECT id INTO person_ids FROM person")?;
+    assert!(!metadata.read_only);
+    let metadata = plan_with_metadata("EXPLAIN ANALYZE DELETE FROM person")?;
+    assert!(!metadata.read_only);
+    Ok(())
 }
 
 #[test]
-fn select_simple_aggregate_with_groupby_aggregate_repeated() {
-    let sql = "SELECT state, MIN(age), MIN(age) FROM person GROUP BY state";
+fn select_simple_aggregate_repeated_aggregate_with_repeated_aliases() {
+    let sql = "SELECT MIN(age) AS a, MIN(age) AS a FROM person";
     let err = logical_plan(sql).expect_err("query should have failed");
 
     assert_snapshot!(
         err.strip_backtrace(),
         @r#"
-        Error during planning: Projections require unique expression names but the expression "min(person.age)" at position 1 and "min(person.age)" at position 2 have the same name. Consider aliasing ("AS") one of them.
+        Error during planning: Projections require unique expression names but the expression "min(person.age) AS a" at position 0 and "min(person.age) AS a" at position 1 have the same name. Consider aliasing ("AS") one of them.
         "#
     );
 }
 
 #[test]
-fn select_simple_aggregate_with_groupby_aggregate_repeated_and_one_has_alias() {
+fn select_simple_aggregate_with_groupby() {
     let plan =
-        logical_plan("SELECT state, MIN(age), MIN(age) AS ma FROM person GROUP BY state")
+        logical_plan("SELECT state, MIN(age), MAX(age) FROM person GROUP BY state")
             .unwrap();
     assert_snapshot!(
         plan,
         @r#"
-        Projection: person.state, min(person.age), min(person.age) AS ma
-          Aggregate: groupBy=[[person.state]], aggr=[[min(person.age)]]
+        Projection: person.state, min(person.age), max(person.age)
+          Aggregate: groupBy=[[person.state]], aggr=[[min(person.age), max(person.age)]]
             TableScan: person
         "#
     );
 }
 
 #[test]
-fn select_simple_aggregate_with_groupby_non_column_expression_unselected() {
+fn select_simple_aggregate_with_groupby_with_aliases() {
     let plan =
-        logical_plan("SELECT MIN(first_name) FROM person GROUP BY age + 1").unwrap();
+        logical_plan("SELECT state AS a, MIN(age) AS b FROM person GROUP BY state")
+            .unwrap();
     assert_snapshot!(
         plan,
         @r#"
-        Projection: min(person.first_name)
-          Aggregate: groupBy=[[person.age + Int64(1)]], aggr=[[min(person.first_name)]]
+        Projection: person.state AS a, min(person.age) AS b
+          Aggregate: groupBy=[[person.state]], aggr=[[min(person.age)]]
             TableScan: person
         "#
     );
 }
 
 #[test]
-fn select_simple_aggregate_with_groupby_non_column_expression_selected_and_resolvable() {
-    let plan =
-        logical_plan("SELECT age + 1, MIN(first_name) FROM person GROUP BY age + 1")
-            .unwrap();
-    assert_snapshot!(
-        plan,
-        @r#"
-        Projection: person.age + Int64(1), min(person.first_name)
-          Aggregate: groupBy=[[person.age + Int64(1)]], aggr=[[min(person.first_name)]]
-            TableScan: person
-        "#
-    );
-    let plan =
-        logical_plan("SELECT MIN(first_name), age + 1 FROM person GROUP BY age + 1")
-            .unwrap();
+fn select_simple_aggregate_with_groupby_with_aliases_repeated() {
+    let sql = "SELECT state AS a, MIN(age) AS a FROM person GROUP BY state";
+    let err = logical_plan(sql).expect_err("query should have failed");
+
     assert_snapshot!(
-        plan,
+        err.strip_backtrace(),
         @r#"
-        Projection: min(person.first_name), person.age + Int64(1)
-          Aggregate: groupBy=[[person.age + Int64(1)]], aggr=[[min(person.first_name)]]
-            TableScan: person
+        Error during planning: Projections require unique expression names but the expression "person.state AS a" at position 0 and "min(person.age) AS a" at position 1 have the same name. Consider aliasing ("AS") one of them.
         "#
     );
 }
 
 #[test]
-fn select_simple_aggregate_with_groupby_non_column_expression_nested_and_resolvable() {
-    let plan = logical_plan(
-        "SELECT ((age + 1) / 2) * (age + 1), MIN(first_name) FROM person GROUP BY age + 1"
-    ).unwrap();
+fn select_simple_aggregate_with_groupby_column_unselected() {
+    let plan =
+        logical_plan("SELECT MIN(age), MAX(age) FROM person GROUP BY state").unwrap();
     assert_snapshot!(
         plan,
         @r#"
-        Projection: person.age + Int64(1) / Int64(2) * person.age + Int64(1), min(person.first_name)
-          Aggregate: groupBy=[[person.age + Int64(1)]], aggr=[[min(person.first_name)]]
+        Projection: min(person.age), max(person.age)
+          Aggregate: groupBy=[[person.state]], aggr=[[min(person.age), max(person.age)]]
             TableScan: person
         "#
     );
 }
 
 #[test]
-fn select_simple_aggregate_with_groupby_non_column_expression_nested_and_not_resolvable()
-{
-    // The query should fail, because age + 9 is not in the group by.
-    let sql = "SELECT ((age + 1) / 2) * (age + 9), MIN(first_name) FROM person GROUP BY age + 1";
+fn select_simple_aggregate_with_groupby_and_column_in_group_by_does_not_exist() {
+    let sql = "SELECT sum(age) FROM person GROUP BY doesnotexist";
     let err = logical_plan(sql).expect_err("query should have failed");
 
     assert_snapshot!(
         err.strip_backtrace(),
         @r#"
-        Error during planning: Column in SELECT must be in GROUP BY or an aggregate function: While expanding wildcard, column "person.age" must appear in the GROUP BY clause or must be part of an aggregate function, currently only "person.age + Int64(1), min(person.first_name)" appears in the SELECT clause satisfies this requirement
+        Schema error: No field named doesnotexist. Valid fields are "sum(person.age)", person.id, person.first_name, person.last_name, person.age, person.state, person.salary, person.birth_date, person."😀".
         "#
     );
 }
 
 #[test]
-fn select_simple_aggregate_with_groupby_non_column_expression_and_its_column_selected() {
-    let sql = "SELECT age, MIN(first_name) FROM person GROUP BY age + 1";
+fn select_simple_aggregate_with_groupby_and_column_in_aggregate_does_not_exist() {
+    let sql = "SELECT sum(doesnotexist) FROM person GROUP BY first_name";
+    let err = logical_plan(sql).expect_err("query should have failed");
+    assert_field_not_found(err, "doesnotexist");
+}
+
+#[test]
+fn select_interval_out_of_range() {
+    let sql = "SELECT INTERVAL '100000000000000000 day'";
     let err = logical_plan(sql).expect_err("query should have failed");
 
     assert_snapshot!(
         err.strip_backtrace(),
         @r#"
-        Error during planning: Column in SELECT must be in GROUP BY or an aggregate function: While expanding wildcard, column "person.age" must appear in the GROUP BY clause or must be part of an aggregate function, currently only "person.age + Int64(1), min(person.first_name)" appears in the SELECT clause satisfies this requirement
+        Arrow error: Invalid argument error: Unable to represent 100000000000000000 days in a signed 32-bit integer
         "#
     );
 }
 
 #[test]
-fn select_simple_aggregate_nested_in_binary_expr_with_groupby() {
-    let plan =
-        logical_plan("SELECT state, MIN(age) < 10 FROM person GROUP BY state").unwrap();
+fn select_interval_duration() {
+    let sql = "SELECT INTERVAL 'P1DT2H30M', -INTERVAL 'PT0.5S'";
+    let plan = logical_plan(sql).unwrap();
     assert_snapshot!(
         plan,
         @r#"
-        Projection: person.state, min(person.age) < Int64(10)
-          Aggregate: groupBy=[[person.state]], aggr=[[min(person.age)]]
-            TableScan: person
-        "#
+    Projection: IntervalMonthDayNano("IntervalMonthDayNano { months: 0, days: 1, nanoseconds: 9000000000000 }"), IntervalMonthDayNano("IntervalMonthDayNano { months: 0, days: 0, nanoseconds: -500000000 }")
+      EmptyRelation: rows=1
+    "#
+    );
+
+    let err = logical_plan("SELECT INTERVAL 'P1DT-2H'").unwrap_err();
+    assert_snapshot!(
+        err.strip_backtrace(),
+        @"Error during planning: Invalid interval component '-2H' in 'P1DT-2H': components have different signs"
+    );
+
+    // The shorthand form is only accepted when enabled
+    let sql = "SELECT INTERVAL '1d2h'";
+    assert!(logical_plan(sql).is_err());
+    let options = ParserOptions::default().with_enable_interval_shorthand(true);
+    let plan = logical_plan_with_options(sql, options).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+    Projection: IntervalMonthDayNano("IntervalMonthDayNano { months: 0, days: 1, nanoseconds: 7200000000000 }")
+      EmptyRelation: rows=1
+    "#
     );
 }
 
 #[test]
-fn select_simple_aggregate_and_nested_groupby_column() {
+fn select_simple_aggregate_with_groupby_and_column_is_in_aggregate_and_groupby() {
     let plan =
-        logical_plan("SELECT MAX(first_name), age + 1 FROM person GROUP BY age").unwrap();
+        logical_plan("SELECT MAX(first_name) FROM person GROUP BY first_name").unwrap();
     assert_snapshot!(
         plan,
         @r#"
-        Projection: max(person.first_name), person.age + Int64(1)
-          Aggregate: groupBy=[[person.age]], aggr=[[max(person.first_name)]]
+        Projection: max(person.first_name)
+          Aggregate: groupBy=[[person.first_name]], aggr=[[max(person.first_name)]]
             TableScan: person
         "#
     );
 }
 
 #[test]
-fn select_aggregate_compounded_with_groupby_column() {
-    let plan = logical_plan("SELECT age + MIN(salary) FROM person GROUP BY age").unwrap();
+fn select_simple_aggregate_with_groupby_can_use_positions() {
+    let plan = logical_plan("SELECT state, age AS b, count(1) FROM person GROUP BY 1, 2")
+        .unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+        Projection: person.state, person.age AS b, count(Int64(1))
+          Aggregate: groupBy=[[person.state, person.age]], aggr=[[count(Int64(1))]]
+            TableScan: person
+        "#
+    );
+    let plan = logical_plan("SELECT state, age AS b, count(1) FROM person GROUP BY 2, 1")
+        .unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+        Projection: person.state, person.age AS b, count(Int64(1))
+          Aggregate: groupBy=[[person.age, person.state]], aggr=[[count(Int64(1))]]
+            TableScan: person
+        "#
+    );
+}
+
+#[test]
+fn select_simple_aggregate_with_groupby_position_out_of_range() {
+    let sql = "SELECT state, MIN(age) FROM person GROUP BY 0";
+    let err = logical_plan(sql).expect_err("query should have failed");
+
+    assert_snapshot!(
+        err.strip_backtrace(),
+        @r#"
+        Error during planning: Cannot find column with position 0 in SELECT clause. Valid columns: 1 to 2
+        "#
+    );
+
+    let sql2 = "SELECT state, MIN(age) FROM person GROUP BY 5";
+    let err2 = logical_plan(sql2).expect_err("query should have failed");
+
+    assert_snapshot!(
+        err2.strip_backtrace(),
+        @r#"
+        Error during planning: Cannot find column with position 5 in SELECT clause. Valid columns: 1 to 2
+        "#
+    );
+}
+
+#[test]
+fn select_simple_aggregate_with_groupby_can_use_alias() {
+    let plan =
+        logical_plan("SELECT state AS a, MIN(age) AS b FROM person GROUP BY a").unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+        Projection: person.state AS a, min(person.age) AS b
+          Aggregate: groupBy=[[person.state]], aggr=[[min(person.age)]]
+            TableScan: person
+        "#
+    );
+}
+
+#[test]
+fn select_simple_aggregate_with_groupby_alias_shadowing_column() {
+    // By default, the input column takes precedence over the alias
+    let sql = "SELECT age + 1 AS age, COUNT(*) FROM person GROUP BY age";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+    Projection: person.age + Int64(1) AS age, count(*)
+      Aggregate: groupBy=[[person.age]], aggr=[[count(*)]]
+        TableScan: person
+    "#
+    );
+
+    // Like in ORDER BY, the alias takes precedence over the input column
+    let options = ParserOptions::default().with_prefer_group_by_aliases(true);
+    let plan = logical_plan_with_options(sql, options).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+    Projection: person.age + Int64(1) AS age, count(*)
+      Aggregate: groupBy=[[person.age + Int64(1)]], aggr=[[count(*)]]
+        TableScan: person
+    "#
+    );
+
+    // A qualified name refers to the input column
+    let plan = logical_plan_with_options(
+        "SELECT age + 1 AS age, COUNT(*) FROM person GROUP BY person.age",
+        options,
+    )
+    .unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+    Projection: person.age + Int64(1) AS age, count(*)
+      Aggregate: groupBy=[[person.age]], aggr=[[count(*)]]
+        TableScan: person
+    "#
+    );
+
+    // An alias of an aggregate can not be grouped by, so the input column is used
+    let plan = logical_plan_with_options(
+        "SELECT state, MIN(age) AS age FROM person GROUP BY state, age",
+        options,
+    )
+    .unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+    Projection: person.state, min(person.age) AS age
+      Aggregate: groupBy=[[person.state, person.age]], aggr=[[min(person.age)]]
+        TableScan: person
+    "#
+    );
+}
+
+#[test]
+fn select_simple_aggregate_with_groupby_expression_of_alias() {
+    let options = ParserOptions::default().with_prefer_group_by_aliases(true);
+    let plan = logical_plan_with_options(
+        "SELECT age * 2 AS age, COUNT(*) FROM person GROUP BY age, age + 1",
+        options,
+    )
+    .unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+    Projection: person.age * Int64(2) AS age, count(*)
+      Aggregate: groupBy=[[person.age * Int64(2), person.age * Int64(2) + Int64(1)]], aggr=[[count(*)]]
+        TableScan: person
+    "#
+    );
+}
+
+#[test]
+fn select_simple_aggregate_with_groupby_aggregate_repeated() {
+    let sql = "SELECT state, MIN(age), MIN(age) FROM person GROUP BY state";
+    let err = logical_plan(sql).expect_err("query should have failed");
+
+    assert_snapshot!(
+        err.strip_backtrace(),
+        @r#"
+        Error during planning: Projections require unique expression names but the expression "min(person.age)" at position 1 and "min(person.age)" at position 2 have the same name. Consider aliasing ("AS") one of them.
+        "#
+    );
+}
+
+#[test]
+fn select_simple_aggregate_with_groupby_aggregate_repeated_and_one_has_alias() {
+    let plan =
+        logical_plan("SELECT state, MIN(age), MIN(age) AS ma FROM person GROUP BY state")
+            .unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+        Projection: person.state, min(person.age), min(person.age) AS ma
+          Aggregate: groupBy=[[person.state]], aggr=[[min(person.age)]]
+            TableScan: person
+        "#
+    );
+}
+
+#[test]
+fn select_simple_aggregate_with_groupby_non_column_expression_unselected() {
+    let plan =
+        logical_plan("SELECT MIN(first_name) FROM person GROUP BY age + 1").unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+        Projection: min(person.first_name)
+          Aggregate: groupBy=[[person.age + Int64(1)]], aggr=[[min(person.first_name)]]
+            TableScan: person
+        "#
+    );
+}
+
+#[test]
+fn select_simple_aggregate_with_groupby_non_column_expression_selected_and_resolvable() {
+    let plan =
+        logical_plan("SELECT age + 1, MIN(first_name) FROM person GROUP BY age + 1")
+            .unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+        Projection: person.age + Int64(1), min(person.first_name)
+          Aggregate: groupBy=[[person.age + Int64(1)]], aggr=[[min(person.first_name)]]
+            TableScan: person
+        "#
+    );
+    let plan =
+        logical_plan("SELECT MIN(first_name), age + 1 FROM person GROUP BY age + 1")
+            .unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+        Projection: min(person.first_name), person.age + Int64(1)
+          Aggregate: groupBy=[[person.age + Int64(1)]], aggr=[[min(person.first_name)]]
+            TableScan: person
+        "#
+    );
+}
+
+#[test]
+fn select_simple_aggregate_with_groupby_non_column_expression_nested_and_resolvable() {
+    let plan = logical_plan(
+        "SELECT ((age + 1) / 2) * (age + 1), MIN(first_name) FROM person GROUP BY age + 1"
+    ).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+        Projection: person.age + Int64(1) / Int64(2) * person.age + Int64(1), min(person.first_name)
+          Aggregate: groupBy=[[person.age + Int64(1)]], aggr=[[min(person.first_name)]]
+            TableScan: person
+        "#
+    );
+}
+
+#[test]
+fn select_simple_aggregate_with_groupby_non_column_expression_nested_and_not_resolvable()
+{
+    // The query should fail, because age + 9 is not in the group by.
+    let sql = "SELECT ((age + 1) / 2) * (age + 9), MIN(first_name) FROM person GROUP BY age + 1";
+    let err = logical_plan(sql).expect_err("query should have failed");
+
+    assert_snapshot!(
+        err.strip_backtrace(),
+        @r#"
+        Error during planning: Column in SELECT must be in GROUP BY or an aggregate function: While expanding wildcard, column "person.age" must appear in the GROUP BY clause or must be part of an aggregate function, currently only "person.age + Int64(1), min(person.first_name)" appears in the SELECT clause satisfies this requirement
+        "#
+    );
+}
+
+#[test]
+fn select_simple_aggregate_with_groupby_non_column_expression_and_its_column_selected() {
+    let sql = "SELECT age, MIN(first_name) FROM person GROUP BY age + 1";
+    let err = logical_plan(sql).expect_err("query should have failed");
+
+    assert_snapshot!(
+        err.strip_backtrace(),
+        @r#"
+        Error during planning: Column in SELECT must be in GROUP BY or an aggregate function: While expanding wildcard, column "person.age" must appear in the GROUP BY clause or must be part of an aggregate function, currently only "person.age + Int64(1), min(person.first_name)" appears in the SELECT clause satisfies this requirement
+        "#
+    );
+}
+
+#[test]
+fn select_simple_aggregate_nested_in_binary_expr_with_groupby() {
+    let plan =
+        logical_plan("SELECT state, MIN(age) < 10 FROM person GROUP BY state").unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+        Projection: person.state, min(person.age) < Int64(10)
+          Aggregate: groupBy=[[person.state]], aggr=[[min(person.age)]]
+            TableScan: person
+        "#
+    );
+}
+
+#[test]
+fn select_simple_aggregate_and_nested_groupby_column() {
+    let plan =
+        logical_plan("SELECT MAX(first_name), age + 1 FROM person GROUP BY age").unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+        Projection: max(person.first_name), person.age + Int64(1)
+          Aggregate: groupBy=[[person.age]], aggr=[[max(person.first_name)]]
+            TableScan: person
+        "#
+    );
+}
+
+#[test]
+fn select_aggregate_compounded_with_groupby_column() {
+    let plan = logical_plan("SELECT age + MIN(salary) FROM person GROUP BY age").unwrap();
     assert_snapshot!(
         plan,
         @r#"
@@ -2281,20 +3287,95 @@ CreateExternalTable: Bare { table: "t" } constraints=[PrimaryKey([0])]
     );
 }
 
-#[test]
-fn create_external_table_wih_schema() {
-    let sql = "CREATE EXTERNAL TABLE staging.foo STORED AS CSV LOCATION 'foo.csv'";
-    let plan = logical_plan(sql).unwrap();
-    assert_snapshot!(
-        plan,
-        @r#"
-CreateExternalTable: Partial { schema: "staging", table: "foo" }
-"#
-    );
+fn create_external_table(plan: LogicalPlan) -> CreateExternalTable {
+    match plan {
+        LogicalPlan::Ddl(DdlStatement::CreateExternalTable(create)) => create,
+        plan => panic!("expected CreateExternalTable, got {plan}"),
+    }
 }
 
 #[test]
-fn create_schema_with_quoted_name() {
+fn create_external_table_with_partition_columns() {
+    let sql = "CREATE EXTERNAL TABLE t(c1 int) STORED AS CSV \
+        PARTITIONED BY (DT DATE, \"Region\" VARCHAR) WITH ORDER (c1 DESC, dt) \
+        LOCATION 'foo/'";
+    let create = create_external_table(logical_plan(sql).unwrap());
+    assert_eq!(create.table_partition_cols, vec!["dt", "Region"]);
+    let fields: Vec<_> = create
+        .schema
+        .fields()
+        .iter()
+        .map(|f| (f.name().as_str(), f.data_type().clone()))
+        .collect();
+    assert_eq!(
+        fields,
+        vec![
+            ("c1", DataType::Int32),
+            ("dt", DataType::Date32),
+            ("Region", DataType::Utf8View)
+        ]
+    );
+    let order: Vec<_> = create.order_exprs[0]
+        .iter()
+        .map(|e| e.to_string())
+        .collect();
+    assert_eq!(order, vec!["c1 DESC NULLS FIRST", "dt ASC NULLS LAST"]);
+    assert_snapshot!(
+        create.definition.unwrap(),
+        @r#"CREATE EXTERNAL TABLE t (c1 INT, DT DATE, "Region" VARCHAR) STORED AS CSV PARTITIONED BY (DT, "Region") WITH ORDER (c1 DESC, dt) LOCATION 'foo/'"#
+    );
+
+    let sql = "CREATE EXTERNAL TABLE t(c1 int, p1 int) STORED AS CSV PARTITIONED BY (p2) LOCATION 'foo/'";
+    let err = logical_plan(sql).expect_err("query should have failed");
+    assert_snapshot!(
+        err.strip_backtrace(),
+        @"Error during planning: Partition column p2 is not in schema"
+    );
+
+    let sql = "CREATE EXTERNAL TABLE t(c1 int) STORED AS CSV PARTITIONED BY (p1 int) WITH ORDER (c2) LOCATION 'foo/'";
+    let err = logical_plan(sql).expect_err("query should have failed");
+    assert_snapshot!(
+        err.strip_backtrace(),
+        @"Error during planning: Column c2 is not in schema"
+    );
+}
+
+#[test]
+fn create_external_table_unsupported_column_option() {
+    let cases = [
+        "CREATE EXTERNAL TABLE t(c1 int COMMENT 'id') STORED AS CSV LOCATION 'foo.csv'",
+        "CREATE EXTERNAL TABLE t(c1 int REFERENCES p (id)) STORED AS CSV LOCATION 'foo.csv'",
+        "CREATE EXTERNAL TABLE t(c1 int CHECK (c1 > 0)) STORED AS CSV LOCATION 'foo.csv'",
+    ];
+    let errors = cases
+        .iter()
+        .map(|sql| logical_plan(sql).unwrap_err().strip_backtrace())
+        .collect::<Vec<_>>()
+        .join("\n");
+    assert_snapshot!(
+        errors,
+        @r#"
+    Error during planning: Unsupported option COMMENT 'id' for column c1 in CREATE EXTERNAL TABLE, supported options are NULL, NOT NULL, DEFAULT, PRIMARY KEY and UNIQUE
+    Error during planning: Unsupported option REFERENCES p (id) for column c1 in CREATE EXTERNAL TABLE, supported options are NULL, NOT NULL, DEFAULT, PRIMARY KEY and UNIQUE
+    Error during planning: Unsupported option CHECK (c1 > 0) for column c1 in CREATE EXTERNAL TABLE, supported options are NULL, NOT NULL, DEFAULT, PRIMARY KEY and UNIQUE
+    "#
+    );
+}
+
+#[test]
+fn create_external_table_wih_schema() {
+    let sql = "CREATE EXTERNAL TABLE staging.foo STORED AS CSV LOCATION 'foo.csv'";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+CreateExternalTable: Partial { schema: "staging", table: "foo" }
+"#
+    );
+}
+
+#[test]
+fn create_schema_with_quoted_name() {
     let sql = "CREATE SCHEMA \"quoted_schema_name\"";
     let plan = logical_plan(sql).unwrap();
     assert_snapshot!(
@@ -2628,6 +3709,31 @@ Distinct:
     );
 }
 
+#[rstest]
+#[case::union(
+    "SELECT order_id, customer_id, qty FROM orders UNION SELECT order_id, qty FROM orders",
+    "Error during planning: UNION queries have different number of columns: left has 3 columns whereas right has 2 columns"
+)]
+#[case::intersect(
+    "SELECT order_id FROM orders INTERSECT SELECT order_id, qty FROM orders",
+    "Error during planning: INTERSECT queries have different number of columns: left has 1 columns whereas right has 2 columns"
+)]
+#[case::nested(
+    "SELECT order_id, customer_id, qty FROM orders \
+     UNION ALL (SELECT order_id, qty FROM orders EXCEPT SELECT customer_id, qty FROM orders)",
+    "Error during planning: UNION queries have different number of columns: left has 3 columns whereas right has 2 columns"
+)]
+#[case::nested_input(
+    "SELECT order_id FROM orders \
+     UNION ALL (SELECT order_id FROM orders EXCEPT SELECT customer_id, qty FROM orders)",
+    "Error during planning: EXCEPT queries have different number of columns: left has 1 columns whereas right has 2 columns"
+)]
+#[test]
+fn set_operation_different_number_of_columns(#[case] sql: &str, #[case] error: &str) {
+    let err = logical_plan(sql).unwrap_err();
+    assert_eq!(err.strip_backtrace(), error);
+}
+
 #[test]
 fn union_by_name_different_columns() {
     let sql = "SELECT order_id from orders UNION BY NAME SELECT order_id, 1 FROM orders";
@@ -2680,6 +3786,98 @@ Union
     );
 }
 
+#[test]
+fn union_order_by_output_names() {
+    let sql = "SELECT order_id AS id FROM orders \
+               UNION ALL SELECT customer_id FROM orders ORDER BY id";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+Sort: id ASC NULLS LAST
+  Union
+    Projection: orders.order_id AS id
+      TableScan: orders
+    Projection: orders.customer_id
+      TableScan: orders
+"#
+    );
+
+    // The output columns are named after the first input
+    let sql = "SELECT order_id AS id FROM orders \
+               UNION ALL SELECT customer_id FROM orders ORDER BY customer_id";
+    let err = logical_plan(sql).expect_err("query should have failed");
+    assert_snapshot!(
+        err.strip_backtrace(),
+        @"Error during planning: ORDER BY column customer_id is not an output column of the set operation, whose columns are named after its first input: id"
+    );
+}
+
+#[test]
+fn union_all_parenthesized_inputs_with_order_by_and_limit() {
+    let sql = "(SELECT order_id AS id FROM orders ORDER BY id LIMIT 1) \
+               UNION ALL (SELECT customer_id AS id FROM orders ORDER BY id DESC LIMIT 1) \
+               ORDER BY id LIMIT 2";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+Limit: skip=0, fetch=2
+  Sort: id ASC NULLS LAST
+    Union
+      Limit: skip=0, fetch=1
+        Sort: id ASC NULLS LAST
+          Projection: orders.order_id AS id
+            TableScan: orders
+      Limit: skip=0, fetch=1
+        Sort: id DESC NULLS FIRST
+          Projection: orders.customer_id AS id
+            TableScan: orders
+"#
+    );
+}
+
+#[test]
+fn set_operation_precedence() {
+    // INTERSECT binds tighter than UNION
+    let sql = "SELECT order_id FROM orders UNION ALL \
+               SELECT customer_id FROM orders INTERSECT SELECT id FROM person";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+Union
+  Projection: orders.order_id
+    TableScan: orders
+  LeftSemi Join: orders.customer_id = person.id
+    Distinct:
+      Projection: orders.customer_id
+        TableScan: orders
+    Projection: person.id
+      TableScan: person
+"#
+    );
+
+    // Parentheses take precedence
+    let sql = "(SELECT order_id FROM orders UNION ALL \
+               SELECT customer_id FROM orders) INTERSECT SELECT id FROM person";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+LeftSemi Join: orders.order_id = person.id
+  Distinct:
+    Union
+      Projection: orders.order_id
+        TableScan: orders
+      Projection: orders.customer_id
+        TableScan: orders
+  Projection: person.id
+    TableScan: person
+"#
+    );
+}
+
 #[test]
 fn union_all_by_name_different_columns() {
     let sql =
@@ -2825,6 +4023,83 @@ Projection: orders.order_id, max(orders.qty) PARTITION BY [orders.order_id] ROWS
     );
 }
 
+#[test]
+fn over_partition_by_distinct() {
+    let sql = "SELECT order_id, COUNT(DISTINCT customer_id) OVER (PARTITION BY qty), \
+        SUM(DISTINCT price) OVER (PARTITION BY qty) from orders";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+Projection: orders.order_id, count(DISTINCT orders.customer_id) PARTITION BY [orders.qty] ROWS BETWEEN UNBOUNDED PRECEDING AND UNBOUNDED FOLLOWING, sum(DISTINCT orders.price) PARTITION BY [orders.qty] ROWS BETWEEN UNBOUNDED PRECEDING AND UNBOUNDED FOLLOWING
+  WindowAggr: windowExpr=[[count(DISTINCT orders.customer_id) PARTITION BY [orders.qty] ROWS BETWEEN UNBOUNDED PRECEDING AND UNBOUNDED FOLLOWING, sum(DISTINCT orders.price) PARTITION BY [orders.qty] ROWS BETWEEN UNBOUNDED PRECEDING AND UNBOUNDED FOLLOWING]]
+    TableScan: orders
+"#
+    );
+
+    // Only aggregate functions can be DISTINCT
+    let sql =
+        "SELECT order_id, ROW_NUMBER(DISTINCT qty) OVER (PARTITION BY qty) from orders";
+    let err = logical_plan(sql).unwrap_err();
+    assert_snapshot!(
+        err.strip_backtrace(),
+        @"Error during planning: DISTINCT is not supported for the window function row_number"
+    );
+}
+
+#[test]
+fn order_by_window_function_not_in_select() {
+    let sql =
+        "SELECT order_id FROM orders ORDER BY MAX(qty) OVER (ORDER BY order_id) DESC";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+Projection: orders.order_id
+  Sort: max(orders.qty) ORDER BY [orders.order_id ASC NULLS LAST] RANGE BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW DESC NULLS FIRST
+    Projection: orders.order_id, max(orders.qty) ORDER BY [orders.order_id ASC NULLS LAST] RANGE BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW
+      WindowAggr: windowExpr=[[max(orders.qty) ORDER BY [orders.order_id ASC NULLS LAST] RANGE BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW]]
+        TableScan: orders
+"#
+    );
+
+    let sql =
+        "SELECT order_id FROM orders GROUP BY order_id ORDER BY MAX(order_id) OVER ()";
+    let err = logical_plan(sql).unwrap_err();
+    assert_snapshot!(
+        err.strip_backtrace(),
+        @"This feature is not implemented: ORDER BY window functions that are not in the SELECT list of an aggregation"
+    );
+}
+
+#[test]
+fn window_functions_in_expressions() {
+    let sql = "SELECT order_id, MAX(qty) OVER w1 / MIN(qty) OVER w1 FROM orders WINDOW w1 AS (ORDER BY order_id)";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+Projection: orders.order_id, max(orders.qty) ORDER BY [orders.order_id ASC NULLS LAST] RANGE BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW / min(orders.qty) ORDER BY [orders.order_id ASC NULLS LAST] RANGE BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW
+  WindowAggr: windowExpr=[[max(orders.qty) ORDER BY [orders.order_id ASC NULLS LAST] RANGE BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW, min(orders.qty) ORDER BY [orders.order_id ASC NULLS LAST] RANGE BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW]]
+    TableScan: orders
+"#
+    );
+
+    // Named windows are resolved in the ORDER BY as well
+    let sql = "SELECT order_id FROM orders WINDOW w1 AS (ORDER BY order_id) ORDER BY MAX(qty) OVER w1 + 1";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+Projection: orders.order_id
+  Sort: max(orders.qty) ORDER BY [orders.order_id ASC NULLS LAST] RANGE BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW + Int64(1) ASC NULLS LAST
+    Projection: orders.order_id, max(orders.qty) ORDER BY [orders.order_id ASC NULLS LAST] RANGE BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW
+      WindowAggr: windowExpr=[[max(orders.qty) ORDER BY [orders.order_id ASC NULLS LAST] RANGE BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW]]
+        TableScan: orders
+"#
+    );
+}
+
 /// psql result
 /// ```text
 ///                                     QUERY PLAN
@@ -2852,6 +4127,34 @@ Projection: orders.order_id, max(orders.qty) ORDER BY [orders.order_id ASC NULLS
     );
 }
 
+#[test]
+fn named_window_default_frames() {
+    // With an ORDER BY the default frame ends at the last peer of the current row
+    let sql =
+        "SELECT order_id, MAX(qty) OVER w FROM orders WINDOW w AS (ORDER BY order_id)";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+Projection: orders.order_id, max(orders.qty) ORDER BY [orders.order_id ASC NULLS LAST] RANGE BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW
+  WindowAggr: windowExpr=[[max(orders.qty) ORDER BY [orders.order_id ASC NULLS LAST] RANGE BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW]]
+    TableScan: orders
+"#
+    );
+
+    // Without an ORDER BY the default frame is the whole partition
+    let sql = "SELECT order_id, MAX(qty) OVER w FROM orders WINDOW w AS (PARTITION BY order_id)";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+Projection: orders.order_id, max(orders.qty) PARTITION BY [orders.order_id] ROWS BETWEEN UNBOUNDED PRECEDING AND UNBOUNDED FOLLOWING
+  WindowAggr: windowExpr=[[max(orders.qty) PARTITION BY [orders.order_id] ROWS BETWEEN UNBOUNDED PRECEDING AND UNBOUNDED FOLLOWING]]
+    TableScan: orders
+"#
+    );
+}
+
 #[test]
 fn over_order_by_with_window_frame_double_end() {
     let sql = "SELECT order_id, MAX(qty) OVER (ORDER BY order_id ROWS BETWEEN 3 PRECEDING and 3 FOLLOWING), MIN(qty) OVER (ORDER BY order_id DESC) from orders";
@@ -3265,6 +4568,7 @@ fn logical_plan_with_dialect_and_options(
     let state = MockSessionState::default()
         .with_scalar_function(Arc::new(unicode::character_length().as_ref().clone()))
         .with_scalar_function(Arc::new(string::concat().as_ref().clone()))
+        .with_scalar_function(math::random())
         .with_scalar_function(Arc::new(make_udf(
             "nullif",
             vec![DataType::Int32, DataType::Int32],
@@ -3297,6 +4601,7 @@ fn logical_plan_with_dialect_and_options(
         .with_aggregate_function(min_udaf())
         .with_aggregate_function(max_udaf())
         .with_aggregate_function(grouping_udaf())
+        .with_aggregate_function(array_agg_udaf())
         .with_window_function(rank_udwf())
         .with_window_function(row_number_udwf())
         .with_expr_planner(Arc::new(CoreFunctionPlanner::default()));
@@ -3361,6 +4666,14 @@ fn parse_decimals_parser_options() -> ParserOptions {
         enable_options_value_normalization: false,
         collect_spans: false,
         default_null_ordering: NullOrdering::NullsMax,
+        duplicate_column_names: DuplicateColumnNames::Disambiguate,
+        max_placeholders: 65535,
+        max_identifier_length: 4096,
+        max_ctes: 1024,
+        max_set_operation_inputs: 10000,
+        max_projection_width: 100000,
+        enable_interval_shorthand: false,
+        prefer_group_by_aliases: false,
     }
 }
 
@@ -3373,6 +4686,14 @@ fn ident_normalization_parser_options_no_ident_normalization() -> ParserOptions
         enable_options_value_normalization: false,
         collect_spans: false,
         default_null_ordering: NullOrdering::NullsMax,
+        duplicate_column_names: DuplicateColumnNames::Disambiguate,
+        max_placeholders: 65535,
+        max_identifier_length: 4096,
+        max_ctes: 1024,
+        max_set_operation_inputs: 10000,
+        max_projection_width: 100000,
+        enable_interval_shorthand: false,
+        prefer_group_by_aliases: false,
     }
 }
 
@@ -3385,9 +4706,21 @@ fn ident_normalization_parser_options_ident_normalization() -> ParserOptions {
         enable_options_value_normalization: false,
         collect_spans: false,
         default_null_ordering: NullOrdering::NullsMax,
+        duplicate_column_names: DuplicateColumnNames::Disambiguate,
+        max_placeholders: 65535,
+        max_identifier_length: 4096,
+        max_ctes: 1024,
+        max_set_operation_inputs: 10000,
+        max_projection_width: 100000,
+        enable_interval_shorthand: false,
+        prefer_group_by_aliases: false,
     }
 }
 
+fn duplicate_column_names_error_parser_options() -> ParserOptions {
+    ParserOptions::default().with_duplicate_column_names(DuplicateColumnNames::Error)
+}
+
 #[test]
 fn select_partially_qualified_column() {
     let sql = "SELECT person.first_name FROM public.person";
@@ -3558,6 +4891,112 @@ Projection: person.id
     );
 }
 
+#[test]
+fn exists_subquery_references_grandparent_query() {
+    let sql = "SELECT j1_id FROM j1 WHERE EXISTS \
+            (SELECT j2_id FROM j2 WHERE EXISTS \
+            (SELECT j3_id FROM j3 WHERE j3_id = j1_id AND j3_string = j2.j2_string))";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+    Projection: j1.j1_id
+      Filter: EXISTS (<subquery>)
+        Subquery:
+          Projection: j2.j2_id
+            Filter: EXISTS (<subquery>)
+              Subquery:
+                Projection: j3.j3_id
+                  Filter: j3.j3_id = outer_ref(j1.j1_id) AND j3.j3_string = outer_ref(j2.j2_string)
+                    TableScan: j3
+              TableScan: j2
+        TableScan: j1
+    "#
+    );
+
+    // The reference to `j1` passes through the middle subquery
+    let LogicalPlan::Projection(projection) = &plan else {
+        panic!("expected a projection, got {plan}");
+    };
+    let LogicalPlan::Filter(filter) = projection.input.as_ref() else {
+        panic!("expected a filter, got {}", projection.input);
+    };
+    let Expr::Exists(exists) = &filter.predicate else {
+        panic!("expected EXISTS, got {}", filter.predicate);
+    };
+    assert_eq!(
+        exists.subquery.outer_ref_columns,
+        vec![Expr::OuterReferenceColumn(
+            DataType::Int32,
+            Column::from_qualified_name("j1.j1_id")
+        )]
+    );
+}
+
+#[test]
+fn in_subquery_references_grandparent_query() {
+    let sql = "SELECT id FROM person p WHERE id IN \
+            (SELECT order_id FROM orders WHERE customer_id = p.id AND qty IN \
+            (SELECT j3_id FROM j3 WHERE j3_string = p.last_name AND j3_string = orders.o_item_id))";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+    Projection: p.id
+      Filter: p.id IN (<subquery>)
+        Subquery:
+          Projection: orders.order_id
+            Filter: orders.customer_id = outer_ref(p.id) AND orders.qty IN (<subquery>)
+              Subquery:
+                Projection: j3.j3_id
+                  Filter: j3.j3_string = outer_ref(p.last_name) AND j3.j3_string = outer_ref(orders.o_item_id)
+                    TableScan: j3
+              TableScan: orders
+        SubqueryAlias: p
+          TableScan: person
+    "#
+    );
+}
+
+#[test]
+fn subquery_resolves_column_to_nearest_query() {
+    // `state` is a column of both enclosing queries, so it refers to the
+    // nearest one
+    let sql = "SELECT id FROM person p1 WHERE EXISTS \
+            (SELECT id FROM person p2 WHERE EXISTS \
+            (SELECT j3_id FROM j3 WHERE j3_string = state))";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+    Projection: p1.id
+      Filter: EXISTS (<subquery>)
+        Subquery:
+          Projection: p2.id
+            Filter: EXISTS (<subquery>)
+              Subquery:
+                Projection: j3.j3_id
+                  Filter: j3.j3_string = outer_ref(p2.state)
+                    TableScan: j3
+              SubqueryAlias: p2
+                TableScan: person
+        SubqueryAlias: p1
+          TableScan: person
+    "#
+    );
+
+    // The ambiguity is reported as a warning
+    let metadata = plan_with_metadata(sql).unwrap();
+    let [warning] = metadata.warnings.as_slice() else {
+        panic!("expected a single warning, got {:?}", metadata.warnings);
+    };
+    assert_eq!(warning.kind, DiagnosticKind::Warning);
+    assert_eq!(
+        warning.message,
+        "Column state matches several enclosing queries of a subquery, resolving it to the nearest one"
+    );
+}
+
 #[test]
 fn in_subquery_uncorrelated() {
     let sql = "SELECT id FROM person p WHERE id IN \
@@ -3647,46 +5086,192 @@ Projection: j1.j1_string, j2.j2_string
 }
 
 #[test]
-fn aggregate_with_rollup() {
-    let sql =
-        "SELECT id, state, age, count(*) FROM person GROUP BY id, ROLLUP (state, age)";
+fn scalar_subquery_in_order_by() {
+    let sql = "SELECT p.id FROM person p \
+        ORDER BY (SELECT max(id) FROM person WHERE last_name = p.last_name), \
+        (SELECT min(j1_id) FROM j1)";
     let plan = logical_plan(sql).unwrap();
     assert_snapshot!(
         plan,
         @r#"
-Projection: person.id, person.state, person.age, count(*)
-  Aggregate: groupBy=[[GROUPING SETS ((person.id), (person.id, person.state), (person.id, person.state, person.age))]], aggr=[[count(*)]]
-    TableScan: person
-"#
+    Projection: p.id
+      Sort: (<subquery>) ASC NULLS LAST, (<subquery>) ASC NULLS LAST
+        Subquery:
+          Projection: max(person.id)
+            Aggregate: groupBy=[[]], aggr=[[max(person.id)]]
+              Filter: person.last_name = outer_ref(p.last_name)
+                TableScan: person
+        Subquery:
+          Projection: min(j1.j1_id)
+            Aggregate: groupBy=[[]], aggr=[[min(j1.j1_id)]]
+              TableScan: j1
+        Projection: p.id, p.last_name
+          SubqueryAlias: p
+            TableScan: person
+    "#
     );
 }
 
 #[test]
-fn aggregate_with_rollup_with_grouping() {
-    let sql = "SELECT id, state, age, grouping(state), grouping(age), grouping(state) + grouping(age), count(*) \
-        FROM person GROUP BY id, ROLLUP (state, age)";
+fn scalar_subquery_in_group_by() {
+    let sql = "SELECT (SELECT max(id) FROM person WHERE last_name = p.last_name) AS m, count(*) \
+        FROM person p \
+        GROUP BY (SELECT max(id) FROM person WHERE last_name = p.last_name), \
+        (SELECT min(j1_id) FROM j1)";
     let plan = logical_plan(sql).unwrap();
     assert_snapshot!(
         plan,
         @r#"
-Projection: person.id, person.state, person.age, grouping(person.state), grouping(person.age), grouping(person.state) + grouping(person.age), count(*)
-  Aggregate: groupBy=[[GROUPING SETS ((person.id), (person.id, person.state), (person.id, person.state, person.age))]], aggr=[[grouping(person.state), grouping(person.age), count(*)]]
-    TableScan: person
-"#
+    Projection: max(person.id) AS m, count(*)
+      Aggregate: groupBy=[[(<subquery>), (<subquery>)]], aggr=[[count(*)]]
+        Subquery:
+          Projection: max(person.id)
+            Aggregate: groupBy=[[]], aggr=[[max(person.id)]]
+              Filter: person.last_name = outer_ref(p.last_name)
+                TableScan: person
+        Subquery:
+          Projection: min(j1.j1_id)
+            Aggregate: groupBy=[[]], aggr=[[min(j1.j1_id)]]
+              TableScan: j1
+        SubqueryAlias: p
+          TableScan: person
+    "#
     );
 }
 
 #[test]
-fn rank_partition_grouping() {
-    let sql = "select
-            sum(age) as total_sum,
-            state,
-            last_name,
-            grouping(state) + grouping(last_name) as x,
-            rank() over (
-                partition by grouping(state) + grouping(last_name),
-                case when grouping(last_name) = 0 then state end
-                order by sum(age) desc
+fn scalar_subquery_in_window_partition_by() {
+    let sql = "SELECT p.id, row_number() OVER ( \
+            PARTITION BY (SELECT max(id) FROM person WHERE last_name = p.last_name), \
+            (SELECT min(j1_id) FROM j1) \
+            ORDER BY p.id) \
+        FROM person p";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+    Projection: p.id, row_number() PARTITION BY [max(person.id), min(j1.j1_id)] ORDER BY [p.id ASC NULLS LAST] RANGE BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW
+      WindowAggr: windowExpr=[[row_number() PARTITION BY [(<subquery>), (<subquery>)] ORDER BY [p.id ASC NULLS LAST] RANGE BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW]]
+        Subquery:
+          Projection: max(person.id)
+            Aggregate: groupBy=[[]], aggr=[[max(person.id)]]
+              Filter: person.last_name = outer_ref(p.last_name)
+                TableScan: person
+        Subquery:
+          Projection: min(j1.j1_id)
+            Aggregate: groupBy=[[]], aggr=[[min(j1.j1_id)]]
+              TableScan: j1
+        SubqueryAlias: p
+          TableScan: person
+    "#
+    );
+}
+
+#[test]
+fn scalar_subquery_returning_multiple_columns() {
+    for sql in [
+        "SELECT id, (SELECT j1_id, j1_string FROM j1 LIMIT 1) FROM person",
+        "SELECT id FROM person GROUP BY id, (SELECT j1_id, j1_string FROM j1 LIMIT 1)",
+        "SELECT id FROM person ORDER BY (SELECT j1_id, j1_string FROM j1 LIMIT 1)",
+    ] {
+        let err = logical_plan(sql).unwrap_err();
+        assert_eq!(
+            err.strip_backtrace(),
+            "Error during planning: Too many columns! The subquery should only return one column: j1.j1_id, j1.j1_string"
+        );
+    }
+}
+
+#[test]
+fn array_subquery() {
+    let sql = "SELECT p.id, ARRAY(SELECT first_name FROM person WHERE last_name = p.last_name ORDER BY id) \
+        FROM person p";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+    Projection: p.id, (<subquery>)
+      Subquery:
+        Projection: CASE WHEN array_agg(person.first_name) ORDER BY [person.id ASC NULLS LAST] IS NULL THEN List([]) ELSE array_agg(person.first_name) ORDER BY [person.id ASC NULLS LAST] END AS first_name
+          Aggregate: groupBy=[[]], aggr=[[array_agg(person.first_name) ORDER BY [person.id ASC NULLS LAST]]]
+            Projection: person.first_name, person.id
+              Filter: person.last_name = outer_ref(p.last_name)
+                TableScan: person
+      SubqueryAlias: p
+        TableScan: person
+    "#
+    );
+}
+
+#[test]
+fn array_subquery_uncorrelated() {
+    let sql = "SELECT id, ARRAY(SELECT j1_id FROM j1) FROM person";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+    Projection: person.id, (<subquery>)
+      Subquery:
+        Projection: CASE WHEN array_agg(j1.j1_id) IS NULL THEN List([]) ELSE array_agg(j1.j1_id) END AS j1_id
+          Aggregate: groupBy=[[]], aggr=[[array_agg(j1.j1_id)]]
+            Projection: j1.j1_id
+              TableScan: j1
+      TableScan: person
+    "#
+    );
+}
+
+#[test]
+fn array_subquery_returning_multiple_columns() {
+    let sql = "SELECT id, ARRAY(SELECT j1_id, j1_string FROM j1) FROM person";
+    let err = logical_plan(sql).unwrap_err();
+    assert_eq!(
+        err.strip_backtrace(),
+        "Error during planning: Too many columns! The ARRAY subquery should only return one column: j1.j1_id, j1.j1_string"
+    );
+}
+
+#[test]
+fn aggregate_with_rollup() {
+    let sql =
+        "SELECT id, state, age, count(*) FROM person GROUP BY id, ROLLUP (state, age)";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+Projection: person.id, person.state, person.age, count(*)
+  Aggregate: groupBy=[[GROUPING SETS ((person.id), (person.id, person.state), (person.id, person.state, person.age))]], aggr=[[count(*)]]
+    TableScan: person
+"#
+    );
+}
+
+#[test]
+fn aggregate_with_rollup_with_grouping() {
+    let sql = "SELECT id, state, age, grouping(state), grouping(age), grouping(state) + grouping(age), count(*) \
+        FROM person GROUP BY id, ROLLUP (state, age)";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+Projection: person.id, person.state, person.age, grouping(person.state), grouping(person.age), grouping(person.state) + grouping(person.age), count(*)
+  Aggregate: groupBy=[[GROUPING SETS ((person.id), (person.id, person.state), (person.id, person.state, person.age))]], aggr=[[grouping(person.state), grouping(person.age), count(*)]]
+    TableScan: person
+"#
+    );
+}
+
+#[test]
+fn rank_partition_grouping() {
+    let sql = "select
+            sum(age) as total_sum,
+            state,
+            last_name,
+            grouping(state) + grouping(last_name) as x,
+            rank() over (
+                partition by grouping(state) + grouping(last_name),
+                case when grouping(last_name) = 0 then state end
+                order by sum(age) desc
                 ) as the_rank
             from
                 person
@@ -3745,6 +5330,64 @@ Projection: person.id, person.state, person.age, count(*)
     );
 }
 
+#[test]
+fn aggregate_with_empty_grouping() {
+    let sql = "SELECT count(*) FROM person GROUP BY ()";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+    Projection: count(*)
+      Aggregate: groupBy=[[]], aggr=[[count(*)]]
+        TableScan: person
+    "#
+    );
+
+    let sql = "SELECT age, count(*) FROM person GROUP BY age, ()";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+    Projection: person.age, count(*)
+      Aggregate: groupBy=[[person.age]], aggr=[[count(*)]]
+        TableScan: person
+    "#
+    );
+
+    // Without aggregates, the single group is planned as a grouping set
+    let sql = "SELECT 1 FROM person GROUP BY ()";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+    Projection: Int64(1)
+      Aggregate: groupBy=[[GROUPING SETS (())]], aggr=[[]]
+        TableScan: person
+    "#
+    );
+
+    let sql = "SELECT age FROM person GROUP BY ()";
+    let err = logical_plan(sql).unwrap_err();
+    assert_contains!(
+        err.strip_backtrace(),
+        "must appear in the GROUP BY clause or must be part of an aggregate function"
+    );
+}
+
+#[test]
+fn aggregate_with_empty_grouping_set() {
+    let sql = "SELECT age, count(*) FROM person GROUP BY GROUPING SETS ((age), ())";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+    Projection: person.age, count(*)
+      Aggregate: groupBy=[[GROUPING SETS ((person.age), ())]], aggr=[[count(*)]]
+        TableScan: person
+    "#
+    );
+}
+
 #[test]
 fn join_on_disjunction_condition() {
     let sql = "SELECT id, order_id \
@@ -3763,156 +5406,895 @@ Projection: person.id, orders.order_id
 }
 
 #[test]
-fn join_on_complex_condition() {
-    let sql = "SELECT id, order_id \
-            FROM person \
-            JOIN orders ON id = customer_id AND (person.age > 30 OR person.last_name = 'X')";
+fn join_on_complex_condition() {
+    let sql = "SELECT id, order_id \
+            FROM person \
+            JOIN orders ON id = customer_id AND (person.age > 30 OR person.last_name = 'X')";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+Projection: person.id, orders.order_id
+  Inner Join:  Filter: person.id = orders.customer_id AND (person.age > Int64(30) OR person.last_name = Utf8("X"))
+    TableScan: person
+    TableScan: orders
+"#
+    );
+}
+
+#[test]
+fn hive_aggregate_with_filter() -> Result<()> {
+    let dialect = &HiveDialect {};
+    let sql = "SELECT sum(age) FILTER (WHERE age > 4) FROM person";
+    let plan = logical_plan_with_dialect(sql, dialect)?;
+
+    assert_snapshot!(
+        plan,
+        @r##"
+        Projection: sum(person.age) FILTER (WHERE person.age > Int64(4))
+          Aggregate: groupBy=[[]], aggr=[[sum(person.age) FILTER (WHERE person.age > Int64(4))]]
+            TableScan: person
+        "##
+    );
+
+    Ok(())
+}
+
+#[test]
+fn order_by_unaliased_name() {
+    // https://github.com/apache/datafusion/issues/3160
+    // This query was failing with:
+    // SchemaError(FieldNotFound { qualifier: Some("p"), name: "state", valid_fields: ["z", "q"] })
+    let sql =
+        "select p.state z, sum(age) q from person p group by p.state order by p.state";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+Projection: z, q
+  Sort: p.state ASC NULLS LAST
+    Projection: p.state AS z, sum(p.age) AS q, p.state
+      Aggregate: groupBy=[[p.state]], aggr=[[sum(p.age)]]
+        SubqueryAlias: p
+          TableScan: person
+"#
+    );
+}
+
+#[test]
+fn order_by_ambiguous_name() {
+    let sql = "select * from person a join person b using (id) order by age";
+    let err = logical_plan(sql).unwrap_err().strip_backtrace();
+
+    assert_snapshot!(
+        err,
+        @r###"
+        Schema error: Ambiguous reference to unqualified field age, which could refer to a.age or b.age
+        "###
+    );
+}
+
+#[test]
+fn group_by_ambiguous_name() {
+    let sql = "select max(id) from person a join person b using (id) group by age";
+    let err = logical_plan(sql).unwrap_err().strip_backtrace();
+
+    assert_snapshot!(
+        err,
+        @r###"
+        Schema error: Ambiguous reference to unqualified field age, which could refer to a.age or b.age
+        "###
+    );
+}
+
+#[test]
+fn test_zero_offset_with_limit() {
+    let sql = "select id from person where person.id > 100 LIMIT 5 OFFSET 0;";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+Limit: skip=0, fetch=5
+  Projection: person.id
+    Filter: person.id > Int64(100)
+      TableScan: person
+"#
+    );
+    // Flip the order of LIMIT and OFFSET in the query. Plan should remain the same.
+    let sql = "SELECT id FROM person WHERE person.id > 100 OFFSET 0 LIMIT 5;";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+Limit: skip=0, fetch=5
+  Projection: person.id
+    Filter: person.id > Int64(100)
+      TableScan: person
+"#
+    );
+}
+
+#[test]
+fn test_large_offset_skip_type() {
+    // The OFFSET of a plan is available as a number to optimizer rules
+    let sql = "SELECT id FROM person ORDER BY id OFFSET 1000000 LIMIT 10";
+    let LogicalPlan::Limit(limit) = logical_plan(sql).unwrap() else {
+        panic!("Expected a Limit plan");
+    };
+    assert!(matches!(
+        limit.get_skip_type().unwrap(),
+        SkipType::Literal(1000000)
+    ));
+    assert!(matches!(
+        limit.get_fetch_type().unwrap(),
+        FetchType::Literal(Some(10))
+    ));
+}
+
+#[test]
+fn test_offset_past_end_of_input() {
+    // The OFFSET is not checked against the number of input rows, as an
+    // OFFSET past the end of the input just returns no rows
+    let sql = "SELECT column1 FROM (VALUES (1), (2), (3), (4), (5)) OFFSET 100 LIMIT 10";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+    Limit: skip=100, fetch=10
+      Projection: column1
+        Values: (Int64(1)), (Int64(2)), (Int64(3)), (Int64(4)), (Int64(5))
+    "#
+    );
+}
+
+#[test]
+fn test_offset_no_limit() {
+    let sql = "SELECT id FROM person WHERE person.id > 100 OFFSET 5;";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+Limit: skip=5, fetch=None
+  Projection: person.id
+    Filter: person.id > Int64(100)
+      TableScan: person
+"#
+    );
+}
+
+#[test]
+fn test_offset_after_limit() {
+    let sql = "select id from person where person.id > 100 LIMIT 5 OFFSET 3;";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+Limit: skip=3, fetch=5
+  Projection: person.id
+    Filter: person.id > Int64(100)
+      TableScan: person
+"#
+    );
+}
+
+#[test]
+fn test_offset_before_limit() {
+    let sql = "select id from person where person.id > 100 OFFSET 3 LIMIT 5;";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+Limit: skip=3, fetch=5
+  Projection: person.id
+    Filter: person.id > Int64(100)
+      TableScan: person
+"#
+    );
+}
+
+#[test]
+fn test_limit_after_distinct() {
+    // The limit counts the deduplicated rows of DISTINCT
+    let sql = "SELECT DISTINCT age FROM person LIMIT 5";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+    Limit: skip=0, fetch=5
+      Distinct:
+        Projection: person.age
+          TableScan: person
+    "#
+    );
+
+    let sql = "SELECT DISTINCT age FROM person ORDER BY age LIMIT 5 OFFSET 2";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+    Limit: skip=2, fetch=5
+      Sort: person.age ASC NULLS LAST
+        Distinct:
+          Projection: person.age
+            TableScan: person
+    "#
+    );
+}
+
+#[test]
+fn test_limit_after_distinct_on() {
+    // The limit counts the deduplicated rows of DISTINCT ON
+    let sql = "SELECT DISTINCT ON (id) id, age FROM person ORDER BY id, age DESC LIMIT 5";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+    Limit: skip=0, fetch=5
+      DistinctOn: on_expr=[[person.id]], select_expr=[[person.id, person.age]], sort_expr=[[person.id ASC NULLS LAST, person.age DESC NULLS FIRST]]
+        TableScan: person
+    "#
+    );
+
+    let sql = "SELECT DISTINCT ON (id) id, age FROM person ORDER BY id, age DESC \
+        OFFSET 1 ROWS FETCH FIRST 5 ROWS ONLY";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+    Limit: skip=1, fetch=5
+      DistinctOn: on_expr=[[person.id]], select_expr=[[person.id, person.age]], sort_expr=[[person.id ASC NULLS LAST, person.age DESC NULLS FIRST]]
+        TableScan: person
+    "#
+    );
+}
+
+#[test]
+fn test_distinct_on_without_expressions() {
+    let sql = "SELECT DISTINCT ON () id, age FROM person";
+    let err = logical_plan(sql).unwrap_err();
+    assert_snapshot!(
+        err.strip_backtrace(),
+        @"Error during planning: DISTINCT ON requires at least one expression, use DISTINCT to remove duplicate rows"
+    );
+}
+
+#[test]
+fn test_fetch_first() {
+    let sql = "SELECT id FROM person ORDER BY id FETCH FIRST ROW ONLY";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+    Limit: skip=0, fetch=1
+      Sort: person.id ASC NULLS LAST
+        Projection: person.id
+          TableScan: person
+    "#
+    );
+
+    let sql =
+        "SELECT id FROM person UNION ALL SELECT age FROM person FETCH NEXT 3 ROWS ONLY";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+    Limit: skip=0, fetch=3
+      Union
+        Projection: person.id
+          TableScan: person
+        Projection: person.age
+          TableScan: person
+    "#
+    );
+}
+
+#[test]
+fn test_fetch_first_in_derived_table() {
+    // The FETCH stays inside of the derived table
+    let sql = "SELECT s.id FROM \
+        (SELECT id FROM person ORDER BY id FETCH FIRST 3 ROWS ONLY) AS s \
+        WHERE s.id > 1 ORDER BY s.id DESC";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+    Sort: s.id DESC NULLS FIRST
+      Projection: s.id
+        Filter: s.id > Int64(1)
+          SubqueryAlias: s
+            Limit: skip=0, fetch=3
+              Sort: person.id ASC NULLS LAST
+                Projection: person.id
+                  TableScan: person
+    "#
+    );
+}
+
+#[test]
+fn test_fetch_first_with_ties() {
+    let cases = [
+        "SELECT id FROM person ORDER BY age FETCH FIRST 2 ROWS WITH TIES",
+        "SELECT id FROM person ORDER BY age OFFSET 1 ROWS FETCH NEXT ROW WITH TIES",
+        "SELECT TOP 2 WITH TIES id FROM person ORDER BY age",
+    ];
+    let errors = cases
+        .iter()
+        .map(|sql| logical_plan(sql).unwrap_err().strip_backtrace())
+        .collect::<Vec<_>>()
+        .join("\n");
+    assert_snapshot!(
+        errors,
+        @r#"
+    This feature is not implemented: FETCH WITH TIES
+    This feature is not implemented: FETCH WITH TIES
+    This feature is not implemented: TOP WITH TIES
+    "#
+    );
+}
+
+#[test]
+fn test_fetch_first_errors() {
+    let cases = [
+        "SELECT id FROM person LIMIT 2 FETCH FIRST 3 ROWS ONLY",
+        "SELECT id FROM person FETCH FIRST 10 PERCENT ROWS ONLY",
+    ];
+    let errors = cases
+        .iter()
+        .map(|sql| logical_plan(sql).unwrap_err().strip_backtrace())
+        .collect::<Vec<_>>()
+        .join("\n");
+    assert_snapshot!(
+        errors,
+        @r#"
+    Error during planning: FETCH cannot be used together with LIMIT
+    This feature is not implemented: FETCH PERCENT is not supported
+    "#
+    );
+}
+
+#[test]
+fn test_limit_spellings_plan_identically() {
+    let equivalent = [
+        [
+            "SELECT id FROM person ORDER BY age LIMIT 2",
+            "SELECT id FROM person ORDER BY age FETCH FIRST 2 ROWS ONLY",
+            "SELECT TOP 2 id FROM person ORDER BY age",
+        ],
+        [
+            "SELECT id FROM person ORDER BY age LIMIT 1",
+            "SELECT id FROM person ORDER BY age FETCH FIRST ROW ONLY",
+            "SELECT TOP 1 id FROM person ORDER BY age",
+            "SELECT id FROM person ORDER BY age LIMIT 1 OFFSET 0",
+        ],
+    ];
+    for sqls in equivalent {
+        let expected = logical_plan(sqls[0]).unwrap().display_indent().to_string();
+        for sql in &sqls[1..] {
+            let plan = logical_plan(sql).unwrap().display_indent().to_string();
+            assert_eq!(
+                plan, expected,
+                "{sql} was planned differently than {}",
+                sqls[0]
+            );
+        }
+    }
+}
+
+#[test]
+fn test_limit_spelling_errors() {
+    let cases = [
+        "SELECT id FROM person FETCH FIRST 2 ROWS WITH TIES",
+        "SELECT TOP 2 WITH TIES id FROM person",
+        "SELECT id FROM person FETCH FIRST 10 PERCENT ROWS ONLY",
+        "SELECT TOP 10 PERCENT id FROM person",
+        "SELECT id FROM person LIMIT 'a'",
+        "SELECT id FROM person FETCH FIRST 'a' ROWS ONLY",
+        "SELECT TOP ('a') id FROM person",
+        "SELECT TOP 2 id FROM person FETCH FIRST 2 ROWS ONLY",
+        "SELECT id FROM person UNION ALL SELECT TOP 2 WITH TIES id FROM person",
+    ];
+    let errors = cases
+        .iter()
+        .map(|sql| logical_plan(sql).unwrap_err().strip_backtrace())
+        .collect::<Vec<_>>()
+        .join("\n");
+    assert_snapshot!(
+        errors,
+        @r#"
+    Error during planning: FETCH WITH TIES requires an ORDER BY
+    Error during planning: TOP WITH TIES requires an ORDER BY
+    This feature is not implemented: FETCH PERCENT is not supported
+    This feature is not implemented: TOP PERCENT is not supported
+    Error during planning: LIMIT requires an integer, but got Utf8
+    Error during planning: FETCH requires an integer, but got Utf8
+    Error during planning: TOP requires an integer, but got Utf8
+    Error during planning: TOP cannot be used together with FETCH
+    Error during planning: TOP WITH TIES requires an ORDER BY
+    "#
+    );
+}
+
+#[test]
+fn test_offset_interval() {
+    let sql = "SELECT id FROM person LIMIT 10 OFFSET INTERVAL '1 day'";
+    let err = logical_plan(sql).expect_err("query should have failed");
+    assert_snapshot!(
+        err.strip_backtrace(),
+        @"Error during planning: OFFSET requires an integer, but got Interval(MonthDayNano)"
+    );
+
+    let sql = "SELECT id FROM person OFFSET INTERVAL '1' HOUR";
+    let err = logical_plan(sql).expect_err("query should have failed");
+    assert_snapshot!(
+        err.strip_backtrace(),
+        @"Error during planning: OFFSET requires an integer, but got Interval(MonthDayNano)"
+    );
+}
+
+#[test]
+fn test_offset_rows() {
+    let sql = "SELECT id FROM person OFFSET 10 ROWS";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+    Limit: skip=10, fetch=None
+      Projection: person.id
+        TableScan: person
+    "#
+    );
+
+    let sql = "SELECT id FROM person OFFSET 1 ROW";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+    Limit: skip=1, fetch=None
+      Projection: person.id
+        TableScan: person
+    "#
+    );
+}
+
+#[test]
+fn test_limit_offset_expressions() {
+    let sql = "SELECT id FROM person LIMIT 1 + 1 OFFSET (5 * 2) ROWS";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+    Limit: skip=Int64(5) * Int64(2), fetch=Int64(1) + Int64(1)
+      Projection: person.id
+        TableScan: person
+    "#
+    );
+
+    let sql = "SELECT id FROM person LIMIT $1 OFFSET $2 ROWS";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+    Limit: skip=$2, fetch=$1
+      Projection: person.id
+        TableScan: person
+    "#
+    );
+}
+
+#[test]
+fn test_limit_offset_non_integer() {
+    let cases = [
+        "SELECT id FROM person OFFSET 1.5",
+        "SELECT id FROM person LIMIT 10 / 4.0",
+        "SELECT id FROM person LIMIT 'a'",
+    ];
+    let errors = cases
+        .iter()
+        .map(|sql| logical_plan(sql).unwrap_err().strip_backtrace())
+        .collect::<Vec<_>>()
+        .join("\n");
+    assert_snapshot!(
+        errors,
+        @r#"
+    Error during planning: OFFSET requires an integer, but got Float64
+    Error during planning: LIMIT requires an integer, but got Float64
+    Error during planning: LIMIT requires an integer, but got Utf8
+    "#
+    );
+}
+
+#[test]
+fn test_select_top() {
+    let sql = "SELECT TOP 5 id FROM person";
+    assert_snapshot!(
+        logical_plan(sql).unwrap(),
+        @r#"
+    Limit: skip=0, fetch=5
+      Projection: person.id
+        TableScan: person
+    "#
+    );
+
+    let sql = "SELECT DISTINCT TOP (2 + 3) age FROM person ORDER BY age DESC";
+    assert_snapshot!(
+        logical_plan(sql).unwrap(),
+        @r#"
+    Limit: skip=0, fetch=Int64(2) + Int64(3)
+      Sort: person.age DESC NULLS FIRST
+        Distinct:
+          Projection: person.age
+            TableScan: person
+    "#
+    );
+
+    let sql = "SELECT TOP 1 id FROM person UNION ALL SELECT TOP 2 id FROM person";
+    assert_snapshot!(
+        logical_plan(sql).unwrap(),
+        @r#"
+    Union
+      Limit: skip=0, fetch=1
+        Projection: person.id
+          TableScan: person
+      Limit: skip=0, fetch=2
+        Projection: person.id
+          TableScan: person
+    "#
+    );
+
+    let sql =
+        "SELECT id FROM person WHERE id IN (SELECT TOP 3 id FROM person ORDER BY age)";
+    assert_snapshot!(
+        logical_plan(sql).unwrap(),
+        @r#"
+    Projection: person.id
+      Filter: person.id IN (<subquery>)
+        Subquery:
+          Limit: skip=0, fetch=3
+            Projection: person.id
+              Sort: person.age ASC NULLS LAST
+                Projection: person.id, person.age
+                  TableScan: person
+        TableScan: person
+    "#
+    );
+}
+
+#[test]
+fn test_limit_keeps_subquery_order() {
+    let sql = "SELECT id FROM (SELECT id, age FROM person ORDER BY age) LIMIT 5";
+    assert_snapshot!(
+        logical_plan(sql).unwrap(),
+        @r#"
+    Limit: skip=0, fetch=5
+      Projection: person.id
+        Sort: person.age ASC NULLS LAST
+          Projection: person.id, person.age
+            TableScan: person
+    "#
+    );
+
+    // the order is kept through filters, projections and nested derived tables
+    let sql = "SELECT id FROM (SELECT * FROM (SELECT id, age FROM person ORDER BY age) AS a) AS b WHERE id > 1 LIMIT 5";
+    assert_snapshot!(
+        logical_plan(sql).unwrap(),
+        @r#"
+    Limit: skip=0, fetch=5
+      Projection: b.id
+        Filter: b.id > Int64(1)
+          SubqueryAlias: b
+            Projection: a.id, a.age
+              SubqueryAlias: a
+                Sort: person.age ASC NULLS LAST
+                  Projection: person.id, person.age
+                    TableScan: person
+    "#
+    );
+
+    let sql =
+        "WITH t AS (SELECT id, age FROM person ORDER BY age) SELECT id FROM t OFFSET 3";
+    assert_snapshot!(
+        logical_plan(sql).unwrap(),
+        @r#"
+    Limit: skip=3, fetch=None
+      Projection: t.id
+        SubqueryAlias: t
+          Sort: person.age ASC NULLS LAST
+            Projection: person.id, person.age
+              TableScan: person
+    "#
+    );
+}
+
+#[test]
+fn test_table_sample_rows() {
+    let sql = "SELECT id FROM person AS p TABLESAMPLE (10 ROWS) WHERE age > 30";
     let plan = logical_plan(sql).unwrap();
     assert_snapshot!(
         plan,
         @r#"
-Projection: person.id, orders.order_id
-  Inner Join:  Filter: person.id = orders.customer_id AND (person.age > Int64(30) OR person.last_name = Utf8("X"))
-    TableScan: person
-    TableScan: orders
-"#
+    Projection: p.id
+      Filter: p.age > Int64(30)
+        SubqueryAlias: p
+          Limit: skip=0, fetch=10
+            Sort: random() ASC NULLS LAST
+              TableScan: person
+    "#
     );
 }
 
 #[test]
-fn hive_aggregate_with_filter() -> Result<()> {
-    let dialect = &HiveDialect {};
-    let sql = "SELECT sum(age) FILTER (WHERE age > 4) FROM person";
-    let plan = logical_plan_with_dialect(sql, dialect)?;
-
-    assert_snapshot!(
-        plan,
-        @r##"
-        Projection: sum(person.age) FILTER (WHERE person.age > Int64(4))
-          Aggregate: groupBy=[[]], aggr=[[sum(person.age) FILTER (WHERE person.age > Int64(4))]]
-            TableScan: person
-        "##
-    );
+fn test_table_sample_unsupported() {
+    for (sql, expected) in [
+        (
+            "SELECT id FROM person TABLESAMPLE (10 PERCENT)",
+            "This feature is not implemented: TABLESAMPLE of a percentage of rows is not supported yet",
+        ),
+        (
+            "SELECT id FROM person TABLESAMPLE (10)",
+            "This feature is not implemented: TABLESAMPLE of a percentage of rows is not supported yet",
+        ),
+        (
+            "SELECT id FROM person TABLESAMPLE SYSTEM (10 ROWS)",
+            "This feature is not implemented: TABLESAMPLE SYSTEM is not supported yet",
+        ),
+        (
+            "SELECT id FROM person TABLESAMPLE (10 ROWS) REPEATABLE (1)",
+            "This feature is not implemented: TABLESAMPLE with a seed is not supported yet",
+        ),
+        (
+            "SELECT id FROM person TABLESAMPLE (BUCKET 1 OUT OF 4 ON id)",
+            "This feature is not implemented: TABLESAMPLE BUCKET is not supported yet",
+        ),
+        (
+            "SELECT id FROM person TABLESAMPLE (1.5 ROWS)",
+            "Error during planning: TABLESAMPLE requires an integer number of rows, but got Float64",
+        ),
+    ] {
+        let err = logical_plan(sql).unwrap_err();
+        assert_eq!(err.strip_backtrace(), expected, "{sql}");
+    }
+}
 
-    Ok(())
+/// The versions of the tables scanned by `plan`
+fn table_scan_versions(plan: &LogicalPlan) -> Vec<Option<ScalarValue>> {
+    let mut versions = vec![];
+    plan.apply(|plan| {
+        if let LogicalPlan::TableScan(scan) = plan {
+            versions.push(scan.source.version().cloned());
+        }
+        Ok(TreeNodeRecursion::Continue)
+    })
+    .unwrap();
+    versions
 }
 
 #[test]
-fn order_by_unaliased_name() {
-    // https://github.com/apache/datafusion/issues/3160
-    // This query was failing with:
-    // SchemaError(FieldNotFound { qualifier: Some("p"), name: "state", valid_fields: ["z", "q"] })
-    let sql =
-        "select p.state z, sum(age) q from person p group by p.state order by p.state";
-    let plan = logical_plan(sql).unwrap();
+fn test_time_travel() {
+    let sql = "SELECT p.id FROM person FOR SYSTEM_TIME AS OF TIMESTAMP '2024-01-01 00:00:00' AS p \
+               JOIN orders ON p.id = orders.customer_id";
+    let plan = logical_plan_with_dialect(sql, &BigQueryDialect {}).unwrap();
     assert_snapshot!(
         plan,
         @r#"
-Projection: z, q
-  Sort: p.state ASC NULLS LAST
-    Projection: p.state AS z, sum(p.age) AS q, p.state
-      Aggregate: groupBy=[[p.state]], aggr=[[sum(p.age)]]
+    Projection: p.id
+      Inner Join:  Filter: p.id = orders.customer_id
         SubqueryAlias: p
           TableScan: person
-"#
+        TableScan: orders
+    "#
+    );
+    assert_eq!(
+        table_scan_versions(&plan),
+        vec![
+            Some(ScalarValue::TimestampNanosecond(
+                Some(1_704_067_200_000_000_000),
+                None
+            )),
+            None
+        ]
     );
-}
-
-#[test]
-fn order_by_ambiguous_name() {
-    let sql = "select * from person a join person b using (id) order by age";
-    let err = logical_plan(sql).unwrap_err().strip_backtrace();
 
-    assert_snapshot!(
-        err,
-        @r###"
-        Schema error: Ambiguous reference to unqualified field age
-        "###
+    let sql = "SELECT id FROM person FOR SYSTEM_TIME AS OF -3";
+    let plan = logical_plan_with_dialect(sql, &BigQueryDialect {}).unwrap();
+    assert_eq!(
+        table_scan_versions(&plan),
+        vec![Some(ScalarValue::Int64(Some(-3)))]
     );
 }
 
 #[test]
-fn group_by_ambiguous_name() {
-    let sql = "select max(id) from person a join person b using (id) group by age";
-    let err = logical_plan(sql).unwrap_err().strip_backtrace();
+fn test_time_travel_unsupported() {
+    let err = logical_plan_with_dialect(
+        "SELECT id FROM person FOR SYSTEM_TIME AS OF 1 + 2",
+        &BigQueryDialect {},
+    )
+    .unwrap_err();
+    assert_eq!(
+        err.strip_backtrace(),
+        "Error during planning: AS OF requires a constant expression, but got Int64(1) + Int64(2)"
+    );
 
-    assert_snapshot!(
-        err,
-        @r###"
-        Schema error: Ambiguous reference to unqualified field age
-        "###
+    let sql =
+        "WITH person AS (SELECT 1 AS id) SELECT id FROM person FOR SYSTEM_TIME AS OF 1";
+    let err = logical_plan_with_dialect(sql, &BigQueryDialect {}).unwrap_err();
+    assert_eq!(
+        err.strip_backtrace(),
+        "Error during planning: Time travel is not supported on the CTE person"
+    );
+
+    let sql = "SELECT id FROM person AT(TIMESTAMP => '2024-01-01')";
+    let err = logical_plan_with_dialect(sql, &SnowflakeDialect {}).unwrap_err();
+    assert_contains!(
+        err.strip_backtrace(),
+        "This feature is not implemented: Time travel with AT(TIMESTAMP => '2024-01-01') is not supported yet"
     );
 }
 
 #[test]
-fn test_zero_offset_with_limit() {
-    let sql = "select id from person where person.id > 100 LIMIT 5 OFFSET 0;";
+fn table_function_named_args() {
+    let sql = "SELECT * FROM echo_args(1, c => 3, b => 2)";
     let plan = logical_plan(sql).unwrap();
     assert_snapshot!(
         plan,
         @r#"
-Limit: skip=0, fetch=5
-  Projection: person.id
-    Filter: person.id > Int64(100)
-      TableScan: person
-"#
+    Projection: echo_args().Int64(1), echo_args().Int64(2), echo_args().Int64(3)
+      TableScan: echo_args()
+    "#
     );
-    // Flip the order of LIMIT and OFFSET in the query. Plan should remain the same.
-    let sql = "SELECT id FROM person WHERE person.id > 100 OFFSET 0 LIMIT 5;";
+
+    let sql = "SELECT * FROM echo_args(B => 2, a => 1) AS t";
     let plan = logical_plan(sql).unwrap();
     assert_snapshot!(
         plan,
         @r#"
-Limit: skip=0, fetch=5
-  Projection: person.id
-    Filter: person.id > Int64(100)
-      TableScan: person
-"#
+    Projection: t.Int64(1), t.Int64(2)
+      SubqueryAlias: t
+        TableScan: echo_args()
+    "#
     );
-}
 
-#[test]
-fn test_offset_no_limit() {
-    let sql = "SELECT id FROM person WHERE person.id > 100 OFFSET 5;";
+    let sql = "SELECT * FROM echo_args(c => 3, a => 1, b => 2)";
     let plan = logical_plan(sql).unwrap();
     assert_snapshot!(
         plan,
         @r#"
-Limit: skip=5, fetch=None
-  Projection: person.id
-    Filter: person.id > Int64(100)
-      TableScan: person
-"#
+    Projection: echo_args().Int64(1), echo_args().Int64(2), echo_args().Int64(3)
+      TableScan: echo_args()
+    "#
     );
 }
 
+#[rstest]
+#[case::unknown_parameter(
+    "SELECT * FROM echo_args(1, d => 4)",
+    "Error during planning: Table function echo_args has no parameter named d, parameters are: a, b, c"
+)]
+#[case::duplicate_parameter(
+    "SELECT * FROM echo_args(1, a => 2)",
+    "Error during planning: Table function echo_args got more than one value for parameter a"
+)]
+#[case::missing_parameter(
+    "SELECT * FROM echo_args(1, c => 3)",
+    "Error during planning: Table function echo_args is missing an argument for parameter b"
+)]
+#[case::missing_required_parameter(
+    "SELECT * FROM generate_series(start => 5)",
+    "Error during planning: Table function generate_series is missing an argument for parameter stop"
+)]
+#[case::positional_after_named(
+    "SELECT * FROM echo_args(a => 1, 2)",
+    "Error during planning: Positional arguments of table function echo_args must precede named arguments"
+)]
+#[case::no_parameter_names(
+    "SELECT * FROM enumerate(value => 'a')",
+    "Error during planning: Table function enumerate does not accept named arguments"
+)]
+#[test]
+fn table_function_named_args_errors(#[case] sql: &str, #[case] error: &str) {
+    let err = logical_plan(sql).unwrap_err();
+    assert_eq!(err.strip_backtrace(), error)
+}
+
 #[test]
-fn test_offset_after_limit() {
-    let sql = "select id from person where person.id > 100 LIMIT 5 OFFSET 3;";
-    let plan = logical_plan(sql).unwrap();
+fn test_table_function_with_ordinality() {
+    let sql = "SELECT * FROM generate_series(1, 3) WITH ORDINALITY";
     assert_snapshot!(
-        plan,
+        logical_plan(sql).unwrap(),
         @r#"
-Limit: skip=3, fetch=5
-  Projection: person.id
-    Filter: person.id > Int64(100)
-      TableScan: person
-"#
+    Projection: generate_series().value, generate_series().ordinality
+      Projection: generate_series().value, row_number() ROWS BETWEEN UNBOUNDED PRECEDING AND UNBOUNDED FOLLOWING AS ordinality
+        WindowAggr: windowExpr=[[row_number() ROWS BETWEEN UNBOUNDED PRECEDING AND UNBOUNDED FOLLOWING]]
+          TableScan: generate_series()
+    "#
+    );
+
+    let sql = "SELECT v, i FROM generate_series(1, 3) WITH ORDINALITY AS t(v, i)";
+    assert_snapshot!(
+        logical_plan(sql).unwrap(),
+        @r#"
+    Projection: t.v, t.i
+      SubqueryAlias: t
+        Projection: generate_series().value AS v, generate_series().ordinality AS i
+          Projection: generate_series().value, row_number() ROWS BETWEEN UNBOUNDED PRECEDING AND UNBOUNDED FOLLOWING AS ordinality
+            WindowAggr: windowExpr=[[row_number() ROWS BETWEEN UNBOUNDED PRECEDING AND UNBOUNDED FOLLOWING]]
+              TableScan: generate_series()
+    "#
+    );
+
+    // The ordinality column does not conflict with an `ordinality` column
+    // returned by the function
+    let sql = "SELECT * FROM enumerate('a') WITH ORDINALITY";
+    assert_snapshot!(
+        logical_plan(sql).unwrap(),
+        @r#"
+    Projection: enumerate().value, enumerate().ordinality, enumerate().ordinality_1
+      Projection: enumerate().value, enumerate().ordinality, row_number() ROWS BETWEEN UNBOUNDED PRECEDING AND UNBOUNDED FOLLOWING AS ordinality_1
+        WindowAggr: windowExpr=[[row_number() ROWS BETWEEN UNBOUNDED PRECEDING AND UNBOUNDED FOLLOWING]]
+          TableScan: enumerate()
+    "#
+    );
+
+    let sql = "SELECT * FROM person WITH ORDINALITY";
+    let err = logical_plan(sql).unwrap_err();
+    assert_eq!(
+        err.strip_backtrace(),
+        "Error during planning: WITH ORDINALITY is only supported on table functions"
     );
 }
 
 #[test]
-fn test_offset_before_limit() {
-    let sql = "select id from person where person.id > 100 OFFSET 3 LIMIT 5;";
-    let plan = logical_plan(sql).unwrap();
+fn test_subquery_order_removed_without_limit() {
+    let sql = "SELECT id FROM (SELECT id, age FROM person ORDER BY age)";
     assert_snapshot!(
-        plan,
+        logical_plan(sql).unwrap(),
         @r#"
-Limit: skip=3, fetch=5
-  Projection: person.id
-    Filter: person.id > Int64(100)
-      TableScan: person
-"#
+    Projection: person.id
+      Projection: person.id, person.age
+        TableScan: person
+    "#
+    );
+
+    // an outer ORDER BY replaces the order of the subquery
+    let sql =
+        "SELECT id FROM (SELECT id, age FROM person ORDER BY age) ORDER BY id LIMIT 5";
+    assert_snapshot!(
+        logical_plan(sql).unwrap(),
+        @r#"
+    Limit: skip=0, fetch=5
+      Sort: person.id ASC NULLS LAST
+        Projection: person.id
+          Projection: person.id, person.age
+            TableScan: person
+    "#
+    );
+
+    // the aggregate does not keep the order of its input
+    let sql = "SELECT count(*) FROM (SELECT id, age FROM person ORDER BY age) LIMIT 5";
+    assert_snapshot!(
+        logical_plan(sql).unwrap(),
+        @r#"
+    Limit: skip=0, fetch=5
+      Projection: count(*)
+        Aggregate: groupBy=[[]], aggr=[[count(*)]]
+          Projection: person.id, person.age
+            TableScan: person
+    "#
+    );
+
+    // the order of a subquery in an expression does not reach the LIMIT
+    let sql = "SELECT id FROM person WHERE id IN (SELECT id FROM (SELECT id, age FROM person ORDER BY age)) LIMIT 5";
+    assert_snapshot!(
+        logical_plan(sql).unwrap(),
+        @r#"
+    Limit: skip=0, fetch=5
+      Projection: person.id
+        Filter: person.id IN (<subquery>)
+          Subquery:
+            Projection: person.id
+              Projection: person.id, person.age
+                TableScan: person
+          TableScan: person
+    "#
     );
 }
 
@@ -4237,9 +6619,17 @@ Projection: person.id, person.age, row_number() PARTITION BY [person.age] ORDER
     "SELECT id, number FROM person LATERAL VIEW explode(numbers) exploded_table AS number",
     "This feature is not implemented: LATERAL VIEWS"
 )]
-#[case::select_top_unsupported(
-    "SELECT TOP (5) * FROM person",
-    "This feature is not implemented: TOP"
+#[case::select_top_percent_unsupported(
+    "SELECT TOP 5 PERCENT * FROM person",
+    "This feature is not implemented: TOP PERCENT is not supported"
+)]
+#[case::select_top_with_ties_without_order_by(
+    "SELECT TOP 5 WITH TIES * FROM person",
+    "Error during planning: TOP WITH TIES requires an ORDER BY"
+)]
+#[case::select_top_with_limit(
+    "SELECT TOP 5 * FROM person LIMIT 3",
+    "Error during planning: TOP cannot be used together with LIMIT"
 )]
 #[case::select_sort_by_unsupported(
     "SELECT * FROM person SORT BY id",
@@ -4251,6 +6641,68 @@ fn test_select_unsupported_syntax_errors(#[case] sql: &str, #[case] error: &str)
     assert_eq!(err.strip_backtrace(), error)
 }
 
+#[test]
+fn select_array_agg_with_multiple_order_by_keys() {
+    let sql = "SELECT array_agg(first_name ORDER BY age, id DESC) FROM person";
+    assert_snapshot!(
+        logical_plan(sql).unwrap(),
+        @r#"
+Projection: array_agg(person.first_name) ORDER BY [person.age ASC NULLS LAST, person.id DESC NULLS FIRST]
+  Aggregate: groupBy=[[]], aggr=[[array_agg(person.first_name) ORDER BY [person.age ASC NULLS LAST, person.id DESC NULLS FIRST]]]
+    TableScan: person
+"#
+    );
+
+    let sql = "SELECT array_agg(first_name ORDER BY age DESC NULLS LAST, id ASC NULLS FIRST) FROM person";
+    assert_snapshot!(
+        logical_plan(sql).unwrap(),
+        @r#"
+Projection: array_agg(person.first_name) ORDER BY [person.age DESC NULLS LAST, person.id ASC NULLS FIRST]
+  Aggregate: groupBy=[[]], aggr=[[array_agg(person.first_name) ORDER BY [person.age DESC NULLS LAST, person.id ASC NULLS FIRST]]]
+    TableScan: person
+"#
+    );
+}
+
+#[test]
+fn select_order_by_all_of_aggregate_window_and_distinct() {
+    let plan = |sql| logical_plan_with_dialect(sql, &DuckDbDialect {}).unwrap();
+
+    let sql = "SELECT state, count(*) FROM person GROUP BY state ORDER BY ALL DESC";
+    assert_snapshot!(
+        plan(sql),
+        @r#"
+Sort: person.state DESC NULLS FIRST, count(*) DESC NULLS FIRST
+  Projection: person.state, count(*)
+    Aggregate: groupBy=[[person.state]], aggr=[[count(*)]]
+      TableScan: person
+"#
+    );
+
+    let sql =
+        "SELECT id, row_number() OVER (ORDER BY age) AS rn FROM person ORDER BY ALL DESC";
+    assert_snapshot!(
+        plan(sql),
+        @r#"
+Sort: person.id DESC NULLS FIRST, rn DESC NULLS FIRST
+  Projection: person.id, row_number() ORDER BY [person.age ASC NULLS LAST] RANGE BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW AS rn
+    WindowAggr: windowExpr=[[row_number() ORDER BY [person.age ASC NULLS LAST] RANGE BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW]]
+      TableScan: person
+"#
+    );
+
+    let sql = "SELECT DISTINCT state, age + 1 AS next_age FROM person ORDER BY ALL DESC";
+    assert_snapshot!(
+        plan(sql),
+        @r#"
+Sort: person.state DESC NULLS FIRST, next_age DESC NULLS FIRST
+  Distinct:
+    Projection: person.state, person.age + Int64(1) AS next_age
+      TableScan: person
+"#
+    );
+}
+
 #[test]
 fn select_order_by_with_cast() {
     let sql =
@@ -4331,7 +6783,7 @@ fn test_ambiguous_column_references_in_on_join() {
     assert_snapshot!(
         err,
         @r###"
-        Schema error: Ambiguous reference to unqualified field id
+        Schema error: Ambiguous reference to unqualified field id, which could refer to p1.id or p2.id
         "###
     );
 }
@@ -4421,27 +6873,112 @@ fn test_field_not_found_window_function() {
         "###
     );
 
-    let partition_by_sql = "SELECT count() OVER (PARTITION BY a);";
-    let partition_by_err = logical_plan(partition_by_sql)
-        .expect_err("query should have failed")
-        .strip_backtrace();
-
+    let partition_by_sql = "SELECT count() OVER (PARTITION BY a);";
+    let partition_by_err = logical_plan(partition_by_sql)
+        .expect_err("query should have failed")
+        .strip_backtrace();
+
+    assert_snapshot!(
+        partition_by_err,
+        @r###"
+        Schema error: No field named a.
+        "###
+    );
+
+    let sql = "SELECT order_id, MAX(qty) OVER (PARTITION BY orders.order_id) from orders";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+Projection: orders.order_id, max(orders.qty) PARTITION BY [orders.order_id] ROWS BETWEEN UNBOUNDED PRECEDING AND UNBOUNDED FOLLOWING
+  WindowAggr: windowExpr=[[max(orders.qty) PARTITION BY [orders.order_id] ROWS BETWEEN UNBOUNDED PRECEDING AND UNBOUNDED FOLLOWING]]
+    TableScan: orders
+"#
+    );
+}
+
+#[test]
+fn select_window_extending_named_window() {
+    let sql = "SELECT \
+        sum(age) OVER w2, \
+        max(age) OVER (w1 ORDER BY id ROWS BETWEEN 1 PRECEDING AND CURRENT ROW), \
+        min(age) OVER w3 \
+        FROM person \
+        WINDOW w1 AS (PARTITION BY state), w2 AS (w1 ORDER BY salary), w3 AS w2";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+    Projection: sum(person.age) PARTITION BY [person.state] ORDER BY [person.salary ASC NULLS LAST] RANGE BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW, max(person.age) PARTITION BY [person.state] ORDER BY [person.id ASC NULLS LAST] ROWS BETWEEN 1 PRECEDING AND CURRENT ROW, min(person.age) PARTITION BY [person.state] ORDER BY [person.salary ASC NULLS LAST] RANGE BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW
+      WindowAggr: windowExpr=[[sum(person.age) PARTITION BY [person.state] ORDER BY [person.salary ASC NULLS LAST] RANGE BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW, min(person.age) PARTITION BY [person.state] ORDER BY [person.salary ASC NULLS LAST] RANGE BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW]]
+        WindowAggr: windowExpr=[[max(person.age) PARTITION BY [person.state] ORDER BY [person.id ASC NULLS LAST] ROWS BETWEEN 1 PRECEDING AND CURRENT ROW]]
+          TableScan: person
+    "#
+    );
+}
+
+#[test]
+fn select_window_extending_named_window_errors() {
+    let cases = [
+        "SELECT sum(age) OVER (w1 PARTITION BY id) FROM person WINDOW w1 AS (PARTITION BY state)",
+        "SELECT sum(age) OVER (w1 ORDER BY id) FROM person WINDOW w1 AS (ORDER BY state)",
+        "SELECT sum(age) OVER (w1 ORDER BY id) FROM person WINDOW w1 AS (ROWS UNBOUNDED PRECEDING)",
+        "SELECT sum(age) OVER w1 FROM person WINDOW w1 AS (w2), w2 AS (w1)",
+        "SELECT sum(age) OVER (w2 ORDER BY id) FROM person WINDOW w1 AS (PARTITION BY state)",
+    ];
+    let errors = cases
+        .iter()
+        .map(|sql| logical_plan(sql).unwrap_err().strip_backtrace())
+        .collect::<Vec<_>>()
+        .join("\n");
+    assert_snapshot!(
+        errors,
+        @r#"
+    Error during planning: Cannot override PARTITION BY clause of window w1
+    Error during planning: Cannot override ORDER BY clause of window w1
+    Error during planning: Cannot copy window w1 because it has a frame clause
+    Error during planning: The window w1 is defined recursively!
+    Error during planning: The window w2 is not defined!
+    "#
+    );
+}
+
+#[test]
+fn like_with_binary_collation() {
+    let sql = r#"SELECT first_name FROM person WHERE first_name LIKE 'A!_%' COLLATE "C" ESCAPE '!'"#;
+    let plan = logical_plan(sql).unwrap();
     assert_snapshot!(
-        partition_by_err,
-        @r###"
-        Schema error: No field named a.
-        "###
+        plan,
+        @r#"
+    Projection: person.first_name
+      Filter: person.first_name LIKE Utf8("A!_%") ESCAPE '!'
+        TableScan: person
+    "#
     );
 
-    let sql = "SELECT order_id, MAX(qty) OVER (PARTITION BY orders.order_id) from orders";
+    let sql =
+        r#"SELECT first_name FROM person WHERE first_name COLLATE "POSIX" ILIKE 'a%'"#;
     let plan = logical_plan(sql).unwrap();
     assert_snapshot!(
         plan,
         @r#"
-Projection: orders.order_id, max(orders.qty) PARTITION BY [orders.order_id] ROWS BETWEEN UNBOUNDED PRECEDING AND UNBOUNDED FOLLOWING
-  WindowAggr: windowExpr=[[max(orders.qty) PARTITION BY [orders.order_id] ROWS BETWEEN UNBOUNDED PRECEDING AND UNBOUNDED FOLLOWING]]
-    TableScan: orders
-"#
+    Projection: person.first_name
+      Filter: person.first_name ILIKE Utf8("a%")
+        TableScan: person
+    "#
+    );
+}
+
+#[test]
+fn like_with_unsupported_collation() {
+    let sql =
+        r#"SELECT first_name FROM person WHERE first_name LIKE 'a%' COLLATE "de_DE""#;
+    let err = logical_plan(sql).unwrap_err().strip_backtrace();
+    assert_snapshot!(
+        err,
+        @r#"
+    This feature is not implemented: COLLATE "de_DE" in LIKE expression
+    "#
     );
 }
 
@@ -4707,6 +7244,49 @@ fn test_error_message_invalid_window_aggregate_function_signature() {
     );
 }
 
+#[test]
+fn test_join_wildcard_follows_from_order() {
+    // The columns of each table are listed in the order of the FROM clause
+    for (sql, expected) in [
+        (
+            "SELECT * FROM j2 JOIN j1 ON j2.j2_id = j1.j1_id",
+            vec!["j2.j2_id", "j2.j2_string", "j1.j1_id", "j1.j1_string"],
+        ),
+        (
+            "SELECT * FROM j2 RIGHT JOIN j1 ON j2.j2_id = j1.j1_id",
+            vec!["j2.j2_id", "j2.j2_string", "j1.j1_id", "j1.j1_string"],
+        ),
+        (
+            "SELECT * FROM j3, j1 JOIN j2 ON j1.j1_id = j2.j2_id",
+            vec![
+                "j3.j3_id",
+                "j3.j3_string",
+                "j1.j1_id",
+                "j1.j1_string",
+                "j2.j2_id",
+                "j2.j2_string",
+            ],
+        ),
+    ] {
+        let plan = logical_plan(sql).unwrap();
+        assert_eq!(plan.schema().field_names(), expected, "{sql}");
+    }
+
+    // ORDER BY 1 sorts by the first column of the first table
+    let sql = "SELECT * FROM j2 JOIN j1 ON j2.j2_id = j1.j1_id ORDER BY 1";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+Sort: j2.j2_id ASC NULLS LAST
+  Projection: j2.j2_id, j2.j2_string, j1.j1_id, j1.j1_string
+    Inner Join:  Filter: j2.j2_id = j1.j1_id
+      TableScan: j2
+      TableScan: j1
+"#
+    );
+}
+
 // Test issue: https://github.com/apache/datafusion/issues/14058
 // Select with wildcard over a USING/NATURAL JOIN should deduplicate condition columns.
 #[test]
@@ -4757,13 +7337,28 @@ fn test_using_join_wildcard_schema() {
     assert_eq!(
         plan.schema().field_names(),
         [
+            "t3.c".to_string(),
             "t1.a".to_string(),
             "t1.b".to_string(),
-            "t2.c".to_string(),
             "t3.d".to_string()
         ]
     );
 
+    // The columns of a full join are coalesced and come first
+    let sql = "WITH t1 AS (SELECT 1 AS a, 1 AS b),
+        t2 AS (SELECT 2 AS b, 2 AS a, 3 AS c)
+        SELECT * FROM t1 FULL JOIN t2 USING (a)";
+    let plan = logical_plan(sql).unwrap();
+    assert_eq!(
+        plan.schema().field_names(),
+        [
+            "a".to_string(),
+            "t1.b".to_string(),
+            "t2.b".to_string(),
+            "t2.c".to_string()
+        ]
+    );
+
     // Subquery
     let sql = "WITH t1 AS (SELECT 1 AS a, 1 AS b),
         t2 AS (SELECT 1 AS a, 2 AS c),
@@ -4773,10 +7368,416 @@ fn test_using_join_wildcard_schema() {
     assert_eq!(
         plan.schema().field_names(),
         [
+            "t2.c".to_string(),
             "t1.a".to_string(),
             "t1.b".to_string(),
-            "t2.c".to_string(),
             "t3.d".to_string()
         ]
     );
 }
+
+#[test]
+fn cte_with_duplicate_column_names() {
+    let sql = "WITH c AS (SELECT id, id FROM person) SELECT * FROM c";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+    Projection: c.id, c.id:1
+      SubqueryAlias: c
+        Projection: person.id, person.id AS id:1
+          TableScan: person
+    "#
+    );
+}
+
+#[test]
+fn lateral_join_with_correlation() {
+    let sql = "SELECT p.id, o.order_id FROM person p, LATERAL (SELECT * FROM orders WHERE orders.customer_id = p.id) AS o";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+    Projection: p.id, o.order_id
+      Cross Join: 
+        SubqueryAlias: p
+          TableScan: person
+        SubqueryAlias: o
+          Subquery:
+            Projection: orders.order_id, orders.customer_id, orders.o_item_id, orders.qty, orders.price, orders.delivered
+              Filter: orders.customer_id = outer_ref(p.id)
+                TableScan: orders
+    "#
+    );
+
+    // The nested lateral subquery is correlated with `p2`, so the outer one
+    // is not correlated
+    let sql = "SELECT p1.id, d.order_id FROM person p1, LATERAL (SELECT p2.id, o.order_id FROM person p2, LATERAL (SELECT * FROM orders WHERE orders.customer_id = p2.id) AS o) AS d";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+    Projection: p1.id, d.order_id
+      Cross Join: 
+        SubqueryAlias: p1
+          TableScan: person
+        SubqueryAlias: d
+          Projection: p2.id, o.order_id
+            Cross Join: 
+              SubqueryAlias: p2
+                TableScan: person
+              SubqueryAlias: o
+                Subquery:
+                  Projection: orders.order_id, orders.customer_id, orders.o_item_id, orders.qty, orders.price, orders.delivered
+                    Filter: orders.customer_id = outer_ref(p2.id)
+                      TableScan: orders
+    "#
+    );
+}
+
+#[test]
+fn apply_join() {
+    let sql = "SELECT p.id, o.order_id FROM person p CROSS APPLY (SELECT * FROM orders WHERE orders.customer_id = p.id) AS o";
+    let plan = logical_plan_with_dialect(sql, &MsSqlDialect {}).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+    Projection: p.id, o.order_id
+      Cross Join: 
+        SubqueryAlias: p
+          TableScan: person
+        SubqueryAlias: o
+          Subquery:
+            Projection: orders.order_id, orders.customer_id, orders.o_item_id, orders.qty, orders.price, orders.delivered
+              Filter: orders.customer_id = outer_ref(p.id)
+                TableScan: orders
+    "#
+    );
+
+    let sql = "SELECT p.id, o.order_id FROM person p OUTER APPLY (SELECT * FROM orders WHERE orders.customer_id = p.id) AS o";
+    let plan = logical_plan_with_dialect(sql, &MsSqlDialect {}).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+    Projection: p.id, o.order_id
+      Left Join: 
+        SubqueryAlias: p
+          TableScan: person
+        SubqueryAlias: o
+          Subquery:
+            Projection: orders.order_id, orders.customer_id, orders.o_item_id, orders.qty, orders.price, orders.delivered
+              Filter: orders.customer_id = outer_ref(p.id)
+                TableScan: orders
+    "#
+    );
+}
+
+#[test]
+fn derived_table_with_duplicate_column_names() {
+    let sql = "SELECT * FROM (SELECT p1.id, p2.id FROM person p1 JOIN person p2 ON p1.id = p2.id) AS d";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+    Projection: d.id, d.id:1
+      SubqueryAlias: d
+        Projection: p1.id, p2.id AS id:1
+          Inner Join:  Filter: p1.id = p2.id
+            SubqueryAlias: p1
+              TableScan: person
+            SubqueryAlias: p2
+              TableScan: person
+    "#
+    );
+
+    let sql = "SELECT * FROM (SELECT p1.id, p2.id FROM person p1 JOIN person p2 ON p1.id = p2.id) AS d(a, b)";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+    Projection: d.a, d.b
+      SubqueryAlias: d
+        Projection: p1.id AS a, id:1 AS b
+          Projection: p1.id, p2.id AS id:1
+            Inner Join:  Filter: p1.id = p2.id
+              SubqueryAlias: p1
+                TableScan: person
+              SubqueryAlias: p2
+                TableScan: person
+    "#
+    );
+}
+
+#[test]
+fn set_operation_with_duplicate_column_names() {
+    let sql =
+        "SELECT * FROM (SELECT id, id FROM person UNION ALL SELECT id, age FROM person)";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+    Projection: id, id:1
+      Union
+        Projection: person.id, person.id AS id:1
+          TableScan: person
+        Projection: person.id, person.age
+          TableScan: person
+    "#
+    );
+
+    let sql = "SELECT p1.id, p2.id FROM person p1 JOIN person p2 ON p1.id = p2.id INTERSECT SELECT id, age FROM person";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+    LeftSemi Join: p1.id = person.id, id:1 = person.age
+      Distinct:
+        Projection: p1.id, p2.id AS id:1
+          Inner Join:  Filter: p1.id = p2.id
+            SubqueryAlias: p1
+              TableScan: person
+            SubqueryAlias: p2
+              TableScan: person
+      Projection: person.id, person.age
+        TableScan: person
+    "#
+    );
+}
+
+#[test]
+fn duplicate_column_names_error() {
+    let options = duplicate_column_names_error_parser_options();
+    for sql in [
+        "WITH c AS (SELECT id, id FROM person) SELECT * FROM c",
+        "SELECT * FROM (SELECT id, id FROM person) AS d",
+        "SELECT id, id FROM person UNION SELECT id, age FROM person",
+    ] {
+        let err = logical_plan_with_options(sql, options).unwrap_err();
+        assert_eq!(
+            err.strip_backtrace(),
+            "Error during planning: Column names of a CTE, derived table or set operation input must be unique, but the expression \"person.id\" at position 0 and \"person.id\" at position 1 are both named \"id\". Consider aliasing (\"AS\") one of them."
+        );
+    }
+
+    // The output of the top-level query is not affected: the same expression
+    // twice is still rejected, while distinct expressions may share a name
+    let err =
+        logical_plan_with_options("SELECT id, id FROM person", options).unwrap_err();
+    assert_eq!(
+        err.strip_backtrace(),
+        "Error during planning: Projections require unique expression names but the expression \"person.id\" at position 0 and \"person.id\" at position 1 have the same name. Consider aliasing (\"AS\") one of them."
+    );
+    let sql = "SELECT p1.id, p2.id FROM person p1 JOIN person p2 ON p1.id = p2.id";
+    logical_plan_with_options(sql, options).unwrap();
+}
+
+#[test]
+fn set_variable() {
+    let cases = [
+        "SET datafusion.execution.batch_size = 1024",
+        "SET SESSION datafusion.execution.batch_size TO '1024'",
+        "SET TIME ZONE 'UTC'",
+        "SET TIME ZONE LOCAL",
+        "SET datafusion.execution.coalesce_batches = ON",
+        "SET datafusion.execution.coalesce_batches TO off",
+        "SET datafusion.execution.batch_size TO DEFAULT",
+        "SET search_path = a, b",
+        "SET search_path = 'a, b', c",
+        "SET search_path = 'on'",
+    ];
+    let plans = cases
+        .iter()
+        .map(|sql| format!("{sql}\n{}", logical_plan(sql).unwrap()))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    assert_snapshot!(
+        plans,
+        @r#"
+    SET datafusion.execution.batch_size = 1024
+    SetVariable: set "datafusion.execution.batch_size" to "1024"
+
+    SET SESSION datafusion.execution.batch_size TO '1024'
+    SetVariable: set "datafusion.execution.batch_size" to "1024"
+
+    SET TIME ZONE 'UTC'
+    SetVariable: set "datafusion.execution.time_zone" to "UTC"
+
+    SET TIME ZONE LOCAL
+    SetVariable: set "datafusion.execution.time_zone" to default
+
+    SET datafusion.execution.coalesce_batches = ON
+    SetVariable: set "datafusion.execution.coalesce_batches" to "true"
+
+    SET datafusion.execution.coalesce_batches TO off
+    SetVariable: set "datafusion.execution.coalesce_batches" to "false"
+
+    SET datafusion.execution.batch_size TO DEFAULT
+    SetVariable: set "datafusion.execution.batch_size" to default
+
+    SET search_path = a, b
+    SetVariable: set "search_path" to ["a", "b"]
+
+    SET search_path = 'a, b', c
+    SetVariable: set "search_path" to ["a, b", "c"]
+
+    SET search_path = 'on'
+    SetVariable: set "search_path" to "on"
+    "#
+    );
+}
+
+#[test]
+fn set_variable_errors() {
+    let err =
+        logical_plan("SET GLOBAL datafusion.execution.batch_size = 1024").unwrap_err();
+    assert_snapshot!(
+        err.strip_backtrace(),
+        @r#"
+    This feature is not implemented: SET GLOBAL is not supported
+    "#
+    );
+
+    let err =
+        logical_plan("SET LOCAL datafusion.execution.batch_size = 1024").unwrap_err();
+    assert_snapshot!(
+        err.strip_backtrace(),
+        @r#"
+    This feature is not implemented: SET LOCAL is not supported
+    "#
+    );
+
+    let err = logical_plan("SET LOCAL TIME ZONE '+08:00'").unwrap_err();
+    assert_snapshot!(
+        err.strip_backtrace(),
+        @r#"
+    This feature is not implemented: SET LOCAL is not supported
+    "#
+    );
+}
+
+#[test]
+fn cte_shadows_table_in_nested_queries() {
+    // `person` refers to the CTE in the subqueries of the SELECT list and the
+    // WHERE clause, while `public.person` is the table
+    let sql = "WITH person AS (SELECT 1 AS id) \
+        SELECT (SELECT max(id) FROM person) \
+        FROM public.person AS p \
+        WHERE p.id IN (SELECT id FROM (SELECT id FROM person))";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+    Projection: (<subquery>)
+      Subquery:
+        Projection: max(person.id)
+          Aggregate: groupBy=[[]], aggr=[[max(person.id)]]
+            SubqueryAlias: person
+              Projection: Int64(1) AS id
+                EmptyRelation: rows=1
+      Filter: p.id IN (<subquery>)
+        Subquery:
+          Projection: person.id
+            Projection: person.id
+              SubqueryAlias: person
+                Projection: Int64(1) AS id
+                  EmptyRelation: rows=1
+        SubqueryAlias: p
+          TableScan: public.person
+    "#
+    );
+}
+
+#[test]
+fn nested_cte_shadows_outer_cte() {
+    let sql = "WITH person AS (SELECT 1 AS id) \
+        SELECT * FROM (WITH person AS (SELECT id + 1 AS id FROM person) SELECT * FROM person)";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(
+        plan,
+        @r#"
+    Projection: person.id
+      Projection: person.id
+        SubqueryAlias: person
+          Projection: person.id + Int64(1) AS id
+            SubqueryAlias: person
+              Projection: Int64(1) AS id
+                EmptyRelation: rows=1
+    "#
+    );
+}
+
+fn create_function(plan: LogicalPlan) -> CreateFunction {
+    match plan {
+        LogicalPlan::Ddl(DdlStatement::CreateFunction(create)) => create,
+        _ => panic!("expected CreateFunction, got {plan}"),
+    }
+}
+
+#[test]
+fn plan_create_function_with_named_arguments() {
+    // The body may refer to the arguments by name and call other functions
+    let sql = "CREATE FUNCTION my_round(x DOUBLE, digits BIGINT) RETURNS FLOAT \
+        LANGUAGE SQL IMMUTABLE RETURN round(x, digits)";
+    let create = create_function(logical_plan(sql).unwrap());
+    assert_eq!(create.name, "my_round");
+    assert_eq!(create.return_type, Some(DataType::Float32));
+    let arg_types = create
+        .args
+        .unwrap()
+        .into_iter()
+        .map(|arg| (arg.name.unwrap().value, arg.data_type))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        arg_types,
+        vec![
+            ("x".to_string(), DataType::Float64),
+            ("digits".to_string(), DataType::Int64)
+        ]
+    );
+    assert_eq!(create.params.language.unwrap().value, "SQL");
+    assert_eq!(create.params.behavior, Some(Volatility::Immutable));
+    assert_snapshot!(create.params.function_body.unwrap(), @"round(x, digits)");
+
+    // Arguments can still be referenced by position
+    let sql = "CREATE FUNCTION add_one(DOUBLE) RETURNS DOUBLE RETURN $1 + 1";
+    let create = create_function(logical_plan(sql).unwrap());
+    assert_snapshot!(create.params.function_body.unwrap(), @"$1 + Int64(1)");
+}
+
+#[test]
+fn plan_create_function_with_unknown_column() {
+    let sql = "CREATE FUNCTION f(x DOUBLE) RETURNS DOUBLE RETURN y + 1";
+    let err = logical_plan(sql).unwrap_err();
+    assert_snapshot!(
+        err.strip_backtrace(),
+        @"Schema error: No field named y. Valid fields are x."
+    );
+}
+
+#[test]
+fn plan_drop_function() {
+    let sql = "DROP FUNCTION IF EXISTS my_round(DOUBLE, BIGINT)";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(plan, @r#"DropFunction: name "my_round" args (Float64, Int64)"#);
+    let LogicalPlan::Ddl(DdlStatement::DropFunction(drop)) = plan else {
+        panic!("expected DropFunction");
+    };
+    assert_eq!(drop.name, "my_round");
+    assert!(drop.if_exists);
+    assert_eq!(drop.args, Some(vec![DataType::Float64, DataType::Int64]));
+
+    let sql = "DROP FUNCTION my_round";
+    let plan = logical_plan(sql).unwrap();
+    assert_snapshot!(plan, @r#"DropFunction: name "my_round""#);
+    let LogicalPlan::Ddl(DdlStatement::DropFunction(drop)) = plan else {
+        panic!("expected DropFunction");
+    };
+    assert!(!drop.if_exists);
+    assert_eq!(drop.args, None);
+
+    let err = logical_plan("DROP FUNCTION f1, f2").unwrap_err();
+    assert_snapshot!(
+        err.strip_backtrace(),
+        @"This feature is not implemented: Dropping more than one function in a statement is not supported"
+    );
+}